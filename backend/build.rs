@@ -0,0 +1,6 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Generated code for the optional gRPC service
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/mapf/v1/verification.proto")?;
+    Ok(())
+}
@@ -0,0 +1,6 @@
+-- Add solver-reported search statistics to verification results
+ALTER TABLE verification_results
+    ADD COLUMN nodes_expanded BIGINT,
+    ADD COLUMN nodes_generated BIGINT,
+    ADD COLUMN peak_memory_bytes BIGINT,
+    ADD COLUMN agent_costs BIGINT[];
@@ -0,0 +1,3 @@
+-- Record the seed each solver run used, for determinism audits and replays
+ALTER TABLE verification_results
+    ADD COLUMN seed BIGINT;
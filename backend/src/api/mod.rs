@@ -1,5 +1,6 @@
 pub mod auth;
 pub mod leaderboard;
+pub mod schema;
 pub mod solver;
 
 use crate::{config::Config, db::Database};
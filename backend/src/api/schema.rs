@@ -0,0 +1,52 @@
+use axum::{http::header, response::IntoResponse};
+
+/// JSON schema describing `ValidationResult` and its stable error codes.
+pub const VALIDATION_RESULT_SCHEMA: &str =
+    include_str!("../../schema/validation_result.schema.json");
+
+/// GET /api/schema/validation
+/// Publish the validation result schema for frontends and CI bots
+pub async fn validation() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "application/schema+json")],
+        VALIDATION_RESULT_SCHEMA,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ValidationErrorType;
+
+    #[test]
+    fn test_schema_lists_every_error_code() {
+        let schema: serde_json::Value = serde_json::from_str(VALIDATION_RESULT_SCHEMA).unwrap();
+        let error = &schema["$defs"]["ValidationError"]["properties"];
+        let types = error["type"]["enum"].as_array().unwrap();
+        let codes = error["code"]["enum"].as_array().unwrap();
+
+        let all = [
+            ValidationErrorType::EmptyPath,
+            ValidationErrorType::DiagonalMove,
+            ValidationErrorType::OutOfBounds,
+            ValidationErrorType::BlockedCell,
+            ValidationErrorType::PathTooLong,
+            ValidationErrorType::CornerCutting,
+            ValidationErrorType::DynamicObstacle,
+            ValidationErrorType::InvalidStart,
+            ValidationErrorType::InvalidGoal,
+            ValidationErrorType::BelowOptimal,
+            ValidationErrorType::AgentCountMismatch,
+            ValidationErrorType::DuplicateStart,
+            ValidationErrorType::DuplicateGoal,
+            ValidationErrorType::MissedWaypoint,
+            ValidationErrorType::VertexCollision,
+            ValidationErrorType::EdgeCollision,
+        ];
+        assert_eq!(types.len(), all.len());
+        for error_type in &all {
+            assert!(types.contains(&error_type.as_str().into()));
+            assert!(codes.contains(&error_type.code().into()));
+        }
+    }
+}
@@ -4,12 +4,15 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use uuid::Uuid;
 
 use crate::{
     auth::AuthenticatedUser,
     error::{AppError, Result},
-    executor::WasmExecutor,
-    validation::{self, Coordinate, GridMap},
+    executor::{GuestStats, SolverLog, SolverResult, SolverStats, WasmExecutor},
+    heatmap::{self, ConflictHeatmap},
+    report,
+    validation::{self, Coordinate, GridMap, RuleSet, TimedObstacle, ValidationOptions},
 };
 
 use super::AppState;
@@ -21,6 +24,30 @@ pub struct VerifyRequest {
     pub map: MapData,
     pub starts: Vec<Coordinate>,
     pub goals: Vec<Coordinate>,
+    /// Per-agent optimal lengths from the scenario, if known.
+    #[serde(default)]
+    pub optimal_lengths: Option<Vec<f64>>,
+    /// Timed obstacles for dynamic-environment variants.
+    #[serde(default)]
+    pub obstacles: Vec<TimedObstacle>,
+    /// Per-agent cells to visit in order before the goal, for multi-goal tasks.
+    #[serde(default)]
+    pub waypoints: Vec<Vec<Coordinate>>,
+    /// Seed passed to the solver, e.g. to replay a recorded run; random if absent.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Rules of the MAPF variant; defaults to cardinal moves, stay at goal.
+    #[serde(default)]
+    pub rules: RuleSet,
+    /// Include an ASCII validation report in the response.
+    #[serde(default)]
+    pub report: bool,
+    /// Include conflict heat-map data in the response.
+    #[serde(default)]
+    pub heatmap: bool,
+    /// Stop validating at the first failing check and report only that error.
+    #[serde(default)]
+    pub fail_fast: bool,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -28,6 +55,41 @@ pub struct MapData {
     pub width: u32,
     pub height: u32,
     pub tiles: Vec<u8>,
+    /// Declared MovingAI map type; defaults to `octile`.
+    #[serde(default)]
+    pub map_type: Option<String>,
+    /// Per-cell entry costs for weighted maps, same layout as `tiles`.
+    #[serde(default)]
+    pub costs: Option<Vec<u32>>,
+}
+
+impl MapData {
+    /// Convert the raw request map into a core grid (1 = passable, 0 = blocked).
+    pub fn to_grid_map(&self) -> Result<GridMap> {
+        let grid = GridMap::from_bytes(self.width, self.height, &self.tiles).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Map tiles length {} doesn't match {}x{}",
+                self.tiles.len(),
+                self.width,
+                self.height
+            ))
+        })?;
+        let grid = match &self.map_type {
+            Some(map_type) => grid.with_map_type(map_type.as_str()),
+            None => grid,
+        };
+        match &self.costs {
+            Some(costs) => grid.with_costs(costs.clone()).ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "Map costs length {} doesn't match {}x{}",
+                    costs.len(),
+                    self.width,
+                    self.height
+                ))
+            }),
+            None => Ok(grid),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -35,8 +97,18 @@ pub struct VerifyResponse {
     pub valid: bool,
     pub solution: Option<validation::Solution>,
     pub validation_errors: Vec<validation::ValidationError>,
+    pub validation_warnings: Vec<validation::ValidationWarning>,
+    /// Per-agent summary, one entry per path (empty in fail-fast mode or
+    /// if the solver failed).
+    pub agents: Vec<validation::AgentStatus>,
     pub stats: ExecutionStats,
     pub error: Option<String>,
+    /// ASCII rendering of the validation errors, when requested.
+    pub report: Option<String>,
+    /// Per-cell conflict counts and events, when requested.
+    pub heatmap: Option<ConflictHeatmap>,
+    /// Messages the solver logged during the run.
+    pub log: SolverLog,
 }
 
 #[derive(Debug, Serialize)]
@@ -45,18 +117,44 @@ pub struct ExecutionStats {
     pub execution_time_ms: u64,
     pub cost: Option<i64>,
     pub makespan: Option<i64>,
+    /// Seed the solver ran with, for replays.
+    pub seed: u64,
+    /// Search statistics reported by the solver, if any.
+    pub guest: Option<GuestStats>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SubmitRequest {
     pub solver_name: String,
-    pub map_name: String,
-    pub scenario_id: String,
     #[serde(rename = "wasmBytes")]
     pub wasm_bytes: Vec<u8>,
+    #[serde(flatten)]
+    pub instance: SubmitInstance,
+}
+
+/// The instance a submission is run on and where it comes from.
+#[derive(Debug, Deserialize)]
+pub struct SubmitInstance {
+    pub map_name: String,
+    pub scenario_id: String,
     pub map: MapData,
     pub starts: Vec<Coordinate>,
     pub goals: Vec<Coordinate>,
+    /// Per-agent optimal lengths from the scenario, if known.
+    #[serde(default)]
+    pub optimal_lengths: Option<Vec<f64>>,
+    /// Timed obstacles for dynamic-environment variants.
+    #[serde(default)]
+    pub obstacles: Vec<TimedObstacle>,
+    /// Per-agent cells to visit in order before the goal, for multi-goal tasks.
+    #[serde(default)]
+    pub waypoints: Vec<Vec<Coordinate>>,
+    /// Seed passed to the solver, e.g. to replay a recorded run; random if absent.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Rules of the MAPF variant; defaults to cardinal moves, stay at goal.
+    #[serde(default)]
+    pub rules: RuleSet,
 }
 
 #[derive(Debug, Serialize)]
@@ -66,56 +164,126 @@ pub struct SubmitResponse {
     pub message: String,
 }
 
-/// POST /api/verify
-/// Verify a WASM solver without storing results (open endpoint for testing)
-pub async fn verify(
-    State(state): State<AppState>,
-    Json(req): Json<VerifyRequest>,
-) -> Result<Json<VerifyResponse>> {
-    // Validate WASM size
+/// What was stored for one run of a submission.
+#[derive(Debug)]
+pub struct RecordedRun {
+    pub verification_id: String,
+    pub valid: bool,
+    pub cost: Option<i64>,
+    pub makespan: Option<i64>,
+    pub error_message: Option<String>,
+    pub stats: SolverStats,
+}
+
+/// Validation options derived from server configuration and the request.
+fn validation_options(
+    state: &AppState,
+    obstacles: &[TimedObstacle],
+    waypoints: &[Vec<Coordinate>],
+    rules: &RuleSet,
+) -> ValidationOptions {
+    ValidationOptions {
+        horizon_factor: state.config.path_length_factor,
+        rules: rules.clone(),
+        obstacles: obstacles.to_vec(),
+        waypoints: waypoints.to_vec(),
+        ..Default::default()
+    }
+}
+
+/// Reject components larger than the configured limit.
+pub(crate) fn check_wasm_size(state: &AppState, wasm_bytes: &[u8]) -> Result<()> {
     let max_size = state.config.max_wasm_size_mb * 1024 * 1024;
-    if req.wasm_bytes.len() > max_size {
+    if wasm_bytes.len() > max_size {
         return Err(AppError::BadRequest(format!(
             "WASM file too large: {} bytes (max: {} MB)",
-            req.wasm_bytes.len(),
+            wasm_bytes.len(),
             state.config.max_wasm_size_mb
         )));
     }
+    Ok(())
+}
 
-    // Create executor
+/// Run a solver component once with the server's limits.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn execute_solver(
+    state: &AppState,
+    wasm_bytes: &[u8],
+    grid_map: &GridMap,
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+    waypoints: &[Vec<Coordinate>],
+    obstacles: &[TimedObstacle],
+    seed: Option<u64>,
+) -> Result<SolverResult> {
     let executor = WasmExecutor::new(
         state.config.solver_timeout_secs,
         state.config.solver_instruction_limit,
     )
-    .map_err(|e| AppError::WasmExecution(format!("Failed to create executor: {}", e)))?;
+    .map_err(|e| AppError::WasmExecution(format!("Failed to create executor: {}", e)))?
+    .with_seed(seed.unwrap_or_else(rand::random));
+
+    executor
+        .execute(wasm_bytes, grid_map, starts, goals, waypoints, obstacles)
+        .await
+        .map_err(|e| AppError::WasmExecution(format!("Execution failed: {}", e)))
+}
+
+/// POST /api/verify
+/// Verify a WASM solver without storing results (open endpoint for testing)
+pub async fn verify(
+    State(state): State<AppState>,
+    Json(req): Json<VerifyRequest>,
+) -> Result<Json<VerifyResponse>> {
+    check_wasm_size(&state, &req.wasm_bytes)?;
 
     // Convert map
-    let grid_map = GridMap {
-        width: req.map.width,
-        height: req.map.height,
-        tiles: req.map.tiles,
-    };
+    let grid_map = req.map.to_grid_map()?;
 
     // Execute solver
-    let solver_result = executor
-        .execute(&req.wasm_bytes, &grid_map, &req.starts, &req.goals)
-        .await
-        .map_err(|e| AppError::WasmExecution(format!("Execution failed: {}", e)))?;
+    let solver_result = execute_solver(
+        &state,
+        &req.wasm_bytes,
+        &grid_map,
+        &req.starts,
+        &req.goals,
+        &req.waypoints,
+        &req.obstacles,
+        req.seed,
+    )
+    .await?;
 
+    check_result(&state, &req, &grid_map, solver_result).map(Json)
+}
+
+/// Validate and score a solver's answer to a verify request.
+pub(crate) fn check_result(
+    state: &AppState,
+    req: &VerifyRequest,
+    grid_map: &GridMap,
+    solver_result: SolverResult,
+) -> Result<VerifyResponse> {
     // If solver failed, return error
     if let Some(error) = &solver_result.error {
-        return Ok(Json(VerifyResponse {
+        return Ok(VerifyResponse {
             valid: false,
             solution: None,
             validation_errors: vec![],
+            validation_warnings: vec![],
+            agents: vec![],
             stats: ExecutionStats {
                 instruction_count: solver_result.stats.instruction_count,
                 execution_time_ms: solver_result.stats.execution_time_ms,
                 cost: None,
                 makespan: None,
+                seed: solver_result.stats.seed,
+                guest: solver_result.stats.guest.clone(),
             },
             error: Some(error.clone()),
-        }));
+            report: None,
+            heatmap: None,
+            log: solver_result.log,
+        });
     }
 
     // Validate solution
@@ -123,35 +291,74 @@ pub async fn verify(
         AppError::WasmExecution("Solver returned no solution and no error".to_string())
     })?;
 
-    let validation_result =
-        validation::validate_solution(&solution, &grid_map, &req.starts, &req.goals);
+    let mut validation_result = validation::validate_solution_with(
+        &solution,
+        grid_map,
+        &req.starts,
+        &req.goals,
+        &ValidationOptions {
+            fail_fast: req.fail_fast,
+            ..validation_options(state, &req.obstacles, &req.waypoints, &req.rules)
+        },
+    );
+
+    // Reject results that beat the scenario's lower bounds
+    if let Some(optimal_lengths) = &req.optimal_lengths {
+        validation_result
+            .errors
+            .extend(validation::validate_against_baselines(&solution.paths, optimal_lengths));
+        validation_result.valid = validation_result.errors.is_empty();
+        // Agents that beat their bound lose credit too
+        if !validation_result.agents.is_empty() {
+            validation_result.agents =
+                validation::agent_statuses(&solution.paths, &validation_result.errors);
+        }
+    }
+
+    // Catch solvers that misreport their own cost
+    if let Some(declared) = solver_result.declared_cost {
+        validation_result
+            .warnings
+            .extend(validation::check_declared_cost(&solution, grid_map, declared));
+    }
+
+    let report = req
+        .report
+        .then(|| report::render_report(&validation_result, &solution, grid_map));
+    let heatmap = req
+        .heatmap
+        .then(|| heatmap::conflict_heatmap(&validation_result, grid_map));
+
+    // Score and return the canonical form so trailing waits don't count
+    let solution = solution.canonicalize();
 
     // Calculate cost and makespan if valid
     let (cost, makespan) = if validation_result.valid {
-        let cost: i64 = solution.paths.iter().map(|p| p.steps.len() as i64).sum();
-        let makespan: i64 = solution
-            .paths
-            .iter()
-            .map(|p| p.steps.len() as i64)
-            .max()
-            .unwrap_or(0);
-        (Some(cost), Some(makespan))
+        let score = mapf_scoring::score(&solution, grid_map);
+        (Some(score.cost as i64), Some(score.makespan as i64))
     } else {
         (None, None)
     };
 
-    Ok(Json(VerifyResponse {
+    Ok(VerifyResponse {
         valid: validation_result.valid,
         solution: Some(solution),
         validation_errors: validation_result.errors,
+        validation_warnings: validation_result.warnings,
+        agents: validation_result.agents,
         stats: ExecutionStats {
             instruction_count: solver_result.stats.instruction_count,
             execution_time_ms: solver_result.stats.execution_time_ms,
             cost,
             makespan,
+            seed: solver_result.stats.seed,
+            guest: solver_result.stats.guest,
         },
         error: None,
-    }))
+        report,
+        heatmap,
+        log: solver_result.log,
+    })
 }
 
 /// POST /api/submit
@@ -162,55 +369,102 @@ pub async fn submit(
     Json(req): Json<SubmitRequest>,
 ) -> Result<Json<SubmitResponse>> {
     // Validate inputs
-    if req.solver_name.is_empty() || req.map_name.is_empty() || req.scenario_id.is_empty() {
+    if req.solver_name.is_empty() {
         return Err(AppError::BadRequest(
             "solver_name, map_name, and scenario_id are required".to_string(),
         ));
     }
+    check_instance(&req.instance)?;
+
+    let submission_id = create_submission(&state, &auth, &req.solver_name, &req.wasm_bytes).await?;
+    let run = record_run(&state, submission_id, &req.wasm_bytes, &req.instance).await?;
 
+    Ok(Json(SubmitResponse {
+        submission_id: submission_id.to_string(),
+        verification_id: run.verification_id,
+        message: if run.valid {
+            "Submission verified and added to leaderboard".to_string()
+        } else {
+            format!(
+                "Submission recorded but not valid: {}",
+                run.error_message.unwrap_or_else(|| "Unknown error".to_string())
+            )
+        },
+    }))
+}
+
+/// Reject instances that can't be placed on the leaderboard.
+pub(crate) fn check_instance(instance: &SubmitInstance) -> Result<()> {
+    if instance.map_name.is_empty() || instance.scenario_id.is_empty() {
+        return Err(AppError::BadRequest(
+            "solver_name, map_name, and scenario_id are required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Store a submission record for `wasm_bytes`, returning its id.
+pub(crate) async fn create_submission(
+    state: &AppState,
+    auth: &AuthenticatedUser,
+    solver_name: &str,
+    wasm_bytes: &[u8],
+) -> Result<Uuid> {
     // Hash WASM for deduplication
     let mut hasher = Sha256::new();
-    hasher.update(&req.wasm_bytes);
+    hasher.update(wasm_bytes);
     let wasm_hash = format!("{:x}", hasher.finalize());
 
-    // Create submission record
     let submission = state
         .db
-        .create_submission(auth.user_id, &req.solver_name, &wasm_hash)
+        .create_submission(auth.user_id, solver_name, &wasm_hash)
         .await?;
+    Ok(submission.id)
+}
 
-    // Execute and validate (reuse verify logic)
-    let executor = WasmExecutor::new(
-        state.config.solver_timeout_secs,
-        state.config.solver_instruction_limit,
-    )
-    .map_err(|e| AppError::WasmExecution(format!("Failed to create executor: {}", e)))?;
-
-    let grid_map = GridMap {
-        width: req.map.width,
-        height: req.map.height,
-        tiles: req.map.tiles,
-    };
+/// Run a submitted solver on one instance, validate its answer and store the
+/// outcome under `submission_id`.
+pub(crate) async fn record_run(
+    state: &AppState,
+    submission_id: Uuid,
+    wasm_bytes: &[u8],
+    instance: &SubmitInstance,
+) -> Result<RecordedRun> {
+    let grid_map = instance.map.to_grid_map()?;
 
-    let solver_result = executor
-        .execute(&req.wasm_bytes, &grid_map, &req.starts, &req.goals)
-        .await
-        .map_err(|e| AppError::WasmExecution(format!("Execution failed: {}", e)))?;
+    let solver_result = execute_solver(
+        state,
+        wasm_bytes,
+        &grid_map,
+        &instance.starts,
+        &instance.goals,
+        &instance.waypoints,
+        &instance.obstacles,
+        instance.seed,
+    )
+    .await?;
 
     let valid = solver_result.error.is_none();
     let (cost, makespan, error_message) = if let Some(solution) = &solver_result.solution {
-        let validation_result =
-            validation::validate_solution(solution, &grid_map, &req.starts, &req.goals);
+        let mut validation_result = validation::validate_solution_with(
+            solution,
+            &grid_map,
+            &instance.starts,
+            &instance.goals,
+            &validation_options(state, &instance.obstacles, &instance.waypoints, &instance.rules),
+        );
+
+        if let Some(optimal_lengths) = &instance.optimal_lengths {
+            validation_result
+                .errors
+                .extend(validation::validate_against_baselines(&solution.paths, optimal_lengths));
+            validation_result.valid = validation_result.errors.is_empty();
+        }
 
         if validation_result.valid {
-            let cost: i64 = solution.paths.iter().map(|p| p.steps.len() as i64).sum();
-            let makespan: i64 = solution
-                .paths
-                .iter()
-                .map(|p| p.steps.len() as i64)
-                .max()
-                .unwrap_or(0);
-            (Some(cost), Some(makespan), None)
+            // Scored in canonical form, so trailing waits don't count
+            let score = mapf_scoring::score(solution, &grid_map);
+            (Some(score.cost as i64), Some(score.makespan as i64), None)
         } else {
             let error_summary = validation_result
                 .errors
@@ -223,41 +477,40 @@ pub async fn submit(
     } else {
         (None, None, solver_result.error.clone())
     };
+    let valid = valid && cost.is_some();
 
     // Store verification result
     let verification = state
         .db
         .create_verification_result(
-            submission.id,
-            &req.map_name,
-            &req.scenario_id,
-            req.starts.len() as i32,
-            valid && cost.is_some(),
+            submission_id,
+            &instance.map_name,
+            &instance.scenario_id,
+            instance.starts.len() as i32,
+            valid,
             cost,
             makespan,
             solver_result.stats.instruction_count.map(|c| c as i64),
             solver_result.stats.execution_time_ms as i64,
+            solver_result.stats.seed as i64,
+            solver_result.stats.guest.as_ref(),
             error_message.as_deref(),
         )
         .await?;
 
     tracing::info!(
         "Submission {} verified: valid={}, cost={:?}",
-        submission.id,
-        valid && cost.is_some(),
+        submission_id,
+        valid,
         cost
     );
 
-    Ok(Json(SubmitResponse {
-        submission_id: submission.id.to_string(),
+    Ok(RecordedRun {
         verification_id: verification.id.to_string(),
-        message: if valid && cost.is_some() {
-            "Submission verified and added to leaderboard".to_string()
-        } else {
-            format!(
-                "Submission recorded but not valid: {}",
-                error_message.unwrap_or_else(|| "Unknown error".to_string())
-            )
-        },
-    }))
+        valid,
+        cost,
+        makespan,
+        error_message,
+        stats: solver_result.stats,
+    })
 }
@@ -64,42 +64,52 @@ where
     type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
-        // Extract API key from Authorization header
-        let auth_header = parts
-            .headers
-            .get("Authorization")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| AppError::Auth("Missing Authorization header".to_string()))?;
-
-        // Parse Bearer token
-        let api_key = auth_header
-            .strip_prefix("Bearer ")
-            .ok_or_else(|| AppError::Auth("Invalid Authorization header format".to_string()))?;
-
         // Get database from extensions (added by middleware)
         let db = parts
             .extensions
             .get::<Database>()
             .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Database not in extensions")))?;
 
-        // Hash the provided key and look it up
-        // Note: In production, consider using a constant-time comparison
-        let key_hash = hash_api_key(api_key)?;
-        
-        let api_key_record = db
-            .get_api_key_by_hash(&key_hash)
-            .await
-            .map_err(|e| AppError::Database(e))?
-            .ok_or_else(|| AppError::Auth("Invalid API key".to_string()))?;
-
-        // Update last used timestamp
-        db.update_api_key_last_used(api_key_record.id)
-            .await
-            .map_err(|e| AppError::Database(e))?;
-
-        Ok(AuthenticatedUser {
-            user_id: api_key_record.user_id,
-            api_key_id: api_key_record.id,
-        })
+        // Extract API key from Authorization header
+        let auth_header = parts
+            .headers
+            .get("Authorization")
+            .and_then(|v| v.to_str().ok());
+
+        authenticate(db, auth_header).await
     }
 }
+
+/// Look up the user behind an `Authorization: Bearer <api_key>` value.
+///
+/// Shared by the HTTP extractor and the gRPC service, which reads the same
+/// value from request metadata.
+pub async fn authenticate(db: &Database, auth_header: Option<&str>) -> Result<AuthenticatedUser> {
+    let auth_header =
+        auth_header.ok_or_else(|| AppError::Auth("Missing Authorization header".to_string()))?;
+
+    // Parse Bearer token
+    let api_key = auth_header
+        .strip_prefix("Bearer ")
+        .ok_or_else(|| AppError::Auth("Invalid Authorization header format".to_string()))?;
+
+    // Hash the provided key and look it up
+    // Note: In production, consider using a constant-time comparison
+    let key_hash = hash_api_key(api_key)?;
+
+    let api_key_record = db
+        .get_api_key_by_hash(&key_hash)
+        .await
+        .map_err(|e| AppError::Database(e))?
+        .ok_or_else(|| AppError::Auth("Invalid API key".to_string()))?;
+
+    // Update last used timestamp
+    db.update_api_key_last_used(api_key_record.id)
+        .await
+        .map_err(|e| AppError::Database(e))?;
+
+    Ok(AuthenticatedUser {
+        user_id: api_key_record.user_id,
+        api_key_id: api_key_record.id,
+    })
+}
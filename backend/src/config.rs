@@ -5,10 +5,13 @@ pub struct Config {
     pub database_url: String,
     pub server_host: String,
     pub server_port: u16,
+    /// Port of the gRPC service, when built with `--features grpc`
+    pub grpc_port: u16,
     pub cors_allowed_origins: Vec<String>,
     pub max_wasm_size_mb: usize,
     pub solver_timeout_secs: u64,
     pub solver_instruction_limit: u64,
+    pub path_length_factor: usize,
 }
 
 impl Config {
@@ -19,6 +22,9 @@ impl Config {
             server_port: env::var("SERVER_PORT")
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()?,
+            grpc_port: env::var("GRPC_PORT")
+                .unwrap_or_else(|_| "50051".to_string())
+                .parse()?,
             cors_allowed_origins: env::var("CORS_ALLOWED_ORIGINS")
                 .unwrap_or_else(|_| "http://localhost:5173".to_string())
                 .split(',')
@@ -33,6 +39,9 @@ impl Config {
             solver_instruction_limit: env::var("SOLVER_INSTRUCTION_LIMIT")
                 .unwrap_or_else(|_| "10000000000".to_string())
                 .parse()?,
+            path_length_factor: env::var("PATH_LENGTH_FACTOR")
+                .unwrap_or_else(|_| "16".to_string())
+                .parse()?,
         })
     }
 }
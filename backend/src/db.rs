@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use sqlx::{postgres::PgPoolOptions, PgPool};
 use uuid::Uuid;
 
+use crate::executor::GuestStats;
+
 #[derive(Clone)]
 pub struct Database {
     pool: PgPool,
@@ -71,6 +73,12 @@ pub struct VerificationResult {
     pub execution_time_ms: i64,
     pub error_message: Option<String>,
     pub verified_at: DateTime<Utc>,
+    pub nodes_expanded: Option<i64>,
+    pub nodes_generated: Option<i64>,
+    pub peak_memory_bytes: Option<i64>,
+    pub agent_costs: Option<Vec<i64>>,
+    /// Seed the solver ran with (bit pattern of the `u64`)
+    pub seed: Option<i64>,
 }
 
 // Repository functions
@@ -169,13 +177,16 @@ impl Database {
         makespan: Option<i64>,
         instruction_count: Option<i64>,
         execution_time_ms: i64,
+        seed: i64,
+        guest_stats: Option<&GuestStats>,
         error_message: Option<&str>,
     ) -> Result<VerificationResult, sqlx::Error> {
         sqlx::query_as::<_, VerificationResult>(
             "INSERT INTO verification_results 
              (submission_id, map_name, scenario_id, num_agents, valid, cost, makespan, 
-              instruction_count, execution_time_ms, error_message)
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING *",
+              instruction_count, execution_time_ms, error_message,
+              nodes_expanded, nodes_generated, peak_memory_bytes, agent_costs, seed)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15) RETURNING *",
         )
         .bind(submission_id)
         .bind(map_name)
@@ -187,6 +198,11 @@ impl Database {
         .bind(instruction_count)
         .bind(execution_time_ms)
         .bind(error_message)
+        .bind(guest_stats.map(|s| s.nodes_expanded as i64))
+        .bind(guest_stats.map(|s| s.nodes_generated as i64))
+        .bind(guest_stats.map(|s| s.peak_memory_bytes as i64))
+        .bind(guest_stats.map(|s| s.agent_costs.iter().map(|&c| c as i64).collect::<Vec<_>>()))
+        .bind(seed)
         .fetch_one(&self.pool)
         .await
     }
@@ -243,6 +259,40 @@ impl Database {
 
         query.fetch_all(&self.pool).await
     }
+
+    /// Best valid result recorded for one instance: lowest cost, then fewest
+    /// instructions.
+    pub async fn get_baseline(
+        &self,
+        map_name: &str,
+        scenario_id: &str,
+        num_agents: i32,
+    ) -> Result<Option<LeaderboardEntry>, sqlx::Error> {
+        sqlx::query_as::<_, LeaderboardEntry>(
+            "SELECT 
+                u.username,
+                ss.solver_name,
+                vr.map_name,
+                vr.scenario_id,
+                vr.num_agents,
+                vr.cost,
+                vr.makespan,
+                vr.instruction_count,
+                vr.execution_time_ms,
+                vr.verified_at
+            FROM verification_results vr
+            JOIN solver_submissions ss ON vr.submission_id = ss.id
+            JOIN users u ON ss.user_id = u.id
+            WHERE vr.valid = true AND vr.map_name = $1 AND vr.scenario_id = $2 AND vr.num_agents = $3
+            ORDER BY vr.cost ASC, vr.instruction_count ASC
+            LIMIT 1",
+        )
+        .bind(map_name)
+        .bind(scenario_id)
+        .bind(num_agents)
+        .fetch_optional(&self.pool)
+        .await
+    }
 }
 
 #[derive(Debug, sqlx::FromRow, serde::Serialize)]
@@ -0,0 +1,381 @@
+/// gRPC interface to the verification pipeline (`--features grpc`)
+/// Same checks as the HTTP API, with streamed progress for long runs
+
+use std::net::SocketAddr;
+
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    api::{
+        solver::{self, ExecutionStats, MapData, SubmitInstance, VerifyRequest, VerifyResponse},
+        AppState,
+    },
+    auth,
+    error::AppError,
+    executor::SolverStats,
+    validation::{Coordinate, RuleSet, TimedObstacle, ValidationError},
+};
+
+pub mod proto {
+    tonic::include_proto!("mapf.v1");
+}
+
+use proto::verification_server::{Verification, VerificationServer};
+
+/// Room for the instances of a suite on top of the component itself
+const MAX_INSTANCE_BYTES: usize = 64 * 1024 * 1024;
+
+/// Serve the verification service on `addr` until the process exits.
+pub async fn serve(state: AppState, addr: SocketAddr) {
+    let max_message = state.config.max_wasm_size_mb * 1024 * 1024 + MAX_INSTANCE_BYTES;
+    let service = VerificationServer::new(VerificationService { state })
+        .max_decoding_message_size(max_message);
+
+    if let Err(e) = tonic::transport::Server::builder()
+        .add_service(service)
+        .serve(addr)
+        .await
+    {
+        tracing::error!("gRPC server stopped: {}", e);
+    }
+}
+
+pub struct VerificationService {
+    state: AppState,
+}
+
+#[tonic::async_trait]
+impl Verification for VerificationService {
+    type VerifyStream = ReceiverStream<Result<proto::VerifyProgress, Status>>;
+    type SubmitSuiteStream = ReceiverStream<Result<proto::SuiteProgress, Status>>;
+
+    async fn verify(
+        &self,
+        request: Request<proto::VerifyRequest>,
+    ) -> Result<Response<Self::VerifyStream>, Status> {
+        let req = request.into_inner();
+        solver::check_wasm_size(&self.state, &req.wasm)?;
+
+        let instance = submit_instance(String::new(), String::new(), req.instance)?;
+        let verify_req = VerifyRequest {
+            wasm_bytes: req.wasm,
+            map: instance.map,
+            starts: instance.starts,
+            goals: instance.goals,
+            optimal_lengths: instance.optimal_lengths,
+            obstacles: instance.obstacles,
+            waypoints: instance.waypoints,
+            seed: instance.seed,
+            rules: instance.rules,
+            report: req.report,
+            heatmap: false,
+            fail_fast: req.fail_fast,
+        };
+        let grid_map = verify_req.map.to_grid_map()?;
+
+        let state = self.state.clone();
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let _ = tx.send(Ok(stage(proto::Stage::Executing))).await;
+            let solver_result = solver::execute_solver(
+                &state,
+                &verify_req.wasm_bytes,
+                &grid_map,
+                &verify_req.starts,
+                &verify_req.goals,
+                &verify_req.waypoints,
+                &verify_req.obstacles,
+                verify_req.seed,
+            )
+            .await;
+
+            let result = match solver_result {
+                Ok(solver_result) => {
+                    let _ = tx.send(Ok(stage(proto::Stage::Validating))).await;
+                    solver::check_result(&state, &verify_req, &grid_map, solver_result)
+                }
+                Err(e) => Err(e),
+            };
+            let update = result.map(|response| proto::VerifyProgress {
+                update: Some(proto::verify_progress::Update::Result(verify_result(
+                    response,
+                ))),
+            });
+            let _ = tx.send(update.map_err(Status::from)).await;
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn submit_suite(
+        &self,
+        request: Request<proto::SubmitSuiteRequest>,
+    ) -> Result<Response<Self::SubmitSuiteStream>, Status> {
+        let header = request
+            .metadata()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let user = auth::authenticate(&self.state.db, header.as_deref()).await?;
+
+        let req = request.into_inner();
+        if req.solver_name.is_empty() {
+            return Err(Status::invalid_argument("solver_name is required"));
+        }
+        if req.instances.is_empty() {
+            return Err(Status::invalid_argument("The suite has no instances"));
+        }
+        solver::check_wasm_size(&self.state, &req.wasm)?;
+
+        // Reject a malformed suite before anything is stored
+        let instances = req
+            .instances
+            .into_iter()
+            .map(|i| -> Result<SubmitInstance, Status> {
+                let instance = submit_instance(i.map_name, i.scenario_id, i.instance)?;
+                solver::check_instance(&instance)?;
+                instance.map.to_grid_map()?;
+                Ok(instance)
+            })
+            .collect::<Result<Vec<_>, Status>>()?;
+
+        let submission_id =
+            solver::create_submission(&self.state, &user, &req.solver_name, &req.wasm).await?;
+
+        let state = self.state.clone();
+        let wasm = req.wasm;
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let total = instances.len() as u32;
+            for (index, instance) in instances.iter().enumerate() {
+                let update = solver::record_run(&state, submission_id, &wasm, instance)
+                    .await
+                    .map(|run| proto::SuiteProgress {
+                        submission_id: submission_id.to_string(),
+                        index: index as u32,
+                        total,
+                        verification_id: run.verification_id,
+                        valid: run.valid,
+                        cost: run.cost,
+                        makespan: run.makespan,
+                        stats: Some(solver_stats(&run.stats, run.cost, run.makespan)),
+                        error: run.error_message,
+                    })
+                    .map_err(Status::from);
+                let failed = update.is_err();
+
+                // Stop early if the client went away or the run couldn't be recorded
+                if tx.send(update).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn baseline(
+        &self,
+        request: Request<proto::BaselineRequest>,
+    ) -> Result<Response<proto::BaselineResponse>, Status> {
+        let req = request.into_inner();
+        let num_agents = i32::try_from(req.num_agents)
+            .map_err(|_| Status::invalid_argument("num_agents is too large"))?;
+
+        let entry = self
+            .state
+            .db
+            .get_baseline(&req.map_name, &req.scenario_id, num_agents)
+            .await
+            .map_err(AppError::from)?;
+
+        let response = match entry {
+            Some(entry) => proto::BaselineResponse {
+                found: true,
+                cost: entry.cost,
+                makespan: entry.makespan,
+                username: entry.username,
+                solver_name: entry.solver_name,
+                instruction_count: entry.instruction_count,
+                execution_time_ms: entry.execution_time_ms,
+                verified_at: entry.verified_at.to_rfc3339(),
+            },
+            None => proto::BaselineResponse::default(),
+        };
+        Ok(Response::new(response))
+    }
+}
+
+impl From<AppError> for Status {
+    fn from(error: AppError) -> Self {
+        match error {
+            AppError::Database(e) => {
+                tracing::error!("Database error: {}", e);
+                Status::internal("Database error")
+            }
+            AppError::WasmExecution(e) | AppError::Validation(e) | AppError::BadRequest(e) => {
+                Status::invalid_argument(e)
+            }
+            AppError::Auth(e) => Status::unauthenticated(e),
+            AppError::NotFound(e) => Status::not_found(e),
+            AppError::Internal(e) => {
+                tracing::error!("Internal error: {}", e);
+                Status::internal("Internal server error")
+            }
+        }
+    }
+}
+
+/// Convert a protobuf instance into the shape the HTTP API accepts.
+fn submit_instance(
+    map_name: String,
+    scenario_id: String,
+    instance: Option<proto::Instance>,
+) -> Result<SubmitInstance, Status> {
+    let instance = instance.ok_or_else(|| Status::invalid_argument("instance is required"))?;
+    let map = instance
+        .map
+        .ok_or_else(|| Status::invalid_argument("instance.map is required"))?;
+    let rules = instance.rules.unwrap_or_default();
+
+    Ok(SubmitInstance {
+        map_name,
+        scenario_id,
+        map: MapData {
+            width: map.width,
+            height: map.height,
+            tiles: map.tiles,
+            map_type: map.map_type,
+            costs: (!map.costs.is_empty()).then_some(map.costs),
+        },
+        starts: coordinates(instance.starts),
+        goals: coordinates(instance.goals),
+        optimal_lengths: (!instance.optimal_lengths.is_empty()).then_some(instance.optimal_lengths),
+        obstacles: instance
+            .obstacles
+            .into_iter()
+            .map(|o| TimedObstacle {
+                x: o.x,
+                y: o.y,
+                from: o.from as usize,
+                until: o.until.map(|t| t as usize),
+            })
+            .collect(),
+        waypoints: instance
+            .waypoints
+            .into_iter()
+            .map(|w| coordinates(w.cells))
+            .collect(),
+        seed: instance.seed,
+        rules: RuleSet {
+            allow_diagonals: rules.allow_diagonals,
+            disappear_at_goal: rules.disappear_at_goal,
+        },
+    })
+}
+
+impl From<proto::Coordinate> for Coordinate {
+    fn from(c: proto::Coordinate) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+impl From<&Coordinate> for proto::Coordinate {
+    fn from(c: &Coordinate) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+fn coordinates(cells: Vec<proto::Coordinate>) -> Vec<Coordinate> {
+    cells.into_iter().map(Coordinate::from).collect()
+}
+
+fn stage(stage: proto::Stage) -> proto::VerifyProgress {
+    proto::VerifyProgress {
+        update: Some(proto::verify_progress::Update::Stage(stage as i32)),
+    }
+}
+
+fn verify_result(response: VerifyResponse) -> proto::VerifyResult {
+    proto::VerifyResult {
+        valid: response.valid,
+        paths: response
+            .solution
+            .map(|solution| {
+                solution
+                    .paths
+                    .iter()
+                    .map(|path| proto::Path {
+                        steps: path.steps.iter().map(proto::Coordinate::from).collect(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        errors: response
+            .validation_errors
+            .iter()
+            .map(validation_error)
+            .collect(),
+        warnings: response
+            .validation_warnings
+            .iter()
+            .map(|w| proto::ValidationWarning {
+                r#type: w.warning_type.as_str().to_string(),
+                code: w.code as u32,
+                agent_index: w.agent_index as u64,
+                timestep: w.timestep.map(|t| t as u64),
+                details: w.details.clone(),
+            })
+            .collect(),
+        stats: Some(execution_stats(&response.stats)),
+        error: response.error,
+        report: response.report,
+        agents: response
+            .agents
+            .iter()
+            .map(|a| proto::AgentStatus {
+                agent_index: a.agent_index as u64,
+                valid: a.valid,
+                cost: a.cost,
+                first_error: a.first_error.as_ref().map(validation_error),
+            })
+            .collect(),
+    }
+}
+
+fn validation_error(e: &ValidationError) -> proto::ValidationError {
+    proto::ValidationError {
+        r#type: e.error_type.as_str().to_string(),
+        code: e.code as u32,
+        agents: e.agents.iter().map(|&a| a as u64).collect(),
+        timestep: e.timestep.map(|t| t as u64),
+        positions: e.positions.iter().map(proto::Coordinate::from).collect(),
+        details: e.details.clone(),
+    }
+}
+
+fn execution_stats(stats: &ExecutionStats) -> proto::ExecutionStats {
+    proto::ExecutionStats {
+        instruction_count: stats.instruction_count,
+        execution_time_ms: stats.execution_time_ms,
+        cost: stats.cost,
+        makespan: stats.makespan,
+        seed: stats.seed,
+    }
+}
+
+fn solver_stats(
+    stats: &SolverStats,
+    cost: Option<i64>,
+    makespan: Option<i64>,
+) -> proto::ExecutionStats {
+    proto::ExecutionStats {
+        instruction_count: stats.instruction_count,
+        execution_time_ms: stats.execution_time_ms,
+        cost,
+        makespan,
+        seed: stats.seed,
+    }
+}
@@ -0,0 +1,104 @@
+//! Conflict heat-map data for the frontend overlay
+//! Aggregates collision errors into per-cell counts and structured events
+
+use serde::{Deserialize, Serialize};
+
+use crate::validation::{Coordinate, GridMap, ValidationErrorType, ValidationResult};
+
+/// A single conflict between agents.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictEvent {
+    /// `vertex_collision` or `edge_collision`.
+    pub kind: ValidationErrorType,
+    pub timestep: usize,
+    pub agents: Vec<usize>,
+    /// The shared cell, or both cells of a swapped edge.
+    pub cells: Vec<Coordinate>,
+}
+
+/// Per-cell conflict counts plus the events they were built from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictHeatmap {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major counts, `counts[y * width + x]`.
+    pub counts: Vec<u32>,
+    pub events: Vec<ConflictEvent>,
+}
+
+/// Collect the collisions in a validation result into heat-map data.
+///
+/// Every cell involved in a conflict is counted once per conflict, so both
+/// ends of a swapped edge light up.
+pub fn conflict_heatmap(result: &ValidationResult, map: &GridMap) -> ConflictHeatmap {
+    let (width, height) = (map.width(), map.height());
+    let mut counts = vec![0; (width * height) as usize];
+    let mut events = Vec::new();
+
+    for error in &result.errors {
+        if !matches!(
+            error.error_type,
+            ValidationErrorType::VertexCollision | ValidationErrorType::EdgeCollision
+        ) {
+            continue;
+        }
+        let Some(timestep) = error.timestep else { continue };
+
+        for cell in &error.positions {
+            if cell.x < width && cell.y < height {
+                counts[(cell.y * width + cell.x) as usize] += 1;
+            }
+        }
+        events.push(ConflictEvent {
+            kind: error.error_type.clone(),
+            timestep,
+            agents: error.agents.clone(),
+            cells: error.positions.clone(),
+        });
+    }
+
+    ConflictHeatmap {
+        width,
+        height,
+        counts,
+        events,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::{validate_solution, Path, Solution};
+
+    #[test]
+    fn test_heatmap_counts_collisions() {
+        let map = GridMap::from_bytes(3, 2, &[1; 6]).unwrap();
+        let c = |x, y| Coordinate { x, y };
+        // Agents 0 and 1 swap; agent 2 runs into agent 1's goal
+        let solution = Solution {
+            paths: vec![
+                Path {
+                    steps: vec![c(0, 0), c(1, 0)],
+                },
+                Path {
+                    steps: vec![c(1, 0), c(0, 0)],
+                },
+                Path {
+                    steps: vec![c(0, 1), c(0, 0)],
+                },
+            ],
+        };
+        let starts = [c(0, 0), c(1, 0), c(0, 1)];
+        let goals = [c(1, 0), c(0, 0), c(2, 1)];
+        let result = validate_solution(&solution, &map, &starts, &goals);
+
+        let heatmap = conflict_heatmap(&result, &map);
+
+        assert_eq!(heatmap.events.len(), 2);
+        assert_eq!(heatmap.counts, vec![2, 1, 0, 0, 0, 0]);
+        assert!(heatmap
+            .events
+            .iter()
+            .any(|e| matches!(e.kind, ValidationErrorType::EdgeCollision) && e.agents == [0, 1]));
+    }
+}
@@ -15,8 +15,12 @@ mod auth;
 mod config;
 mod db;
 mod error;
-mod executor;
-mod validation;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod heatmap;
+mod report;
+
+use mapf_executor::{executor, validation};
 
 use config::Config;
 use db::Database;
@@ -64,6 +68,13 @@ async fn main() -> anyhow::Result<()> {
     // Build application state
     let state = api::AppState::new(config.clone(), db);
 
+    #[cfg(feature = "grpc")]
+    {
+        let addr = SocketAddr::from(([0, 0, 0, 0], config.grpc_port));
+        tracing::info!("Starting gRPC server on {}", addr);
+        tokio::spawn(grpc::serve(state.clone(), addr));
+    }
+
     // Build router
     let app = Router::new()
         .route("/health", get(health_check))
@@ -71,6 +82,7 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/verify", post(api::solver::verify))
         .route("/api/submit", post(api::solver::submit))
         .route("/api/leaderboard", get(api::leaderboard::list))
+        .route("/api/schema/validation", get(api::schema::validation))
         .layer(TraceLayer::new_for_http())
         .layer(cors)
         .with_state(state);
@@ -0,0 +1,162 @@
+//! Human-readable rendering of validation results
+//! Draws an ASCII timeline around each error so solver authors can see what went wrong
+
+use std::fmt::Write;
+
+use crate::validation::{position_at, Coordinate, GridMap, Solution, ValidationResult};
+
+/// Cells shown on each side of the error location.
+const WINDOW_RADIUS: u32 = 3;
+/// Errors rendered in full; the rest are only counted.
+const MAX_RENDERED_ERRORS: usize = 5;
+
+/// Render a validation result as an ASCII report.
+///
+/// For every error (up to a limit) the report shows the involved agents' local
+/// paths and map snapshots at `t-1`, `t` and `t+1` centred on the offending cell.
+/// Legend: `@` blocked, `.` free, `A`/`B` involved agents, `X` both in one cell.
+pub fn render_report(result: &ValidationResult, solution: &Solution, map: &GridMap) -> String {
+    let mut out = String::new();
+
+    if result.valid {
+        let _ = writeln!(out, "Solution is valid ({} agents)", solution.paths.len());
+        return out;
+    }
+
+    let _ = writeln!(out, "Solution is invalid: {} error(s)", result.errors.len());
+
+    for error in result.errors.iter().take(MAX_RENDERED_ERRORS) {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{:?}: {}", error.error_type, error.details);
+
+        let Some(t) = error.timestep else { continue };
+        let Some(path) = solution.paths.get(error.agent_index) else { continue };
+        let Some(&focus) = position_at(path, t) else { continue };
+
+        // Reported agent first so it is drawn as `A`
+        let mut agents = vec![error.agent_index];
+        agents.extend(error.agents.iter().filter(|&&a| a != error.agent_index));
+
+        for &agent in &agents {
+            let _ = writeln!(out, "  agent {}: {}", agent, local_path(solution, agent, t));
+        }
+        render_frames(&mut out, solution, map, &agents, focus, t);
+    }
+
+    if result.errors.len() > MAX_RENDERED_ERRORS {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "... and {} more error(s)",
+            result.errors.len() - MAX_RENDERED_ERRORS
+        );
+    }
+
+    out
+}
+
+/// Positions around timestep `t`, with the position at `t` bracketed.
+fn local_path(solution: &Solution, agent: usize, t: usize) -> String {
+    let path = &solution.paths[agent];
+    (t.saturating_sub(2)..=t + 2)
+        .filter_map(|step| {
+            let pos = position_at(path, step)?;
+            Some(if step == t {
+                format!("[t={} ({},{})]", step, pos.x, pos.y)
+            } else {
+                format!("({},{})", pos.x, pos.y)
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Side-by-side map snapshots at `t-1`, `t` and `t+1`.
+fn render_frames(
+    out: &mut String,
+    solution: &Solution,
+    map: &GridMap,
+    agents: &[usize],
+    focus: Coordinate,
+    t: usize,
+) {
+    let x0 = focus.x.saturating_sub(WINDOW_RADIUS);
+    let y0 = focus.y.saturating_sub(WINDOW_RADIUS);
+    let x1 = focus.x.saturating_add(WINDOW_RADIUS).min(map.width().saturating_sub(1));
+    let y1 = focus.y.saturating_add(WINDOW_RADIUS).min(map.height().saturating_sub(1));
+    let frame_width = (x1.saturating_sub(x0) + 1) as usize;
+
+    let steps: Vec<usize> = (t.saturating_sub(1)..=t + 1).collect();
+
+    let headers: Vec<String> = steps
+        .iter()
+        .map(|step| format!("{:<width$}", format!("t={}", step), width = frame_width))
+        .collect();
+    let _ = writeln!(out, "  {}", headers.join("   "));
+
+    for y in y0..=y1 {
+        let rows: Vec<String> = steps
+            .iter()
+            .map(|&step| {
+                (x0..=x1)
+                    .map(|x| cell_char(solution, map, agents, Coordinate { x, y }, step))
+                    .collect()
+            })
+            .collect();
+        let _ = writeln!(out, "  {}", rows.join("   "));
+    }
+}
+
+fn cell_char(
+    solution: &Solution,
+    map: &GridMap,
+    agents: &[usize],
+    cell: Coordinate,
+    t: usize,
+) -> char {
+    let here: Vec<usize> = agents
+        .iter()
+        .copied()
+        .filter(|&agent| position_at(&solution.paths[agent], t) == Some(&cell))
+        .collect();
+
+    match here.as_slice() {
+        [] if map.is_passable(cell.x, cell.y) => '.',
+        [] => '@',
+        [agent] if *agent == agents[0] => 'A',
+        [_] => 'B',
+        _ => 'X',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::{validate_solution, Path};
+
+    #[test]
+    fn test_report_marks_vertex_collision() {
+        let map = GridMap::from_bytes(3, 1, &[1, 1, 1]).unwrap();
+        let solution = Solution {
+            paths: vec![
+                Path {
+                    steps: vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 1, y: 0 }],
+                },
+                Path {
+                    steps: vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 1, y: 0 }],
+                },
+            ],
+        };
+        let starts = [Coordinate { x: 0, y: 0 }, Coordinate { x: 2, y: 0 }];
+        let goals = [Coordinate { x: 1, y: 0 }, Coordinate { x: 1, y: 0 }];
+
+        let result = validate_solution(&solution, &map, &starts, &goals);
+        let report = render_report(&result, &solution, &map);
+
+        assert!(report.contains("VertexCollision"));
+        assert!(report.contains("agent 1: (2,0) -> [t=1 (1,0)]"));
+        assert!(report.contains("agent 0:"));
+        // t=0: A and B apart, t=1: both on the middle cell
+        assert!(report.contains("B.A   .X."));
+    }
+}
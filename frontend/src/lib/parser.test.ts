@@ -84,6 +84,27 @@ describe('parseScenario', () => {
 		expect(e0.goalX).toBe(1);
 		expect(e0.goalY).toBe(0);
 		expect(e0.optimalLength).toBeCloseTo(1.0);
+		expect(e0.startTime).toBe(0);
+	});
+
+	it('reads the optional start time column', () => {
+		const scen = parseScenario('version 1\n0\tempty-8-8.map\t8\t8\t0\t0\t1\t0\t1.0\t12\n');
+		expect(scen.entries[0].startTime).toBe(12);
+	});
+
+	it('reads agent annotations', () => {
+		const scen = parseScenario(
+			'version 1\n0\tempty-8-8.map\t8\t8\t0\t0\t1\t0\t1.0\t3\tid=scout\tgroup=red\tpriority=2\n' +
+				'0\tempty-8-8.map\t8\t8\t2\t2\t3\t2\t1.0\tgroup=blue\n'
+		);
+		const [first, second] = scen.entries;
+		expect(first.startTime).toBe(3);
+		expect(first.id).toBe('scout');
+		expect(first.group).toBe('red');
+		expect(first.priority).toBe(2);
+		expect(second.startTime).toBe(0);
+		expect(second.group).toBe('blue');
+		expect(second.id).toBeUndefined();
 	});
 
 	it('throws on missing version', () => {
@@ -69,9 +69,11 @@ export function parseMap(input: string): GridMap {
  * Format:
  * ```
  * version N
- * bucket\tmap\twidth\theight\tstart_x\tstart_y\tgoal_x\tgoal_y\toptimal
+ * bucket\tmap\twidth\theight\tstart_x\tstart_y\tgoal_x\tgoal_y\toptimal[\tstart_time][\tkey=value...]
  * ...
  * ```
+ *
+ * Trailing `id=`, `group=` and `priority=` columns annotate the agent.
  */
 export function parseScenario(input: string): Scenario {
 	const lines = input.split(/\r?\n/);
@@ -93,7 +95,10 @@ export function parseScenario(input: string): Scenario {
 		const parts = trimmed.split('\t');
 		if (parts.length < 9) continue; // skip malformed lines
 
-		entries.push({
+		const extra = parts.slice(9);
+		const startTime =
+			extra.length > 0 && !extra[0].includes('=') ? parseInt(extra.shift()!, 10) : 0;
+		const entry: ScenarioEntry = {
 			bucket: parseInt(parts[0], 10),
 			mapName: parts[1],
 			mapWidth: parseInt(parts[2], 10),
@@ -102,8 +107,18 @@ export function parseScenario(input: string): Scenario {
 			startY: parseInt(parts[5], 10),
 			goalX: parseInt(parts[6], 10),
 			goalY: parseInt(parts[7], 10),
-			optimalLength: parseFloat(parts[8])
-		});
+			optimalLength: parseFloat(parts[8]),
+			startTime
+		};
+		for (const column of extra) {
+			const split = column.indexOf('=');
+			const key = column.slice(0, split);
+			const value = column.slice(split + 1);
+			if (key === 'id') entry.id = value;
+			else if (key === 'group') entry.group = value;
+			else if (key === 'priority') entry.priority = parseInt(value, 10);
+		}
+		entries.push(entry);
 	}
 
 	if (version === null) {
@@ -1,2 +1,2 @@
 // Re-exports for solver module
-export { SolverRunner, type SolverRunnerConfig } from './runner';
+export { SolverRunner, type SolverRunnerConfig, type SolverProgress } from './runner';
@@ -10,7 +10,7 @@ export interface SolverRunnerConfig {
 	maxMemoryMb?: number;
 }
 
-export type WorkerMessageType = 'init' | 'solve' | 'info';
+export type WorkerMessageType = 'init' | 'solve' | 'info' | 'cancel';
 
 export interface WorkerRequest {
     id: number;
@@ -26,9 +26,20 @@ export interface WorkerRequest {
         goals?: Uint32Array;
         algorithm?: string;
         heuristic?: string;
+        /** Time budget passed to component solvers (WIT `deadline-ms`) */
+        deadlineMs?: number;
+        /** Ask the worker to forward solver progress while solving */
+        reportProgress?: boolean;
     };
 }
 
+/** Partial statistics reported while a solve is running. */
+export interface SolverProgress {
+	nodesExpanded: number;
+	/** Lower bound on the optimal cost found so far */
+	bestCost: number;
+}
+
 export interface WorkerResponse {
 	id: number;
 	success: boolean;
@@ -37,8 +48,12 @@ export interface WorkerResponse {
 		paths?: Path[];
 		cost?: number;
 		nodesExpanded?: number;
+		nodesGenerated?: number;
+		lowLevelCalls?: number;
 		timeUs?: number;
 	};
+	/** Set on progress updates, which precede the final response */
+	progress?: SolverProgress;
 	error?: string;
 }
 
@@ -51,9 +66,15 @@ export class SolverRunner {
 	private requestId = 0;
 	private pendingRequests = new Map<
 		number,
-		{ resolve: (value: WorkerResponse) => void; reject: (error: Error) => void }
+		{
+			resolve: (value: WorkerResponse) => void;
+			reject: (error: Error) => void;
+			onProgress?: (progress: SolverProgress) => void;
+		}
 	>();
 	private running = false;
+	/** Id of the solve request in flight, for cancel() */
+	private solveId: number | null = null;
 
 	constructor(config: SolverRunnerConfig = {}) {
 		this.config = {
@@ -75,9 +96,11 @@ export class SolverRunner {
 		});
 
 		this.worker.onmessage = (event: MessageEvent<WorkerResponse>) => {
-			const { id, success, data, error } = event.data;
+			const { id, success, data, error, progress } = event.data;
 			const pending = this.pendingRequests.get(id);
-			if (pending) {
+			if (pending && progress) {
+				pending.onProgress?.(progress);
+			} else if (pending) {
 				this.pendingRequests.delete(id);
 				if (success) {
 					pending.resolve(event.data);
@@ -125,7 +148,11 @@ export class SolverRunner {
 		map: GridMap, 
 		starts: Coordinate[], 
 		goals: Coordinate[],
-		options: { algorithm?: string; heuristic?: string } = {}
+		options: {
+			algorithm?: string;
+			heuristic?: string;
+			onProgress?: (progress: SolverProgress) => void;
+		} = {}
 	): Promise<SolverResult> {
 		if (!this.worker) {
 			return { status: 'error', error: 'Solver not initialized' };
@@ -149,8 +176,10 @@ export class SolverRunner {
 				starts: SolverRunner.flattenCoordinates(starts),
 				goals: SolverRunner.flattenCoordinates(goals),
 				algorithm: options.algorithm,
-				heuristic: options.heuristic
-			});
+				heuristic: options.heuristic,
+				deadlineMs: this.config.timeoutMs,
+				reportProgress: options.onProgress !== undefined
+			}, options.onProgress);
 
 			const endTime = performance.now();
 			// Paths come pre-parsed from worker
@@ -163,7 +192,10 @@ export class SolverRunner {
 
 			const stats: SolverStats = {
 				nodesExpanded: response.data?.nodesExpanded ?? 0,
-				timeUs: Math.round((endTime - startTime) * 1000)
+				nodesGenerated: response.data?.nodesGenerated,
+				lowLevelCalls: response.data?.lowLevelCalls,
+				// Prefer the solver's own measurement over the round trip
+				timeUs: response.data?.timeUs ?? Math.round((endTime - startTime) * 1000)
 			};
 
 			// Validate the solution
@@ -187,9 +219,24 @@ export class SolverRunner {
 			if (e instanceof Error && e.message.includes('timeout')) {
 				return { status: 'timeout', error: 'Solver timed out' };
 			}
+			if (e instanceof Error && e.message.includes('cancelled')) {
+				return { status: 'cancelled', error: 'Solve cancelled' };
+			}
 			return { status: 'error', error: e instanceof Error ? e.message : 'Solve failed' };
 		} finally {
 			this.running = false;
+			this.solveId = null;
+		}
+	}
+
+	/**
+	 * Cancel the running solve; its solve() call resolves with status
+	 * 'cancelled'. The worker checks for cancellation between search slices.
+	 */
+	cancel(): void {
+		if (this.worker && this.solveId !== null) {
+			const request: WorkerRequest = { id: this.solveId, type: 'cancel' };
+			this.worker.postMessage(request);
 		}
 	}
 
@@ -218,7 +265,8 @@ export class SolverRunner {
 
 	private sendRequest(
 		type: WorkerMessageType,
-		payload?: WorkerRequest['payload']
+		payload?: WorkerRequest['payload'],
+		onProgress?: (progress: SolverProgress) => void
 	): Promise<WorkerResponse> {
 		return new Promise((resolve, reject) => {
 			if (!this.worker) {
@@ -227,7 +275,10 @@ export class SolverRunner {
 			}
 
 			const id = ++this.requestId;
-			this.pendingRequests.set(id, { resolve, reject });
+			this.pendingRequests.set(id, { resolve, reject, onProgress });
+			if (type === 'solve') {
+				this.solveId = id;
+			}
 
 			const request: WorkerRequest = { id, type, payload };
 			this.worker.postMessage(request);
@@ -236,7 +287,8 @@ export class SolverRunner {
 
 	private sendRequestWithTimeout(
 		type: WorkerMessageType,
-		payload?: WorkerRequest['payload']
+		payload?: WorkerRequest['payload'],
+		onProgress?: (progress: SolverProgress) => void
 	): Promise<WorkerResponse> {
 		return new Promise((resolve, reject) => {
 			const timeout = setTimeout(() => {
@@ -246,7 +298,7 @@ export class SolverRunner {
 				reject(new Error('Request timeout'));
 			}, this.config.timeoutMs);
 
-			this.sendRequest(type, payload)
+			this.sendRequest(type, payload, onProgress)
 				.then((response) => {
 					clearTimeout(timeout);
 					resolve(response);
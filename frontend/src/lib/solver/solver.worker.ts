@@ -8,7 +8,7 @@ interface Coordinate {
 
 interface WorkerRequest {
 	id: number;
-	type: 'init' | 'solve' | 'info';
+	type: 'init' | 'solve' | 'info' | 'cancel';
 	payload?: {
 		mode?: 'bindgen' | 'component';
 		wasmUrl?: string;
@@ -20,9 +20,16 @@ interface WorkerRequest {
 		goals?: Uint32Array;   // Flattened: [x1, y1, x2, y2, ...]
 		algorithm?: string;
 		heuristic?: string;
+		deadlineMs?: number;
+		reportProgress?: boolean;
 	};
 }
 
+interface SolverProgress {
+	nodesExpanded: number;
+	bestCost: number;
+}
+
 interface WorkerResponse {
 	id: number;
 	success: boolean;
@@ -31,22 +38,56 @@ interface WorkerResponse {
 		paths?: { steps: Coordinate[] }[];
 		cost?: number;
 		nodesExpanded?: number;
+		nodesGenerated?: number;
+		lowLevelCalls?: number;
 		timeUs?: number;
 	};
+	progress?: SolverProgress;
 	error?: string;
 }
 
 // WASM Solution interface
 interface WasmSolution {
 	paths: Uint32Array;
+	paths_len: number;
+	write_paths(target: Uint32Array, offset: number): number;
+	paths_view(): Uint32Array;
 	cost: number;
 	nodes_expanded: bigint;
+	nodes_generated: bigint;
+	low_level_calls: bigint;
+	time_us: bigint;
+	free(): void;
+}
+
+// Options accepted by MapfSolver.solve
+interface SolveOptions {
+	algorithm?: 'prioritized' | 'sipp' | 'push-and-rotate' | 'centralized' | 'cbs' | 'ecbs' | 'id';
+	objective?: 'sum-of-costs' | 'makespan';
+	/** Diagonal moves too with 'octile'; the arena only accepts 'cardinal' */
+	movement?: 'cardinal' | 'octile';
+	/** Bound on cost over the optimum for 'ecbs' and 'id', at least 1 */
+	suboptimality?: number;
+	/** Heuristic weight for 'prioritized' and 'sipp', at least 1 */
+	weight?: number;
+	timeBudgetMs?: number;
+	onProgress?: (progress: SolverProgress) => void;
+	progressInterval?: number;
+}
+
+// Solve in progress, advanced with step()
+interface SolveTask {
+	step(maxExpansions: number): WasmSolution | undefined;
 	free(): void;
 }
 
 // MapfSolver class from WASM (new pattern)
 interface MapfSolver {
-	solve(starts: Uint32Array, goals: Uint32Array): WasmSolution;
+	solve(starts: Uint32Array, goals: Uint32Array, options?: SolveOptions): WasmSolution;
+	start(starts: Uint32Array, goals: Uint32Array, options?: SolveOptions): SolveTask;
+	set_cell(x: number, y: number, passable: boolean): void;
+	set_costs(costs: Uint32Array): void;
+	toggle_obstacle(x: number, y: number): boolean;
 	width: number;
 	height: number;
 	free(): void;
@@ -60,8 +101,9 @@ interface MapfSolverConstructor {
 // WASM module interface
 interface WasmModule {
 	MapfSolver?: MapfSolverConstructor; // bindgen
-	solve?: (mapData: Uint8Array, width: number, height: number, starts: any, goals: any) => any; // component
+	solve?: (mapData: Uint8Array, width: number, height: number, starts: any, goals: any, deadlineMs: bigint) => any; // component
 	solver_info?: () => string; // bindgen
+	score_solution?: (mapData: Uint8Array, width: number, height: number, paths: Uint32Array) => { cost: number; makespan: number }; // bindgen
 	info?: () => string; // component
 	get_stats?: () => any; // component optional
 }
@@ -69,8 +111,17 @@ interface WasmModule {
 let wasmModule: WasmModule | null = null;
 let currentSolver: MapfSolver | null = null;
 let currentMapHash: string | null = null;
+// Tiles the current solver was built with (or edited to)
+let currentMapData: Uint8Array | null = null;
 let solverMode: 'bindgen' | 'component' = 'bindgen';
 
+// Expansions per slice of a chunked solve; between slices the worker
+// handles messages, so cancel requests get through
+const EXPANSIONS_PER_STEP = 5000;
+
+// Ids of solve requests cancelled while running
+const cancelledSolves = new Set<number>();
+
 /**
  * Handle messages from the main thread.
  */
@@ -88,6 +139,10 @@ self.onmessage = async (event: MessageEvent<WorkerRequest>) => {
 			case 'info':
 				handleInfo(id);
 				break;
+			case 'cancel':
+				// Picked up by the running solve between slices
+				cancelledSolves.add(id);
+				break;
 			default:
 				sendError(id, `Unknown message type: ${type}`);
 		}
@@ -201,13 +256,13 @@ async function handleSolve(
 		throw new Error('Missing required solve parameters');
 	}
 
-	const { mapData, width, height, starts, goals, algorithm, heuristic } = payload;
+	const { mapData, width, height, starts, goals, algorithm, heuristic, deadlineMs, reportProgress } = payload;
 
 	if (!starts || !goals) {
 		throw new Error('Missing starts or goals');
 	}
 
-	// TODO: Pass algorithm and heuristic to WASM when supported
+	// TODO: Map the arena's algorithm and heuristic choices onto solve options
 	// console.log(`Solving with ${algorithm} using ${heuristic}`);
 
 	// starts and goals are already Uint32Array from runner (flattened [x1, y1, x2, y2, ...])
@@ -217,6 +272,10 @@ async function handleSolve(
 	let paths: { steps: Coordinate[] }[] = [];
 	let cost = 0;
 	let nodesExpanded = 0;
+	let nodesGenerated: number | undefined;
+	let lowLevelCalls: number | undefined;
+	// Time reported by the solver, excluding pauses between solve steps
+	let solverTimeUs: number | undefined;
 
 	if (solverMode === 'bindgen') {
 		if (!wasmModule.MapfSolver) throw new Error('MapfSolver class not found in WASM module');
@@ -226,31 +285,58 @@ async function handleSolve(
 		// Create a hash of the map to detect changes
 		const mapHash = `${width}x${height}:${mapData.length}`;
 		
-		// Reuse solver if same map, otherwise create new one
-		if (currentMapHash !== mapHash || !currentSolver) {
+		// Reuse solver if same map size, otherwise create new one
+		if (currentMapHash !== mapHash || !currentSolver || !currentMapData) {
 			if (currentSolver) {
 				currentSolver.free();
 			}
 			currentSolver = new SolverClass(mapData, width, height);
 			currentMapHash = mapHash;
+			currentMapData = mapData.slice();
+		} else {
+			// Apply editor changes in place instead of rebuilding the solver
+			for (let i = 0; i < mapData.length; i++) {
+				if (mapData[i] !== currentMapData[i]) {
+					currentSolver.set_cell(i % width, Math.floor(i / width), mapData[i] !== 0);
+					currentMapData[i] = mapData[i];
+				}
+			}
 		}
 
 		// Call the WASM solver with pre-flattened Uint32Array coordinates
-		const solution = currentSolver!.solve(starts, goals);
+		const task = currentSolver!.start(starts, goals, {
+			timeBudgetMs: deadlineMs,
+			onProgress: reportProgress ? (progress) => sendProgress(id, progress) : undefined
+		});
+		let solution: WasmSolution | undefined;
+		try {
+			while (!(solution = task.step(EXPANSIONS_PER_STEP))) {
+				// Yield so queued messages (e.g. cancel) are handled
+				await new Promise((resolve) => setTimeout(resolve, 0));
+				if (cancelledSolves.delete(id)) {
+					throw new Error('Solve cancelled');
+				}
+			}
+		} finally {
+			cancelledSolves.delete(id);
+			task.free();
+		}
 		
-		paths = parseFlattenedPaths(solution.paths);
+		// Read straight from WASM memory; the view dies with the next WASM call
+		paths = parseFlattenedPaths(solution.paths_view());
 		
-		// Use makespan as cost
-		cost = paths.reduce((max, path) => {
-			const pathCost = Math.max(0, path.steps.length - 1);
-			return Math.max(max, pathCost);
-		}, 0);
+		// Score with the leaderboard's rules, so the cost shown matches a submission's
+		if (!wasmModule.score_solution) throw new Error('score_solution not found in WASM module');
+		cost = wasmModule.score_solution(mapData, width, height, solution.paths).cost;
 		
 		nodesExpanded = Number(solution.nodes_expanded);
+		nodesGenerated = Number(solution.nodes_generated);
+		lowLevelCalls = Number(solution.low_level_calls);
+		solverTimeUs = Number(solution.time_us);
 		solution.free();
 	} else {
 		// Component Mode (JCO)
-		// Component expects: solve(mapData, width, height, starts, goals)
+		// Component expects: solve(mapData, width, height, starts, goals, deadlineMs)
 		// Coordinates need to be unflattened for Component Model!
 		// WIT: solve(..., starts: list<coordinate>, goals: list<coordinate>)
 		if (!wasmModule.solve) throw new Error('solve function not found in Component module');
@@ -259,7 +345,15 @@ async function handleSolve(
 		const goalCoords = unflattenCoords(goals);
 
 		// JCO handles the Uint8Array -> list<u8> conversion automatically
-		const result = wasmModule.solve(mapData, width, height, startCoords, goalCoords);
+		// u64 maps to BigInt in JCO
+		const result = wasmModule.solve(
+			mapData,
+			width,
+			height,
+			startCoords,
+			goalCoords,
+			BigInt(deadlineMs ?? 30000)
+		);
 		
 		if (typeof result === 'string') {
 			throw new Error(result); // Result is Result<Solution, string>, JCO might map Err to throw or return object
@@ -298,6 +392,8 @@ async function handleSolve(
 			const stats = wasmModule.get_stats ? wasmModule.get_stats() : null;
 			if (stats) {
 				nodesExpanded = Number(stats['nodes-expanded']);
+				nodesGenerated = Number(stats['nodes-generated']);
+				solverTimeUs = Number(stats['time-us']);
 			}
 		} else {
 			// Direct return (if no error variant used, but we used result<...>)
@@ -313,7 +409,9 @@ async function handleSolve(
 		paths,
 		cost,
 		nodesExpanded,
-		timeUs: Math.round((endTime - startTime) * 1000)
+		nodesGenerated,
+		lowLevelCalls,
+		timeUs: solverTimeUs ?? Math.round((endTime - startTime) * 1000)
 	});
 }
 
@@ -375,6 +473,14 @@ function sendResponse(id: number, data: WorkerResponse['data']): void {
 	self.postMessage(response);
 }
 
+/**
+ * Send a progress update for a running solve; the request stays pending.
+ */
+function sendProgress(id: number, progress: SolverProgress): void {
+	const response: WorkerResponse = { id, success: true, progress };
+	self.postMessage(response);
+}
+
 /**
  * Send an error response.
  */
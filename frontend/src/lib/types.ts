@@ -20,6 +20,9 @@ export interface Solution {
 /** Statistics from a solver run */
 export interface SolverStats {
 	nodesExpanded: number;
+	nodesGenerated?: number;
+	/** Single-agent searches run, for solvers built on them */
+	lowLevelCalls?: number;
 	timeUs: number;
 }
 
@@ -42,6 +45,14 @@ export interface ScenarioEntry {
 	goalX: number;
 	goalY: number;
 	optimalLength: number;
+	/** Timestep the agent appears at (optional tenth column, default 0) */
+	startTime: number;
+	/** Agent name (`id=` annotation column) */
+	id?: string;
+	/** Group or team (`group=` annotation column) */
+	group?: string;
+	/** Planning priority, higher first (`priority=` annotation column) */
+	priority?: number;
 }
 
 /** A parsed MovingAI scenario */
@@ -51,7 +62,7 @@ export interface Scenario {
 }
 
 /** Solver status during execution */
-export type SolverStatus = 'idle' | 'running' | 'success' | 'error' | 'timeout';
+export type SolverStatus = 'idle' | 'running' | 'success' | 'error' | 'timeout' | 'cancelled';
 
 /** Result from running a solver */
 export interface SolverResult {
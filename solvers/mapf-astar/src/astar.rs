@@ -1,62 +1,189 @@
 //! A* pathfinding implementation with multi-agent support.
 //!
-//! Uses prioritized step-by-step planning for multi-agent pathfinding (MAPF):
-//! - At each timestep, agents choose actions in priority order
-//! - If an action causes a conflict, the agent tries alternatives
+//! Uses prioritized planning for multi-agent pathfinding (MAPF):
+//! - Agents plan whole paths one after another, in priority order
+//! - Each plans with space-time A* around a reservation table of the paths
+//!   planned before it
 //! - This avoids vertex collisions (same cell at same time)
 //! - This avoids edge collisions (agents swapping positions)
 
 /// Centralized A* MAPF solver using Grid
 pub fn solve_mapf_centralized_grid(
     grid: &Grid,
-    agents: &[((u32, u32), (u32, u32))],
+    agents: &[AgentTask],
 ) -> Option<Vec<Path>> {
-    let num_agents = agents.len();
-    let starts: Vec<Coordinate> = agents.iter().map(|&((sx, sy), _)| Coordinate { x: sx, y: sy }).collect();
-    let goals: Vec<Coordinate> = agents.iter().map(|&(_, (gx, gy))| Coordinate { x: gx, y: gy }).collect();
-
-    // Initial state
-    let start_state = GlobalState {
-        positions: starts.clone(),
-        paths: starts.iter().map(|&p| vec![p]).collect(),
-        cost: 0,
-        timestep: 0,
-        goals: goals.clone(),
-    };
+    solve_mapf_centralized_grid_with(grid, agents, Objective::SumOfCosts, |_| false)
+}
+
+/// Centralized A* MAPF solver using Grid, optimizing `objective`.
+///
+/// `should_stop` is called with the search progress before every expansion;
+/// returning `true` aborts the search with `None`, e.g. once a time budget
+/// runs out.
+pub fn solve_mapf_centralized_grid_with(
+    grid: &Grid,
+    agents: &[AgentTask],
+    objective: Objective,
+    should_stop: impl FnMut(SearchProgress) -> bool,
+) -> Option<Vec<Path>> {
+    match CentralizedSearch::new(grid, agents, objective).run(should_stop) {
+        SearchStatus::Solved(paths) => Some(paths),
+        SearchStatus::Running | SearchStatus::Exhausted => None,
+    }
+}
+
+/// Where a [`CentralizedSearch`] stands after a call to `run` or `step`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchStatus {
+    /// Stopped early; calling `run` or `step` again resumes the search
+    Running,
+    /// Found collision-free paths for all agents
+    Solved(Vec<Path>),
+    /// The whole state space was searched without reaching the goals
+    Exhausted,
+}
+
+/// Centralized A* MAPF search that can be paused and resumed, so callers
+/// can spread a long search over several slices of work.
+pub struct CentralizedSearch {
+    grid: Grid,
+    open: BinaryHeap<GlobalState>, // Priority queue (min-heap)
+    best_cost: HashMap<StateKey, u32>, // Cheapest cost each state was generated with
+    closed: HashSet<StateKey>, // States already expanded
+    stats: SearchStats,
+}
+
+/// Identity of a centralized search state: positions, deferred goal waits
+/// and timestep
+type StateKey = (Vec<Coordinate>, Vec<u32>, u32);
+
+impl CentralizedSearch {
+    /// Set up a search for `agents` on `grid`, optimizing `objective`.
+    pub fn new(grid: &Grid, agents: &[AgentTask], objective: Objective) -> Self {
+        let starts: Vec<Coordinate> = agents.iter().map(|&((sx, sy), _)| Coordinate { x: sx, y: sy }).collect();
+        let goals: Vec<Coordinate> = agents.iter().map(|&(_, (gx, gy))| Coordinate { x: gx, y: gy }).collect();
 
-    // Priority queue (min-heap)
-    let mut open = BinaryHeap::new();
-    open.push(start_state);
+        // Initial state
+        let start_state = GlobalState {
+            paths: starts.iter().map(|&p| vec![p]).collect(),
+            positions: starts,
+            cost: 0,
+            deferred: vec![0; agents.len()],
+            timestep: 0,
+            distances: goal_distances(grid, &goals, objective),
+            goals,
+            objective,
+            width: grid.width,
+        };
 
-    // Visited set: (positions, timestep)
-    let mut visited: HashSet<(Vec<Coordinate>, u32)> = HashSet::new();
-    visited.insert((starts.clone(), 0));
+        let mut best_cost = HashMap::new();
+        best_cost.insert(start_state.key(), 0);
+        let mut open = BinaryHeap::new();
+        open.push(start_state);
 
-    // Main search loop
-    while let Some(state) = open.pop() {
-        // Check if all agents reached their goals
-        if state.positions.iter().zip(state.goals.iter()).all(|(p, g)| p == g) {
-            // Return solution paths
-            return Some(state.paths.into_iter().map(|steps| Path { steps }).collect());
+        Self {
+            grid: grid.clone(),
+            open,
+            best_cost,
+            closed: HashSet::new(),
+            // The heuristic's distance maps, one single-agent search each
+            stats: SearchStats {
+                low_level_calls: agents.len() as u64,
+                ..SearchStats::default()
+            },
         }
+    }
+
+    /// Nodes expanded so far.
+    pub fn nodes_expanded(&self) -> u64 {
+        self.stats.nodes_expanded
+    }
+
+    /// Search effort so far.
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    /// Expand at most `max_expansions` more nodes.
+    pub fn step(&mut self, max_expansions: u64) -> SearchStatus {
+        let limit = self.stats.nodes_expanded.saturating_add(max_expansions);
+        self.run(|progress| progress.nodes_expanded > limit)
+    }
+
+    /// Search until solved, exhausted, or `should_stop` returns `true` for
+    /// the node about to be expanded; that node stays queued, so the search
+    /// can be resumed.
+    pub fn run(&mut self, mut should_stop: impl FnMut(SearchProgress) -> bool) -> SearchStatus {
+        // Main search loop
+        while let Some(next) = self.open.peek() {
+            let progress = SearchProgress {
+                nodes_expanded: self.stats.nodes_expanded + 1,
+                best_cost: next.f_cost(),
+            };
+            if should_stop(progress) {
+                return SearchStatus::Running;
+            }
+
+            let state = self.open.pop().expect("peeked above");
+            // Step costs differ, so a state may be queued again more cheaply
+            // before its first copy is expanded
+            if !self.closed.insert(state.key()) {
+                continue;
+            }
+            self.stats.nodes_expanded += 1;
+
+            // Check if all agents reached their goals
+            if state.positions.iter().zip(state.goals.iter()).all(|(p, g)| p == g) {
+                // Return solution paths; agents that disappear at their goal
+                // stop at their first arrival
+                let mut paths = state.paths;
+                if self.grid.disappears_at_goal() {
+                    for (steps, goal) in paths.iter_mut().zip(state.goals.iter()) {
+                        let arrival = steps.iter().position(|c| c == goal).expect("at its goal");
+                        steps.truncate(arrival + 1);
+                    }
+                }
+                return SearchStatus::Solved(paths.into_iter().map(|steps| Path { steps }).collect());
+            }
+
+            self.expand(&state);
+        }
+        SearchStatus::Exhausted
+    }
+
+    /// Push every conflict-free successor of `state` that is cheaper than
+    /// any copy of it generated before.
+    fn expand(&mut self, state: &GlobalState) {
+        let num_agents = state.positions.len();
+        // Agents that disappear at their goal are off the map once there
+        let gone: Vec<bool> = state
+            .positions
+            .iter()
+            .zip(state.goals.iter())
+            .map(|(p, g)| self.grid.disappears_at_goal() && p == g)
+            .collect();
 
         // Generate all possible moves for each agent (including wait)
         let mut moves_per_agent: Vec<Vec<Coordinate>> = Vec::with_capacity(num_agents);
-        for i in 0..num_agents {
+        for (&position, &gone) in state.positions.iter().zip(&gone) {
+            if gone {
+                moves_per_agent.push(vec![position]);
+                continue;
+            }
             let mut moves = Vec::new();
-            // Cardinal moves
-            for (neighbor, _) in neighbors_grid(state.positions[i], grid) {
+            // Moves to neighboring cells
+            for &(neighbor, _) in neighbors_grid(position, &self.grid).iter() {
                 moves.push(neighbor);
             }
             // Wait (NoOp)
-            moves.push(state.positions[i]);
+            moves.push(position);
             moves_per_agent.push(moves);
         }
 
         // Generate all joint moves (cartesian product)
         let mut joint_moves = vec![];
         fn backtrack(
-            moves_per_agent: &Vec<Vec<Coordinate>>,
+            moves_per_agent: &[Vec<Coordinate>],
             current: &mut Vec<Coordinate>,
             idx: usize,
             joint_moves: &mut Vec<Vec<Coordinate>>,
@@ -77,13 +204,17 @@ pub fn solve_mapf_centralized_grid(
         for next_positions in joint_moves {
             // Vertex conflict: two agents in same cell
             let mut unique = HashSet::new();
-            if !next_positions.iter().all(|p| unique.insert(*p)) {
+            let present = next_positions.iter().zip(&gone).filter(|(_, &g)| !g);
+            if !present.map(|(p, _)| p).all(|p| unique.insert(*p)) {
                 continue; // skip joint move with vertex conflict
             }
             // Edge conflict: agents swap positions
             let mut edge_conflict = false;
             for i in 0..num_agents {
                 for j in (i+1)..num_agents {
+                    if gone[i] || gone[j] {
+                        continue;
+                    }
                     if state.positions[i] == next_positions[j] && state.positions[j] == next_positions[i] {
                         edge_conflict = true;
                         break;
@@ -93,12 +224,24 @@ pub fn solve_mapf_centralized_grid(
             }
             if edge_conflict { continue; }
 
-            // Check if already visited
-            let visit_key = (next_positions.clone(), state.timestep + 1);
-            if visited.contains(&visit_key) {
+            let (step_cost, deferred) =
+                state.step_cost(&next_positions, |c| self.grid.cell_cost(c.x, c.y));
+            let new_cost = state.cost + step_cost;
+
+            // Skip states already reached at no higher cost
+            let key = (next_positions.clone(), deferred.clone(), state.timestep + 1);
+            if self.closed.contains(&key) {
                 continue;
             }
-            visited.insert(visit_key);
+            match self.best_cost.entry(key) {
+                Entry::Occupied(entry) if *entry.get() <= new_cost => continue,
+                Entry::Occupied(mut entry) => {
+                    entry.insert(new_cost);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(new_cost);
+                }
+            }
 
             // Build new paths
             let mut new_paths = state.paths.clone();
@@ -106,29 +249,49 @@ pub fn solve_mapf_centralized_grid(
                 new_paths[i].push(next_positions[i]);
             }
 
-            // Cost: +1 per agent move (wait counts as move)
-            let new_cost = state.cost + 1;
             let new_state = GlobalState {
                 positions: next_positions,
                 paths: new_paths,
                 cost: new_cost,
+                deferred,
                 timestep: state.timestep + 1,
                 goals: state.goals.clone(),
+                objective: state.objective,
+                distances: state.distances.clone(),
+                width: state.width,
             };
-            open.push(new_state);
+            self.open.push(new_state);
+            self.stats.nodes_generated += 1;
         }
     }
-    None
 }
 
-use std::cmp::Ordering;
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::bucket_queue::BucketQueue;
+
+/// A (start, goal) pair of (x, y) positions for one agent.
+pub(crate) type AgentTask = ((u32, u32), (u32, u32));
 
-/// A 2D coordinate.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Coordinate {
-    pub x: u32,
-    pub y: u32,
+pub use mapf_core::Coordinate;
+use mapf_core::GridMap;
+
+/// How agents may move between cells in one timestep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Movement {
+    /// North, south, east or west; the arena's rules
+    #[default]
+    Cardinal,
+    /// Also diagonally, as on MovingAI `octile` maps, but without cutting
+    /// corners: both cells beside a diagonal move must be passable. Every
+    /// move still takes one timestep.
+    Octile,
 }
 
 /// A grid map for pathfinding.
@@ -139,17 +302,211 @@ pub struct Grid {
     data: Vec<u8>,
     pub width: u32,
     pub height: u32,
+    movement: Movement,
+    /// Entry cost of each cell (row-major), or `None` if every action costs 1
+    costs: Option<Vec<u32>>,
+    /// Lowest entry cost, scaling the heuristics so they stay admissible
+    min_cost: u32,
+    /// Distance maps computed so far, per goal
+    heuristics: HeuristicCache,
+    /// Every cell's neighbors, if precomputed with `with_neighbor_table`
+    adjacency: Option<Adjacency>,
+    /// Whether agents leave the map once their path ends
+    disappear_at_goal: bool,
+}
+
+/// Every cell's neighbors and the costs of moving there, in one array
+/// (compressed sparse rows): those of cell `i` (row-major) are
+/// `neighbors[offsets[i]..offsets[i + 1]]`.
+#[derive(Debug, Clone)]
+struct Adjacency {
+    offsets: Vec<u32>,
+    neighbors: Vec<(Coordinate, u32)>,
+}
+
+impl Adjacency {
+    fn build(grid: &Grid) -> Self {
+        let mut offsets = Vec::with_capacity(grid.data.len() + 1);
+        let mut neighbors = Vec::new();
+        offsets.push(0);
+        for y in 0..grid.height {
+            for x in 0..grid.width {
+                neighbors.extend(open_neighbors(Coordinate { x, y }, grid));
+                offsets.push(neighbors.len() as u32);
+            }
+        }
+        Self { offsets, neighbors }
+    }
+
+    fn of(&self, cell: usize) -> &[(Coordinate, u32)] {
+        &self.neighbors[self.offsets[cell] as usize..self.offsets[cell + 1] as usize]
+    }
+}
+
+/// Exact distance maps per goal, shared between clones of a grid until one
+/// of them changes.
+#[derive(Clone, Default)]
+struct HeuristicCache(Arc<Mutex<HashMap<Coordinate, Arc<[u32]>>>>);
+
+impl HeuristicCache {
+    fn lock(&self) -> MutexGuard<'_, HashMap<Coordinate, Arc<[u32]>>> {
+        // The maps are only ever inserted whole, so a panic can't leave
+        // one half-written
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for HeuristicCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeuristicCache")
+            .field("goals", &self.lock().len())
+            .finish()
+    }
 }
 
 impl Grid {
-    /// Create a new Grid from raw map data.
+    /// Create a new Grid from raw map data, with cardinal movement.
     pub fn from_raw(map_data: &[u8], width: u32, height: u32) -> Self {
         Self {
             data: map_data.to_vec(),
             width,
             height,
+            movement: Movement::Cardinal,
+            costs: None,
+            min_cost: 1,
+            heuristics: HeuristicCache::default(),
+            adjacency: None,
+            disappear_at_goal: false,
         }
     }
+
+    /// Create a Grid from a parsed map, with cardinal movement and the
+    /// map's terrain costs, if it has any.
+    pub fn from_map(map: &GridMap) -> Self {
+        let grid = Self::from_raw(&map.to_bytes(), map.width(), map.height());
+        match map.costs() {
+            Some(costs) => grid
+                .with_costs(costs.to_vec())
+                .expect("map costs match its size"),
+            None => grid,
+        }
+    }
+
+    /// Attach per-cell entry costs (row-major, one per cell): every action,
+    /// move or wait, then costs the entry cost of the cell it ends on, and
+    /// every search minimizes that instead of timesteps. Returns `None` if
+    /// the length doesn't match.
+    pub fn with_costs(mut self, costs: Vec<u32>) -> Option<Self> {
+        if costs.len() != self.data.len() {
+            return None;
+        }
+        self.min_cost = costs.iter().copied().min().unwrap_or(1);
+        self.costs = Some(costs);
+        self.changed();
+        Some(self)
+    }
+
+    /// Whether the grid carries terrain costs.
+    pub fn is_weighted(&self) -> bool {
+        self.costs.is_some()
+    }
+
+    /// Cost of an action ending on (x, y): the cell's entry cost, or 1 on
+    /// unweighted grids and out of bounds.
+    #[inline]
+    pub fn cell_cost(&self, x: u32, y: u32) -> u32 {
+        match &self.costs {
+            Some(costs) if self.in_bounds(x, y) => costs[(y * self.width + x) as usize],
+            _ => 1,
+        }
+    }
+
+    /// Weighted cost of `path` on this grid: the sum of the costs of its
+    /// actions. Equals [`Path::cost`] on unweighted grids.
+    pub fn path_cost(&self, path: &Path) -> u32 {
+        path.steps.iter().skip(1).map(|c| self.cell_cost(c.x, c.y)).sum()
+    }
+
+    /// Lowest cost any action can have.
+    pub(crate) fn min_cost(&self) -> u32 {
+        self.min_cost
+    }
+
+    /// This grid with every action costing 1 again.
+    pub(crate) fn unweighted(&self) -> Self {
+        let mut grid = Self {
+            costs: None,
+            min_cost: 1,
+            ..self.clone()
+        };
+        grid.changed();
+        grid
+    }
+
+    /// Let every search on this grid move as `movement` allows.
+    pub fn with_movement(mut self, movement: Movement) -> Self {
+        self.movement = movement;
+        self.changed();
+        self
+    }
+
+    /// Precompute every cell's neighbors once, so searches read them from
+    /// a table instead of checking bounds and passability on every
+    /// expansion. Worth it for grids that are searched many times; takes
+    /// up to 8 entries of memory per cell, and is rebuilt whenever the
+    /// grid changes.
+    pub fn with_neighbor_table(mut self) -> Self {
+        self.adjacency = Some(Adjacency::build(&self));
+        self
+    }
+
+    /// Drop or rebuild everything derived from the cells, costs and
+    /// movement after one of them changed.
+    fn changed(&mut self) {
+        self.heuristics = HeuristicCache::default();
+        if self.adjacency.is_some() {
+            self.adjacency = Some(Adjacency::build(self));
+        }
+    }
+
+    /// How agents move on this grid.
+    pub fn movement(&self) -> Movement {
+        self.movement
+    }
+
+    /// Let agents vanish once they reach their goal, so they no longer
+    /// block anyone, instead of staying there for good (the default, and
+    /// the arena's rules). Every search on this grid then ends each path
+    /// at its agent's first arrival, and only checks collisions until then.
+    pub fn with_disappear_at_goal(mut self, disappear_at_goal: bool) -> Self {
+        self.disappear_at_goal = disappear_at_goal;
+        self
+    }
+
+    /// Whether agents vanish once they reach their goal.
+    pub fn disappears_at_goal(&self) -> bool {
+        self.disappear_at_goal
+    }
+
+    /// Exact distances from every cell to `goal` (row-major): the fewest
+    /// moves, or on weighted grids the cheapest sum of entry costs;
+    /// `u32::MAX` where it can't be reached. Computed by a backward search
+    /// the first time a goal is asked for and cached until the grid
+    /// changes, so every search on maze-like maps gets a perfect heuristic
+    /// instead of the Manhattan distance.
+    pub fn heuristic_for(&self, goal: Coordinate) -> Arc<[u32]> {
+        if let Some(distances) = self.heuristics.lock().get(&goal) {
+            return distances.clone();
+        }
+        // Search without holding the lock; a concurrent caller may have
+        // filled in the same map meanwhile, which is harmless
+        let distances: Arc<[u32]> = distances_to(self, goal).into();
+        self.heuristics
+            .lock()
+            .entry(goal)
+            .or_insert(distances)
+            .clone()
+    }
     
     /// Check if a coordinate is passable.
     #[inline]
@@ -171,6 +528,18 @@ impl Grid {
     pub fn data(&self) -> &[u8] {
         &self.data
     }
+    
+    /// Make a cell passable or blocked.
+    /// Returns `false` (and changes nothing) if the cell is out of bounds.
+    pub fn set_passable(&mut self, x: u32, y: u32, passable: bool) -> bool {
+        if !self.in_bounds(x, y) {
+            return false;
+        }
+        let idx = (y * self.width + x) as usize;
+        self.data[idx] = passable as u8;
+        self.changed();
+        true
+    }
 }
 
 /// A path from start to goal.
@@ -207,46 +576,47 @@ impl Path {
         }
         true
     }
-}
-
-/// Node for A* priority queue.
-#[derive(Clone, Eq, PartialEq)]
-struct Node {
-    coord: Coordinate,
-    g_cost: u32, // cost from start
-    f_cost: u32, // g + heuristic
-}
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Reverse for min-heap
-        other.f_cost.cmp(&self.f_cost)
-            .then_with(|| other.g_cost.cmp(&self.g_cost))
+    /// Validate that every step moves to one of the eight surrounding cells
+    /// or waits. Corners are checked against the map by [`Path::is_valid_on`].
+    pub fn is_valid_octile(&self) -> bool {
+        self.steps
+            .windows(2)
+            .all(|step| step[0].x.abs_diff(step[1].x) <= 1 && step[0].y.abs_diff(step[1].y) <= 1)
     }
-}
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Validate that the path stays on passable cells of `grid` and only
+    /// makes moves its [`Movement`] allows, or waits.
+    pub fn is_valid_on(&self, grid: &Grid) -> bool {
+        self.steps.iter().all(|c| grid.is_passable(c.x, c.y))
+            && self.steps.windows(2).all(|step| {
+                step[0] == step[1]
+                    || neighbors_grid(step[0], grid).iter().any(|&(next, _)| next == step[1])
+            })
     }
 }
 
-/// Manhattan distance heuristic.
-fn heuristic(from: Coordinate, to: Coordinate) -> u32 {
-    let dx = (from.x as i32 - to.x as i32).unsigned_abs();
-    let dy = (from.y as i32 - to.y as i32).unsigned_abs();
-    dx + dy
+/// Get valid neighbors: 4-connected (North, South, East, West) for cardinal
+/// movement, plus the diagonals for octile movement. A diagonal move may
+/// not cut a corner: both cells it passes between must be passable. Each
+/// comes with the cost of moving there, the neighbor's entry cost.
+///
+/// Borrowed from the grid's neighbor table if it has one.
+pub(crate) fn neighbors_grid(coord: Coordinate, grid: &Grid) -> Cow<'_, [(Coordinate, u32)]> {
+    match &grid.adjacency {
+        Some(adjacency) => Cow::Borrowed(adjacency.of((coord.y * grid.width + coord.x) as usize)),
+        None => Cow::Owned(open_neighbors(coord, grid)),
+    }
 }
 
-/// Get valid neighbors (4-connected: North, South, East, West only).
-/// MAPF requires cardinal movement only - no diagonal moves allowed.
-fn neighbors_grid(coord: Coordinate, grid: &Grid) -> Vec<(Coordinate, u32)> {
-    let mut result = Vec::with_capacity(4);
+/// [`neighbors_grid`] worked out from the cells.
+fn open_neighbors(coord: Coordinate, grid: &Grid) -> Vec<(Coordinate, u32)> {
+    let mut result = Vec::with_capacity(8);
     let (x, y) = (coord.x as i32, coord.y as i32);
     let w = grid.width as i32;
     let h = grid.height as i32;
 
-    // Cardinal directions only: North, South, West, East (cost 1 each)
+    // Cardinal directions: North, South, West, East
     let cardinals = [
         (0, -1),  // North
         (0, 1),   // South
@@ -261,37 +631,23 @@ fn neighbors_grid(coord: Coordinate, grid: &Grid) -> Vec<(Coordinate, u32)> {
             let ux = nx as u32;
             let uy = ny as u32;
             if grid.is_passable(ux, uy) {
-                result.push((Coordinate { x: ux, y: uy }, 1));
+                result.push((Coordinate { x: ux, y: uy }, grid.cell_cost(ux, uy)));
             }
         }
     }
 
-    result
-}
-
-/// Get valid neighbors using raw map data (for backward compatibility).
-fn neighbors(coord: Coordinate, width: u32, height: u32, map: &[u8]) -> Vec<(Coordinate, u32)> {
-    let mut result = Vec::with_capacity(4);
-    let (x, y) = (coord.x as i32, coord.y as i32);
-    let w = width as i32;
-    let h = height as i32;
-
-    // Cardinal directions only: North, South, West, East (cost 1 each)
-    // No diagonal movement allowed in standard MAPF
-    let cardinals = [
-        (0, -1),  // North
-        (0, 1),   // South
-        (-1, 0),  // West
-        (1, 0),   // East
-    ];
-    
-    for (dx, dy) in cardinals {
-        let nx = x + dx;
-        let ny = y + dy;
-        if nx >= 0 && nx < w && ny >= 0 && ny < h {
-            let idx = (ny as u32 * width + nx as u32) as usize;
-            if map[idx] != 0 {
-                result.push((Coordinate { x: nx as u32, y: ny as u32 }, 1));
+    if grid.movement == Movement::Octile {
+        for (dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+            let (nx, ny) = (x + dx, y + dy);
+            if nx < 0 || nx >= w || ny < 0 || ny >= h {
+                continue;
+            }
+            let (ux, uy) = (nx as u32, ny as u32);
+            if grid.is_passable(ux, uy)
+                && grid.is_passable(ux, coord.y)
+                && grid.is_passable(coord.x, uy)
+            {
+                result.push((Coordinate { x: ux, y: uy }, grid.cell_cost(ux, uy)));
             }
         }
     }
@@ -314,18 +670,40 @@ pub fn astar_single(
     start: (u32, u32),
     goal: (u32, u32),
 ) -> Option<Path> {
+    astar_single_weighted(map, width, height, start, goal, 1.0)
+}
+
+/// Like [`astar_single`], with weighted A*: nodes are ordered by
+/// `f = g + weight * h`, which finds a path sooner the larger `weight` is.
+///
+/// The path costs at most `weight` times the shortest one; values below 1
+/// count as 1, which is plain, optimal A*.
+pub fn astar_single_weighted(
+    map: &[u8],
+    width: u32,
+    height: u32,
+    start: (u32, u32),
+    goal: (u32, u32),
+    weight: f64,
+) -> Option<Path> {
+    astar_single_grid(&Grid::from_raw(map, width, height), start, goal, weight)
+}
+
+/// Weighted single-agent A* on a pre-parsed [`Grid`], moving as its
+/// [`Movement`] allows and minimizing its terrain costs, if any; see
+/// [`astar_single_weighted`] for the bound on the path cost.
+pub fn astar_single_grid(
+    grid: &Grid,
+    start: (u32, u32),
+    goal: (u32, u32),
+    weight: f64,
+) -> Option<Path> {
+    let weight = weight.max(1.0);
     let start = Coordinate { x: start.0, y: start.1 };
     let goal = Coordinate { x: goal.0, y: goal.1 };
 
-    // Bounds check
-    if start.x >= width || start.y >= height || goal.x >= width || goal.y >= height {
-        return None;
-    }
-
-    // Check start/goal passability
-    let start_idx = (start.y * width + start.x) as usize;
-    let goal_idx = (goal.y * width + goal.x) as usize;
-    if map.get(start_idx).copied() != Some(1) || map.get(goal_idx).copied() != Some(1) {
+    // Start and goal must be in bounds and passable
+    if !grid.is_passable(start.x, start.y) || !grid.is_passable(goal.x, goal.y) {
         return None;
     }
 
@@ -334,367 +712,434 @@ pub fn astar_single(
         return Some(Path { steps: vec![start] });
     }
 
-    let mut open = BinaryHeap::new();
-    let mut came_from: HashMap<Coordinate, Coordinate> = HashMap::new();
-    let mut g_score: HashMap<Coordinate, u32> = HashMap::new();
-
-    g_score.insert(start, 0);
-    open.push(Node {
-        coord: start,
-        g_cost: 0,
-        f_cost: heuristic(start, goal),
-    });
-
-    while let Some(current) = open.pop() {
-        if current.coord == goal {
-            // Reconstruct path
-            let mut path = vec![goal];
-            let mut curr = goal;
-            while let Some(&prev) = came_from.get(&curr) {
-                path.push(prev);
-                curr = prev;
+    let distances = grid.heuristic_for(goal);
+    let estimate = |c: Coordinate| distances[(c.y * grid.width + c.x) as usize];
+    if estimate(start) == u32::MAX {
+        return None;
+    }
+    let index = |c: Coordinate| (c.y * grid.width + c.x) as usize;
+    SCRATCH.with(|scratch| {
+        let mut scratch = scratch.borrow_mut();
+        scratch.reset(grid.data.len());
+        // (cell, cost so far) by f-value
+        let mut open = BucketQueue::new();
+
+        scratch.set(index(start), 0, start);
+        open.push(scaled(weight, estimate(start)), (start, 0));
+
+        while let Some((_, (coord, g))) = open.pop() {
+            let current_g = scratch.g(index(coord));
+            // Superseded by a cheaper way to the same cell
+            if g > current_g {
+                continue;
+            }
+            if coord == goal {
+                // Reconstruct path
+                let mut path = vec![goal];
+                let mut curr = goal;
+                while curr != start {
+                    curr = scratch.parent[index(curr)];
+                    path.push(curr);
+                }
+                path.reverse();
+                return Some(Path { steps: path });
+            }
+
+            // Last to first, as the queue hands out equal f-values last in,
+            // first out
+            for &(neighbor, move_cost) in neighbors_grid(coord, grid).iter().rev() {
+                let tentative_g = current_g + move_cost;
+
+                if tentative_g < scratch.g(index(neighbor)) {
+                    scratch.set(index(neighbor), tentative_g, coord);
+                    let f = tentative_g + scaled(weight, estimate(neighbor));
+                    open.push(f, (neighbor, tentative_g));
+                }
             }
-            path.reverse();
-            return Some(Path { steps: path });
         }
 
-        let current_g = g_score[&current.coord];
+        None // No path found
+    })
+}
 
-        for (neighbor, move_cost) in neighbors(current.coord, width, height, map) {
-            let tentative_g = current_g + move_cost;
+/// Per-cell g-scores and parents for [`astar_single_grid`], kept per thread
+/// and reused across calls. An entry only counts if its stamp is the
+/// current generation, so a new search starts without clearing anything.
+#[derive(Default)]
+struct CellScratch {
+    generation: u32,
+    stamps: Vec<u32>,
+    g: Vec<u32>,
+    parent: Vec<Coordinate>,
+}
 
-            if tentative_g < *g_score.get(&neighbor).unwrap_or(&u32::MAX) {
-                came_from.insert(neighbor, current.coord);
-                g_score.insert(neighbor, tentative_g);
-                open.push(Node {
-                    coord: neighbor,
-                    g_cost: tentative_g,
-                    f_cost: tentative_g + heuristic(neighbor, goal),
-                });
-            }
+impl CellScratch {
+    /// Forget the previous search and make room for `cells` cells.
+    fn reset(&mut self, cells: usize) {
+        if self.stamps.len() < cells {
+            self.stamps.resize(cells, 0);
+            self.g.resize(cells, u32::MAX);
+            self.parent.resize(cells, Coordinate { x: 0, y: 0 });
+        }
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            // Stamps from 2^32 searches ago would look current again
+            self.stamps.fill(0);
+            self.generation = 1;
         }
     }
 
-    None // No path found
+    /// Cheapest cost found to `cell` this search, `u32::MAX` if none yet.
+    fn g(&self, cell: usize) -> u32 {
+        if self.stamps[cell] == self.generation {
+            self.g[cell]
+        } else {
+            u32::MAX
+        }
+    }
+
+    fn set(&mut self, cell: usize, g: u32, parent: Coordinate) {
+        self.stamps[cell] = self.generation;
+        self.g[cell] = g;
+        self.parent[cell] = parent;
+    }
 }
 
-/// Action types an agent can take.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ActionType {
-    /// Move to an adjacent cell (N/S/E/W)
-    Move,
-    /// Stay in place (wait)
-    NoOp,
+thread_local! {
+    static SCRATCH: RefCell<CellScratch> = RefCell::default();
 }
 
-/// An action an agent can take.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Action {
-    action_type: ActionType,
-    from: Coordinate,
-    target: Coordinate,
-    priority: u32, // lower is better (distance to goal)
-}
-
-/// Get prioritized actions for an agent at a position.
-/// Actions are sorted by how much they reduce distance to goal.
-/// Includes both Move actions (cardinal directions) and NoOp (wait).
-fn get_prioritized_actions(
-    pos: Coordinate,
-    goal: Coordinate,
-    width: u32,
-    height: u32,
-    map: &[u8],
-) -> Vec<Action> {
-    let mut actions = Vec::with_capacity(5);
-    
-    // Add cardinal Move actions
-    for (neighbor, _) in neighbors(pos, width, height, map) {
-        actions.push(Action {
-            action_type: ActionType::Move,
-            from: pos,
-            target: neighbor,
-            priority: heuristic(neighbor, goal),
+/// Where already planned agents are over time. Agents stay at the end of
+/// their path for good, unless they disappear at their goal.
+#[derive(Debug, Default)]
+pub(crate) struct ReservationTable {
+    /// Agents on each cell at each timestep before their last, and at
+    /// their last too if they disappear there
+    cells: HashMap<(Coordinate, u32), u32>,
+    /// Agents making each move, by (from, to, arrival timestep)
+    moves: HashMap<(Coordinate, Coordinate, u32), u32>,
+    /// Timesteps agents settle on each cell for good
+    parked: HashMap<Coordinate, Vec<u32>>,
+    /// Last timestep each cell is passed through, not counting parking
+    last_visit: HashMap<Coordinate, u32>,
+    /// Last timestep anything is reserved at
+    horizon: u32,
+    /// Whether agents vanish at the end of their path instead of parking
+    disappear_at_goal: bool,
+}
+
+impl ReservationTable {
+    /// Reserve `paths` under `grid`'s rules for what agents do at their goal.
+    pub(crate) fn new<'a>(grid: &Grid, paths: impl IntoIterator<Item = &'a Path>) -> Self {
+        let mut table = Self {
+            disappear_at_goal: grid.disappears_at_goal(),
+            ..Self::default()
+        };
+        for path in paths {
+            table.add(path);
+        }
+        table
+    }
+
+    /// Reserve the cells and moves of `path`, and its last cell from then
+    /// on unless the agent disappears there.
+    pub(crate) fn add(&mut self, path: &Path) {
+        let Some((&end, rest)) = path.steps.split_last() else {
+            return;
+        };
+        for (t, &cell) in rest.iter().enumerate() {
+            *self.cells.entry((cell, t as u32)).or_default() += 1;
+            let last = self.last_visit.entry(cell).or_default();
+            *last = (*last).max(t as u32);
+        }
+        for (t, step) in path.steps.windows(2).enumerate() {
+            *self
+                .moves
+                .entry((step[0], step[1], t as u32 + 1))
+                .or_default() += 1;
+        }
+        let arrival = rest.len() as u32;
+        if self.disappear_at_goal {
+            *self.cells.entry((end, arrival)).or_default() += 1;
+            let last = self.last_visit.entry(end).or_default();
+            *last = (*last).max(arrival);
+        } else {
+            self.parked.entry(end).or_default().push(arrival);
+        }
+        self.horizon = self.horizon.max(arrival);
+    }
+
+    /// Collisions caused by moving `from` -> `to`, arriving at timestep `t`.
+    pub(crate) fn conflicts(&self, from: Coordinate, to: Coordinate, t: u32) -> u32 {
+        let vertex = self.cells.get(&(to, t)).copied().unwrap_or(0);
+        let parked = self.parked.get(&to).map_or(0, |times| {
+            times.iter().filter(|&&since| since <= t).count() as u32
         });
+        let swap = if from == to {
+            0
+        } else {
+            self.moves.get(&(to, from, t)).copied().unwrap_or(0)
+        };
+        vertex + parked + swap
+    }
+
+    /// First timestep from which an agent could stay on `cell` for good
+    /// without a collision, or `None` if another agent settles there. Any
+    /// timestep will do for agents that disappear at their goal.
+    fn free_from(&self, cell: Coordinate) -> Option<u32> {
+        if self.disappear_at_goal {
+            return Some(0);
+        }
+        if self.parked.contains_key(&cell) {
+            return None;
+        }
+        Some(self.last_visit.get(&cell).map_or(0, |&t| t + 1))
     }
-    
-    // Add NoOp action (stay in place / wait)
-    actions.push(Action {
-        action_type: ActionType::NoOp,
-        from: pos,
-        target: pos,
-        priority: heuristic(pos, goal) + 1, // slightly worse than moving toward goal
-    });
-    
-    // Sort by priority (lower distance to goal = better)
-    actions.sort_by_key(|a| a.priority);
-    
-    actions
 }
 
-/// Get prioritized actions using Grid.
-fn get_prioritized_actions_grid(
-    pos: Coordinate,
-    goal: Coordinate,
+/// `value` times a weight or suboptimality factor, rounded down.
+pub(crate) fn scaled(factor: f64, value: u32) -> u32 {
+    (factor * value as f64).floor() as u32
+}
+
+/// Row-major distances from every cell to `goal`: the fewest moves, or on
+/// weighted grids the cheapest sum of entry costs; `u32::MAX` where it
+/// can't be reached.
+fn distances_to(grid: &Grid, goal: Coordinate) -> Vec<u32> {
+    let mut distances = vec![u32::MAX; (grid.width as usize) * (grid.height as usize)];
+    if !grid.is_passable(goal.x, goal.y) {
+        return distances;
+    }
+    let index = |c: Coordinate| (c.y * grid.width + c.x) as usize;
+    distances[index(goal)] = 0;
+    if !grid.is_weighted() {
+        let mut queue = VecDeque::from([goal]);
+        while let Some(cell) = queue.pop_front() {
+            for &(next, _) in neighbors_grid(cell, grid).iter() {
+                if distances[index(next)] == u32::MAX {
+                    distances[index(next)] = distances[index(cell)] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+        return distances;
+    }
+
+    // Dijkstra backwards: stepping from `next` onto `cell` costs `cell`'s
+    // entry cost. Moves are symmetric, so `next` neighbors `cell` too.
+    let mut open = BinaryHeap::from([Reverse((0, goal.y, goal.x))]);
+    while let Some(Reverse((distance, y, x))) = open.pop() {
+        let cell = Coordinate { x, y };
+        if distance > distances[index(cell)] {
+            continue;
+        }
+        let through = distance.saturating_add(grid.cell_cost(x, y));
+        for &(next, _) in neighbors_grid(cell, grid).iter() {
+            if through < distances[index(next)] {
+                distances[index(next)] = through;
+                open.push(Reverse((through, next.y, next.x)));
+            }
+        }
+    }
+    distances
+}
+
+/// Space-time A* for one agent: a cheapest path from `start` to `goal`
+/// that never collides with the agents in `reserved` and ends where the
+/// agent can stay, or one at most `weight` times as costly with a weighted
+/// heuristic. `None` if there is none.
+pub(crate) fn plan_around(
     grid: &Grid,
-) -> Vec<Action> {
-    let mut actions = Vec::with_capacity(5);
-    
-    // Add cardinal Move actions
-    for (neighbor, _) in neighbors_grid(pos, grid) {
-        actions.push(Action {
-            action_type: ActionType::Move,
-            from: pos,
-            target: neighbor,
-            priority: heuristic(neighbor, goal),
-        });
+    start: Coordinate,
+    goal: Coordinate,
+    reserved: &ReservationTable,
+    weight: f64,
+    stats: &mut SearchStats,
+) -> Option<Path> {
+    stats.low_level_calls += 1;
+    if !grid.is_passable(start.x, start.y) || reserved.conflicts(start, start, 0) > 0 {
+        return None;
     }
-    
-    // Add NoOp action (stay in place / wait)
-    actions.push(Action {
-        action_type: ActionType::NoOp,
-        from: pos,
-        target: pos,
-        priority: heuristic(pos, goal) + 1,
-    });
-    
-    actions.sort_by_key(|a| a.priority);
-    actions
-}
-
-/// Check for conflicts between two agents' actions.
-/// Returns true if there is a conflict.
-/// 
-/// Conflict types:
-/// 1. Vertex conflict: Two agents move to the same cell
-/// 2. Edge conflict: Two agents swap positions (A->B and B->A)
-fn has_conflict(action_a: &Action, action_b: &Action) -> bool {
-    // NoOp actions don't cause conflicts with movement
-    // (but can cause vertex conflict if another agent moves INTO the NoOp agent's cell)
-    
-    // Vertex conflict: both agents want to be in the same cell
-    if action_a.target == action_b.target {
-        return true;
+    let finish = reserved.free_from(goal)?;
+    let distances = grid.heuristic_for(goal);
+    let distance = |c: Coordinate| distances[(c.y * grid.width + c.x) as usize];
+    if distance(start) == u32::MAX {
+        return None;
     }
-    
-    // Edge conflict (swap): agent A goes from X to Y, agent B goes from Y to X
-    // Only check if both are actually moving (not NoOp)
-    if action_a.action_type == ActionType::Move && action_b.action_type == ActionType::Move {
-        if action_a.from == action_b.target && action_a.target == action_b.from {
-            return true;
+    // Once nothing is reserved any more waiting can't help, so any path
+    // needs at most one visit per cell more
+    let horizon = reserved
+        .horizon
+        .max(finish)
+        .saturating_add(grid.width.saturating_mul(grid.height));
+    // Every action until `finish` costs at least the cheapest cell
+    let heuristic = |cell: Coordinate, t: u32| {
+        distance(cell).max(finish.saturating_sub(t).saturating_mul(grid.min_cost))
+    };
+
+    // (cell, parent, cost so far) per node; on unweighted grids the cost
+    // is also the timestep
+    let mut nodes: Vec<(Coordinate, Option<usize>, u32)> = vec![(start, None, 0)];
+    // (timestep, node) by f-value
+    let mut open = BucketQueue::new();
+    open.push(scaled(weight, heuristic(start, 0)), (0, 0));
+    // Cheapest cost found to each (cell, timestep)
+    let mut best = HashMap::from([((start, 0), 0)]);
+    while let Some((_, (t, id))) = open.pop() {
+        let (cell, _, g) = nodes[id];
+        // Superseded by a cheaper way to the same place and time
+        if best[&(cell, t)] < g {
+            continue;
+        }
+        stats.nodes_expanded += 1;
+        if cell == goal && t >= finish {
+            let mut steps = vec![cell];
+            let mut at = id;
+            while let Some(parent) = nodes[at].1 {
+                steps.push(nodes[parent].0);
+                at = parent;
+            }
+            steps.reverse();
+            return Some(Path { steps });
+        }
+
+        let t = t + 1;
+        if t > horizon {
+            continue;
+        }
+        // Generated last to first: the queue hands out equal f-values last
+        // in, first out, so moves are tried in the usual order, then waits
+        let wait = (cell, grid.cell_cost(cell.x, cell.y));
+        let moves = neighbors_grid(cell, grid);
+        for (next, cost) in [wait].into_iter().chain(moves.iter().rev().copied()) {
+            if reserved.conflicts(cell, next, t) > 0 {
+                continue;
+            }
+            let g = g.saturating_add(cost);
+            if best.get(&(next, t)).is_some_and(|&old| old <= g) {
+                continue;
+            }
+            let h = heuristic(next, t);
+            if h == u32::MAX {
+                continue;
+            }
+            best.insert((next, t), g);
+            nodes.push((next, Some(id), g));
+            let f = g.saturating_add(scaled(weight, h));
+            open.push(f, (t, nodes.len() - 1));
+            stats.nodes_generated += 1;
         }
     }
-    
-    false
+    None
 }
 
-/// Solve MAPF for multiple agents using step-by-step prioritized planning.
+/// Solve MAPF for multiple agents using prioritized planning.
 ///
-/// At each timestep:
-/// 1. Each agent generates a priority queue of actions (Move + NoOp)
-/// 2. Agents commit to actions in priority order (by agent index)
-/// 3. If the best action conflicts, try the next action
-/// 4. Continue until all agents reach their goals
+/// Agents are planned one at a time in index order, each with space-time
+/// A* around the paths of the agents before it, held in a reservation
+/// table. Fast, but neither optimal nor complete: an agent fails if the
+/// earlier ones leave it no way through.
 ///
-/// Returns paths for all agents, or None if any agent gets stuck.
+/// Returns paths for all agents, or None if any agent can't be planned.
 pub fn solve_mapf(
     map: &[u8],
     width: u32,
     height: u32,
-    agents: &[((u32, u32), (u32, u32))], // (start, goal) pairs
+    agents: &[AgentTask],
 ) -> Option<Vec<Path>> {
-    let num_agents = agents.len();
-    
-    // Current positions
-    let mut positions: Vec<Coordinate> = agents
-        .iter()
-        .map(|&((sx, sy), _)| Coordinate { x: sx, y: sy })
-        .collect();
-    
-    // Goals
-    let goals: Vec<Coordinate> = agents
-        .iter()
-        .map(|&(_, (gx, gy))| Coordinate { x: gx, y: gy })
-        .collect();
-    
-    // Paths being built
-    let mut paths: Vec<Vec<Coordinate>> = positions.iter().map(|&p| vec![p]).collect();
-    
-    // Maximum timesteps to prevent infinite loops
-    let max_timesteps: u32 = (width + height) * 1000;
-    
-    for _t in 0..max_timesteps {
-        // Check if all agents have reached their goals
-        let all_done: bool = positions.iter().zip(goals.iter()).all(|(p, g)| p == g);
-        if all_done {
-            break;
-        }
-        
-        // Committed actions for this timestep
-        let mut committed_actions: Vec<Option<Action>> = vec![None; num_agents];
-        
-        // Process agents in priority order (by index)
-        for i in 0..num_agents {
-            
-            // Get prioritized actions
-            let actions = get_prioritized_actions(
-                positions[i],
-                goals[i],
-                width,
-                height,
-                map,
-            );
-            
-            // Try each action in priority order
-            let mut found_action = false;
-            for action in actions {
-                // Check for conflicts with all previously committed actions
-                let mut has_any_conflict = false;
-                for j in 0..i {
-                    if let Some(ref other_action) = committed_actions[j] {
-                        if has_conflict(&action, other_action) {
-                            has_any_conflict = true;
-                            break;
-                        }
-                    }
-                }
-                
-                if has_any_conflict {
-                    continue;
-                }
-                
-                // This action is valid - commit it
-                committed_actions[i] = Some(action);
-                found_action = true;
-                break;
-            }
-            
-            if !found_action {
-                // Agent is stuck - no valid action available
-                eprintln!("Agent {} stuck at ({}, {})", i, positions[i].x, positions[i].y);
-                return None;
-            }
-        }
-        
-        // Apply all committed actions
-        for i in 0..num_agents {
-            if let Some(action) = committed_actions[i] {
-                positions[i] = action.target;
-                // Add to path (including NoOp for wait actions)
-                let last = paths[i].last().unwrap();
-                if action.target != *last || action.target != goals[i] {
-                    paths[i].push(action.target);
-                }
-            }
-        }
-    }
-    
-    // Verify all agents reached their goals
-    for (i, (pos, goal)) in positions.iter().zip(goals.iter()).enumerate() {
-        if pos != goal {
-            eprintln!("Agent {} did not reach goal", i);
-            return None;
-        }
+    if map.len() != (width as usize) * (height as usize) {
+        return None;
     }
-    
-    Some(paths.into_iter().map(|steps| Path { steps }).collect())
+    solve_mapf_grid(&Grid::from_raw(map, width, height), agents)
 }
 
 /// Solve MAPF using a pre-parsed Grid (more efficient for multiple solves).
 pub fn solve_mapf_grid(
     grid: &Grid,
-    agents: &[((u32, u32), (u32, u32))],
+    agents: &[AgentTask],
 ) -> Option<Vec<Path>> {
-    let num_agents = agents.len();
-    
-    // Current positions
-    let mut positions: Vec<Coordinate> = agents
-        .iter()
-        .map(|&((sx, sy), _)| Coordinate { x: sx, y: sy })
-        .collect();
-    
-    // Goals
-    let goals: Vec<Coordinate> = agents
-        .iter()
-        .map(|&(_, (gx, gy))| Coordinate { x: gx, y: gy })
-        .collect();
-    
-    // Paths being built
-    let mut paths: Vec<Vec<Coordinate>> = positions.iter().map(|&p| vec![p]).collect();
-    
-    // Maximum timesteps to prevent infinite loops
-    let max_timesteps = (grid.width + grid.height) * 4;
-    
-    for _t in 0..max_timesteps {
-        // Check if all agents have reached their goals
-        let all_done = positions.iter().zip(goals.iter()).all(|(p, g)| p == g);
-        if all_done {
-            break;
-        }
-        
-        // Committed actions for this timestep
-        let mut committed_actions: Vec<Option<Action>> = vec![None; num_agents];
-        
-        // Process agents in priority order (by index)
-        for i in 0..num_agents {
-            // Get prioritized actions
-            let actions = get_prioritized_actions_grid(
-                positions[i],
-                goals[i],
-                grid,
-            );
-            
-            // Try each action in priority order
-            let mut found_action = false;
-            for action in actions {
-                // Check for conflicts with all previously committed actions
-                let mut has_any_conflict = false;
-                for j in 0..i {
-                    if let Some(ref other_action) = committed_actions[j] {
-                        if has_conflict(&action, other_action) {
-                            has_any_conflict = true;
-                            break;
-                        }
-                    }
-                }
-                
-                if has_any_conflict {
-                    continue;
-                }
-                
-                // This action is valid - commit it
-                committed_actions[i] = Some(action);
-                found_action = true;
-                break;
-            }
-            
-            if !found_action {
-                eprintln!("Agent {} stuck at ({}, {})", i, positions[i].x, positions[i].y);
-                return None;
-            }
-        }
-        
-        // Apply all committed actions
-        for i in 0..num_agents {
-            if let Some(action) = committed_actions[i] {
-                positions[i] = action.target;
-                let last = paths[i].last().unwrap();
-                if action.target != *last || action.target != goals[i] {
-                    paths[i].push(action.target);
-                }
-            }
-        }
+    solve_mapf_grid_with_stats(grid, agents, &mut SearchStats::default())
+}
+
+/// Like [`solve_mapf_grid`], adding the effort spent to `stats`: the
+/// space-time nodes expanded and generated over all agents.
+pub fn solve_mapf_grid_with_stats(
+    grid: &Grid,
+    agents: &[AgentTask],
+    stats: &mut SearchStats,
+) -> Option<Vec<Path>> {
+    solve_mapf_grid_weighted(grid, agents, 1.0, stats)
+}
+
+/// Like [`solve_mapf_grid_with_stats`], planning each agent with weighted
+/// space-time A* (`f = g + weight * h`).
+///
+/// Each path costs at most `weight` times the shortest path around the
+/// agents planned before it; values below 1 count as 1. Larger weights
+/// expand fewer nodes on long paths.
+pub fn solve_mapf_grid_weighted(
+    grid: &Grid,
+    agents: &[AgentTask],
+    weight: f64,
+    stats: &mut SearchStats,
+) -> Option<Vec<Path>> {
+    let weight = weight.max(1.0);
+    let mut reserved = ReservationTable::new(grid, []);
+    let mut paths = Vec::with_capacity(agents.len());
+    for &(start, goal) in agents {
+        let path = plan_around(grid, start.into(), goal.into(), &reserved, weight, stats)?;
+        reserved.add(&path);
+        paths.push(path);
     }
-    
-    // Verify all agents reached their goals
-    for (i, (pos, goal)) in positions.iter().zip(goals.iter()).enumerate() {
-        if pos != goal {
-            eprintln!("Agent {} did not reach goal", i);
-            return None;
+    Some(paths)
+}
+
+/// What the centralized and conflict-based searches minimize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Objective {
+    /// Sum over agents of the timesteps until each rests at its goal; on
+    /// weighted grids, of the costs of their actions until then
+    #[default]
+    SumOfCosts,
+    /// Timestep at which the last agent reaches its goal, whatever the
+    /// terrain costs
+    Makespan,
+}
+
+impl Objective {
+    /// Combine per-agent (or per-group) costs into this objective's cost:
+    /// their sum, or their maximum for makespan.
+    pub(crate) fn total(self, costs: impl IntoIterator<Item = u32>) -> u32 {
+        let costs = costs.into_iter();
+        match self {
+            Objective::SumOfCosts => costs.sum(),
+            Objective::Makespan => costs.max().unwrap_or(0),
         }
     }
-    
-    Some(paths.into_iter().map(|steps| Path { steps }).collect())
+}
+
+/// Effort spent by a search.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// Nodes taken off the open list and expanded
+    pub nodes_expanded: u64,
+    /// Successor nodes added to the open list
+    pub nodes_generated: u64,
+    /// Single-agent searches run: one per agent for prioritized planning,
+    /// one per agent planned or replanned for conflict-based search, one
+    /// per path or free cell looked for by push and rotate, and one per
+    /// agent's distance map for the centralized search
+    pub low_level_calls: u64,
+}
+
+/// Snapshot of a running search, handed to its `should_stop` hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchProgress {
+    /// Nodes expanded so far, counting the one about to be expanded
+    pub nodes_expanded: u64,
+    /// f-cost of the node about to be expanded; a lower bound on the
+    /// optimal cost, rising as the search goes on
+    pub best_cost: u32,
 }
 
 /// Global state for centralized MAPF A*
@@ -703,8 +1148,22 @@ struct GlobalState {
     positions: Vec<Coordinate>, // Current positions of all agents
     paths: Vec<Vec<Coordinate>>, // Paths for all agents so far
     cost: u32, // Total cost so far
+    deferred: Vec<u32>, // Cost of each agent's waits at its goal, charged if it leaves
     timestep: u32, // Current timestep
     goals: Vec<Coordinate>, // Store goals for f_cost
+    objective: Objective, // What cost and heuristic measure
+    distances: Arc<[Arc<[u32]>]>, // Distance map to each agent's goal
+    width: u32, // Row length of the distance maps
+}
+
+/// Distance maps to `goals` for the centralized heuristic: in timesteps
+/// for makespan, in terrain costs for sum of costs.
+fn goal_distances(grid: &Grid, goals: &[Coordinate], objective: Objective) -> Arc<[Arc<[u32]>]> {
+    let grid = match objective {
+        Objective::SumOfCosts => grid.clone(),
+        Objective::Makespan => grid.unweighted(),
+    };
+    goals.iter().map(|&goal| grid.heuristic_for(goal)).collect()
 }
 
 // Implement ordering for BinaryHeap (min-heap by f_cost)
@@ -722,131 +1181,61 @@ impl PartialOrd for GlobalState {
 }
 
 impl GlobalState {
-    /// Heuristic: sum of Manhattan distances to goals
+    /// Heuristic: sum (or max, for makespan) of exact single-agent
+    /// distances to goals; saturates if a goal is out of reach
     fn heuristic(&self) -> u32 {
-        self.positions.iter().zip(self.goals.iter())
-            .map(|(p, g)| heuristic(*p, *g))
-            .sum()
+        let distances = self.positions.iter().zip(self.distances.iter())
+            .map(|(p, d)| d[(p.y * self.width + p.x) as usize]);
+        match self.objective {
+            Objective::SumOfCosts => distances.fold(0, u32::saturating_add),
+            Objective::Makespan => distances.max().unwrap_or(0),
+        }
+    }
+    /// Cost of moving all agents to `next`, and the deferred goal waits
+    /// after it: one per timestep for makespan. For sum of costs, each agent
+    /// pays the `cell_cost` of its next cell, but waits at its goal are only
+    /// charged once it leaves the goal again, as waits ending a path are free
+    fn step_cost(
+        &self,
+        next: &[Coordinate],
+        cell_cost: impl Fn(Coordinate) -> u32,
+    ) -> (u32, Vec<u32>) {
+        let mut deferred = self.deferred.clone();
+        match self.objective {
+            Objective::SumOfCosts => {
+                let mut cost = 0;
+                for (i, (p, n)) in self.positions.iter().zip(next).enumerate() {
+                    if *p == self.goals[i] && *n == self.goals[i] {
+                        deferred[i] += cell_cost(*n);
+                    } else {
+                        cost += std::mem::take(&mut deferred[i]) + cell_cost(*n);
+                    }
+                }
+                (cost, deferred)
+            }
+            Objective::Makespan => (1, deferred),
+        }
+    }
+    /// Key for duplicate detection
+    fn key(&self) -> StateKey {
+        (self.positions.clone(), self.deferred.clone(), self.timestep)
     }
     /// Total estimated cost (g + h)
     fn f_cost(&self) -> u32 {
-        self.cost + self.heuristic()
+        self.cost.saturating_add(self.heuristic())
     }
 }
 
-/// Centralized A* MAPF solver
+/// Centralized A* MAPF solver on a raw map (row-major, nonzero =
+/// passable). Returns `None` if the map size doesn't match.
 pub fn solve_mapf_centralized(
     map: &[u8],
     width: u32,
     height: u32,
-    agents: &[((u32, u32), (u32, u32))],
+    agents: &[AgentTask],
 ) -> Option<Vec<Path>> {
-    let num_agents = agents.len();
-    let starts: Vec<Coordinate> = agents.iter().map(|&((sx, sy), _)| Coordinate { x: sx, y: sy }).collect();
-    let goals: Vec<Coordinate> = agents.iter().map(|&(_, (gx, gy))| Coordinate { x: gx, y: gy }).collect();
-
-    // Initial state
-    let start_state = GlobalState {
-        positions: starts.clone(),
-        paths: starts.iter().map(|&p| vec![p]).collect(),
-        cost: 0,
-        timestep: 0,
-        goals: goals.clone(),
-    };
-
-    // Priority queue (min-heap)
-    let mut open = BinaryHeap::new();
-    open.push(start_state);
-
-    // Visited set: (positions, timestep)
-    let mut visited: HashSet<(Vec<Coordinate>, u32)> = HashSet::new();
-    visited.insert((starts.clone(), 0));
-
-    // Main search loop
-    while let Some(state) = open.pop() {
-        // Check if all agents reached their goals
-        if state.positions.iter().zip(state.goals.iter()).all(|(p, g)| p == g) {
-            // Return solution paths
-            return Some(state.paths.into_iter().map(|steps| Path { steps }).collect());
-        }
-
-        // Generate all possible moves for each agent (including wait)
-        let mut moves_per_agent: Vec<Vec<Coordinate>> = Vec::with_capacity(num_agents);
-        for i in 0..num_agents {
-            let mut moves = Vec::new();
-            // Cardinal moves
-            for (neighbor, _) in neighbors(state.positions[i], width, height, map) {
-                moves.push(neighbor);
-            }
-            // Wait (NoOp)
-            moves.push(state.positions[i]);
-            moves_per_agent.push(moves);
-        }
-
-        // Generate all joint moves (cartesian product)
-        let mut joint_moves = vec![];
-        fn backtrack(
-            moves_per_agent: &Vec<Vec<Coordinate>>,
-            current: &mut Vec<Coordinate>,
-            idx: usize,
-            joint_moves: &mut Vec<Vec<Coordinate>>,
-        ) {
-            if idx == moves_per_agent.len() {
-                joint_moves.push(current.clone());
-                return;
-            }
-            for &m in &moves_per_agent[idx] {
-                current.push(m);
-                backtrack(moves_per_agent, current, idx + 1, joint_moves);
-                current.pop();
-            }
-        }
-        backtrack(&moves_per_agent, &mut Vec::with_capacity(num_agents), 0, &mut joint_moves);
-
-        // For each joint move, check for conflicts and expand
-        for next_positions in joint_moves {
-            // Vertex conflict: two agents in same cell
-            let mut unique = HashSet::new();
-            if !next_positions.iter().all(|p| unique.insert(*p)) {
-                continue; // skip joint move with vertex conflict
-            }
-            // Edge conflict: agents swap positions
-            let mut edge_conflict = false;
-            for i in 0..num_agents {
-                for j in (i+1)..num_agents {
-                    if state.positions[i] == next_positions[j] && state.positions[j] == next_positions[i] {
-                        edge_conflict = true;
-                        break;
-                    }
-                }
-                if edge_conflict { break; }
-            }
-            if edge_conflict { continue; }
-
-            // Check if already visited
-            let visit_key = (next_positions.clone(), state.timestep + 1);
-            if visited.contains(&visit_key) {
-                continue;
-            }
-            visited.insert(visit_key);
-
-            // Build new paths
-            let mut new_paths = state.paths.clone();
-            for i in 0..num_agents {
-                new_paths[i].push(next_positions[i]);
-            }
-
-            // Cost: +1 per agent move (wait counts as move)
-            let new_cost = state.cost + 1;
-            let new_state = GlobalState {
-                positions: next_positions,
-                paths: new_paths,
-                cost: new_cost,
-                timestep: state.timestep + 1,
-                goals: state.goals.clone(),
-            };
-            open.push(new_state);
-        }
+    if map.len() != (width as usize) * (height as usize) {
+        return None;
     }
-    None
+    solve_mapf_centralized_grid(&Grid::from_raw(map, width, height), agents)
 }
@@ -0,0 +1,58 @@
+//! Open list for the grid searches.
+//!
+//! Grid searches order their nodes by small integer f-values, so a queue
+//! with one bucket per value pushes and pops in constant amortized time,
+//! where a binary heap pays a logarithm on both.
+
+use std::collections::VecDeque;
+
+/// Min-priority queue over integer priorities with one bucket per value.
+///
+/// Items of equal priority come out last in, first out, which favors the
+/// node generated most recently, usually the deepest. Memory grows with the
+/// spread between the lowest and highest priority queued at once, so it
+/// suits f-values, which stay within a path's cost of each other.
+#[derive(Debug)]
+pub(crate) struct BucketQueue<T> {
+    /// Items by priority, starting at `base`
+    buckets: VecDeque<Vec<T>>,
+    /// Priority of the front bucket
+    base: u32,
+}
+
+impl<T> BucketQueue<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            buckets: VecDeque::new(),
+            base: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, priority: u32, item: T) {
+        if self.buckets.is_empty() {
+            self.base = priority;
+        }
+        // Inconsistent or weighted heuristics can undercut the front
+        while priority < self.base {
+            self.buckets.push_front(Vec::new());
+            self.base -= 1;
+        }
+        let at = (priority - self.base) as usize;
+        if at >= self.buckets.len() {
+            self.buckets.resize_with(at + 1, Vec::new);
+        }
+        self.buckets[at].push(item);
+    }
+
+    /// Take an item with the lowest priority, along with that priority.
+    pub(crate) fn pop(&mut self) -> Option<(u32, T)> {
+        while let Some(bucket) = self.buckets.front_mut() {
+            if let Some(item) = bucket.pop() {
+                return Some((self.base, item));
+            }
+            self.buckets.pop_front();
+            self.base += 1;
+        }
+        None
+    }
+}
@@ -0,0 +1,519 @@
+//! Conflict-based search with bounded suboptimality (ECBS).
+//!
+//! The high level searches a tree of constraint sets. Each node plans every
+//! agent on its own under the node's constraints; the first collision
+//! between two agents splits the node into two children, each forbidding
+//! one of them the contested cell or move. The low level plans one agent in
+//! space-time.
+//!
+//! Both levels keep a focal list beside the open list: among the nodes
+//! within a factor `w` of the lowest cost bound, the one with the fewest
+//! conflicts goes first. Solutions cost at most `w` times the optimal sum of
+//! costs, and `w = 1` is plain, optimal CBS.
+//!
+//! The high level can minimize makespan instead: a node then costs the
+//! latest arrival of its paths, bounded below by the largest of the
+//! agents' bounds. Agents still take their shortest paths, which arrive no
+//! later than needed, and terrain costs don't count.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::astar::{
+    neighbors_grid, scaled, AgentTask, Coordinate, Grid, Objective, Path, ReservationTable,
+    SearchProgress, SearchStats, SearchStatus,
+};
+use crate::conflicts::{find_conflicts, Conflict, ConflictKind};
+use crate::parallel;
+
+/// Solve MAPF with ECBS: paths whose sum of costs is at most
+/// `suboptimality` times the optimum, or `None` if there are none.
+///
+/// CBS can't always tell an unsolvable instance from a hard one and may
+/// search forever; bound it with [`solve_mapf_ecbs_grid_with`] where that
+/// matters.
+pub fn solve_mapf_ecbs_grid(
+    grid: &Grid,
+    agents: &[AgentTask],
+    suboptimality: f64,
+) -> Option<Vec<Path>> {
+    solve_mapf_ecbs_grid_with(grid, agents, Objective::SumOfCosts, suboptimality, |_| {
+        false
+    })
+}
+
+/// Like [`solve_mapf_ecbs_grid`], minimizing `objective` and calling
+/// `should_stop` with the search progress before every high-level
+/// expansion; returning `true` aborts the search with `None`.
+pub fn solve_mapf_ecbs_grid_with(
+    grid: &Grid,
+    agents: &[AgentTask],
+    objective: Objective,
+    suboptimality: f64,
+    should_stop: impl FnMut(SearchProgress) -> bool,
+) -> Option<Vec<Path>> {
+    match EcbsSearch::new(grid, agents, objective, suboptimality).run(should_stop) {
+        SearchStatus::Solved(paths) => Some(paths),
+        SearchStatus::Running | SearchStatus::Exhausted => None,
+    }
+}
+
+/// A restriction on one agent, added when splitting a high-level node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Constraint {
+    /// `agent` may not be on `cell` at timestep `t`
+    Vertex {
+        agent: usize,
+        cell: Coordinate,
+        t: u32,
+    },
+    /// `agent` may not move from `from` to `to`, arriving at timestep `t`
+    Edge {
+        agent: usize,
+        from: Coordinate,
+        to: Coordinate,
+        t: u32,
+    },
+}
+
+impl Constraint {
+    fn agent(&self) -> usize {
+        match *self {
+            Constraint::Vertex { agent, .. } | Constraint::Edge { agent, .. } => agent,
+        }
+    }
+
+    /// The two ways of resolving `conflict`, one per agent.
+    fn resolving(conflict: &Conflict) -> [Constraint; 2] {
+        let ((a, b), t, cell) = (conflict.agents, conflict.time, conflict.location);
+        match conflict.kind {
+            ConflictKind::Vertex => [
+                Constraint::Vertex { agent: a, cell, t },
+                Constraint::Vertex { agent: b, cell, t },
+            ],
+            // Edge conflicts are stamped with the departure timestep
+            ConflictKind::Edge { to } => [
+                Constraint::Edge {
+                    agent: a,
+                    from: cell,
+                    to,
+                    t: t + 1,
+                },
+                Constraint::Edge {
+                    agent: b,
+                    from: to,
+                    to: cell,
+                    t: t + 1,
+                },
+            ],
+        }
+    }
+}
+
+/// A constraint set with the paths planned under it.
+struct HighNode {
+    constraints: Vec<Constraint>,
+    paths: Vec<Path>,
+    /// Lower bound on each agent's cost under `constraints`
+    bounds: Vec<u32>,
+    cost: u32,
+    lower_bound: u32,
+    /// Pairs of agents whose paths collide
+    conflicts: u32,
+    first_conflict: Option<Conflict>,
+}
+
+impl HighNode {
+    fn new(
+        search: &EcbsSearch,
+        constraints: Vec<Constraint>,
+        paths: Vec<Path>,
+        bounds: Vec<u32>,
+    ) -> Self {
+        let (first_conflict, conflicts) = find_conflicts(&paths, search.grid.disappears_at_goal());
+        let objective = search.objective;
+        Self {
+            constraints,
+            cost: objective.total(paths.iter().map(|path| search.grid.path_cost(path))),
+            lower_bound: objective.total(bounds.iter().copied()),
+            paths,
+            bounds,
+            conflicts,
+            first_conflict,
+        }
+    }
+}
+
+/// ECBS search that can be paused and resumed, like
+/// [`CentralizedSearch`](crate::CentralizedSearch). Stats count high-level
+/// nodes, and the low-level searches run for them.
+pub struct EcbsSearch {
+    /// The grid searched; without terrain costs for makespan, which counts
+    /// timesteps
+    grid: Grid,
+    objective: Objective,
+    starts: Vec<Coordinate>,
+    goals: Vec<Coordinate>,
+    /// Per agent, row-major distances to its goal; `u32::MAX` where it
+    /// can't be reached
+    distances: Vec<Arc<[u32]>>,
+    suboptimality: f64,
+    /// High-level nodes by id; taken once expanded
+    nodes: Vec<Option<HighNode>>,
+    /// Unexpanded nodes by (lower bound, id)
+    open: BTreeSet<(u32, usize)>,
+    /// Unexpanded nodes by (conflicts, cost, id) that cost at most `bound`
+    focal: BTreeSet<(u32, u32, usize)>,
+    /// Unexpanded nodes by (cost, id) that cost more than `bound`
+    waiting: BTreeSet<(u32, usize)>,
+    /// `suboptimality` times the lowest lower bound in `open`
+    bound: u32,
+    stats: SearchStats,
+}
+
+impl EcbsSearch {
+    /// Set up a search for `agents` on `grid` within a factor
+    /// `suboptimality` of the optimal `objective`; values below 1 count as
+    /// 1. Plans the root node right away.
+    pub fn new(
+        grid: &Grid,
+        agents: &[AgentTask],
+        objective: Objective,
+        suboptimality: f64,
+    ) -> Self {
+        let grid = match objective {
+            Objective::SumOfCosts => grid.clone(),
+            Objective::Makespan => grid.unweighted(),
+        };
+        let starts: Vec<Coordinate> = agents.iter().map(|&(start, _)| start.into()).collect();
+        let goals: Vec<Coordinate> = agents.iter().map(|&(_, goal)| goal.into()).collect();
+        let mut search = Self {
+            distances: goals.iter().map(|&goal| grid.heuristic_for(goal)).collect(),
+            grid,
+            objective,
+            starts,
+            goals,
+            suboptimality: suboptimality.max(1.0),
+            nodes: Vec::new(),
+            open: BTreeSet::new(),
+            focal: BTreeSet::new(),
+            waiting: BTreeSet::new(),
+            bound: 0,
+            stats: SearchStats::default(),
+        };
+
+        // Shared starts, or goals agents stay at, collide whatever the
+        // paths; leave nothing to search
+        let distinct = |cells: &[Coordinate]| {
+            let mut seen = HashSet::new();
+            cells.iter().all(|&c| seen.insert(c))
+        };
+        let goals_shared = !search.grid.disappears_at_goal() && !distinct(&search.goals);
+        if !distinct(&search.starts) || goals_shared {
+            return search;
+        }
+
+        // Root: each agent planned around the ones before it
+        let mut paths = Vec::with_capacity(agents.len());
+        let mut bounds = Vec::with_capacity(agents.len());
+        for agent in 0..agents.len() {
+            let others = ReservationTable::new(&search.grid, &paths);
+            search.stats.low_level_calls += 1;
+            let Some((path, bound)) = search.plan(agent, &[], &others) else {
+                return search;
+            };
+            paths.push(path);
+            bounds.push(bound);
+        }
+        let root = HighNode::new(&search, Vec::new(), paths, bounds);
+        search.push(root);
+        search
+    }
+
+    /// High-level nodes expanded so far.
+    pub fn nodes_expanded(&self) -> u64 {
+        self.stats.nodes_expanded
+    }
+
+    /// Search effort so far.
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+
+    /// Expand at most `max_expansions` more high-level nodes.
+    pub fn step(&mut self, max_expansions: u64) -> SearchStatus {
+        let limit = self.stats.nodes_expanded.saturating_add(max_expansions);
+        self.run(|progress| progress.nodes_expanded > limit)
+    }
+
+    /// Search until solved, exhausted, or `should_stop` returns `true` for
+    /// the node about to be expanded; that node stays queued, so the search
+    /// can be resumed. `best_cost` is the lowest lower bound left, which
+    /// never exceeds the optimal cost.
+    pub fn run(&mut self, mut should_stop: impl FnMut(SearchProgress) -> bool) -> SearchStatus {
+        loop {
+            self.refresh_focal();
+            let (Some(&(_, _, id)), Some(&(lower_bound, _))) =
+                (self.focal.first(), self.open.first())
+            else {
+                return SearchStatus::Exhausted;
+            };
+            let progress = SearchProgress {
+                nodes_expanded: self.stats.nodes_expanded + 1,
+                best_cost: lower_bound,
+            };
+            if should_stop(progress) {
+                return SearchStatus::Running;
+            }
+
+            self.focal.pop_first();
+            let node = self.nodes[id].take().expect("queued nodes are kept");
+            self.open.remove(&(node.lower_bound, id));
+            self.stats.nodes_expanded += 1;
+
+            let Some(conflict) = node.first_conflict else {
+                return SearchStatus::Solved(node.paths);
+            };
+            // The two children replan different agents independently
+            let [left, right] = Constraint::resolving(&conflict);
+            let (left, right) =
+                parallel::join(|| self.split(&node, left), || self.split(&node, right));
+            for child in [left, right] {
+                self.stats.low_level_calls += 1;
+                if let Some(child) = child {
+                    self.push(child);
+                    self.stats.nodes_generated += 1;
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, node: HighNode) {
+        let id = self.nodes.len();
+        self.open.insert((node.lower_bound, id));
+        if node.cost <= self.bound {
+            self.focal.insert((node.conflicts, node.cost, id));
+        } else {
+            self.waiting.insert((node.cost, id));
+        }
+        self.nodes.push(Some(node));
+    }
+
+    /// Raise `bound` to match the lowest lower bound and move the nodes it
+    /// now covers into focal.
+    fn refresh_focal(&mut self) {
+        let Some(&(lower_bound, lowest)) = self.open.first() else {
+            return;
+        };
+        self.bound = self.bound.max(scaled(self.suboptimality, lower_bound));
+        while let Some(&(cost, id)) = self.waiting.first() {
+            if cost > self.bound {
+                break;
+            }
+            self.waiting.pop_first();
+            let node = self.nodes[id].as_ref().expect("queued nodes are kept");
+            self.focal.insert((node.conflicts, cost, id));
+        }
+        // Paths cost at most `suboptimality` times their bound, so the
+        // lowest node is always covered; rounding aside
+        if self.focal.is_empty() {
+            let node = self.nodes[lowest].as_ref().expect("queued nodes are kept");
+            self.waiting.remove(&(node.cost, lowest));
+            self.focal.insert((node.conflicts, node.cost, lowest));
+        }
+    }
+
+    /// Child of `node` with `constraint` added and its agent replanned, or
+    /// `None` if the agent can't reach its goal any more.
+    fn split(&self, node: &HighNode, constraint: Constraint) -> Option<HighNode> {
+        let agent = constraint.agent();
+        let mut constraints = node.constraints.clone();
+        constraints.push(constraint);
+
+        let others = ReservationTable::new(
+            &self.grid,
+            node.paths
+                .iter()
+                .enumerate()
+                .filter(|&(other, _)| other != agent)
+                .map(|(_, path)| path),
+        );
+        let (path, bound) = self.plan(agent, &constraints, &others)?;
+        let mut paths = node.paths.clone();
+        paths[agent] = path;
+        let mut bounds = node.bounds.clone();
+        // More constraints never make the optimum cheaper
+        bounds[agent] = bounds[agent].max(bound);
+        Some(HighNode::new(self, constraints, paths, bounds))
+    }
+
+    /// Focal A* for one agent in space-time under the constraints on it,
+    /// preferring moves that collide with fewer of `others`. Returns the
+    /// path, costing at most `suboptimality` times the optimum, and a lower
+    /// bound on the optimal cost.
+    fn plan(
+        &self,
+        agent: usize,
+        constraints: &[Constraint],
+        others: &ReservationTable,
+    ) -> Option<(Path, u32)> {
+        let (start, goal) = (self.starts[agent], self.goals[agent]);
+        let distances = &self.distances[agent];
+        let distance = |c: Coordinate| {
+            if self.grid.in_bounds(c.x, c.y) {
+                distances[(c.y * self.grid.width + c.x) as usize]
+            } else {
+                u32::MAX
+            }
+        };
+        if distance(start) == u32::MAX {
+            return None;
+        }
+
+        let mut vertices = HashSet::new();
+        let mut edges = HashSet::new();
+        // Last constrained timestep, and the first the agent may stay at its goal from
+        let (mut last, mut finish) = (0, 0);
+        for constraint in constraints.iter().filter(|c| c.agent() == agent) {
+            match *constraint {
+                Constraint::Vertex { cell, t, .. } => {
+                    vertices.insert((cell, t));
+                    // Agents that disappear at their goal may still arrive before `t`
+                    if cell == goal && !self.grid.disappears_at_goal() {
+                        finish = finish.max(t + 1);
+                    }
+                    last = last.max(t);
+                }
+                Constraint::Edge { from, to, t, .. } => {
+                    edges.insert((from, to, t));
+                    last = last.max(t);
+                }
+            }
+        }
+        if vertices.contains(&(start, 0)) {
+            return None;
+        }
+        // Past the last constraint waiting can't help, so any path needs at
+        // most one visit per cell more
+        let horizon = last.saturating_add(self.grid.width.saturating_mul(self.grid.height));
+        let min_cost = self.grid.min_cost();
+        let heuristic = |cell: Coordinate, t: u32| {
+            distance(cell).max(finish.saturating_sub(t).saturating_mul(min_cost))
+        };
+
+        let mut nodes = vec![LowNode {
+            cell: start,
+            t: 0,
+            g: 0,
+            f: heuristic(start, 0),
+            conflicts: 0,
+            parent: None,
+        }];
+        // Unexpanded nodes by (f, deepest first, id), and those within the bound by conflicts first
+        let mut open = BTreeSet::from([(nodes[0].f, Reverse(0), 0)]);
+        let mut focal = BTreeSet::from([(0, nodes[0].f, Reverse(0), 0)]);
+        let mut bound = scaled(self.suboptimality, nodes[0].f);
+        let mut best: HashMap<(Coordinate, u32), usize> = HashMap::from([((start, 0), 0)]);
+        let mut closed: HashSet<(Coordinate, u32)> = HashSet::new();
+
+        loop {
+            let &(f_min, _, _) = open.first()?;
+            let raised = scaled(self.suboptimality, f_min);
+            if raised > bound {
+                for &(f, g, id) in open.range((bound.saturating_add(1), Reverse(u32::MAX), 0)..) {
+                    if f > raised {
+                        break;
+                    }
+                    focal.insert((nodes[id].conflicts, f, g, id));
+                }
+                bound = raised;
+            }
+
+            let (_, f, depth, id) = focal.pop_first().expect("the lowest f is within the bound");
+            open.remove(&(f, depth, id));
+            let LowNode {
+                cell,
+                t,
+                g,
+                conflicts,
+                ..
+            } = nodes[id];
+            closed.insert((cell, t));
+
+            if cell == goal && t >= finish {
+                let mut steps = vec![cell];
+                let mut at = id;
+                while let Some(parent) = nodes[at].parent {
+                    steps.push(nodes[parent].cell);
+                    at = parent;
+                }
+                steps.reverse();
+                return Some((Path { steps }, f_min));
+            }
+
+            let t = t + 1;
+            if t > horizon {
+                continue;
+            }
+            let wait = (cell, self.grid.cell_cost(cell.x, cell.y));
+            for (next, cost) in neighbors_grid(cell, &self.grid)
+                .iter()
+                .copied()
+                .chain([wait])
+            {
+                if vertices.contains(&(next, t)) || edges.contains(&(cell, next, t)) {
+                    continue;
+                }
+                let h = heuristic(next, t);
+                if h == u32::MAX {
+                    continue;
+                }
+                let g = g.saturating_add(cost);
+                let conflicts = conflicts + others.conflicts(cell, next, t);
+                if let Some(&old) = best.get(&(next, t)) {
+                    let old_node = &nodes[old];
+                    // A cheaper way reopens even an expanded node; fewer
+                    // conflicts at the same cost only an unexpanded one
+                    let closed = closed.contains(&(next, t));
+                    if old_node.g < g
+                        || (old_node.g == g && (closed || old_node.conflicts <= conflicts))
+                    {
+                        continue;
+                    }
+                    open.remove(&(old_node.f, Reverse(t), old));
+                    focal.remove(&(old_node.conflicts, old_node.f, Reverse(t), old));
+                }
+                closed.remove(&(next, t));
+                let child = nodes.len();
+                let f = g.saturating_add(h);
+                nodes.push(LowNode {
+                    cell: next,
+                    t,
+                    g,
+                    f,
+                    conflicts,
+                    parent: Some(id),
+                });
+                open.insert((f, Reverse(t), child));
+                if f <= bound {
+                    focal.insert((conflicts, f, Reverse(t), child));
+                }
+                best.insert((next, t), child);
+            }
+        }
+    }
+}
+
+/// Low-level search node: the agent on `cell` at timestep `t`.
+#[derive(Clone, Copy)]
+struct LowNode {
+    cell: Coordinate,
+    t: u32,
+    /// Cost so far; the timestep again on unweighted grids
+    g: u32,
+    f: u32,
+    /// Collisions with other agents along the way
+    conflicts: u32,
+    parent: Option<usize>,
+}
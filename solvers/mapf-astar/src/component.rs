@@ -0,0 +1,45 @@
+//! `mapf-solver` WIT component, so the reference solver can be submitted
+//! to the arena like any user solver.
+
+use mapf_solver_sdk::{
+    export_solver, report_low_level_calls, report_nodes, Agent, Error, GridMap, Solution, Solver,
+};
+
+use crate::astar::{solve_mapf_grid_with_stats, Grid, SearchStats};
+
+struct AStar;
+
+impl Solver for AStar {
+    fn solve(map: &GridMap, agents: &[Agent]) -> Result<Solution, Error> {
+        if agents.iter().any(|a| !a.waypoints.is_empty()) {
+            return Err(Error::Unsupported("waypoints"));
+        }
+
+        let grid = Grid::from_map(map);
+        let agents: Vec<_> = agents
+            .iter()
+            .map(|a| ((a.start.x, a.start.y), (a.goal.x, a.goal.y)))
+            .collect();
+
+        // Prioritized planning is polynomial, so it finishes well within any
+        // deadline; the optimal searches could run far past it
+        let mut stats = SearchStats::default();
+        let paths = solve_mapf_grid_with_stats(&grid, &agents, &mut stats);
+        // Reported through `get-stats`, with the time the SDK measures
+        report_nodes(stats.nodes_expanded, stats.nodes_generated);
+        report_low_level_calls(stats.low_level_calls);
+        let paths = paths.ok_or(Error::NoSolution)?;
+        Ok(Solution {
+            paths: paths
+                .into_iter()
+                .map(|path| mapf_solver_sdk::Path { steps: path.steps })
+                .collect(),
+        })
+    }
+
+    fn info() -> String {
+        "A* Reference Solver v0.2.0 (Prioritized Planning)".to_string()
+    }
+}
+
+export_solver!(AStar);
@@ -0,0 +1,122 @@
+//! Collisions between planned paths.
+//!
+//! Agents stay at the end of their path once they get there, so a short
+//! path still blocks its last cell for as long as the longest one runs;
+//! unless they disappear at their goal, when they leave the map right
+//! after their last step.
+//! Two agents collide when they are on the same cell at the same timestep
+//! (a vertex conflict) or swap cells in one timestep (an edge conflict).
+//! Conflicts come from [`mapf_core::validate::check_collisions_with`], so
+//! they match what the arena reports, timesteps included.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use mapf_core::validate::{check_collisions_with, Violation, ViolationKind};
+
+use crate::astar::{Coordinate, Path};
+
+/// How two agents collide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    /// Both agents are on `location` at `time`
+    Vertex,
+    /// The first agent moves from `location` to `to` while the second
+    /// moves from `to` to `location`, both leaving at `time` and arriving
+    /// at `time + 1`
+    Edge { to: Coordinate },
+}
+
+/// A collision between two agents, identified by their indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    /// The colliding agents, in index order
+    pub agents: (usize, usize),
+    /// Timestep of the collision; for edge conflicts, the one the agents
+    /// leave from, as in the arena's validation errors
+    pub time: u32,
+    /// The contested cell; for edge conflicts, where the first agent
+    /// moves from
+    pub location: Coordinate,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a, b) = self.agents;
+        let Coordinate { x, y } = self.location;
+        match self.kind {
+            ConflictKind::Vertex => write!(
+                f,
+                "agents {a} and {b} both on ({x}, {y}) at timestep {}",
+                self.time
+            ),
+            ConflictKind::Edge { to } => write!(
+                f,
+                "agents {a} and {b} swap ({x}, {y}) and ({}, {}) between timesteps {} and {}",
+                to.x,
+                to.y,
+                self.time,
+                self.time + 1
+            ),
+        }
+    }
+}
+
+impl Conflict {
+    /// The conflict behind a collision found by the core checker.
+    fn from_violation(violation: &Violation) -> Self {
+        let kind = match violation.kind {
+            ViolationKind::EdgeCollision => ConflictKind::Edge {
+                to: violation.positions[1],
+            },
+            _ => ConflictKind::Vertex,
+        };
+        Conflict {
+            kind,
+            agents: (violation.agents[0], violation.agents[1]),
+            time: violation.timestep.expect("collisions have a timestep") as u32,
+            location: violation.positions[0],
+        }
+    }
+}
+
+/// The earliest collision between `paths`, or `None` if they are
+/// collision-free. Empty paths never collide.
+pub fn find_first_conflict(paths: &[Path]) -> Option<Conflict> {
+    find_conflicts(paths, false).0
+}
+
+/// Like [`find_first_conflict`], with agents leaving the map after their
+/// last step if `disappear_at_goal` is set.
+pub fn find_first_conflict_with(paths: &[Path], disappear_at_goal: bool) -> Option<Conflict> {
+    find_conflicts(paths, disappear_at_goal).0
+}
+
+/// Number of agent pairs whose paths collide at least once.
+pub fn count_conflicts(paths: &[Path]) -> u32 {
+    find_conflicts(paths, false).1
+}
+
+/// Like [`count_conflicts`], with agents leaving the map after their last
+/// step if `disappear_at_goal` is set.
+pub fn count_conflicts_with(paths: &[Path], disappear_at_goal: bool) -> u32 {
+    find_conflicts(paths, disappear_at_goal).1
+}
+
+/// [`find_first_conflict_with`] and [`count_conflicts_with`] in one pass,
+/// on top of the arena's own collision check.
+pub(crate) fn find_conflicts(paths: &[Path], disappear_at_goal: bool) -> (Option<Conflict>, u32) {
+    let paths: Vec<mapf_core::Path> = paths
+        .iter()
+        .map(|path| mapf_core::Path {
+            steps: path.steps.clone(),
+        })
+        .collect();
+    let conflicts: Vec<Conflict> = check_collisions_with(&paths, disappear_at_goal)
+        .iter()
+        .map(Conflict::from_violation)
+        .collect();
+    let pairs: HashSet<_> = conflicts.iter().map(|conflict| conflict.agents).collect();
+    (conflicts.first().copied(), pairs.len() as u32)
+}
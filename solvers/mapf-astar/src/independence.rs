@@ -0,0 +1,238 @@
+//! Independence detection (ID) around conflict-based search.
+//!
+//! Every agent starts out in a group of its own and is planned alone. While
+//! the paths of two groups collide, the two are merged and the merged group
+//! is solved jointly with ECBS, ignoring every other group. Agents that
+//! never get in each other's way are never searched together, so large maps
+//! with many agents that mostly keep apart stay cheap. With a factor of 1
+//! each group is solved optimally, and so is the whole instance, for sum of
+//! costs or makespan alike.
+
+use crate::astar::{AgentTask, Grid, Objective, Path, SearchProgress, SearchStats, SearchStatus};
+use crate::cbs::EcbsSearch;
+use crate::conflicts::find_conflicts;
+use crate::parallel;
+
+/// Solve MAPF with independence detection, solving the groups that collide
+/// with ECBS within a factor `suboptimality` of their optimal sum of costs.
+/// Returns `None` if some group has no solution.
+///
+/// Like [`solve_mapf_ecbs_grid`](crate::solve_mapf_ecbs_grid), it may
+/// search forever on an unsolvable instance; bound it with
+/// [`solve_mapf_id_grid_with`] where that matters.
+pub fn solve_mapf_id_grid(
+    grid: &Grid,
+    agents: &[AgentTask],
+    suboptimality: f64,
+) -> Option<Vec<Path>> {
+    solve_mapf_id_grid_with(grid, agents, Objective::SumOfCosts, suboptimality, |_| {
+        false
+    })
+}
+
+/// Like [`solve_mapf_id_grid`], minimizing `objective` and calling
+/// `should_stop` with the search progress before every high-level
+/// expansion; returning `true` aborts the search with `None`.
+pub fn solve_mapf_id_grid_with(
+    grid: &Grid,
+    agents: &[AgentTask],
+    objective: Objective,
+    suboptimality: f64,
+    should_stop: impl FnMut(SearchProgress) -> bool,
+) -> Option<Vec<Path>> {
+    match IdSearch::new(grid, agents, objective, suboptimality).run(should_stop) {
+        SearchStatus::Solved(paths) => Some(paths),
+        SearchStatus::Running | SearchStatus::Exhausted => None,
+    }
+}
+
+/// Independence detection search that can be paused and resumed, like
+/// [`EcbsSearch`]. Stats add up the high-level nodes and low-level calls of
+/// every group search.
+///
+/// With the `parallel` feature, the first call to `run` or `step` plans
+/// every agent alone at once, on all cores, before it asks `should_stop`.
+pub struct IdSearch {
+    grid: Grid,
+    agents: Vec<AgentTask>,
+    objective: Objective,
+    suboptimality: f64,
+    /// Group of each agent
+    group_of: Vec<usize>,
+    /// Agents of each group, in order; empty once merged into another
+    groups: Vec<Vec<usize>>,
+    /// Current path of each agent; empty until its group is first solved
+    paths: Vec<Path>,
+    /// The group being solved, with its search
+    solving: Option<(usize, EcbsSearch)>,
+    /// Groups still to be solved on their own for the first time
+    unsolved: Vec<usize>,
+    /// Searches for the last groups in `unsolved`, in the same order, when
+    /// they are set up ahead of time on several threads
+    prepared: Vec<EcbsSearch>,
+    exhausted: bool,
+    /// Effort of the group searches already finished
+    stats: SearchStats,
+}
+
+impl IdSearch {
+    /// Set up a search for `agents` on `grid`, solving groups within a
+    /// factor `suboptimality` of their optimal `objective`; values below 1
+    /// count as 1.
+    pub fn new(
+        grid: &Grid,
+        agents: &[AgentTask],
+        objective: Objective,
+        suboptimality: f64,
+    ) -> Self {
+        Self {
+            grid: grid.clone(),
+            agents: agents.to_vec(),
+            objective,
+            suboptimality,
+            group_of: (0..agents.len()).collect(),
+            groups: (0..agents.len()).map(|agent| vec![agent]).collect(),
+            paths: vec![Path { steps: Vec::new() }; agents.len()],
+            solving: None,
+            unsolved: (0..agents.len()).rev().collect(),
+            prepared: Vec::new(),
+            exhausted: false,
+            stats: SearchStats::default(),
+        }
+    }
+
+    /// High-level nodes expanded so far.
+    pub fn nodes_expanded(&self) -> u64 {
+        self.stats().nodes_expanded
+    }
+
+    /// Search effort so far.
+    pub fn stats(&self) -> SearchStats {
+        let mut stats = self.stats;
+        if let Some((_, search)) = &self.solving {
+            stats.nodes_expanded += search.stats().nodes_expanded;
+            stats.nodes_generated += search.stats().nodes_generated;
+            stats.low_level_calls += search.stats().low_level_calls;
+        }
+        stats
+    }
+
+    /// Agents of each group planned together so far, with the groups of
+    /// more than one agent first.
+    pub fn groups(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<_> = self
+            .groups
+            .iter()
+            .filter(|g| !g.is_empty())
+            .cloned()
+            .collect();
+        groups.sort_by_key(|group| std::cmp::Reverse(group.len()));
+        groups
+    }
+
+    /// Expand at most `max_expansions` more high-level nodes.
+    pub fn step(&mut self, max_expansions: u64) -> SearchStatus {
+        let limit = self.nodes_expanded().saturating_add(max_expansions);
+        self.run(|progress| progress.nodes_expanded > limit)
+    }
+
+    /// Search until solved, exhausted, or `should_stop` returns `true` for
+    /// the node about to be expanded; the search can then be resumed.
+    /// `best_cost` combines the cost of the groups not being searched with
+    /// the lower bound of the one that is.
+    pub fn run(&mut self, mut should_stop: impl FnMut(SearchProgress) -> bool) -> SearchStatus {
+        if self.exhausted {
+            return SearchStatus::Exhausted;
+        }
+        loop {
+            if let Some((group, mut search)) = self.solving.take() {
+                let (done, settled) = (self.stats.nodes_expanded, self.settled_cost(group));
+                let objective = self.objective;
+                let status = search.run(|progress| {
+                    should_stop(SearchProgress {
+                        nodes_expanded: done + progress.nodes_expanded,
+                        best_cost: objective.total([settled, progress.best_cost]),
+                    })
+                });
+                match status {
+                    SearchStatus::Running => {
+                        self.solving = Some((group, search));
+                        return SearchStatus::Running;
+                    }
+                    SearchStatus::Exhausted => {
+                        self.exhausted = true;
+                        return SearchStatus::Exhausted;
+                    }
+                    SearchStatus::Solved(paths) => {
+                        for (&agent, path) in self.groups[group].iter().zip(paths) {
+                            self.paths[agent] = path;
+                        }
+                        self.stats.nodes_expanded += search.stats().nodes_expanded;
+                        self.stats.nodes_generated += search.stats().nodes_generated;
+                        self.stats.low_level_calls += search.stats().low_level_calls;
+                    }
+                }
+            }
+
+            // Agents alone don't affect each other, so plan them all at once
+            if parallel::ENABLED && self.prepared.is_empty() && self.unsolved.len() > 1 {
+                self.prepared = parallel::map(&self.unsolved, |&group| self.group_search(group));
+            }
+            let search = match self.unsolved.pop() {
+                Some(group) => {
+                    let search = self.prepared.pop();
+                    (group, search.unwrap_or_else(|| self.group_search(group)))
+                }
+                None => {
+                    let Some(conflict) =
+                        find_conflicts(&self.paths, self.grid.disappears_at_goal()).0
+                    else {
+                        return SearchStatus::Solved(self.paths.clone());
+                    };
+                    let (a, b) = conflict.agents;
+                    let group = self.merge(self.group_of[a], self.group_of[b]);
+                    (group, self.group_search(group))
+                }
+            };
+            self.solving = Some(search);
+        }
+    }
+
+    /// Search solving the agents of `group` together, ignoring the others.
+    fn group_search(&self, group: usize) -> EcbsSearch {
+        let agents: Vec<AgentTask> = self.groups[group]
+            .iter()
+            .map(|&agent| self.agents[agent])
+            .collect();
+        EcbsSearch::new(&self.grid, &agents, self.objective, self.suboptimality)
+    }
+
+    /// Merge group `b` into group `a`, keeping agents in order, and return
+    /// the merged group.
+    fn merge(&mut self, a: usize, b: usize) -> usize {
+        // Paths within a group never collide, so these always differ
+        debug_assert_ne!(a, b);
+        let moved = std::mem::take(&mut self.groups[b]);
+        for &agent in &moved {
+            self.group_of[agent] = a;
+        }
+        self.groups[a].extend(moved);
+        self.groups[a].sort_unstable();
+        a
+    }
+
+    /// Cost of the agents outside `group` under the objective; zero for
+    /// those not planned yet.
+    fn settled_cost(&self, group: usize) -> u32 {
+        let costs = self
+            .paths
+            .iter()
+            .zip(&self.group_of)
+            .filter(|&(_, &of)| of != group)
+            .map(|(path, _)| match self.objective {
+                Objective::SumOfCosts => self.grid.path_cost(path),
+                Objective::Makespan => path.cost(),
+            });
+        self.objective.total(costs)
+    }
+}
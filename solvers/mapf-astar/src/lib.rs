@@ -4,12 +4,64 @@
 //! 
 //! ## Features
 //! - Single-agent A* pathfinding
-//! - Multi-agent MAPF with step-by-step prioritized planning
+//! - Multi-agent MAPF with prioritized space-time A* planning
+//! - Safe interval path planning (SIPP) around moving agents, and
+//!   prioritized planning built on it
+//! - Weighted A* (`f = g + w*h`) for the single-agent and prioritized
+//!   searches: paths at most `w` times as long, found faster
+//! - Optimal centralized A* that can be paused and resumed (`CentralizedSearch`)
+//! - Bounded-suboptimal conflict-based search (ECBS, `EcbsSearch`); plain CBS
+//!   with a suboptimality factor of 1
+//! - Independence detection (`IdSearch`): agents planned alone, and only
+//!   the groups whose paths collide solved together with ECBS
+//! - Rule-based Push and Swap / Push and Rotate style planning for crowded
+//!   maps where searches run out of room
 //! - Grid struct for efficient map storage and reuse
-//! - Cardinal movement only (no diagonals)
+//! - Cardinal movement by default; octile (diagonal) movement without
+//!   corner cutting via `Grid::with_movement`
+//! - Terrain costs via `Grid::with_costs`: every action costs the entry
+//!   cost of the cell it ends on, and the searches minimize that instead
+//!   of timesteps
+//! - Exact distance-to-goal heuristics, computed once per goal and cached
+//!   in the grid (`Grid::heuristic_for`), so maze maps search as fast as
+//!   open ones
+//! - An optional precomputed neighbor table (`Grid::with_neighbor_table`)
+//!   for grids that are searched many times
+//! - Conflict analysis for any set of paths (`find_first_conflict`,
+//!   `count_conflicts`), the same checks conflict-based search splits on
+//! - Disappear-at-goal semantics via `Grid::with_disappear_at_goal`:
+//!   agents leave the map on reaching their goal, in every search and in
+//!   the conflict checks' `_with` variants
+//! - With the `parallel` feature (native only): the two children of an
+//!   ECBS split, and the agents independence detection first plans alone,
+//!   searched on several threads with rayon
+//!
+//! ## Targets
+//! - Browser: wasm-bindgen API (`wasm-pack build`)
+//! - Arena: `mapf-solver` WIT component (`--features component`, `wasm32-wasip2`)
 
 mod astar;
-#[cfg(target_arch = "wasm32")]
+mod bucket_queue;
+mod cbs;
+mod conflicts;
+mod independence;
+mod parallel;
+mod push_and_rotate;
+mod sipp;
+#[cfg(feature = "component")]
+mod component;
+#[cfg(all(target_arch = "wasm32", not(feature = "component")))]
 mod wasm;
 
-pub use astar::{astar_single, solve_mapf, solve_mapf_grid, solve_mapf_centralized, solve_mapf_centralized_grid, Coordinate, Grid, Path};
+pub use astar::{astar_single, astar_single_grid, astar_single_weighted, solve_mapf, solve_mapf_grid, solve_mapf_grid_weighted, solve_mapf_grid_with_stats, solve_mapf_centralized, solve_mapf_centralized_grid, solve_mapf_centralized_grid_with, CentralizedSearch, Coordinate, Grid, Movement, Objective, Path, SearchProgress, SearchStats, SearchStatus};
+pub use cbs::{solve_mapf_ecbs_grid, solve_mapf_ecbs_grid_with, EcbsSearch};
+pub use conflicts::{
+    count_conflicts, count_conflicts_with, find_first_conflict, find_first_conflict_with, Conflict,
+    ConflictKind,
+};
+pub use independence::{solve_mapf_id_grid, solve_mapf_id_grid_with, IdSearch};
+pub use push_and_rotate::{solve_mapf_push_and_rotate, solve_mapf_push_and_rotate_with_stats};
+pub use sipp::{
+    sipp_single, solve_mapf_sipp_grid, solve_mapf_sipp_grid_weighted,
+    solve_mapf_sipp_grid_with_stats,
+};
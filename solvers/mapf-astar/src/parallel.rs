@@ -0,0 +1,45 @@
+//! Concurrency for independent low-level searches.
+//!
+//! With the `parallel` feature on a native target the helpers here hand
+//! their work to rayon; otherwise, and always on wasm32, they run it in
+//! order on the calling thread. Either way the results are the same.
+
+/// Whether the helpers actually run work concurrently.
+pub(crate) const ENABLED: bool = cfg!(all(feature = "parallel", not(target_arch = "wasm32")));
+
+/// Run `a` and `b`, possibly at the same time, and return both results.
+pub(crate) fn join<A, B, RA, RB>(a: A, b: B) -> (RA, RB)
+where
+    A: FnOnce() -> RA + Send,
+    B: FnOnce() -> RB + Send,
+    RA: Send,
+    RB: Send,
+{
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        rayon::join(a, b)
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        (a(), b())
+    }
+}
+
+/// `f` applied to every item, possibly concurrently, with the results in
+/// the order of `items`.
+pub(crate) fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        items.iter().map(f).collect()
+    }
+}
@@ -0,0 +1,626 @@
+//! Rule-based MAPF for dense instances, in the style of Push and Swap /
+//! Push and Rotate.
+//!
+//! Agents move one at a time, each into an empty cell, so the plan never
+//! collides however crowded the map is. Agents are sent to their goals one
+//! after another in an order that keeps the rest of the map connected; once
+//! there they stay put. On the way an agent *pushes* whoever is in front of
+//! it towards the nearest empty cell, and if that isn't possible it *swaps*
+//! places with them at a junction and undoes everything else it moved.
+//! Where a junction has too little room to swap at, the two take turns on
+//! a cycle through it instead, *rotating* everyone on the cycle one cell
+//! along, all at once if the cycle is full.
+//! The sequential plan is then compressed so agents move at the same time
+//! wherever that is safe.
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use crate::astar::{neighbors_grid, AgentTask, Coordinate, Grid, Path, SearchStats};
+
+/// Solve MAPF by pushing, swapping and rotating agents.
+///
+/// Works where search-based planners run out of room, such as maps with
+/// only a couple of free cells, but paths are far from the shortest, and
+/// terrain costs on weighted grids are ignored.
+/// Agents in an area need two cells left free there to swap, or a cycle
+/// to rotate on.
+/// Returns `None` for invalid or shared starts and goals, or if the rules
+/// get stuck, e.g. when a goal cuts the map in two and no order of the
+/// goals avoids it. Agents stay at their goals even on grids where they
+/// may disappear there, which collides with no one either way.
+pub fn solve_mapf_push_and_rotate(grid: &Grid, agents: &[AgentTask]) -> Option<Vec<Path>> {
+    solve_mapf_push_and_rotate_with_stats(grid, agents, &mut SearchStats::default())
+}
+
+/// Like [`solve_mapf_push_and_rotate`], adding the effort spent to `stats`:
+/// one low-level call per breadth-first search for a path or an empty
+/// cell, and the cells those searches expanded and generated.
+pub fn solve_mapf_push_and_rotate_with_stats(
+    grid: &Grid,
+    agents: &[AgentTask],
+    stats: &mut SearchStats,
+) -> Option<Vec<Path>> {
+    let cell = |(x, y): (u32, u32)| {
+        grid.is_passable(x, y)
+            .then_some((y * grid.width + x) as usize)
+    };
+    let starts = agents
+        .iter()
+        .map(|&(start, _)| cell(start))
+        .collect::<Option<Vec<_>>>()?;
+    let goals = agents
+        .iter()
+        .map(|&(_, goal)| cell(goal))
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut board = Board::new(grid, &starts)?;
+    let solved = goal_order(&board.adjacent, &goals)?
+        .into_iter()
+        .all(|agent| {
+            let reached = board.push_to(agent, goals[agent]);
+            board.fixed[goals[agent]] = true;
+            reached
+        });
+    let effort = board.stats.get();
+    stats.nodes_expanded += effort.nodes_expanded;
+    stats.nodes_generated += effort.nodes_generated;
+    stats.low_level_calls += effort.low_level_calls;
+    solved.then(|| board.schedule(&starts))
+}
+
+/// Agents in the order they go to their goals: each goal filled leaves the
+/// remaining free cells connected where possible, dead ends first.
+/// `None` if two agents share a goal.
+fn goal_order(adjacent: &[Vec<usize>], goals: &[usize]) -> Option<Vec<usize>> {
+    let mut removed = vec![false; adjacent.len()];
+    for &goal in goals {
+        if std::mem::replace(&mut removed[goal], true) {
+            return None;
+        }
+    }
+    removed.fill(false);
+
+    let mut remaining: Vec<usize> = (0..goals.len()).collect();
+    let mut order = Vec::with_capacity(goals.len());
+    while !remaining.is_empty() {
+        let cuts = cut_cells(adjacent, &removed);
+        let degree = |cell: usize| adjacent[cell].iter().filter(|&&n| !removed[n]).count();
+        let (index, _) = remaining.iter().enumerate().min_by_key(|&(_, &agent)| {
+            let goal = goals[agent];
+            (cuts[goal], degree(goal), agent)
+        })?;
+        let agent = remaining.remove(index);
+        removed[goals[agent]] = true;
+        order.push(agent);
+    }
+    Some(order)
+}
+
+/// Which cells would split the cells that aren't `removed` into more
+/// areas if they were removed too (articulation points).
+fn cut_cells(adjacent: &[Vec<usize>], removed: &[bool]) -> Vec<bool> {
+    let cells = adjacent.len();
+    let mut cut = vec![false; cells];
+    // Discovery order and lowest reachable discovery order per cell;
+    // 0 means not visited yet
+    let mut order = vec![0; cells];
+    let mut low = vec![0; cells];
+    let mut visited = 0;
+    for root in 0..cells {
+        if removed[root] || order[root] != 0 {
+            continue;
+        }
+        visited += 1;
+        order[root] = visited;
+        low[root] = visited;
+        let mut root_children = 0;
+        // (cell, parent, next neighbor to look at)
+        let mut stack = vec![(root, usize::MAX, 0)];
+        while let Some(&mut (cell, parent, ref mut next)) = stack.last_mut() {
+            if let Some(&neighbor) = adjacent[cell].get(*next) {
+                *next += 1;
+                if removed[neighbor] || neighbor == parent {
+                    continue;
+                }
+                if order[neighbor] == 0 {
+                    visited += 1;
+                    order[neighbor] = visited;
+                    low[neighbor] = visited;
+                    stack.push((neighbor, cell, 0));
+                } else {
+                    low[cell] = low[cell].min(order[neighbor]);
+                }
+                continue;
+            }
+            stack.pop();
+            if parent == usize::MAX {
+                continue;
+            }
+            low[parent] = low[parent].min(low[cell]);
+            if parent == root {
+                root_children += 1;
+            } else if low[cell] >= order[parent] {
+                cut[parent] = true;
+            }
+        }
+        cut[root] = root_children > 1;
+    }
+    cut
+}
+
+/// Agents on the map, as they are moved one at a time.
+struct Board {
+    width: u32,
+    /// Passable neighbors of each cell
+    adjacent: Vec<Vec<usize>>,
+    /// Agent on each cell
+    occupant: Vec<Option<usize>>,
+    /// Cell of each agent
+    positions: Vec<usize>,
+    /// Cells of agents that reached their goal and mustn't move again
+    fixed: Vec<bool>,
+    /// Every move made so far, as (agent, from, to)
+    moves: Vec<(usize, usize, usize)>,
+    /// Runs of `moves` that rotate a full cycle, so happen all at once
+    rotations: Vec<Range<usize>>,
+    /// Effort spent by `path_to`
+    stats: Cell<SearchStats>,
+}
+
+impl Board {
+    /// `None` if two agents start on the same cell.
+    fn new(grid: &Grid, starts: &[usize]) -> Option<Self> {
+        let cells = (grid.width as usize) * (grid.height as usize);
+        let adjacent = (0..cells)
+            .map(|cell| {
+                let at = Coordinate {
+                    x: (cell % grid.width as usize) as u32,
+                    y: (cell / grid.width as usize) as u32,
+                };
+                if !grid.is_passable(at.x, at.y) {
+                    return Vec::new();
+                }
+                neighbors_grid(at, grid)
+                    .iter()
+                    .map(|&(n, _)| (n.y * grid.width + n.x) as usize)
+                    .collect()
+            })
+            .collect();
+        let mut occupant = vec![None; cells];
+        for (agent, &start) in starts.iter().enumerate() {
+            if occupant[start].replace(agent).is_some() {
+                return None;
+            }
+        }
+        Some(Self {
+            width: grid.width,
+            adjacent,
+            occupant,
+            positions: starts.to_vec(),
+            fixed: vec![false; cells],
+            moves: Vec::new(),
+            rotations: Vec::new(),
+            stats: Cell::default(),
+        })
+    }
+
+    /// Move `agent` to the empty neighboring cell `to`.
+    fn step(&mut self, agent: usize, to: usize) {
+        let from = self.positions[agent];
+        debug_assert!(self.occupant[to].is_none() && self.adjacent[from].contains(&to));
+        self.occupant[from] = None;
+        self.occupant[to] = Some(agent);
+        self.positions[agent] = to;
+        self.moves.push((agent, from, to));
+    }
+
+    /// Move every agent on `cycle` to the next cell along it at once; the
+    /// last cell's agent moves to the first. The cycle must be full.
+    fn rotate(&mut self, cycle: &[usize]) {
+        let agents: Vec<usize> = cycle
+            .iter()
+            .map(|&cell| self.occupant[cell].expect("rotated cycles are full"))
+            .collect();
+        let start = self.moves.len();
+        for (i, &agent) in agents.iter().enumerate() {
+            let (from, to) = (cycle[i], cycle[(i + 1) % cycle.len()]);
+            debug_assert!(self.adjacent[from].contains(&to));
+            self.occupant[to] = Some(agent);
+            self.positions[agent] = to;
+            self.moves.push((agent, from, to));
+        }
+        self.rotations.push(start..self.moves.len());
+    }
+
+    /// Take back moves until only `count` are left.
+    fn undo_to(&mut self, count: usize) {
+        for (agent, from, to) in self.moves.drain(count..).rev() {
+            // Taking back a rotation, `to` may already hold the agent that
+            // was there before
+            if self.occupant[to] == Some(agent) {
+                self.occupant[to] = None;
+            }
+            self.occupant[from] = Some(agent);
+            self.positions[agent] = from;
+        }
+        while self.rotations.last().is_some_and(|run| run.end > count) {
+            self.rotations.pop();
+        }
+    }
+
+    /// Shortest path of cells from `from` to the nearest cell `is_target`
+    /// accepts, through cells that aren't `avoid`ed.
+    fn path_to(
+        &self,
+        from: usize,
+        is_target: impl Fn(usize) -> bool,
+        avoid: impl Fn(usize) -> bool,
+    ) -> Option<Vec<usize>> {
+        let mut stats = self.stats.get();
+        stats.low_level_calls += 1;
+        let mut parents = vec![usize::MAX; self.adjacent.len()];
+        parents[from] = from;
+        let mut queue = VecDeque::from([from]);
+        let found = loop {
+            let Some(cell) = queue.pop_front() else {
+                break None;
+            };
+            stats.nodes_expanded += 1;
+            if cell != from && is_target(cell) {
+                let mut path = vec![cell];
+                let mut at = cell;
+                while at != from {
+                    at = parents[at];
+                    path.push(at);
+                }
+                path.reverse();
+                break Some(path);
+            }
+            for &next in &self.adjacent[cell] {
+                if parents[next] == usize::MAX && !avoid(next) {
+                    parents[next] = cell;
+                    queue.push_back(next);
+                    stats.nodes_generated += 1;
+                }
+            }
+        };
+        self.stats.set(stats);
+        found
+    }
+
+    /// Empty `cell` by pushing agents along the way to the nearest empty
+    /// cell, without passing through `blocked`.
+    fn clear(&mut self, cell: usize, blocked: &[usize]) -> bool {
+        if self.occupant[cell].is_none() {
+            return true;
+        }
+        let Some(path) = self.path_to(
+            cell,
+            |c| self.occupant[c].is_none(),
+            |c| self.fixed[c] || blocked.contains(&c),
+        ) else {
+            return false;
+        };
+        // Everyone between `cell` and the empty cell shifts one along,
+        // starting from the far end
+        for i in (0..path.len() - 1).rev() {
+            if let Some(agent) = self.occupant[path[i]] {
+                self.step(agent, path[i + 1]);
+            }
+        }
+        true
+    }
+
+    /// Move `agent` to `goal`, pushing and swapping whoever is in the way.
+    /// Agents at their goals are only passed if there's no way around, and
+    /// put back right after.
+    fn push_to(&mut self, agent: usize, goal: usize) -> bool {
+        let from = self.positions[agent];
+        let Some(path) = self
+            .path_to(from, |c| c == goal, |c| self.fixed[c])
+            .or_else(|| self.path_to(from, |c| c == goal, |_| false))
+        else {
+            return from == goal;
+        };
+        // Agents swapped off their goals on the way, with those goals
+        let mut displaced = Vec::new();
+        for &next in &path[1..] {
+            let mut blocked: Vec<usize> = displaced
+                .iter()
+                .map(|&(other, _)| self.positions[other])
+                .collect();
+            blocked.push(self.positions[agent]);
+            if !self.fixed[next] && self.clear(next, &blocked) {
+                self.step(agent, next);
+            } else {
+                let other = self.occupant[next].expect("uncleared cells are occupied");
+                if !self.swap(agent, other) {
+                    return false;
+                }
+                if self.fixed[next] {
+                    displaced.push((other, next));
+                    continue;
+                }
+            }
+            if !self.restore(&mut displaced) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Put agents swapped off their goals back, the last one first: each
+    /// steps forward into its goal, or swaps with whoever took it.
+    fn restore(&mut self, displaced: &mut Vec<(usize, usize)>) -> bool {
+        while let Some((agent, goal)) = displaced.pop() {
+            match self.occupant[goal] {
+                None => self.step(agent, goal),
+                Some(other) => {
+                    if !self.swap(agent, other) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Exchange the cells of neighboring agents `a` and `b`, leaving
+    /// everyone else where they were.
+    fn swap(&mut self, a: usize, b: usize) -> bool {
+        // Agents at their goals may make room too; they end up back there
+        let fixed = std::mem::replace(&mut self.fixed, vec![false; self.adjacent.len()]);
+        let swapped = self.swap_anywhere(a, b);
+        self.fixed = fixed;
+        swapped
+    }
+
+    fn swap_anywhere(&mut self, a: usize, b: usize) -> bool {
+        // Junctions: cells with at least three neighbors
+        let junction = |c: usize| self.adjacent[c].len() >= 3;
+        let mut junctions = Vec::new();
+        let mut seen = vec![false; self.adjacent.len()];
+        seen[self.positions[a]] = true;
+        let mut queue = VecDeque::from([self.positions[a]]);
+        while let Some(cell) = queue.pop_front() {
+            if junction(cell) {
+                junctions.push(cell);
+            }
+            for &next in &self.adjacent[cell] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        // Swapping needs two free neighbors of a junction; rotating on a
+        // cycle through it needs no free cell at all
+        for rotate in [false, true] {
+            for &junction in &junctions {
+                let before = self.moves.len();
+                let exchanged = if rotate {
+                    self.rotate_at(a, b, junction)
+                } else {
+                    self.swap_at(a, b, junction)
+                };
+                if exchanged {
+                    return true;
+                }
+                self.undo_to(before);
+            }
+        }
+        false
+    }
+
+    /// Bring `a` and `b` to `junction`, one on it and the other right
+    /// behind, returned as (lead, follow).
+    fn bring(&mut self, a: usize, b: usize, junction: usize) -> Option<(usize, usize)> {
+        let route = |board: &Self, lead: usize, follow: usize| {
+            let behind = board.positions[follow];
+            board.path_to(board.positions[lead], |c| c == junction, |c| c == behind)
+        };
+        // Whichever of the two can get there without passing the other leads
+        let (lead, follow, path) = match (route(self, a, b), route(self, b, a)) {
+            (Some(p), Some(q)) if q.len() < p.len() => (b, a, q),
+            (Some(p), _) => (a, b, p),
+            (None, Some(q)) => (b, a, q),
+            (None, None) if self.positions[a] == junction => (a, b, vec![junction]),
+            (None, None) if self.positions[b] == junction => (b, a, vec![junction]),
+            (None, None) => return None,
+        };
+        for &next in &path[1..] {
+            let (ahead, behind) = (self.positions[lead], self.positions[follow]);
+            if !self.clear(next, &[ahead, behind]) {
+                return None;
+            }
+            self.step(lead, next);
+            self.step(follow, ahead);
+        }
+        Some((lead, follow))
+    }
+
+    /// Swap `a` and `b` at `junction`: bring them there one behind the
+    /// other, clear two more neighbors of it, swap, then replay every other
+    /// move backwards.
+    fn swap_at(&mut self, a: usize, b: usize, junction: usize) -> bool {
+        let before = self.moves.len();
+        let Some((lead, follow)) = self.bring(a, b, junction) else {
+            return false;
+        };
+        let behind = self.positions[follow];
+        let Some((first, second)) = self.clear_sides(junction, behind) else {
+            return false;
+        };
+
+        let brought = self.moves.len();
+        self.step(lead, first);
+        self.step(follow, junction);
+        self.step(follow, second);
+        self.step(lead, junction);
+        self.step(lead, behind);
+        self.step(follow, junction);
+        self.replay_exchanged(before..brought, lead, follow);
+        true
+    }
+
+    /// Swap `a` and `b` on a cycle through `junction`: bring them there one
+    /// behind the other, and step the one in front onto the cycle and the
+    /// other onto the junction. Rotating the cycle then carries the second
+    /// on round it and the first back to the junction, from where it steps
+    /// out behind. The rest of the cycle rotates back, and every other move
+    /// is replayed backwards.
+    fn rotate_at(&mut self, a: usize, b: usize, junction: usize) -> bool {
+        let before = self.moves.len();
+        let Some((lead, follow)) = self.bring(a, b, junction) else {
+            return false;
+        };
+        let behind = self.positions[follow];
+        let brought = self.moves.len();
+        for &entry in &self.adjacent[junction].clone() {
+            if entry == behind {
+                continue;
+            }
+            let Some(cycle) = self.cycle_through(junction, entry, behind) else {
+                continue;
+            };
+            if !self.clear(entry, &[junction, behind]) {
+                self.undo_to(brought);
+                continue;
+            }
+
+            let cleared = self.moves.len();
+            self.step(lead, entry);
+            self.step(follow, junction);
+            self.advance(&cycle);
+            self.step(lead, behind);
+            self.step(follow, junction);
+            // The others are one cell along; starting from the cell
+            // `follow` left, each steps back into the one before
+            for i in 3..=cycle.len() {
+                if let Some(other) = self.occupant[cycle[i % cycle.len()]] {
+                    self.step(other, cycle[i - 1]);
+                }
+            }
+            self.replay_exchanged(before..cleared, lead, follow);
+            return true;
+        }
+        false
+    }
+
+    /// Replay `moves` backwards, with `lead` and `follow` in each other's
+    /// place: after exchanging the two, everything is as it was after those
+    /// moves, with the two exchanged.
+    fn replay_exchanged(&mut self, moves: Range<usize>, lead: usize, follow: usize) {
+        for i in moves.rev() {
+            let (agent, from, _) = self.moves[i];
+            let agent = match agent {
+                _ if agent == lead => follow,
+                _ if agent == follow => lead,
+                other => other,
+            };
+            self.step(agent, from);
+        }
+    }
+
+    /// A cycle of cells through `entry`, `junction` and on round back to
+    /// `entry`, without passing `behind`; ordered in that direction.
+    fn cycle_through(&self, junction: usize, entry: usize, behind: usize) -> Option<Vec<usize>> {
+        let exit = |c: usize| c != entry && c != behind && self.adjacent[junction].contains(&c);
+        let path = self.path_to(entry, exit, |c| c == junction || c == behind)?;
+        let mut cycle = vec![entry, junction];
+        cycle.extend(path[1..].iter().rev());
+        Some(cycle)
+    }
+
+    /// Move everyone on `cycle` one cell along it: one at a time into a
+    /// free cell if it has one, otherwise all at once.
+    fn advance(&mut self, cycle: &[usize]) {
+        let n = cycle.len();
+        let Some(free) = (0..n).find(|&i| self.occupant[cycle[i]].is_none()) else {
+            self.rotate(cycle);
+            return;
+        };
+        for k in 1..n {
+            let i = (free + n - k) % n;
+            if let Some(agent) = self.occupant[cycle[i]] {
+                self.step(agent, cycle[(i + 1) % n]);
+            }
+        }
+    }
+
+    /// Empty two neighbors of `junction` other than `behind`, trying each
+    /// pair in each order: clearing the first may push agents through the
+    /// second, as long as the second can be cleared after.
+    fn clear_sides(&mut self, junction: usize, behind: usize) -> Option<(usize, usize)> {
+        let sides: Vec<usize> = self.adjacent[junction]
+            .iter()
+            .copied()
+            .filter(|&c| c != behind)
+            .collect();
+        let before = self.moves.len();
+        for &first in &sides {
+            for &second in &sides {
+                if first != second
+                    && self.clear(first, &[junction, behind])
+                    && self.clear(second, &[junction, behind, first])
+                {
+                    return Some((first, second));
+                }
+                self.undo_to(before);
+            }
+        }
+        None
+    }
+
+    /// Compress the moves made into paths from `starts`, moving each agent
+    /// as early as it can: as soon as its last move is done and whoever was
+    /// on the next cell has left, or with the rest of its rotation.
+    fn schedule(&self, starts: &[usize]) -> Vec<Path> {
+        let coordinate = |cell: usize| Coordinate {
+            x: (cell % self.width as usize) as u32,
+            y: (cell / self.width as usize) as u32,
+        };
+        let mut arrivals: Vec<Vec<(u32, usize)>> = starts.iter().map(|&s| vec![(0, s)]).collect();
+        let mut vacated = vec![0; self.adjacent.len()];
+        let mut rotations = self.rotations.iter().peekable();
+        let mut next = 0;
+        while next < self.moves.len() {
+            let run = match rotations.next_if(|run| run.start == next) {
+                Some(run) => run.clone(),
+                None => next..next + 1,
+            };
+            // A rotation moves everyone on it together, once all are ready
+            let moves = &self.moves[run.clone()];
+            let t = moves
+                .iter()
+                .map(|&(agent, _, to)| {
+                    let (ready, _) = *arrivals[agent].last().expect("agents have a start");
+                    (ready + 1).max(vacated[to])
+                })
+                .max()
+                .expect("runs hold a move");
+            for &(agent, from, to) in moves {
+                vacated[from] = t;
+                arrivals[agent].push((t, to));
+            }
+            next = run.end;
+        }
+
+        arrivals
+            .into_iter()
+            .map(|timeline| {
+                let mut steps = Vec::new();
+                for (t, cell) in timeline {
+                    while steps.len() < t as usize {
+                        steps.push(*steps.last().expect("timelines start at 0"));
+                    }
+                    steps.push(coordinate(cell));
+                }
+                Path { steps }
+            })
+            .collect()
+    }
+}
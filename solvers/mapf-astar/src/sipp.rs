@@ -0,0 +1,286 @@
+//! Safe interval path planning (SIPP).
+//!
+//! Rather than searching every (cell, timestep) pair, SIPP searches over
+//! (cell, safe interval) pairs: the maximal runs of timesteps during which
+//! no other agent is on a cell. Waiting is folded into the moves, so an
+//! agent held up by an obstacle that stays put for a long time costs one
+//! node instead of one per timestep waited. The paths are the same as
+//! space-time A* finds.
+//!
+//! On weighted grids the earliest arrival in a safe interval is no longer
+//! the cheapest way on, since waiting costs more on some cells than on
+//! others; there the searches fall back to space-time A*.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::astar::{
+    neighbors_grid, plan_around, scaled, solve_mapf_grid_weighted, AgentTask, Coordinate, Grid,
+    Path, ReservationTable, SearchStats,
+};
+use crate::bucket_queue::BucketQueue;
+
+/// Plan a shortest path from `start` to `goal` that never collides with
+/// the `obstacles`, other agents moving along their paths and staying at
+/// the end of them for good, or leaving the map there if `grid` lets agents
+/// disappear at their goal. The path ends where the agent can stay.
+///
+/// Returns `None` if there is no such path.
+pub fn sipp_single(
+    grid: &Grid,
+    start: (u32, u32),
+    goal: (u32, u32),
+    obstacles: &[Path],
+) -> Option<Path> {
+    if grid.is_weighted() {
+        let reserved = ReservationTable::new(grid, obstacles);
+        let stats = &mut SearchStats::default();
+        return plan_around(grid, start.into(), goal.into(), &reserved, 1.0, stats);
+    }
+    let intervals = SafeIntervals::new(grid, obstacles);
+    plan(
+        grid,
+        start.into(),
+        goal.into(),
+        &intervals,
+        1.0,
+        &mut SearchStats::default(),
+    )
+}
+
+/// Solve MAPF with prioritized planning, each agent planned with SIPP
+/// around the agents before it.
+///
+/// Gives the same paths as [`solve_mapf_grid`](crate::solve_mapf_grid), but
+/// faster where agents wait a long time or park early on long paths.
+/// Returns `None` if any agent can't be planned.
+pub fn solve_mapf_sipp_grid(grid: &Grid, agents: &[AgentTask]) -> Option<Vec<Path>> {
+    solve_mapf_sipp_grid_with_stats(grid, agents, &mut SearchStats::default())
+}
+
+/// Like [`solve_mapf_sipp_grid`], adding the effort spent to `stats`: the
+/// (cell, interval) nodes expanded and generated over all agents.
+pub fn solve_mapf_sipp_grid_with_stats(
+    grid: &Grid,
+    agents: &[AgentTask],
+    stats: &mut SearchStats,
+) -> Option<Vec<Path>> {
+    solve_mapf_sipp_grid_weighted(grid, agents, 1.0, stats)
+}
+
+/// Like [`solve_mapf_sipp_grid_with_stats`], ordering each agent's search
+/// by `f = g + weight * h`.
+///
+/// Each path costs at most `weight` times the shortest path around the
+/// agents planned before it; values below 1 count as 1.
+pub fn solve_mapf_sipp_grid_weighted(
+    grid: &Grid,
+    agents: &[AgentTask],
+    weight: f64,
+    stats: &mut SearchStats,
+) -> Option<Vec<Path>> {
+    if grid.is_weighted() {
+        return solve_mapf_grid_weighted(grid, agents, weight, stats);
+    }
+    let weight = weight.max(1.0);
+    let mut intervals = SafeIntervals::new(grid, &[]);
+    let mut paths = Vec::with_capacity(agents.len());
+    for &(start, goal) in agents {
+        let path = plan(grid, start.into(), goal.into(), &intervals, weight, stats)?;
+        intervals.add(&path);
+        paths.push(path);
+    }
+    Some(paths)
+}
+
+/// Timesteps at which the cells are taken by planned agents.
+#[derive(Debug, Default)]
+struct SafeIntervals {
+    /// Timesteps each cell is passed through
+    occupied: HashMap<Coordinate, BTreeSet<u32>>,
+    /// First timestep an agent settles on each cell for good
+    parked: HashMap<Coordinate, u32>,
+    /// Moves made, by (from, to, arrival timestep)
+    moves: HashSet<(Coordinate, Coordinate, u32)>,
+    /// Whether agents vanish at the end of their path instead of parking
+    disappear_at_goal: bool,
+}
+
+impl SafeIntervals {
+    fn new(grid: &Grid, paths: &[Path]) -> Self {
+        let mut intervals = Self {
+            disappear_at_goal: grid.disappears_at_goal(),
+            ..Self::default()
+        };
+        for path in paths {
+            intervals.add(path);
+        }
+        intervals
+    }
+
+    fn add(&mut self, path: &Path) {
+        let Some((&end, rest)) = path.steps.split_last() else {
+            return;
+        };
+        for (t, &cell) in rest.iter().enumerate() {
+            self.occupied.entry(cell).or_default().insert(t as u32);
+        }
+        for (t, step) in path.steps.windows(2).enumerate() {
+            self.moves.insert((step[0], step[1], t as u32 + 1));
+        }
+        let arrival = rest.len() as u32;
+        if self.disappear_at_goal {
+            self.occupied.entry(end).or_default().insert(arrival);
+        } else {
+            let since = self.parked.entry(end).or_insert(u32::MAX);
+            *since = (*since).min(arrival);
+        }
+    }
+
+    /// The safe intervals of `cell` in order, as inclusive (first, last)
+    /// timesteps; the last one ends at `u32::MAX` unless an agent parks.
+    fn of(&self, cell: Coordinate) -> Vec<(u32, u32)> {
+        let parked = self.parked.get(&cell).copied();
+        let mut intervals = Vec::new();
+        let mut first = 0;
+        let taken = self.occupied.get(&cell).into_iter().flatten().copied();
+        for t in taken.take_while(|&t| parked.is_none_or(|since| t < since)) {
+            if t > first {
+                intervals.push((first, t - 1));
+            }
+            first = t + 1;
+        }
+        match parked {
+            Some(since) if since > first => intervals.push((first, since - 1)),
+            Some(_) => {}
+            None => intervals.push((first, u32::MAX)),
+        }
+        intervals
+    }
+
+    /// Whether moving `from` -> `to`, arriving at timestep `t`, swaps
+    /// places with another agent.
+    fn swaps(&self, from: Coordinate, to: Coordinate, t: u32) -> bool {
+        self.moves.contains(&(to, from, t))
+    }
+}
+
+/// Search node: the agent arrived on `cell` at timestep `t`, as early as it
+/// can within the `interval`-th safe interval of the cell.
+struct Node {
+    cell: Coordinate,
+    interval: usize,
+    t: u32,
+    parent: Option<usize>,
+}
+
+/// SIPP for one agent around `intervals`, with the heuristic scaled by
+/// `weight`, counting effort in `stats`.
+fn plan(
+    grid: &Grid,
+    start: Coordinate,
+    goal: Coordinate,
+    intervals: &SafeIntervals,
+    weight: f64,
+    stats: &mut SearchStats,
+) -> Option<Path> {
+    stats.low_level_calls += 1;
+    if !grid.is_passable(start.x, start.y) {
+        return None;
+    }
+    let distances = grid.heuristic_for(goal);
+    let distance = |c: Coordinate| distances[(c.y * grid.width + c.x) as usize];
+    if distance(start) == u32::MAX {
+        return None;
+    }
+    let mut safe: HashMap<Coordinate, Vec<(u32, u32)>> = HashMap::new();
+    let mut safe_of = |cell: Coordinate| {
+        safe.entry(cell)
+            .or_insert_with(|| intervals.of(cell))
+            .clone()
+    };
+    if safe_of(start).first().is_none_or(|&(first, _)| first > 0) {
+        return None;
+    }
+    // The agent can only stop once the goal is free for good, unless it
+    // disappears there
+    let disappear = intervals.disappear_at_goal;
+    let finish = match safe_of(goal).last() {
+        _ if disappear => 0,
+        Some(&(first, u32::MAX)) => first,
+        _ => return None,
+    };
+    let heuristic =
+        |cell: Coordinate, t: u32| scaled(weight, distance(cell).max(finish.saturating_sub(t)));
+
+    let mut nodes = vec![Node {
+        cell: start,
+        interval: 0,
+        t: 0,
+        parent: None,
+    }];
+    let mut open = BucketQueue::new();
+    open.push(heuristic(start, 0), 0);
+    // Earliest arrival found so far in each (cell, interval)
+    let mut arrivals = HashMap::from([((start, 0), 0)]);
+    while let Some((_, id)) = open.pop() {
+        let (cell, interval, t) = (nodes[id].cell, nodes[id].interval, nodes[id].t);
+        if arrivals[&(cell, interval)] < t {
+            continue;
+        }
+        stats.nodes_expanded += 1;
+        let (_, last) = safe_of(cell)[interval];
+        if cell == goal && (disappear || last == u32::MAX) {
+            return Some(unwind(&nodes, id));
+        }
+
+        // Last to first, as the queue hands out equal f-values last in,
+        // first out
+        for &(next, _) in neighbors_grid(cell, grid).iter().rev() {
+            for (index, (first, end)) in safe_of(next).into_iter().enumerate() {
+                if first > last.saturating_add(1) {
+                    break;
+                }
+                // Wait here as long as it is safe, then step over; skip
+                // arrival times that would swap places with someone
+                let latest = end.min(last.saturating_add(1));
+                let Some(arrival) =
+                    (first.max(t + 1)..=latest).find(|&at| !intervals.swaps(cell, next, at))
+                else {
+                    continue;
+                };
+                if arrivals
+                    .get(&(next, index))
+                    .is_some_and(|&best| best <= arrival)
+                {
+                    continue;
+                }
+                arrivals.insert((next, index), arrival);
+                nodes.push(Node {
+                    cell: next,
+                    interval: index,
+                    t: arrival,
+                    parent: Some(id),
+                });
+                let f = arrival.saturating_add(heuristic(next, arrival));
+                open.push(f, nodes.len() - 1);
+                stats.nodes_generated += 1;
+            }
+        }
+    }
+    None
+}
+
+/// The path to node `id`, waiting out the gaps between arrivals.
+fn unwind(nodes: &[Node], id: usize) -> Path {
+    let mut steps = Vec::new();
+    let mut at = id;
+    while let Some(parent) = nodes[at].parent {
+        steps.push(nodes[at].cell);
+        let waits = nodes[at].t - nodes[parent].t - 1;
+        steps.extend(std::iter::repeat_n(nodes[parent].cell, waits as usize));
+        at = parent;
+    }
+    steps.push(nodes[at].cell);
+    steps.reverse();
+    Path { steps }
+}
@@ -1,8 +1,202 @@
 //! WASM bindings for the A* solver.
 
-use js_sys::Uint32Array;
+use js_sys::{Array, Date, Function, Object, Reflect, Uint32Array, Uint8Array};
+use mapf_core::validate::{validate, Violation};
+use mapf_core::{GridMap, Scenario};
 use wasm_bindgen::prelude::*;
-use crate::astar::{solve_mapf_centralized_grid, Grid};
+use wasm_bindgen::JsCast;
+use crate::astar::{
+    solve_mapf_grid_weighted, CentralizedSearch, Grid, Movement, Objective, Path, SearchProgress,
+    SearchStats, SearchStatus,
+};
+use crate::cbs::EcbsSearch;
+use crate::independence::IdSearch;
+use crate::push_and_rotate::solve_mapf_push_and_rotate_with_stats;
+use crate::sipp::solve_mapf_sipp_grid_weighted;
+
+/// Search algorithm chosen via the `algorithm` solve option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum Algorithm {
+    /// Prioritized space-time A* planning; fast but incomplete
+    #[default]
+    Prioritized,
+    /// Prioritized planning with safe interval path planning; same paths,
+    /// faster on long ones
+    Sipp,
+    /// Rule-based Push and Rotate; for crowded maps, with long paths
+    PushAndRotate,
+    /// Optimal A* over joint states; exponential in the number of agents
+    Centralized,
+    /// Optimal conflict-based search
+    Cbs,
+    /// Conflict-based search within the `suboptimality` factor of the
+    /// optimal cost
+    Ecbs,
+    /// Independence detection, solving only the groups of agents that
+    /// collide with ECBS
+    Id,
+}
+
+/// Options accepted by [`MapfSolver::solve`] as a plain JS object:
+///
+/// ```js
+/// solver.solve(starts, goals, {
+///   // "prioritized" | "sipp" | "push-and-rotate" | "centralized" | "cbs" | "ecbs" | "id"
+///   algorithm: "prioritized",
+///   objective: "makespan",    // "sum-of-costs" | "makespan"
+///   movement: "octile",       // "cardinal" | "octile" (diagonals too)
+///   suboptimality: 1.5,       // "ecbs" and "id", at least 1 (default 1.2)
+///   weight: 2,                // "prioritized" and "sipp" heuristic, at least 1 (default 1)
+///   timeBudgetMs: 500,
+///   // Called every `progressInterval` expansions (default 1000)
+///   onProgress: ({ nodesExpanded, bestCost }) => { ... },
+///   progressInterval: 1000,
+/// });
+/// ```
+///
+/// Every field is optional; omitting the object plans with prioritized
+/// planning for sum of costs without a time budget; optimal solutions need
+/// `"centralized"` or `"cbs"`. The centralized and conflict-based searches
+/// report progress; `bestCost` is their current lower bound on the optimal
+/// cost. Conflict-based searches count high-level nodes.
+#[derive(Debug, Clone)]
+struct SolveOptions {
+    algorithm: Algorithm,
+    objective: Objective,
+    movement: Movement,
+    suboptimality: f64,
+    weight: f64,
+    time_budget_ms: Option<f64>,
+    on_progress: Option<Function>,
+    progress_interval: u64,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            algorithm: Algorithm::default(),
+            objective: Objective::default(),
+            movement: Movement::default(),
+            suboptimality: 1.2,
+            weight: 1.0,
+            time_budget_ms: None,
+            on_progress: None,
+            progress_interval: 1000,
+        }
+    }
+}
+
+impl SolveOptions {
+    fn from_js(options: &JsValue) -> Result<Self, JsError> {
+        let mut parsed = SolveOptions::default();
+        if options.is_undefined() || options.is_null() {
+            return Ok(parsed);
+        }
+        if !options.is_object() {
+            return Err(JsError::new("options must be an object"));
+        }
+
+        if let Some(algorithm) = string_field(options, "algorithm")? {
+            parsed.algorithm = match algorithm.as_str() {
+                "prioritized" => Algorithm::Prioritized,
+                "sipp" => Algorithm::Sipp,
+                "push-and-rotate" => Algorithm::PushAndRotate,
+                "centralized" => Algorithm::Centralized,
+                "cbs" => Algorithm::Cbs,
+                "ecbs" => Algorithm::Ecbs,
+                "id" => Algorithm::Id,
+                other => return Err(JsError::new(&format!("Unknown algorithm: {other}"))),
+            };
+        }
+        if let Some(objective) = string_field(options, "objective")? {
+            parsed.objective = match objective.as_str() {
+                "sum-of-costs" => Objective::SumOfCosts,
+                "makespan" => Objective::Makespan,
+                other => return Err(JsError::new(&format!("Unknown objective: {other}"))),
+            };
+        }
+        if let Some(movement) = string_field(options, "movement")? {
+            parsed.movement = match movement.as_str() {
+                "cardinal" => Movement::Cardinal,
+                "octile" => Movement::Octile,
+                other => return Err(JsError::new(&format!("Unknown movement: {other}"))),
+            };
+        }
+        let suboptimality = field(options, "suboptimality")?;
+        if !suboptimality.is_undefined() && !suboptimality.is_null() {
+            parsed.suboptimality = suboptimality
+                .as_f64()
+                .filter(|&w| w >= 1.0)
+                .ok_or_else(|| JsError::new("suboptimality must be a number of at least 1"))?;
+        }
+        let weight = field(options, "weight")?;
+        if !weight.is_undefined() && !weight.is_null() {
+            parsed.weight = weight
+                .as_f64()
+                .filter(|&w| w >= 1.0)
+                .ok_or_else(|| JsError::new("weight must be a number of at least 1"))?;
+        }
+        let budget = field(options, "timeBudgetMs")?;
+        if !budget.is_undefined() && !budget.is_null() {
+            parsed.time_budget_ms = Some(
+                budget
+                    .as_f64()
+                    .ok_or_else(|| JsError::new("timeBudgetMs must be a number"))?,
+            );
+        }
+        let on_progress = field(options, "onProgress")?;
+        if !on_progress.is_undefined() && !on_progress.is_null() {
+            parsed.on_progress = Some(
+                on_progress
+                    .dyn_into()
+                    .map_err(|_| JsError::new("onProgress must be a function"))?,
+            );
+        }
+        let interval = field(options, "progressInterval")?;
+        if !interval.is_undefined() && !interval.is_null() {
+            parsed.progress_interval = interval
+                .as_f64()
+                .filter(|&n| n >= 1.0)
+                .ok_or_else(|| JsError::new("progressInterval must be a positive number"))?
+                as u64;
+        }
+        Ok(parsed)
+    }
+}
+
+/// Call `on_progress` with `{ nodesExpanded, bestCost }`.
+fn report_progress(on_progress: &Function, progress: SearchProgress) -> Result<(), JsValue> {
+    let report = Object::new();
+    Reflect::set(&report, &"nodesExpanded".into(), &(progress.nodes_expanded as f64).into())?;
+    Reflect::set(&report, &"bestCost".into(), &progress.best_cost.into())?;
+    on_progress.call1(&JsValue::NULL, &report)?;
+    Ok(())
+}
+
+/// Message of a thrown JS value, for wrapping it in a [`JsError`].
+fn js_message(value: &JsValue) -> String {
+    value
+        .dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| value.as_string())
+        .unwrap_or_else(|| format!("{value:?}"))
+}
+
+fn field(object: &JsValue, key: &str) -> Result<JsValue, JsError> {
+    Reflect::get(object, &JsValue::from_str(key))
+        .map_err(|_| JsError::new(&format!("Cannot read option {key}")))
+}
+
+fn string_field(object: &JsValue, key: &str) -> Result<Option<String>, JsError> {
+    let value = field(object, key)?;
+    if value.is_undefined() || value.is_null() {
+        return Ok(None);
+    }
+    value
+        .as_string()
+        .map(Some)
+        .ok_or_else(|| JsError::new(&format!("{key} must be a string")))
+}
 
 /// MAPF Solver that holds a pre-parsed grid for efficient reuse.
 #[wasm_bindgen]
@@ -36,8 +230,71 @@ impl MapfSolver {
         self.grid.height
     }
     
+    /// Make a cell passable or blocked in place, without rebuilding the
+    /// solver or copying the map again.
+    ///
+    /// `set_passable` invalidates the cached heuristic tables, so later
+    /// solves see the change; tasks already started keep searching the old
+    /// map.
+    pub fn set_cell(&mut self, x: u32, y: u32, passable: bool) -> Result<(), JsError> {
+        if !self.grid.set_passable(x, y, passable) {
+            return Err(JsError::new(&format!("Cell ({x}, {y}) is out of bounds")));
+        }
+        Ok(())
+    }
+    
+    /// Give every cell an entry cost (row-major, one per cell); searches
+    /// then minimize the sum of the costs of the cells agents move or wait
+    /// on. Tasks already started keep the old costs.
+    pub fn set_costs(&mut self, costs: &[u32]) -> Result<(), JsError> {
+        self.grid = self
+            .grid
+            .clone()
+            .with_costs(costs.to_vec())
+            .ok_or_else(|| JsError::new("Costs length doesn't match width*height"))?;
+        Ok(())
+    }
+    
+    /// Flip a cell between passable and blocked; returns whether it is
+    /// passable now.
+    pub fn toggle_obstacle(&mut self, x: u32, y: u32) -> Result<bool, JsError> {
+        let passable = !self.grid.is_passable(x, y);
+        self.set_cell(x, y, passable)?;
+        Ok(passable)
+    }
+    
     /// Solve for a specific set of agents on the pre-loaded map.
-    pub fn solve(&self, starts: &[u32], goals: &[u32]) -> Result<WasmSolution, JsError> {
+    ///
+    /// `options` selects the algorithm, objective and time budget and may
+    /// register a progress callback; see [`SolveOptions`] for the accepted
+    /// fields. A callback that throws aborts the solve.
+    pub fn solve(
+        &self,
+        starts: &[u32],
+        goals: &[u32],
+        options: JsValue,
+    ) -> Result<WasmSolution, JsError> {
+        let mut task = self.start(starts, goals, options)?;
+        loop {
+            if let Some(solution) = task.step(u32::MAX)? {
+                return Ok(solution);
+            }
+        }
+    }
+
+    /// Start a solve that runs in slices via [`SolveTask::step`], so long
+    /// searches can yield to the event loop and be cancelled in between.
+    ///
+    /// Takes the same `options` as [`MapfSolver::solve`]; the time budget
+    /// counts from this call.
+    pub fn start(
+        &self,
+        starts: &[u32],
+        goals: &[u32],
+        options: JsValue,
+    ) -> Result<SolveTask, JsError> {
+        let options = SolveOptions::from_js(&options)?;
+
         // Validate input
         if starts.len() != goals.len() || starts.len() % 2 != 0 {
             return Err(JsError::new("starts and goals must have same length and be pairs"));
@@ -54,42 +311,248 @@ impl MapfSolver {
             })
             .collect();
         
-        // Solve using the pre-parsed grid
-        match solve_mapf_centralized_grid(&self.grid, &agents) {
-            Some(paths) => {
-                let mut all_paths: Vec<u32> = Vec::new();
-                let mut total_cost: u32 = 0;
-                
-                for path in &paths {
-                    // Store path length followed by coordinates
-                    all_paths.push(path.steps.len() as u32);
-                    for coord in &path.steps {
-                        all_paths.push(coord.x);
-                        all_paths.push(coord.y);
+        // Solve using the pre-parsed grid, with the requested moves
+        let grid = self.grid.clone().with_movement(options.movement);
+        let deadline = options.time_budget_ms.map(|budget| Date::now() + budget);
+        let started = now_ms();
+        let mut stats = SearchStats::default();
+        let state = match options.algorithm {
+            // Polynomial, so it runs to completion right away
+            Algorithm::Prioritized => TaskState::Planned(solve_mapf_grid_weighted(
+                &grid,
+                &agents,
+                options.weight,
+                &mut stats,
+            )),
+            Algorithm::Sipp => TaskState::Planned(solve_mapf_sipp_grid_weighted(
+                &grid,
+                &agents,
+                options.weight,
+                &mut stats,
+            )),
+            Algorithm::PushAndRotate => TaskState::Planned(solve_mapf_push_and_rotate_with_stats(
+                &grid, &agents, &mut stats,
+            )),
+            Algorithm::Centralized => TaskState::Searching(Search::Centralized(
+                CentralizedSearch::new(&grid, &agents, options.objective),
+            )),
+            Algorithm::Cbs => TaskState::Searching(Search::Ecbs(EcbsSearch::new(
+                &grid,
+                &agents,
+                options.objective,
+                1.0,
+            ))),
+            Algorithm::Ecbs => TaskState::Searching(Search::Ecbs(EcbsSearch::new(
+                &grid,
+                &agents,
+                options.objective,
+                options.suboptimality,
+            ))),
+            Algorithm::Id => TaskState::Searching(Search::Id(IdSearch::new(
+                &grid,
+                &agents,
+                options.objective,
+                options.suboptimality,
+            ))),
+        };
+
+        Ok(SolveTask {
+            state,
+            grid,
+            options,
+            deadline,
+            stats,
+            busy_ms: now_ms() - started,
+        })
+    }
+}
+
+/// A solve in progress, created by [`MapfSolver::start`].
+///
+/// Call `step` until it returns a solution, yielding to the event loop in
+/// between; stop calling it and `free()` the task to cancel.
+#[wasm_bindgen]
+pub struct SolveTask {
+    state: TaskState,
+    /// The grid being searched, which prices the solution
+    grid: Grid,
+    options: SolveOptions,
+    /// `Date.now()` past which the search gives up
+    deadline: Option<f64>,
+    stats: SearchStats,
+    /// Time spent solving, excluding pauses between steps
+    busy_ms: f64,
+}
+
+enum TaskState {
+    /// Result of a planner that runs to completion up front
+    Planned(Option<Vec<Path>>),
+    Searching(Search),
+    Finished,
+}
+
+/// A search that runs in slices.
+enum Search {
+    Centralized(CentralizedSearch),
+    Ecbs(EcbsSearch),
+    Id(IdSearch),
+}
+
+impl Search {
+    fn run(&mut self, should_stop: impl FnMut(SearchProgress) -> bool) -> SearchStatus {
+        match self {
+            Search::Centralized(search) => search.run(should_stop),
+            Search::Ecbs(search) => search.run(should_stop),
+            Search::Id(search) => search.run(should_stop),
+        }
+    }
+
+    fn stats(&self) -> SearchStats {
+        match self {
+            Search::Centralized(search) => search.stats(),
+            Search::Ecbs(search) => search.stats(),
+            Search::Id(search) => search.stats(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SolveTask {
+    /// Expand at most `max_expansions` more nodes.
+    ///
+    /// Returns the solution once found and `undefined` while the search is
+    /// still running. Throws if no solution exists, the time budget is spent
+    /// or the progress callback throws; the task is finished afterwards.
+    pub fn step(&mut self, max_expansions: u32) -> Result<Option<WasmSolution>, JsError> {
+        let started = now_ms();
+        let paths = self.advance(max_expansions as u64);
+        self.busy_ms += now_ms() - started;
+        Ok(paths?
+            .map(|paths| WasmSolution::from_paths(&self.grid, &paths, self.stats, self.busy_ms)))
+    }
+
+    /// Nodes expanded so far.
+    #[wasm_bindgen(getter)]
+    pub fn nodes_expanded(&self) -> u64 {
+        self.stats.nodes_expanded
+    }
+}
+
+impl SolveTask {
+    fn advance(&mut self, max_expansions: u64) -> Result<Option<Vec<Path>>, JsError> {
+        match std::mem::replace(&mut self.state, TaskState::Finished) {
+            TaskState::Planned(Some(paths)) => Ok(Some(paths)),
+            TaskState::Planned(None) => Err(no_solution()),
+            TaskState::Searching(mut search) => {
+                let status = self.search_slice(&mut search, max_expansions);
+                self.stats = search.stats();
+                match status? {
+                    SearchStatus::Running => {
+                        self.state = TaskState::Searching(search);
+                        Ok(None)
+                    }
+                    SearchStatus::Solved(paths) => Ok(Some(paths)),
+                    SearchStatus::Exhausted => Err(no_solution()),
+                }
+            }
+            TaskState::Finished => Err(JsError::new("Solve task already finished")),
+        }
+    }
+
+    /// Run `search` for up to `max_expansions` nodes, reporting progress and
+    /// enforcing the time budget.
+    fn search_slice(
+        &self,
+        search: &mut Search,
+        max_expansions: u64,
+    ) -> Result<SearchStatus, JsError> {
+        let limit = search.stats().nodes_expanded.saturating_add(max_expansions);
+        let out_of_time = || self.deadline.is_some_and(|deadline| Date::now() >= deadline);
+        let mut callback_error = None;
+        let status = search.run(|progress| {
+            // Checked first so a resumed slice doesn't report the same node twice
+            if progress.nodes_expanded > limit {
+                return true;
+            }
+            if let Some(on_progress) = &self.options.on_progress {
+                if progress.nodes_expanded.is_multiple_of(self.options.progress_interval) {
+                    if let Err(e) = report_progress(on_progress, progress) {
+                        callback_error = Some(e);
+                        return true;
                     }
-                    total_cost += path.cost();
                 }
-                
-                Ok(WasmSolution {
-                    paths_vec: all_paths,
-                    cost: total_cost,
-                    nodes_expanded: 0, // Not tracked in current implementation
-                })
             }
-            None => Err(JsError::new("Failed to find collision-free paths for all agents")),
+            out_of_time()
+        });
+
+        if let Some(e) = callback_error {
+            return Err(JsError::new(&format!("Progress callback failed: {}", js_message(&e))));
         }
+        if status == SearchStatus::Running && out_of_time() {
+            return Err(JsError::new("Time budget exceeded"));
+        }
+        Ok(status)
     }
 }
 
+fn no_solution() -> JsError {
+    JsError::new("Failed to find collision-free paths for all agents")
+}
+
+/// `performance.now()` where available (browsers and workers), else `Date.now()`.
+fn now_ms() -> f64 {
+    let performance = Reflect::get(&js_sys::global(), &"performance".into())
+        .ok()
+        .filter(JsValue::is_object);
+    performance
+        .and_then(|performance| {
+            let now: Function = Reflect::get(&performance, &"now".into()).ok()?.dyn_into().ok()?;
+            now.call0(&performance).ok()?.as_f64()
+        })
+        .unwrap_or_else(Date::now)
+}
+
 /// Result of solving a MAPF instance (WASM-friendly).
 #[wasm_bindgen]
 pub struct WasmSolution {
     /// Flattened paths: [path1_len, x1, y1, x2, y2, ..., path2_len, ...]
     paths_vec: Vec<u32>,
-    /// Total cost (sum of path lengths - 1 for each agent)
+    /// Total cost: the sum of the agents' path costs, priced by terrain if set
     cost: u32,
     /// Nodes expanded during search
     nodes_expanded: u64,
+    /// Nodes generated during search
+    nodes_generated: u64,
+    /// Single-agent searches run
+    low_level_calls: u64,
+    /// Time spent solving in microseconds
+    time_us: u64,
+}
+
+impl WasmSolution {
+    fn from_paths(grid: &Grid, paths: &[Path], stats: SearchStats, time_ms: f64) -> Self {
+        let mut all_paths: Vec<u32> = Vec::new();
+        let mut total_cost: u32 = 0;
+        
+        for path in paths {
+            // Store path length followed by coordinates
+            all_paths.push(path.steps.len() as u32);
+            for coord in &path.steps {
+                all_paths.push(coord.x);
+                all_paths.push(coord.y);
+            }
+            total_cost += grid.path_cost(path);
+        }
+        
+        WasmSolution {
+            paths_vec: all_paths,
+            cost: total_cost,
+            nodes_expanded: stats.nodes_expanded,
+            nodes_generated: stats.nodes_generated,
+            low_level_calls: stats.low_level_calls,
+            time_us: (time_ms * 1000.0).round() as u64,
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -101,6 +564,39 @@ impl WasmSolution {
         Uint32Array::from(&self.paths_vec[..])
     }
 
+    /// Number of values in the flattened paths, for sizing a buffer passed
+    /// to `write_paths`.
+    #[wasm_bindgen(getter)]
+    pub fn paths_len(&self) -> u32 {
+        self.paths_vec.len() as u32
+    }
+
+    /// Copy the flattened paths into `target` from `offset` on, without an
+    /// intermediate JS array; `target` may be a view over a
+    /// `SharedArrayBuffer` or a buffer about to be transferred.
+    /// Returns the number of values written.
+    pub fn write_paths(&self, target: &Uint32Array, offset: u32) -> Result<u32, JsError> {
+        let len = self.paths_len();
+        let end = offset
+            .checked_add(len)
+            .filter(|&end| end <= target.length())
+            .ok_or_else(|| {
+                JsError::new(&format!("target needs room for {len} values at offset {offset}"))
+            })?;
+        target.subarray(offset, end).copy_from(&self.paths_vec);
+        Ok(len)
+    }
+
+    /// View of the flattened paths in WASM memory, without copying.
+    ///
+    /// Only valid until the next call into the module or `free()`, as
+    /// either may move or reuse the memory; read or copy it right away.
+    pub fn paths_view(&self) -> Uint32Array {
+        // SAFETY: the view borrows `paths_vec` for as long as the caller
+        // honours the contract above; nothing allocates in between.
+        unsafe { Uint32Array::view(&self.paths_vec) }
+    }
+
     #[wasm_bindgen(getter)]
     pub fn cost(&self) -> u32 {
         self.cost
@@ -110,12 +606,196 @@ impl WasmSolution {
     pub fn nodes_expanded(&self) -> u64 {
         self.nodes_expanded
     }
+
+    #[wasm_bindgen(getter)]
+    pub fn nodes_generated(&self) -> u64 {
+        self.nodes_generated
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn low_level_calls(&self) -> u64 {
+        self.low_level_calls
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn time_us(&self) -> u64 {
+        self.time_us
+    }
 }
 
 /// Solver information.
 #[wasm_bindgen]
 pub fn solver_info() -> String {
-    "A* Reference Solver v0.2.0 (Multi-Agent with Prioritized Planning)".to_string()
+    "A* Reference Solver v0.2.0 (Prioritized, SIPP, Push and Rotate, Centralized A*, CBS, ECBS or ID)"
+        .to_string()
+}
+
+/// Check a solution against the MAPF rules without a backend round trip.
+///
+/// `paths` uses the flattened format of `WasmSolution.paths`; `starts` and
+/// `goals` are `[x1, y1, x2, y2, ...]`. Returns one object per violation:
+///
+/// ```js
+/// { type: "vertex_collision", code: 300, agents: [0, 1], timestep: 3,
+///   positions: [{ x: 4, y: 2 }], details: "Agents 0 and 1 collide at (4,2) at timestep 3" }
+/// ```
+///
+/// An empty array means the solution is valid. Error names and codes match
+/// the backend's validation results.
+#[wasm_bindgen]
+pub fn validate_solution(
+    map_data: &[u8],
+    width: u32,
+    height: u32,
+    starts: &[u32],
+    goals: &[u32],
+    paths: &[u32],
+) -> Result<Array, JsError> {
+    let map = GridMap::from_bytes(width, height, map_data)
+        .ok_or_else(|| JsError::new("Map data length doesn't match width*height"))?;
+    if !starts.len().is_multiple_of(2) || !goals.len().is_multiple_of(2) {
+        return Err(JsError::new("starts and goals must be pairs"));
+    }
+
+    let solution = mapf_core::Solution {
+        paths: unflatten_paths(paths)?,
+    };
+    let violations = validate(&map, &unflatten(starts), &unflatten(goals), &solution);
+    violations.iter().map(violation_to_js).collect()
+}
+
+/// Score a solution the way the leaderboard does, so the browser shows the
+/// number a submission would get: `{ cost, makespan }`.
+///
+/// `paths` uses the flattened format of `WasmSolution.paths`. The solution
+/// isn't validated; see `validate_solution`.
+#[wasm_bindgen]
+pub fn score_solution(
+    map_data: &[u8],
+    width: u32,
+    height: u32,
+    paths: &[u32],
+) -> Result<JsValue, JsError> {
+    let map = GridMap::from_bytes(width, height, map_data)
+        .ok_or_else(|| JsError::new("Map data length doesn't match width*height"))?;
+    let solution = mapf_core::Solution {
+        paths: unflatten_paths(paths)?,
+    };
+    let score = mapf_scoring::score(&solution, &map);
+
+    let object = Object::new();
+    set(&object, "cost", (score.cost as f64).into())?;
+    set(&object, "makespan", (score.makespan as f64).into())?;
+    Ok(object.into())
+}
+
+fn unflatten(flat: &[u32]) -> Vec<mapf_core::Coordinate> {
+    flat.chunks_exact(2)
+        .map(|c| mapf_core::Coordinate { x: c[0], y: c[1] })
+        .collect()
+}
+
+/// Parse `[path1_len, x1, y1, ..., path2_len, ...]`.
+fn unflatten_paths(flat: &[u32]) -> Result<Vec<mapf_core::Path>, JsError> {
+    let mut paths = Vec::new();
+    let mut rest = flat;
+    while let Some((&len, tail)) = rest.split_first() {
+        let len = len as usize * 2;
+        if tail.len() < len {
+            return Err(JsError::new("paths array is truncated"));
+        }
+        paths.push(mapf_core::Path {
+            steps: unflatten(&tail[..len]),
+        });
+        rest = &tail[len..];
+    }
+    Ok(paths)
+}
+
+fn violation_to_js(violation: &Violation) -> Result<JsValue, JsError> {
+    let positions = Array::new();
+    for pos in &violation.positions {
+        let cell = Object::new();
+        set(&cell, "x", pos.x.into())?;
+        set(&cell, "y", pos.y.into())?;
+        positions.push(&cell);
+    }
+    let agents: Array = violation
+        .agents
+        .iter()
+        .map(|&agent| JsValue::from(agent as u32))
+        .collect();
+
+    let object = Object::new();
+    set(&object, "type", violation.kind.as_str().into())?;
+    set(&object, "code", violation.kind.code().into())?;
+    set(&object, "agents", agents.into())?;
+    set(
+        &object,
+        "timestep",
+        violation.timestep.map_or(JsValue::UNDEFINED, |t| (t as u32).into()),
+    )?;
+    set(&object, "positions", positions.into())?;
+    set(&object, "details", violation.details.as_str().into())?;
+    Ok(object.into())
+}
+
+/// Parse a MovingAI `.map` file into `{ width, height, tiles }`, with
+/// row-major `tiles` (1 = passable, 0 = blocked) in a `Uint8Array`.
+#[wasm_bindgen]
+pub fn parse_map(input: &str) -> Result<JsValue, JsError> {
+    let map = GridMap::parse(input).map_err(|e| JsError::new(&e.to_string()))?;
+
+    let object = Object::new();
+    set(&object, "width", map.width().into())?;
+    set(&object, "height", map.height().into())?;
+    set(&object, "tiles", Uint8Array::from(&map.to_bytes()[..]).into())?;
+    Ok(object.into())
+}
+
+/// Parse a MovingAI `.scen` file into `{ version, entries }`; entries use
+/// camelCase fields (`mapName`, `startX`, `optimalLength`, `startTime`, ...),
+/// plus `id`, `group` and `priority` on annotated agents.
+#[wasm_bindgen]
+pub fn parse_scenario(input: &str) -> Result<JsValue, JsError> {
+    let scenario = Scenario::parse(input).map_err(|e| JsError::new(&e.to_string()))?;
+
+    let entries = Array::new();
+    for entry in scenario.entries() {
+        let object = Object::new();
+        set(&object, "bucket", entry.bucket.into())?;
+        set(&object, "mapName", entry.map_name.as_str().into())?;
+        set(&object, "mapWidth", entry.map_width.into())?;
+        set(&object, "mapHeight", entry.map_height.into())?;
+        set(&object, "startX", entry.start_x.into())?;
+        set(&object, "startY", entry.start_y.into())?;
+        set(&object, "goalX", entry.goal_x.into())?;
+        set(&object, "goalY", entry.goal_y.into())?;
+        set(&object, "optimalLength", entry.optimal_length.into())?;
+        set(&object, "startTime", (entry.start_time as f64).into())?;
+        if let Some(id) = &entry.id {
+            set(&object, "id", id.as_str().into())?;
+        }
+        if let Some(group) = &entry.group {
+            set(&object, "group", group.as_str().into())?;
+        }
+        if let Some(priority) = entry.priority {
+            set(&object, "priority", priority.into())?;
+        }
+        entries.push(&object);
+    }
+
+    let object = Object::new();
+    set(&object, "version", scenario.version().into())?;
+    set(&object, "entries", entries.into())?;
+    Ok(object.into())
+}
+
+/// Set `object[key] = value`.
+fn set(object: &Object, key: &str, value: JsValue) -> Result<(), JsError> {
+    Reflect::set(object, &key.into(), &value)
+        .map(|_| ())
+        .map_err(|e| JsError::new(&js_message(&e)))
 }
 
 // Legacy function for backward compatibility
@@ -129,5 +809,5 @@ pub fn solve(
     goals: &[u32],
 ) -> Result<WasmSolution, JsError> {
     let solver = MapfSolver::new(map_data, width, height)?;
-    solver.solve(starts, goals)
+    solver.solve(starts, goals, JsValue::UNDEFINED)
 }
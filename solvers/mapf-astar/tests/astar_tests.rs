@@ -1,43 +1,58 @@
 //! Tests for A* pathfinding implementation.
 
-use mapf_astar::{astar_single, solve_mapf, Coordinate, Path};
+use mapf_astar::{
+    astar_single, astar_single_grid, astar_single_weighted, count_conflicts, count_conflicts_with,
+    find_first_conflict, find_first_conflict_with, sipp_single, solve_mapf, solve_mapf_centralized,
+    solve_mapf_centralized_grid, solve_mapf_centralized_grid_with, solve_mapf_ecbs_grid,
+    solve_mapf_ecbs_grid_with, solve_mapf_grid_weighted, solve_mapf_grid_with_stats,
+    solve_mapf_id_grid, solve_mapf_id_grid_with, solve_mapf_push_and_rotate,
+    solve_mapf_push_and_rotate_with_stats, solve_mapf_sipp_grid_weighted,
+    solve_mapf_sipp_grid_with_stats, CentralizedSearch, Conflict, ConflictKind, Coordinate,
+    EcbsSearch, Grid, IdSearch, Movement, Objective, Path, SearchStats, SearchStatus,
+};
+use mapf_core::GridMap;
+use std::sync::Arc;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // Single-agent A* tests
 // ─────────────────────────────────────────────────────────────────────────────
 
-/// 3x3 open grid:
-/// ```
-/// ...
-/// ...
-/// ...
-/// ```
+/// Tile bytes of a map drawn in MovingAI characters.
+fn grid(ascii: &str) -> Vec<u8> {
+    GridMap::from_ascii(ascii).unwrap().to_bytes()
+}
+
+/// 3x3 open grid
 fn open_3x3() -> Vec<u8> {
-    vec![1, 1, 1, 1, 1, 1, 1, 1, 1]
+    grid(
+        "
+        ...
+        ...
+        ...
+        ",
+    )
 }
 
-/// 3x3 grid with center blocked:
-/// ```
-/// ...
-/// .#.
-/// ...
-/// ```
+/// 3x3 grid with center blocked
 fn blocked_center_3x3() -> Vec<u8> {
-    vec![1, 1, 1, 1, 0, 1, 1, 1, 1]
+    grid(
+        "
+        ...
+        .@.
+        ...
+        ",
+    )
 }
 
-/// 5x3 corridor with wall:
-/// ```
-/// .....
-/// .###.
-/// .....
-/// ```
+/// 5x3 corridor with wall
 fn corridor_5x3() -> Vec<u8> {
-    vec![
-        1, 1, 1, 1, 1, // row 0
-        1, 0, 0, 0, 1, // row 1
-        1, 1, 1, 1, 1, // row 2
-    ]
+    grid(
+        "
+        .....
+        .@@@.
+        .....
+        ",
+    )
 }
 
 #[test]
@@ -119,6 +134,80 @@ fn astar_out_of_bounds_start() {
     assert!(result.is_none());
 }
 
+#[test]
+fn astar_searches_back_to_back_on_different_grids() {
+    // Each search must ignore what the one before left behind
+    let big = open_10x10();
+    let small = blocked_center_3x3();
+    assert_eq!(astar_single(&big, 10, 10, (0, 0), (9, 9)).unwrap().cost(), 18);
+    let around = astar_single(&small, 3, 3, (0, 1), (2, 1)).unwrap();
+    assert_eq!(around.cost(), 4);
+    assert!(!around.steps.contains(&Coordinate { x: 1, y: 1 }));
+    assert!(astar_single(&small, 3, 3, (0, 0), (1, 1)).is_none());
+    assert_eq!(astar_single(&big, 10, 10, (9, 0), (0, 9)).unwrap().cost(), 18);
+}
+
+#[test]
+fn astar_weighted_stays_within_the_bound() {
+    // The wall hides the goal; the way round is at the far end
+    let map = grid(
+        "
+        .........
+        .........
+        @@@@@@@@.
+        .........
+        .........
+        ",
+    );
+    let optimal = astar_single(&map, 9, 5, (0, 0), (0, 4)).expect("Should find a path");
+    assert_eq!(optimal.cost(), 20);
+    for weight in [0.5, 1.0, 1.5, 3.0] {
+        let path = astar_single_weighted(&map, 9, 5, (0, 0), (0, 4), weight)
+            .expect("Should find a path");
+        assert!(path.is_valid_cardinal());
+        assert_eq!(path.steps.first(), Some(&Coordinate { x: 0, y: 0 }));
+        assert_eq!(path.steps.last(), Some(&Coordinate { x: 0, y: 4 }));
+        assert!(path.cost() as f64 <= weight.max(1.0) * 20.0);
+    }
+}
+
+#[test]
+fn octile_movement_takes_diagonals() {
+    let cardinal = Grid::from_raw(&open_5x5(), 5, 5);
+    let octile = cardinal.clone().with_movement(Movement::Octile);
+    assert_eq!(cardinal.movement(), Movement::Cardinal);
+
+    let path = astar_single_grid(&cardinal, (0, 0), (4, 4), 1.0).unwrap();
+    assert_eq!(path.cost(), 8);
+    let path = astar_single_grid(&octile, (0, 0), (4, 4), 1.0).unwrap();
+    assert_eq!(path.cost(), 4);
+    assert!(!path.is_valid_cardinal());
+    assert!(path.is_valid_octile());
+    assert!(path.is_valid_on(&octile));
+    assert!(!path.is_valid_on(&cardinal));
+}
+
+#[test]
+fn octile_movement_does_not_cut_corners() {
+    let map = grid(
+        "
+        .@.
+        ...
+        ",
+    );
+    let grid = Grid::from_raw(&map, 3, 2).with_movement(Movement::Octile);
+    // Both diagonals out of the corner pass the wall, so it takes two moves
+    let path = astar_single_grid(&grid, (0, 0), (1, 1), 1.0).unwrap();
+    assert_eq!(path.cost(), 2);
+    assert!(path.is_valid_on(&grid));
+
+    let cutting = Path {
+        steps: vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 1, y: 1 }],
+    };
+    assert!(cutting.is_valid_octile());
+    assert!(!cutting.is_valid_on(&grid));
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Path validation
 // ─────────────────────────────────────────────────────────────────────────────
@@ -373,44 +462,825 @@ fn multiagent_three_agents() {
     verify_no_collisions(&paths);
 }
 
-/// Helper function to verify no collisions between paths
-fn verify_no_collisions(paths: &[Path]) {
-    let max_len = paths.iter().map(|p| p.steps.len()).max().unwrap_or(0);
-    
-    for t in 0..max_len {
-        // Get positions of all agents at timestep t (or their final position)
-        let positions: Vec<_> = paths.iter().map(|p| {
-            p.steps.get(t).or(p.steps.last()).unwrap()
-        }).collect();
-        
-        // Check for vertex collisions
-        for i in 0..positions.len() {
-            for j in (i+1)..positions.len() {
-                assert_ne!(
-                    positions[i], positions[j],
-                    "Vertex collision between agents {} and {} at timestep {}: both at ({}, {})",
-                    i, j, t, positions[i].x, positions[i].y
-                );
-            }
+#[test]
+fn multiagent_corridor_needs_a_detour() {
+    // Swapping ends of a corridor with one side loop: the second agent has
+    // to take the long way round rather than head straight for its goal
+    let map = corridor_5x3();
+    let agents = vec![((0, 0), (4, 0)), ((4, 0), (0, 0))];
+
+    let paths = solve_mapf(&map, 5, 3, &agents).expect("Should route around the corridor");
+    // The first agent goes straight through, the second loops round the bottom
+    assert_eq!(paths[0].steps.len(), 5);
+    assert_eq!(paths[1].steps.len(), 9);
+    for path in &paths {
+        assert!(path.is_valid_cardinal());
+    }
+    verify_no_collisions(&paths);
+}
+
+#[test]
+fn multiagent_blocked_goal_fails() {
+    // The first agent settles in the only cell the second can pass through
+    let map = grid(
+        "
+        ...
+        @.@
+        ...
+        ",
+    );
+    let agents = vec![((2, 0), (1, 1)), ((0, 0), (1, 2))];
+    assert!(solve_mapf(&map, 3, 3, &agents).is_none());
+}
+
+#[test]
+fn centralized_raw_map_matches_grid() {
+    let map = grid(
+        "
+        ...
+        .@.
+        ...
+        ",
+    );
+    let agents = vec![((0, 0), (2, 2)), ((2, 2), (0, 0))];
+    let expected = solve_mapf_centralized_grid(&Grid::from_raw(&map, 3, 3), &agents);
+    assert!(expected.is_some());
+    assert_eq!(solve_mapf_centralized(&map, 3, 3, &agents), expected);
+    assert!(solve_mapf_centralized(&map, 3, 2, &agents).is_none());
+}
+
+#[test]
+fn disappearing_agents_free_their_goal() {
+    // One-wide corridor: the second agent can only get past the first by
+    // waiting for it to reach its goal and leave the map
+    let map = grid(".....");
+    let agents = vec![((1, 0), (2, 0)), ((0, 0), (4, 0))];
+    let parking = Grid::from_raw(&map, 5, 1);
+    assert!(!parking.disappears_at_goal());
+    assert!(solve_mapf_grid_with_stats(&parking, &agents, &mut SearchStats::default()).is_none());
+
+    let grid = parking.with_disappear_at_goal(true);
+    assert!(grid.disappears_at_goal());
+    let stats = &mut SearchStats::default();
+    let all_paths = [
+        solve_mapf_grid_with_stats(&grid, &agents, stats),
+        solve_mapf_sipp_grid_with_stats(&grid, &agents, stats),
+        solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |_| false),
+        solve_mapf_ecbs_grid(&grid, &agents, 1.0),
+        solve_mapf_id_grid(&grid, &agents, 1.0),
+    ];
+    for paths in all_paths {
+        let paths = paths.expect("Should pass once the first agent is gone");
+        // Paths end on each agent's first arrival at its goal
+        assert_eq!(paths[0].steps, path(&[(1, 0), (2, 0)]).steps);
+        assert_eq!(
+            paths[1].steps,
+            path(&[(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)]).steps
+        );
+        assert_eq!(find_first_conflict_with(&paths, true), None);
+        // The first agent would still be in the way if it stayed
+        assert!(find_first_conflict(&paths).is_some());
+    }
+}
+
+#[test]
+fn grid_cells_can_be_edited() {
+    let mut grid = Grid::from_raw(&open_3x3(), 3, 3);
+    assert!(grid.set_passable(1, 1, false));
+    assert!(!grid.is_passable(1, 1));
+    assert_eq!(grid.data(), &[1, 1, 1, 1, 0, 1, 1, 1, 1]);
+
+    assert!(grid.set_passable(1, 1, true));
+    assert!(grid.is_passable(1, 1));
+
+    assert!(!grid.set_passable(3, 0, false), "Out-of-bounds edits are refused");
+    assert_eq!(grid.data(), open_3x3().as_slice());
+}
+
+#[test]
+fn heuristic_is_the_exact_maze_distance() {
+    let map = grid(
+        "
+        .....
+        @@@@.
+        .....
+        .@@@@
+        .....
+        ",
+    );
+    let mut grid = Grid::from_raw(&map, 5, 5);
+    let goal = Coordinate { x: 4, y: 4 };
+    let distances = grid.heuristic_for(goal);
+    // Manhattan says 4; the corridor winds through 16 moves
+    assert_eq!(distances[0], 16);
+    assert_eq!(distances[5], u32::MAX, "Walls can't reach the goal");
+    let path = astar_single_grid(&grid, (0, 0), (4, 4), 1.0).unwrap();
+    assert_eq!(path.cost(), 16);
+    assert!(Arc::ptr_eq(&distances, &grid.heuristic_for(goal)), "Cached per goal");
+
+    // Editing the grid drops the cache
+    assert!(grid.set_passable(0, 3, false));
+    assert_eq!(grid.heuristic_for(goal)[0], u32::MAX);
+    assert!(astar_single_grid(&grid, (0, 0), (4, 4), 1.0).is_none());
+}
+
+#[test]
+fn neighbor_table_gives_the_same_plans() {
+    let agents: Vec<Task> = vec![((0, 0), (9, 9)), ((9, 0), (0, 9)), ((0, 9), (9, 0))];
+    for movement in [Movement::Cardinal, Movement::Octile] {
+        let plain = Grid::from_raw(&open_10x10(), 10, 10).with_movement(movement);
+        let mut tabled = plain.clone().with_neighbor_table();
+        assert_eq!(
+            astar_single_grid(&plain, (0, 0), (9, 9), 1.0),
+            astar_single_grid(&tabled, (0, 0), (9, 9), 1.0)
+        );
+        assert_eq!(
+            solve_mapf_ecbs_grid(&plain, &agents, 1.0),
+            solve_mapf_ecbs_grid(&tabled, &agents, 1.0)
+        );
+
+        // Edits keep the table up to date
+        let mut plain = plain;
+        for y in 0..9 {
+            assert!(plain.set_passable(5, y, false));
+            assert!(tabled.set_passable(5, y, false));
+        }
+        let path = astar_single_grid(&tabled, (0, 0), (9, 0), 1.0).unwrap();
+        assert!(path.is_valid_on(&plain));
+        assert_eq!(path, astar_single_grid(&plain, (0, 0), (9, 0), 1.0).unwrap());
+    }
+}
+
+#[test]
+fn centralized_objectives() {
+    // Perpendicular crossing: both straight paths meet at (2,2) at t=2, so
+    // one agent must wait once.
+    let grid = Grid::from_raw(&open_5x5(), 5, 5);
+    let agents = vec![((0, 2), (4, 2)), ((2, 0), (2, 4))];
+
+    let paths = solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |_| false)
+        .expect("Should find paths");
+    verify_no_collisions(&paths);
+    let sum_of_costs: usize = paths.iter().map(arrival_time).sum();
+    assert_eq!(sum_of_costs, 9);
+
+    let paths = solve_mapf_centralized_grid_with(&grid, &agents, Objective::Makespan, |_| false)
+        .expect("Should find paths");
+    verify_no_collisions(&paths);
+    assert_eq!(paths.iter().map(arrival_time).max(), Some(5));
+}
+
+#[test]
+fn centralized_stops_when_asked() {
+    let grid = Grid::from_raw(&open_5x5(), 5, 5);
+    let agents = vec![((0, 2), (4, 2)), ((2, 0), (2, 4))];
+
+    let mut seen = Vec::new();
+    let result = solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |progress| {
+        seen.push(progress);
+        progress.nodes_expanded > 3
+    });
+    assert!(result.is_none(), "Search should give up once stopped");
+    assert_eq!(seen.len(), 4);
+    for (i, progress) in seen.iter().enumerate() {
+        assert_eq!(progress.nodes_expanded, i as u64 + 1);
+    }
+    // A* pops nodes in f-cost order, starting from the heuristic estimate
+    assert_eq!(seen[0].best_cost, 8);
+    assert!(seen.windows(2).all(|w| w[0].best_cost <= w[1].best_cost));
+}
+
+#[test]
+fn centralized_search_resumes_in_steps() {
+    let grid = Grid::from_raw(&open_5x5(), 5, 5);
+    let agents = vec![((0, 2), (4, 2)), ((2, 0), (2, 4))];
+    let expected = solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |_| false);
+
+    let mut search = CentralizedSearch::new(&grid, &agents, Objective::SumOfCosts);
+    let mut steps = 0;
+    let paths = loop {
+        match search.step(2) {
+            SearchStatus::Running => steps += 1,
+            SearchStatus::Solved(paths) => break paths,
+            SearchStatus::Exhausted => panic!("Search should find paths"),
+        }
+        assert_eq!(search.nodes_expanded(), steps * 2);
+    };
+    assert!(steps > 1, "Search should take several steps");
+    assert_eq!(Some(paths), expected);
+}
+
+#[test]
+fn search_stats_count_effort() {
+    let grid = Grid::from_raw(&open_5x5(), 5, 5);
+    let agents = vec![((0, 2), (4, 2)), ((2, 0), (2, 4))];
+
+    let mut stats = SearchStats::default();
+    let paths = solve_mapf_grid_with_stats(&grid, &agents, &mut stats).expect("Should find paths");
+    // At least one space-time node per step of each agent's path
+    let steps = paths.iter().map(|p| p.steps.len()).sum::<usize>() as u64;
+    assert!(stats.nodes_expanded >= steps);
+    assert!(stats.nodes_generated >= stats.nodes_expanded);
+    // One single-agent search per agent
+    assert_eq!(stats.low_level_calls, 2);
+
+    let mut search = CentralizedSearch::new(&grid, &agents, Objective::SumOfCosts);
+    assert!(matches!(search.step(u64::MAX), SearchStatus::Solved(_)));
+    let stats = search.stats();
+    assert_eq!(stats.nodes_expanded, search.nodes_expanded());
+    assert!(stats.nodes_generated > stats.nodes_expanded);
+    // One distance map per agent for the heuristic
+    assert_eq!(stats.low_level_calls, 2);
+
+    // The root plans both agents, and every split replans one of them
+    let mut search = EcbsSearch::new(&grid, &agents, Objective::SumOfCosts, 1.0);
+    assert!(matches!(search.step(u64::MAX), SearchStatus::Solved(_)));
+    let stats = search.stats();
+    assert_eq!(stats.low_level_calls, 2 + 2 * (stats.nodes_expanded - 1));
+
+    // Push and rotate searches the map for each agent's way to its goal
+    let mut stats = SearchStats::default();
+    solve_mapf_push_and_rotate_with_stats(&grid, &agents, &mut stats).expect("Should find paths");
+    assert!(stats.low_level_calls >= 2);
+    assert!(stats.nodes_generated > 0);
+}
+
+#[test]
+fn weighted_prioritized_planning_trades_cost_for_effort() {
+    let grid = Grid::from_raw(&open_16x16(), 16, 16);
+    let agents: Vec<_> = (0..6).map(|i| ((i, 0), (15 - i, 15))).collect();
+    let cost = |paths: &[Path]| paths.iter().map(Path::cost).sum::<u32>();
+
+    let mut plain = SearchStats::default();
+    let optimal = solve_mapf_grid_with_stats(&grid, &agents, &mut plain).unwrap();
+    for weight in [1.5, 3.0] {
+        for solve in [solve_mapf_grid_weighted, solve_mapf_sipp_grid_weighted] {
+            let mut stats = SearchStats::default();
+            let paths = solve(&grid, &agents, weight, &mut stats).expect("Should find paths");
+            verify_no_collisions(&paths);
+            assert!(cost(&paths) as f64 <= weight * cost(&optimal) as f64);
+        }
+        let mut stats = SearchStats::default();
+        solve_mapf_grid_weighted(&grid, &agents, weight, &mut stats).unwrap();
+        assert!(stats.nodes_expanded <= plain.nodes_expanded);
+    }
+}
+
+#[test]
+fn multiagent_octile_movement() {
+    let grid = Grid::from_raw(&open_5x5(), 5, 5).with_movement(Movement::Octile);
+    // All three head through the center at the same time; one of them
+    // loses a timestep getting round the others
+    let agents = vec![((0, 0), (4, 4)), ((4, 0), (0, 4)), ((0, 2), (4, 2))];
+
+    let optimal = solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |_| false)
+        .expect("Should find paths");
+    let cost = |paths: &[Path]| paths.iter().map(arrival_time).sum::<usize>();
+    assert_eq!(cost(&optimal), 13);
+    let planned = [
+        solve_mapf_grid_with_stats(&grid, &agents, &mut SearchStats::default()),
+        solve_mapf_sipp_grid_with_stats(&grid, &agents, &mut SearchStats::default()),
+        solve_mapf_ecbs_grid(&grid, &agents, 1.0),
+    ];
+    for paths in planned {
+        let paths = paths.expect("Should find paths");
+        verify_no_collisions(&paths);
+        for path in &paths {
+            assert!(path.is_valid_on(&grid));
+        }
+    }
+    let paths = solve_mapf_ecbs_grid(&grid, &agents, 1.0).unwrap();
+    assert_eq!(cost(&paths), cost(&optimal));
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// ECBS tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn ecbs_at_factor_one_is_optimal() {
+    let cases = [
+        (Grid::from_raw(&open_5x5(), 5, 5), vec![((0, 2), (4, 2)), ((2, 0), (2, 4))]),
+        // Swapping ends of the corridor: one agent steps aside into the bottom row
+        (Grid::from_raw(&corridor_5x3(), 5, 3), vec![((0, 0), (4, 0)), ((4, 0), (0, 0))]),
+        (
+            Grid::from_raw(&blocked_center_3x3(), 3, 3),
+            vec![((0, 0), (2, 2)), ((2, 2), (0, 0)), ((2, 0), (0, 2))],
+        ),
+    ];
+    for (grid, agents) in cases {
+        let optimal = solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |_| false)
+            .expect("Should find paths");
+        let paths = solve_mapf_ecbs_grid(&grid, &agents, 1.0).expect("Should find paths");
+        verify_no_collisions(&paths);
+        for (path, &(start, goal)) in paths.iter().zip(&agents) {
+            assert!(path.is_valid_cardinal());
+            assert_eq!(path.steps.first(), Some(&start.into()));
+            assert_eq!(path.steps.last(), Some(&goal.into()));
+            assert!(path.steps.iter().all(|c| grid.is_passable(c.x, c.y)));
+        }
+        let cost = |paths: &[Path]| paths.iter().map(arrival_time).sum::<usize>();
+        assert_eq!(cost(&paths), cost(&optimal));
+    }
+}
+
+#[test]
+fn centralized_charges_goal_waits_when_leaving() {
+    // Agent 0 reaches its goal at once, then has to step off it to let
+    // agent 1 by; the waits it spent there are no longer free
+    let map = grid(
+        "
+        ....@
+        .....
+        .....
+        ",
+    );
+    let grid = Grid::from_raw(&map, 5, 3);
+    let agents = vec![((4, 2), (3, 2)), ((0, 2), (4, 2))];
+
+    let cost = |paths: &[Path]| paths.iter().map(arrival_time).sum::<usize>();
+    let centralized = solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |_| false)
+        .expect("Should find paths");
+    let cbs = solve_mapf_ecbs_grid(&grid, &agents, 1.0).expect("Should find paths");
+    verify_no_collisions(&centralized);
+    assert_eq!(cost(&centralized), cost(&cbs));
+    assert_eq!(cost(&centralized), 7);
+}
+
+#[test]
+fn conflict_based_searches_minimize_makespan() {
+    let cases = [
+        (Grid::from_raw(&corridor_5x3(), 5, 3), vec![((0, 0), (4, 0)), ((4, 0), (0, 0))]),
+        (
+            Grid::from_raw(&blocked_center_3x3(), 3, 3),
+            vec![((0, 0), (2, 2)), ((2, 2), (0, 0)), ((2, 0), (0, 2))],
+        ),
+        // Costly terrain doesn't change the timesteps
+        (swamp_5x3(), vec![((0, 1), (4, 1)), ((1, 0), (1, 2))]),
+    ];
+    let makespan = |paths: &[Path]| paths.iter().map(arrival_time).max().unwrap_or(0);
+    for (grid, agents) in cases {
+        let optimal = solve_mapf_centralized_grid_with(&grid, &agents, Objective::Makespan, |_| false)
+            .expect("Should find paths");
+        let ecbs = solve_mapf_ecbs_grid_with(&grid, &agents, Objective::Makespan, 1.0, |_| false)
+            .expect("Should find paths");
+        let id = solve_mapf_id_grid_with(&grid, &agents, Objective::Makespan, 1.0, |_| false)
+            .expect("Should find paths");
+        for paths in [&ecbs, &id] {
+            verify_no_collisions(paths);
+            assert_eq!(makespan(paths), makespan(&optimal));
+        }
+    }
+}
+
+#[test]
+fn ecbs_stays_within_the_factor() {
+    let grid = Grid::from_raw(&open_10x10(), 10, 10);
+    let agents: Vec<_> = (0..8).map(|i| ((i, 0), (9 - i, 9))).chain([((0, 5), (9, 5))]).collect();
+    let cost = |paths: &[Path]| paths.iter().map(Path::cost).sum::<u32>();
+
+    let optimal = cost(&solve_mapf_ecbs_grid(&grid, &agents, 1.0).expect("Should find paths"));
+    for factor in [1.2, 1.5, 3.0] {
+        let paths = solve_mapf_ecbs_grid(&grid, &agents, factor).expect("Should find paths");
+        verify_no_collisions(&paths);
+        assert!(cost(&paths) as f64 <= factor * optimal as f64);
+    }
+}
+
+#[test]
+fn ecbs_gives_up_on_impossible_instances() {
+    let grid = Grid::from_raw(&open_3x3(), 3, 3);
+    // Shared goal, blocked goal
+    assert!(solve_mapf_ecbs_grid(&grid, &[((0, 0), (2, 2)), ((2, 0), (2, 2))], 1.0).is_none());
+    let grid = Grid::from_raw(&blocked_center_3x3(), 3, 3);
+    assert!(solve_mapf_ecbs_grid(&grid, &[((0, 0), (1, 1))], 1.0).is_none());
+
+    // Agents can't pass each other in a one-wide corridor; CBS keeps
+    // splitting until stopped, with a rising lower bound
+    let grid = Grid::from_raw(&[1; 4], 4, 1);
+    let mut seen = Vec::new();
+    let result = solve_mapf_ecbs_grid_with(&grid, &[((0, 0), (3, 0)), ((3, 0), (0, 0))], Objective::SumOfCosts, 1.0, |progress| {
+        seen.push(progress);
+        progress.nodes_expanded > 50
+    });
+    assert!(result.is_none());
+    assert_eq!(seen.last().unwrap().nodes_expanded, 51);
+    assert_eq!(seen[0].best_cost, 6);
+    assert!(seen.windows(2).all(|w| w[0].best_cost <= w[1].best_cost));
+}
+
+#[test]
+fn ecbs_search_resumes_in_steps() {
+    let grid = Grid::from_raw(&open_5x5(), 5, 5);
+    let agents = vec![((0, 2), (4, 2)), ((2, 0), (2, 4)), ((4, 0), (0, 4))];
+    let expected = solve_mapf_ecbs_grid(&grid, &agents, 1.0);
+
+    let mut search = EcbsSearch::new(&grid, &agents, Objective::SumOfCosts, 1.0);
+    let paths = loop {
+        match search.step(1) {
+            SearchStatus::Running => {}
+            SearchStatus::Solved(paths) => break paths,
+            SearchStatus::Exhausted => panic!("Search should find paths"),
         }
-        
-        // Check for edge collisions (agents swapping positions)
-        if t > 0 {
-            let prev_positions: Vec<_> = paths.iter().map(|p| {
-                p.steps.get(t - 1).or(p.steps.last()).unwrap()
-            }).collect();
-            
-            for i in 0..positions.len() {
-                for j in (i+1)..positions.len() {
-                    // Edge collision: agent i was where j is now, and j was where i is now
-                    if prev_positions[i] == positions[j] && prev_positions[j] == positions[i] {
-                        panic!(
-                            "Edge collision between agents {} and {} at timestep {}: swapped positions",
-                            i, j, t
-                        );
-                    }
-                }
-            }
+    };
+    assert!(search.nodes_expanded() > 1, "Search should split at least once");
+    assert_eq!(Some(paths), expected);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Independence detection tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn id_merges_only_agents_that_collide() {
+    let grid = Grid::from_raw(&open_10x10(), 10, 10);
+    // Two agents swap along the top row; two more cross far below them
+    let agents = vec![
+        ((0, 0), (4, 0)),
+        ((4, 0), (0, 0)),
+        ((0, 7), (9, 7)),
+        ((9, 9), (0, 9)),
+    ];
+
+    let mut search = IdSearch::new(&grid, &agents, Objective::SumOfCosts, 1.0);
+    let SearchStatus::Solved(paths) = search.run(|_| false) else {
+        panic!("Search should find paths");
+    };
+    assert_eq!(search.groups(), vec![vec![0, 1], vec![2], vec![3]]);
+    verify_no_collisions(&paths);
+    for (path, &(start, goal)) in paths.iter().zip(&agents) {
+        assert!(path.is_valid_cardinal());
+        assert_eq!(path.steps.first(), Some(&start.into()));
+        assert_eq!(path.steps.last(), Some(&goal.into()));
+    }
+    let cost = |paths: &[Path]| paths.iter().map(Path::cost).sum::<u32>();
+    let optimal = solve_mapf_ecbs_grid(&grid, &agents, 1.0).expect("Should find paths");
+    assert_eq!(cost(&paths), cost(&optimal));
+}
+
+#[test]
+fn id_gives_up_on_impossible_instances() {
+    // Agents that share a goal only meet once planned alone
+    let grid = Grid::from_raw(&open_3x3(), 3, 3);
+    assert!(solve_mapf_id_grid(&grid, &[((0, 0), (2, 2)), ((2, 0), (2, 2))], 1.0).is_none());
+
+    let grid = Grid::from_raw(&[1; 4], 4, 1);
+    let mut seen = Vec::new();
+    let result = solve_mapf_id_grid_with(
+        &grid,
+        &[((0, 0), (3, 0)), ((3, 0), (0, 0))],
+        Objective::SumOfCosts,
+        1.0,
+        |progress| {
+            seen.push(progress);
+            progress.nodes_expanded > 50
+        },
+    );
+    assert!(result.is_none());
+    assert_eq!(seen.last().unwrap().nodes_expanded, 51);
+    assert!(seen.windows(2).all(|w| w[0].best_cost <= w[1].best_cost));
+}
+
+#[test]
+fn id_search_resumes_in_steps() {
+    let grid = Grid::from_raw(&open_5x5(), 5, 5);
+    let agents = vec![((0, 2), (4, 2)), ((2, 0), (2, 4)), ((4, 0), (0, 4))];
+    let expected = solve_mapf_id_grid(&grid, &agents, 1.0);
+
+    let mut search = IdSearch::new(&grid, &agents, Objective::SumOfCosts, 1.0);
+    let paths = loop {
+        match search.step(1) {
+            SearchStatus::Running => {}
+            SearchStatus::Solved(paths) => break paths,
+            SearchStatus::Exhausted => panic!("Search should find paths"),
+        }
+    };
+    assert_eq!(search.nodes_expanded(), search.stats().nodes_expanded);
+    assert_eq!(Some(paths), expected);
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// SIPP tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Path from (x, y) pairs
+fn path(steps: &[(u32, u32)]) -> Path {
+    Path {
+        steps: steps.iter().map(|&cell| cell.into()).collect(),
+    }
+}
+
+#[test]
+fn sipp_waits_out_a_long_obstacle() {
+    // Another agent holds the middle of the corridor for ten steps before
+    // stepping into the side pocket for good
+    let map = grid(
+        "
+        .....
+        @@.@@
+        ",
+    );
+    let grid = Grid::from_raw(&map, 5, 2);
+    let mut steps = vec![(2, 0); 11];
+    steps.push((2, 1));
+    let obstacles = [path(&steps)];
+
+    let result = sipp_single(&grid, (0, 0), (4, 0), &obstacles).expect("Should wait and pass");
+    assert_eq!(result.steps.len() - 1, 13);
+    assert_eq!(result.steps[11], Coordinate { x: 2, y: 0 });
+    verify_no_collisions(&[obstacles[0].clone(), result]);
+}
+
+#[test]
+fn sipp_needs_a_goal_it_can_stay_on() {
+    let grid = Grid::from_raw(&open_3x3(), 3, 3);
+    let obstacles = [path(&[(1, 0), (1, 1)])];
+    assert!(sipp_single(&grid, (0, 0), (1, 1), &obstacles).is_none());
+    // Passing through is fine
+    let result = sipp_single(&grid, (1, 2), (1, 0), &[path(&[(1, 1), (0, 1)])]).unwrap();
+    assert_eq!(result.steps.len() - 1, 2);
+}
+
+#[test]
+fn sipp_prioritized_planning_matches_space_time_a_star() {
+    let cases = [
+        (
+            Grid::from_raw(&corridor_5x3(), 5, 3),
+            vec![((0, 0), (4, 0)), ((4, 0), (0, 0))],
+        ),
+        (
+            Grid::from_raw(&[1; 81], 9, 9),
+            vec![
+                ((0, 4), (8, 4)),
+                ((8, 4), (0, 4)),
+                ((4, 0), (4, 8)),
+                ((4, 8), (4, 0)),
+            ],
+        ),
+    ];
+    for (grid, agents) in cases {
+        let mut stats = SearchStats::default();
+        let paths =
+            solve_mapf_sipp_grid_with_stats(&grid, &agents, &mut stats).expect("Should find paths");
+        let expected =
+            solve_mapf_grid_with_stats(&grid, &agents, &mut SearchStats::default()).unwrap();
+        let costs = |paths: &[Path]| paths.iter().map(|p| p.steps.len()).collect::<Vec<_>>();
+        assert_eq!(costs(&paths), costs(&expected));
+        for (path, &(start, goal)) in paths.iter().zip(&agents) {
+            assert!(path.is_valid_cardinal());
+            assert_eq!(path.steps[0], start.into());
+            assert_eq!(*path.steps.last().unwrap(), goal.into());
+        }
+        verify_no_collisions(&paths);
+        assert!(stats.nodes_expanded >= agents.len() as u64);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Push and Rotate tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Start and goal of an agent
+type Task = ((u32, u32), (u32, u32));
+
+/// Check that `paths` take every agent from its start to its goal
+fn assert_plan(paths: &[Path], agents: &[Task]) {
+    assert_eq!(paths.len(), agents.len());
+    for (path, &(start, goal)) in paths.iter().zip(agents) {
+        assert!(path.is_valid_cardinal());
+        assert_eq!(path.steps[0], start.into());
+        assert_eq!(*path.steps.last().unwrap(), goal.into());
+    }
+    verify_no_collisions(paths);
+}
+
+#[test]
+fn push_and_rotate_swaps_in_a_corridor() {
+    // Prioritized planning gets stuck: the first agent heads straight for
+    // its goal and shuts the second one out; swapping at the junction
+    // with the side pocket gets both through
+    let map = grid(
+        "
+        .....
+        @@.@@
+        ",
+    );
+    let grid = Grid::from_raw(&map, 5, 2);
+    let agents = vec![((1, 0), (3, 0)), ((3, 0), (1, 0))];
+    assert!(solve_mapf(&map, 5, 2, &agents).is_none());
+
+    let paths = solve_mapf_push_and_rotate(&grid, &agents).expect("Should swap at the junction");
+    assert_plan(&paths, &agents);
+}
+
+#[test]
+fn push_and_rotate_solves_crowded_grids() {
+    // Eight agents move two places round a 3x3 grid with a single free cell
+    let grid = Grid::from_raw(&open_3x3(), 3, 3);
+    let ring = [
+        (0, 0),
+        (1, 0),
+        (2, 0),
+        (2, 1),
+        (2, 2),
+        (1, 2),
+        (0, 2),
+        (0, 1),
+    ];
+    let agents: Vec<_> = (0..ring.len())
+        .map(|i| (ring[i], ring[(i + 2) % ring.len()]))
+        .collect();
+    let paths = solve_mapf_push_and_rotate(&grid, &agents).expect("Should solve the rotation");
+    assert_plan(&paths, &agents);
+
+    // Fourteen agents reverse their order on a 4x4 grid
+    let grid = Grid::from_raw(&[1; 16], 4, 4);
+    let agents: Vec<_> = (0..14u32)
+        .map(|i| ((i % 4, i / 4), ((13 - i) % 4, (13 - i) / 4)))
+        .collect();
+    let paths = solve_mapf_push_and_rotate(&grid, &agents).expect("Should solve the reversal");
+    assert_plan(&paths, &agents);
+}
+
+#[test]
+fn push_and_rotate_rotates_full_cycles() {
+    // Agent 0 has to get past agent 3 into the dead end at the top right,
+    // with only two free cells: nowhere to swap, but agent 3 can ride the
+    // ring in the lower left round while everyone on it moves at once
+    let map = grid(
+        "
+        @@.
+        ...
+        ..@
+        ",
+    );
+    let grid = Grid::from_raw(&map, 3, 3);
+    let agents = vec![
+        ((0, 2), (2, 0)),
+        ((1, 2), (1, 1)),
+        ((0, 1), (0, 1)),
+        ((2, 0), (1, 2)),
+    ];
+    let mut stats = SearchStats::default();
+    let paths = solve_mapf_push_and_rotate_with_stats(&grid, &agents, &mut stats)
+        .expect("Should rotate round the ring");
+    assert_plan(&paths, &agents);
+    assert!(stats.low_level_calls > 0);
+    assert!(stats.nodes_expanded > 0);
+}
+
+#[test]
+fn push_and_rotate_rejects_invalid_tasks() {
+    let grid = Grid::from_raw(&corridor_5x3(), 5, 3);
+    // Shared goal
+    let agents = vec![((0, 0), (4, 0)), ((0, 2), (4, 0))];
+    assert!(solve_mapf_push_and_rotate(&grid, &agents).is_none());
+    // Start in a wall
+    let agents = vec![((1, 1), (4, 0))];
+    assert!(solve_mapf_push_and_rotate(&grid, &agents).is_none());
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Terrain cost tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Open 5x3 grid whose middle row costs 10 to enter between the ends
+fn swamp_5x3() -> Grid {
+    let mut costs = vec![1; 15];
+    costs[6..9].fill(10);
+    Grid::from_raw(&[1; 15], 5, 3).with_costs(costs).unwrap()
+}
+
+#[test]
+fn astar_detours_around_costly_terrain() {
+    assert!(Grid::from_raw(&[1; 15], 5, 3)
+        .with_costs(vec![1; 14])
+        .is_none());
+    let grid = swamp_5x3();
+    assert!(grid.is_weighted());
+    assert_eq!(grid.cell_cost(2, 1), 10);
+
+    // Straight through costs 31; around the swamp, six moves cost 6
+    let path = astar_single_grid(&grid, (0, 1), (4, 1), 1.0).unwrap();
+    assert_eq!(path.cost(), 6);
+    assert_eq!(grid.path_cost(&path), 6);
+    assert!(path.steps.iter().all(|c| c.y != 1 || c.x == 0 || c.x == 4));
+}
+
+#[test]
+fn waiting_happens_on_cheap_cells() {
+    let map = grid(
+        "
+        .....
+        @@@.@
+        ",
+    );
+    let grid = Grid::from_raw(&map, 5, 2)
+        .with_costs(vec![1, 1, 5, 1, 1, 1, 1, 1, 1, 1])
+        .unwrap();
+    // Holds (3, 0) until timestep 5, then parks out of the way
+    let obstacle = path(&[(3, 0), (3, 0), (3, 0), (3, 0), (3, 0), (3, 0), (3, 1)]);
+    let planned = sipp_single(&grid, (0, 0), (4, 0), &[obstacle]).unwrap();
+    assert_eq!(planned.cost(), 7);
+    assert_eq!(grid.path_cost(&planned), 11);
+    // The three waits are spent before the costly cell, not on it
+    let costly = planned
+        .steps
+        .iter()
+        .filter(|c| (c.x, c.y) == (2, 0))
+        .count();
+    assert_eq!(costly, 1);
+}
+
+#[test]
+fn multiagent_searches_minimize_terrain_costs() {
+    let grid = swamp_5x3();
+    let agents = vec![((0, 1), (4, 1)), ((4, 2), (0, 2))];
+    // The centralized search pads paths with waits at the goal; those are free
+    let sum = |paths: &[Path]| {
+        paths
+            .iter()
+            .map(|p| {
+                grid.path_cost(&Path {
+                    steps: p.steps[..=arrival_time(p)].to_vec(),
+                })
+            })
+            .sum::<u32>()
+    };
+
+    let mut stats = SearchStats::default();
+    let prioritized = solve_mapf_grid_weighted(&grid, &agents, 1.0, &mut stats).unwrap();
+    let sipp = solve_mapf_sipp_grid_weighted(&grid, &agents, 1.0, &mut stats).unwrap();
+    let ecbs = solve_mapf_ecbs_grid(&grid, &agents, 1.0).unwrap();
+    let id = solve_mapf_id_grid(&grid, &agents, 1.0).unwrap();
+    let centralized =
+        solve_mapf_centralized_grid_with(&grid, &agents, Objective::SumOfCosts, |_| false).unwrap();
+    for paths in [&prioritized, &sipp, &ecbs, &id, &centralized] {
+        verify_no_collisions(paths);
+        assert_eq!(sum(paths), 10);
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// Conflict analysis tests
+// ─────────────────────────────────────────────────────────────────────────────
+
+#[test]
+fn conflicts_are_found_and_counted() {
+    let c = |x, y| Coordinate { x, y };
+    // 0 and 1 swap cells between timesteps 0 and 1; 2 then runs into 0,
+    // parked at its goal
+    let paths = vec![
+        path(&[(0, 0), (1, 0)]),
+        path(&[(1, 0), (0, 0)]),
+        path(&[(1, 2), (1, 1), (1, 0)]),
+    ];
+    let first = find_first_conflict(&paths).unwrap();
+    assert_eq!(
+        first,
+        Conflict {
+            kind: ConflictKind::Edge { to: c(1, 0) },
+            agents: (0, 1),
+            time: 0,
+            location: c(0, 0),
         }
+    );
+    assert_eq!(
+        first.to_string(),
+        "agents 0 and 1 swap (0, 0) and (1, 0) between timesteps 0 and 1"
+    );
+    assert_eq!(count_conflicts(&paths), 2);
+
+    let later = find_first_conflict(&[paths[0].clone(), paths[2].clone()]).unwrap();
+    assert_eq!(later.kind, ConflictKind::Vertex);
+    assert_eq!(
+        (later.agents, later.time, later.location),
+        ((0, 1), 2, c(1, 0))
+    );
+    assert_eq!(
+        later.to_string(),
+        "agents 0 and 1 both on (1, 0) at timestep 2"
+    );
+
+    assert_eq!(find_first_conflict(&paths[..1]), None);
+    assert_eq!(count_conflicts(&[]), 0);
+
+    // Once 0 leaves the map at its goal, only the swap remains
+    let gone = [paths[0].clone(), paths[2].clone()];
+    assert_eq!(find_first_conflict_with(&gone, true), None);
+    assert_eq!(find_first_conflict_with(&paths, true), Some(first));
+    assert_eq!(count_conflicts_with(&paths, true), 1);
+}
+
+/// Timestep at which an agent reaches its goal for the last time
+fn arrival_time(path: &Path) -> usize {
+    let goal = path.steps.last().unwrap();
+    path.steps.iter().rposition(|c| c != goal).map_or(0, |i| i + 1)
+}
+
+/// Helper function to verify no collisions between paths
+fn verify_no_collisions(paths: &[Path]) {
+    if let Some(conflict) = find_first_conflict(paths) {
+        panic!("Collision: {conflict}");
     }
 }
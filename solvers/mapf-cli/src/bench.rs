@@ -0,0 +1,314 @@
+//! `bench`: sweep a benchmark directory over several solvers, like a local
+//! run of the arena.
+
+use std::fs;
+use std::path::{Path as FsPath, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, ValueEnum};
+use mapf_astar::Objective;
+use mapf_core::{validate, Instance};
+
+use crate::report::{self, Outcome, Run};
+use crate::{
+    instance, read_map, read_scenario, solve_native, Algorithm, Rules, DEFAULT_SUBOPTIMALITY,
+};
+
+#[derive(Args)]
+pub struct BenchArgs {
+    /// Benchmark directory: `.map` files, with `<map>-*.scen` files anywhere below it
+    dir: PathBuf,
+    /// Solver to run: `prioritized`, `sipp`, `push-and-rotate`,
+    /// `centralized`, `ecbs`, `id` or a `.wasm` component (repeatable)
+    #[arg(short, long = "solver", required = true)]
+    solvers: Vec<String>,
+    /// Agent counts to try, e.g. `2,4,8`
+    #[arg(short, long, value_delimiter = ',', required = true)]
+    agents: Vec<usize>,
+    /// Only maps with these names (file stems), e.g. `empty-8-8,maze-32-32-2`
+    #[arg(long, value_delimiter = ',')]
+    maps: Vec<String>,
+    /// Use at most this many scenario files per map
+    #[arg(long)]
+    scenarios: Option<usize>,
+    /// Time limit per run, in seconds
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+    /// Instruction (fuel) limit for WASM solvers
+    #[arg(long, default_value_t = 10_000_000_000)]
+    fuel: u64,
+    /// Write the report to this file (`.md` or `.html`) instead of printing Markdown
+    #[arg(short, long)]
+    report: Option<PathBuf>,
+}
+
+/// A solver taking part in the sweep.
+enum Solver {
+    Native(Algorithm),
+    Wasm { name: String, bytes: Vec<u8> },
+}
+
+impl Solver {
+    fn parse(spec: &str) -> Result<Self> {
+        if spec.ends_with(".wasm") {
+            let path = FsPath::new(spec);
+            let bytes = fs::read(path).with_context(|| format!("Failed to read {}", spec))?;
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            return Ok(Solver::Wasm { name, bytes });
+        }
+        match Algorithm::from_str(spec, true) {
+            Ok(algorithm) => Ok(Solver::Native(algorithm)),
+            Err(_) => bail!(
+                "Unknown solver {:?}; use prioritized, sipp, push-and-rotate, centralized, ecbs, id or a .wasm file",
+                spec
+            ),
+        }
+    }
+
+    fn name(&self) -> String {
+        match self {
+            Solver::Native(algorithm) => algorithm
+                .to_possible_value()
+                .map(|v| v.get_name().to_string())
+                .unwrap_or_default(),
+            Solver::Wasm { name, .. } => name.clone(),
+        }
+    }
+}
+
+/// A map and the scenario files that belong to it.
+struct Benchmark {
+    name: String,
+    map: PathBuf,
+    scenarios: Vec<PathBuf>,
+}
+
+pub fn run(args: BenchArgs) -> Result<ExitCode> {
+    let solvers = args
+        .solvers
+        .iter()
+        .map(|spec| Solver::parse(spec))
+        .collect::<Result<Vec<_>>>()?;
+    let benchmarks = discover(&args.dir, &args.maps, args.scenarios)?;
+    if benchmarks.is_empty() {
+        bail!("No maps with scenarios found in {}", args.dir.display());
+    }
+    let timeout = Duration::from_secs(args.timeout);
+
+    let mut runs = Vec::new();
+    for benchmark in &benchmarks {
+        let map = read_map(&benchmark.map)?;
+        for scenario_path in &benchmark.scenarios {
+            let scenario = read_scenario(scenario_path)?;
+            let scenario_name = scenario_path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+
+            for &agents in &args.agents {
+                // Smaller scenarios simply don't take part at this size
+                if agents > scenario.entries().len() {
+                    continue;
+                }
+                let instance = instance(map.clone(), &scenario, Some(agents))?;
+
+                for solver in &solvers {
+                    let (outcome, time_ms) = run_one(solver, &instance, timeout, args.fuel)?;
+                    let status = match &outcome {
+                        Ok(score) => format!("cost {}", score.cost),
+                        Err(reason) => reason.clone(),
+                    };
+                    eprintln!(
+                        "{} on {} ({}, {} agents): {}",
+                        solver.name(),
+                        benchmark.name,
+                        scenario_name,
+                        agents,
+                        status
+                    );
+                    runs.push(Run {
+                        solver: solver.name(),
+                        map: benchmark.name.clone(),
+                        agents,
+                        outcome,
+                        time_ms,
+                    });
+                }
+            }
+        }
+    }
+
+    match &args.report {
+        Some(path) => {
+            let contents = match path.extension().and_then(|e| e.to_str()) {
+                Some("md") => report::markdown(&runs),
+                Some("html") => report::html(&runs),
+                _ => bail!(
+                    "Unknown report format for {}; use .md or .html",
+                    path.display()
+                ),
+            };
+            fs::write(path, contents)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        None => print!("{}", report::markdown(&runs)),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Run `solver` once and check its answer; returns the outcome and the
+/// solve time in milliseconds.
+fn run_one(
+    solver: &Solver,
+    instance: &Instance,
+    timeout: Duration,
+    fuel: u64,
+) -> Result<(Outcome, f64)> {
+    let (solution, time_ms) = match solver {
+        Solver::Native(algorithm) => {
+            let run = solve_native(
+                instance,
+                *algorithm,
+                Objective::SumOfCosts,
+                DEFAULT_SUBOPTIMALITY,
+                1.0,
+                Rules::default(),
+                Some(timeout),
+            );
+            (
+                run.result.map_err(String::from),
+                run.elapsed.as_secs_f64() * 1000.0,
+            )
+        }
+        Solver::Wasm { bytes, .. } => run_component(bytes, instance, timeout, fuel)?,
+    };
+
+    let outcome = solution.and_then(|solution| {
+        let violations =
+            validate::validate(&instance.map, &instance.starts, &instance.goals, &solution);
+        match violations.first() {
+            None => Ok(mapf_scoring::score(&solution, &instance.map)),
+            Some(first) => Err(format!("invalid: {}", first.kind.as_str())),
+        }
+    });
+    Ok((outcome, time_ms))
+}
+
+#[cfg(feature = "wasm")]
+fn run_component(
+    bytes: &[u8],
+    instance: &Instance,
+    timeout: Duration,
+    fuel: u64,
+) -> Result<(Result<mapf_core::Solution, String>, f64)> {
+    use mapf_executor::executor::WasmExecutor;
+
+    let executor = WasmExecutor::new(timeout.as_secs(), fuel)?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let result = runtime.block_on(executor.execute(
+        bytes,
+        &instance.map,
+        &instance.starts,
+        &instance.goals,
+        &[],
+        &[],
+    ))?;
+
+    let solution = match (result.solution, result.error) {
+        (_, Some(error)) => Err(error),
+        (Some(solution), None) => Ok(solution),
+        (None, None) => Err("no solution".to_string()),
+    };
+    Ok((solution, result.stats.execution_time_ms as f64))
+}
+
+#[cfg(not(feature = "wasm"))]
+fn run_component(
+    _bytes: &[u8],
+    _instance: &Instance,
+    _timeout: Duration,
+    _fuel: u64,
+) -> Result<(Result<mapf_core::Solution, String>, f64)> {
+    bail!("WASM solvers need the executor; rebuild with `--features wasm`")
+}
+
+/// Maps in `dir` (optionally only those named in `only`) with their
+/// scenario files, at most `limit` per map, in natural name order.
+fn discover(dir: &FsPath, only: &[String], limit: Option<usize>) -> Result<Vec<Benchmark>> {
+    let mut scenario_files = Vec::new();
+    collect_files(dir, "scen", &mut scenario_files)?;
+
+    let mut benchmarks = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let map = entry?.path();
+        if map.extension().and_then(|e| e.to_str()) != Some("map") {
+            continue;
+        }
+        let name = map
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        if !only.is_empty() && !only.contains(&name) {
+            continue;
+        }
+
+        // `maze-128-128-1-even-1.scen` belongs to `maze-128-128-1`, not `maze-128-128-10`
+        let prefix = format!("{}-", name);
+        let mut scenarios: Vec<PathBuf> = scenario_files
+            .iter()
+            .filter(|p| file_name(p).starts_with(&prefix))
+            .cloned()
+            .collect();
+        scenarios.sort_by_key(|p| natural_key(&file_name(p)));
+        scenarios.truncate(limit.unwrap_or(usize::MAX));
+
+        if !scenarios.is_empty() {
+            benchmarks.push(Benchmark {
+                name,
+                map,
+                scenarios,
+            });
+        }
+    }
+    benchmarks.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(benchmarks)
+}
+
+/// Every file with `extension` under `dir`, recursively.
+fn collect_files(dir: &FsPath, extension: &str, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, extension, files)?;
+        } else if path.extension().and_then(|e| e.to_str()) == Some(extension) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn file_name(path: &FsPath) -> String {
+    path.file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Sort key placing `even-2` before `even-10`: the name up to its trailing
+/// number, then that number.
+fn natural_key(name: &str) -> (String, u64) {
+    let stem = name.strip_suffix(".scen").unwrap_or(name);
+    let digits = stem.len() - stem.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (text, number) = stem.split_at(stem.len() - digits);
+    (text.to_string(), number.parse().unwrap_or(0))
+}
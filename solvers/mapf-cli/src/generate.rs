@@ -0,0 +1,176 @@
+//! `generate`: mint a fresh benchmark map with scenario files, laid out
+//! like the MovingAI benchmarks so `bench` and the arena pick them up.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use mapf_gen::cave::{self, CaveParams};
+use mapf_gen::city::{self, CityParams};
+use mapf_gen::scenario;
+use mapf_gen::warehouse::{self, WarehouseParams};
+
+#[derive(Args)]
+pub struct GenerateArgs {
+    /// Directory to write `<name>.map` and `scen-random/<name>-random-<n>.scen` into
+    dir: PathBuf,
+    #[command(subcommand)]
+    layout: Layout,
+    /// Seed for the map; the scenarios' seeds are derived from it
+    #[arg(long, global = true, default_value_t = 0)]
+    seed: u64,
+    /// Number of scenario files
+    #[arg(long, global = true, default_value_t = 25)]
+    scenarios: u32,
+    /// Agents (tasks) per scenario file
+    #[arg(short, long, global = true, default_value_t = 100)]
+    agents: usize,
+    /// Map name; defaults to `<layout>-<width>-<height>-<seed>`
+    #[arg(long, global = true)]
+    name: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Layout {
+    /// Shelf blocks separated by aisles, inside an open border
+    Warehouse(WarehouseArgs),
+    /// Blocks of buildings between streets, with some open parks
+    City(CityArgs),
+    /// Smoothed random caves, trimmed to one connected chamber
+    Cave(CaveArgs),
+}
+
+#[derive(Args)]
+struct WarehouseArgs {
+    /// Shelf blocks per row
+    #[arg(long, default_value_t = WarehouseParams::default().columns)]
+    columns: u32,
+    /// Rows of shelf blocks
+    #[arg(long, default_value_t = WarehouseParams::default().rows)]
+    rows: u32,
+    /// Length of a shelf block, in cells
+    #[arg(long, default_value_t = WarehouseParams::default().shelf_length)]
+    shelf_length: u32,
+    /// Depth of a shelf block, in cells
+    #[arg(long, default_value_t = WarehouseParams::default().shelf_depth)]
+    shelf_depth: u32,
+    /// Width of the aisles between blocks
+    #[arg(long, default_value_t = WarehouseParams::default().aisle_width)]
+    aisle_width: u32,
+    /// Width of the open area around the shelves
+    #[arg(long, default_value_t = WarehouseParams::default().border)]
+    border: u32,
+    /// Chance that a shelf block is left out (0-1)
+    #[arg(long, default_value_t = WarehouseParams::default().gap_probability)]
+    gaps: f64,
+}
+
+#[derive(Args)]
+struct CityArgs {
+    #[arg(long, default_value_t = CityParams::default().width)]
+    width: u32,
+    #[arg(long, default_value_t = CityParams::default().height)]
+    height: u32,
+    /// Width of every street, in cells
+    #[arg(long, default_value_t = CityParams::default().street_width)]
+    street_width: u32,
+    /// Smallest side of a block, in cells
+    #[arg(long, default_value_t = CityParams::default().block_min)]
+    block_min: u32,
+    /// Largest side of a block, in cells
+    #[arg(long, default_value_t = CityParams::default().block_max)]
+    block_max: u32,
+    /// Chance that a block is open ground (0-1)
+    #[arg(long, default_value_t = CityParams::default().park_probability)]
+    parks: f64,
+}
+
+#[derive(Args)]
+struct CaveArgs {
+    #[arg(long, default_value_t = CaveParams::default().width)]
+    width: u32,
+    #[arg(long, default_value_t = CaveParams::default().height)]
+    height: u32,
+    /// Share of the initial noise that is rock (0-1)
+    #[arg(long, default_value_t = CaveParams::default().fill)]
+    fill: f64,
+    /// Smoothing passes
+    #[arg(long, default_value_t = CaveParams::default().smoothing)]
+    smoothing: u32,
+}
+
+pub fn run(args: GenerateArgs) -> Result<ExitCode> {
+    let (kind, map) = match &args.layout {
+        Layout::Warehouse(a) => {
+            let params = WarehouseParams {
+                columns: a.columns,
+                rows: a.rows,
+                shelf_length: a.shelf_length,
+                shelf_depth: a.shelf_depth,
+                aisle_width: a.aisle_width,
+                border: a.border,
+                gap_probability: a.gaps,
+            };
+            ("warehouse", warehouse::generate(&params, args.seed)?)
+        }
+        Layout::City(a) => {
+            let params = CityParams {
+                width: a.width,
+                height: a.height,
+                street_width: a.street_width,
+                block_min: a.block_min,
+                block_max: a.block_max,
+                park_probability: a.parks,
+            };
+            ("city", city::generate(&params, args.seed)?)
+        }
+        Layout::Cave(a) => {
+            let params = CaveParams {
+                width: a.width,
+                height: a.height,
+                fill: a.fill,
+                smoothing: a.smoothing,
+            };
+            ("cave", cave::generate(&params, args.seed)?)
+        }
+    };
+    let name = args
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("{}-{}-{}-{}", kind, map.width(), map.height(), args.seed));
+
+    let scen_dir = args.dir.join("scen-random");
+    fs::create_dir_all(&scen_dir)
+        .with_context(|| format!("Failed to create {}", scen_dir.display()))?;
+    let map_path = args.dir.join(format!("{}.map", name));
+    fs::write(&map_path, map.to_movingai())
+        .with_context(|| format!("Failed to write {}", map_path.display()))?;
+
+    let map_name = format!("{}.map", name);
+    for n in 1..=args.scenarios {
+        // Spread the seeds so neighbouring map seeds don't share scenarios
+        let seed = args.seed ^ (n as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let scen = scenario::generate(&map, &map_name, args.agents, seed)?;
+        let path = scen_dir.join(format!("{}-random-{}.scen", name, n));
+        fs::write(&path, scen.to_movingai())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    let free = map.to_bytes().iter().filter(|&&t| t != 0).count();
+    println!(
+        "map:             {} ({}x{}, {} free cells)",
+        map_path.display(),
+        map.width(),
+        map.height(),
+        free
+    );
+    println!(
+        "scenarios:       {} of {} agents in {}",
+        args.scenarios,
+        args.agents,
+        scen_dir.display()
+    );
+    Ok(ExitCode::SUCCESS)
+}
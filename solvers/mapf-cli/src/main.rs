@@ -0,0 +1,735 @@
+//! Command-line runner for MAPF solvers: the offline counterpart to the arena.
+//!
+//! Loads a MovingAI `.map`/`.scen` pair and either solves it with the native
+//! reference algorithms, runs a submitted WIT component through the arena's
+//! executor (`--features wasm`), or checks, renders, plays back or compares
+//! existing solutions. `bench` sweeps a whole benchmark directory and writes
+//! a report; `convert` brings maps from other tools into MovingAI format and
+//! `generate` mints new ones.
+
+mod bench;
+mod generate;
+mod play;
+mod report;
+
+use std::fs;
+use std::io::BufReader;
+use std::path::{Path as FsPath, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use mapf_astar::{
+    CentralizedSearch, EcbsSearch, Grid, IdSearch, Movement, Objective, SearchStats, SearchStatus,
+};
+use mapf_convert::Format;
+use mapf_core::{validate, GridMap, Instance, Path, Scenario, Solution};
+use mapf_viz::RenderOptions;
+
+#[derive(Parser)]
+#[command(
+    name = "mapf-cli",
+    version,
+    about = "Run and check MAPF solvers offline"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve an instance with a native reference algorithm
+    Solve(SolveArgs),
+    /// Run a solver component the way the arena backend does
+    RunWasm(RunWasmArgs),
+    /// Check a solution against the MAPF rules
+    Validate(ValidateArgs),
+    /// Draw a solution as an SVG overview or an animated GIF
+    Render(RenderArgs),
+    /// Step through a solution in the terminal
+    Play(play::PlayArgs),
+    /// Compare two solutions to the same instance
+    Diff(DiffArgs),
+    /// Compare solvers over a benchmark directory and report the results
+    Bench(bench::BenchArgs),
+    /// Convert a map between MovingAI, JSON, RLE and PNG formats
+    Convert(ConvertArgs),
+    /// Generate a warehouse, city or cave map with scenario files
+    Generate(generate::GenerateArgs),
+}
+
+/// A map, a scenario, and how many of its agents to use.
+#[derive(Args)]
+struct InstanceArgs {
+    /// MovingAI `.map` file, possibly gzipped or zipped
+    map: PathBuf,
+    /// MovingAI `.scen` file, possibly gzipped or zipped
+    scen: PathBuf,
+    /// Use only the first N agents of the scenario
+    #[arg(short, long)]
+    agents: Option<usize>,
+}
+
+#[derive(Args)]
+struct SolveArgs {
+    #[command(flatten)]
+    instance: InstanceArgs,
+    #[arg(long, value_enum, default_value_t = Algorithm::Prioritized)]
+    algorithm: Algorithm,
+    /// Cost the centralized, ECBS and ID searches minimize; the others plan
+    /// each agent's shortest path
+    #[arg(long, value_enum, default_value_t = ObjectiveArg::SumOfCosts)]
+    objective: ObjectiveArg,
+    /// How far above the optimal cost ECBS (also within ID groups) may go,
+    /// as a factor of at least 1; 1 is plain, optimal CBS
+    #[arg(long, default_value_t = DEFAULT_SUBOPTIMALITY)]
+    suboptimality: f64,
+    /// Heuristic weight of the prioritized and SIPP searches, at least 1:
+    /// each path costs at most this factor more than the shortest way
+    /// around the agents before it, but is found faster
+    #[arg(long, default_value_t = 1.0)]
+    weight: f64,
+    /// Moves agents may make; the arena and `validate` only accept cardinal
+    /// ones
+    #[arg(long, value_enum, default_value_t = MovementArg::Cardinal)]
+    movement: MovementArg,
+    /// Let agents vanish once they reach their goal instead of blocking it;
+    /// the arena keeps them there
+    #[arg(long)]
+    disappear_at_goal: bool,
+    /// Give up on the centralized, ECBS or ID search after this many seconds
+    #[arg(long)]
+    timeout: Option<u64>,
+    /// Write the solution as JSON to this file
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct RunWasmArgs {
+    /// Solver component (`.wasm`)
+    component: PathBuf,
+    #[command(flatten)]
+    instance: InstanceArgs,
+    /// Wall-clock budget in seconds
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+    /// Instruction (fuel) limit
+    #[arg(long, default_value_t = 10_000_000_000)]
+    fuel: u64,
+    /// Seed passed to the solver
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Write the solution as JSON to this file
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct ValidateArgs {
+    #[command(flatten)]
+    instance: InstanceArgs,
+    /// Solution JSON, as written by `solve --output` or returned by the API
+    solution: PathBuf,
+    /// Check collisions as if agents vanish once their path ends, as with
+    /// `solve --disappear-at-goal`
+    #[arg(long)]
+    disappear_at_goal: bool,
+}
+
+#[derive(Args)]
+struct RenderArgs {
+    /// MovingAI `.map` file
+    map: PathBuf,
+    /// Solution JSON, as written by `solve --output` or returned by the API
+    solution: PathBuf,
+    /// Image to write; `.svg` or `.gif`
+    #[arg(short, long)]
+    output: PathBuf,
+    /// Side of one grid cell, in pixels
+    #[arg(long, default_value_t = 16)]
+    cell_size: u32,
+    /// Time each GIF frame (one timestep) is shown, in milliseconds
+    #[arg(long, default_value_t = 250)]
+    delay: u32,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// First solution JSON
+    a: PathBuf,
+    /// Second solution JSON
+    b: PathBuf,
+    /// Write an SVG overlaying both solutions here; needs `--map`
+    #[arg(short, long, requires = "map")]
+    output: Option<PathBuf>,
+    /// MovingAI `.map` file the solutions are for; totals are then leaderboard scores
+    #[arg(long)]
+    map: Option<PathBuf>,
+    /// Side of one grid cell, in pixels
+    #[arg(long, default_value_t = 16)]
+    cell_size: u32,
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    /// Map to read
+    input: PathBuf,
+    /// Map to write
+    output: PathBuf,
+    /// Input format, if its extension doesn't say
+    #[arg(long)]
+    from: Option<MapFormat>,
+    /// Output format, if its extension doesn't say
+    #[arg(long)]
+    to: Option<MapFormat>,
+    /// PNG input: pixels darker than this luminance (0-255) are obstacles
+    #[arg(long, default_value_t = mapf_convert::png::DEFAULT_THRESHOLD)]
+    threshold: u8,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Algorithm {
+    /// Fast prioritized space-time A* planning; not optimal, may fail
+    Prioritized,
+    /// Prioritized planning with safe interval path planning (SIPP); same
+    /// paths, faster when agents wait long or paths are long
+    Sipp,
+    /// Rule-based Push and Rotate; for crowded maps, with long paths
+    PushAndRotate,
+    /// Optimal joint-state A*; only practical for a few agents
+    Centralized,
+    /// Conflict-based search within `--suboptimality` of the optimal cost;
+    /// scales to many more agents
+    Ecbs,
+    /// Independence detection: agents planned alone, and only the groups
+    /// that collide solved together with ECBS; for many agents that rarely
+    /// meet
+    Id,
+}
+
+/// ECBS suboptimality factor when none is given.
+const DEFAULT_SUBOPTIMALITY: f64 = 1.2;
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ObjectiveArg {
+    SumOfCosts,
+    Makespan,
+}
+
+impl From<ObjectiveArg> for Objective {
+    fn from(objective: ObjectiveArg) -> Self {
+        match objective {
+            ObjectiveArg::SumOfCosts => Objective::SumOfCosts,
+            ObjectiveArg::Makespan => Objective::Makespan,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MovementArg {
+    /// North, south, east and west
+    Cardinal,
+    /// Diagonals too, without cutting corners
+    Octile,
+}
+
+impl From<MovementArg> for Movement {
+    fn from(movement: MovementArg) -> Self {
+        match movement {
+            MovementArg::Cardinal => Movement::Cardinal,
+            MovementArg::Octile => Movement::Octile,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MapFormat {
+    /// MovingAI `.map` text
+    Map,
+    /// The API's `{ width, height, tiles }` object
+    Json,
+    /// Run-length encoded bytes
+    Rle,
+    /// Occupancy image, one pixel per cell
+    Png,
+}
+
+impl From<MapFormat> for Format {
+    fn from(format: MapFormat) -> Self {
+        match format {
+            MapFormat::Map => Format::MovingAi,
+            MapFormat::Json => Format::Json,
+            MapFormat::Rle => Format::Rle,
+            MapFormat::Png => Format::Png,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Solve(args) => solve(args),
+        Command::RunWasm(args) => run_wasm(args),
+        Command::Validate(args) => validate(args),
+        Command::Render(args) => render(args),
+        Command::Play(args) => play::run(args),
+        Command::Diff(args) => diff(args),
+        Command::Bench(args) => bench::run(args),
+        Command::Convert(args) => convert(args),
+        Command::Generate(args) => generate::run(args),
+    };
+    result.unwrap_or_else(|e| {
+        eprintln!("error: {:#}", e);
+        ExitCode::FAILURE
+    })
+}
+
+/// Load the instance named on the command line.
+fn load_instance(args: &InstanceArgs) -> Result<Instance> {
+    let map = read_map(&args.map)?;
+    let scenario = read_scenario(&args.scen)?;
+    instance(map, &scenario, args.agents)
+}
+
+/// The first `agents` agents of `scenario` (all of them if `None`) on `map`.
+fn instance(map: GridMap, scenario: &Scenario, agents: Option<usize>) -> Result<Instance> {
+    let count = agents.unwrap_or(scenario.entries().len());
+
+    // List every entry in use that doesn't fit the map, not just the first
+    let errors: Vec<String> = scenario
+        .validate_against(&map)
+        .iter()
+        .filter(|e| e.entry() < count)
+        .map(ToString::to_string)
+        .collect();
+    if !errors.is_empty() {
+        bail!("Scenario doesn't fit the map:\n  {}", errors.join("\n  "));
+    }
+    Ok(Instance::from_scenario(map, scenario, count)?)
+}
+
+fn tasks(instance: &Instance) -> Vec<((u32, u32), (u32, u32))> {
+    instance
+        .starts
+        .iter()
+        .zip(&instance.goals)
+        .map(|(s, g)| ((s.x, s.y), (g.x, g.y)))
+        .collect()
+}
+
+fn solve(args: SolveArgs) -> Result<ExitCode> {
+    let instance = load_instance(&args.instance)?;
+    let timeout = args.timeout.map(Duration::from_secs);
+    let run = solve_native(
+        &instance,
+        args.algorithm,
+        args.objective.into(),
+        args.suboptimality,
+        args.weight,
+        Rules {
+            movement: args.movement.into(),
+            disappear_at_goal: args.disappear_at_goal,
+        },
+        timeout,
+    );
+
+    println!("agents:          {}", instance.num_agents());
+    println!("nodes expanded:  {}", run.stats.nodes_expanded);
+    println!("nodes generated: {}", run.stats.nodes_generated);
+    println!("low-level calls: {}", run.stats.low_level_calls);
+    println!(
+        "time:            {:.3} ms",
+        run.elapsed.as_secs_f64() * 1000.0
+    );
+
+    let solution = match run.result {
+        Ok(solution) => solution,
+        Err(reason) => {
+            println!("result:          {}", reason);
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    print_costs(&solution, &instance.map);
+    if let Some(output) = &args.output {
+        write_solution(output, &solution)?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Outcome of a native solver run.
+struct NativeRun {
+    /// The solution, or why there is none
+    result: Result<Solution, &'static str>,
+    stats: SearchStats,
+    elapsed: Duration,
+}
+
+/// How agents may move and what becomes of them at their goal; the
+/// arena's rules by default.
+#[derive(Clone, Copy, Default)]
+struct Rules {
+    movement: Movement,
+    disappear_at_goal: bool,
+}
+
+/// Solve `instance` with a reference algorithm. `timeout` only bounds the
+/// centralized, ECBS and ID searches; the others always finish quickly.
+fn solve_native(
+    instance: &Instance,
+    algorithm: Algorithm,
+    objective: Objective,
+    suboptimality: f64,
+    weight: f64,
+    rules: Rules,
+    timeout: Option<Duration>,
+) -> NativeRun {
+    let grid = Grid::from_map(&instance.map)
+        .with_movement(rules.movement)
+        .with_disappear_at_goal(rules.disappear_at_goal)
+        .with_neighbor_table();
+    let tasks = tasks(instance);
+
+    let started = Instant::now();
+    let deadline = timeout.map(|t| started + t);
+    let out_of_time = |_| deadline.is_some_and(|d| Instant::now() >= d);
+    let outcome = |status| match status {
+        SearchStatus::Solved(paths) => Ok(paths),
+        SearchStatus::Running => Err("timed out"),
+        SearchStatus::Exhausted => Err("no solution"),
+    };
+    let (result, stats) = match algorithm {
+        Algorithm::Prioritized => {
+            let mut stats = SearchStats::default();
+            let paths = mapf_astar::solve_mapf_grid_weighted(&grid, &tasks, weight, &mut stats);
+            (paths.ok_or("no solution"), stats)
+        }
+        Algorithm::Sipp => {
+            let mut stats = SearchStats::default();
+            let paths =
+                mapf_astar::solve_mapf_sipp_grid_weighted(&grid, &tasks, weight, &mut stats);
+            (paths.ok_or("no solution"), stats)
+        }
+        Algorithm::PushAndRotate => {
+            let mut stats = SearchStats::default();
+            let paths =
+                mapf_astar::solve_mapf_push_and_rotate_with_stats(&grid, &tasks, &mut stats);
+            (paths.ok_or("no solution"), stats)
+        }
+        Algorithm::Centralized => {
+            let mut search = CentralizedSearch::new(&grid, &tasks, objective);
+            (outcome(search.run(out_of_time)), search.stats())
+        }
+        Algorithm::Ecbs => {
+            let mut search = EcbsSearch::new(&grid, &tasks, objective, suboptimality);
+            (outcome(search.run(out_of_time)), search.stats())
+        }
+        Algorithm::Id => {
+            let mut search = IdSearch::new(&grid, &tasks, objective, suboptimality);
+            (outcome(search.run(out_of_time)), search.stats())
+        }
+    };
+
+    NativeRun {
+        result: result.map(|paths| Solution {
+            paths: paths.into_iter().map(|p| Path { steps: p.steps }).collect(),
+        }),
+        stats,
+        elapsed: started.elapsed(),
+    }
+}
+
+#[cfg(feature = "wasm")]
+fn run_wasm(args: RunWasmArgs) -> Result<ExitCode> {
+    use mapf_executor::executor::WasmExecutor;
+
+    let instance = load_instance(&args.instance)?;
+    let wasm_bytes = fs::read(&args.component)
+        .with_context(|| format!("Failed to read {}", args.component.display()))?;
+
+    let executor = WasmExecutor::new(args.timeout, args.fuel)?.with_seed(args.seed);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let result = runtime.block_on(executor.execute(
+        &wasm_bytes,
+        &instance.map,
+        &instance.starts,
+        &instance.goals,
+        &[],
+        &[],
+    ))?;
+
+    for entry in &result.log.entries {
+        eprintln!("[{}] {}", entry.level, entry.message);
+    }
+    if result.log.truncated {
+        eprintln!("(solver log truncated)");
+    }
+
+    let stats = &result.stats;
+    println!("agents:          {}", instance.num_agents());
+    println!("seed:            {}", stats.seed);
+    if let Some(fuel) = stats.fuel_consumed {
+        println!("instructions:    {}", fuel);
+    }
+    println!("time:            {} ms", stats.execution_time_ms);
+    if let Some(guest) = &stats.guest {
+        println!("nodes expanded:  {} (reported)", guest.nodes_expanded);
+        println!("nodes generated: {} (reported)", guest.nodes_generated);
+        println!("low-level calls: {} (reported)", guest.low_level_calls);
+    }
+
+    if let Some(error) = &result.error {
+        println!("result:          {}", error);
+        return Ok(ExitCode::FAILURE);
+    }
+    let Some(solution) = &result.solution else {
+        bail!("Solver returned no solution and no error");
+    };
+    print_costs(solution, &instance.map);
+    if let Some(declared) = result.declared_cost {
+        println!("declared cost:   {}", declared);
+    }
+    if let Some(output) = &args.output {
+        write_solution(output, solution)?;
+    }
+    Ok(report_violations(&instance, solution, false))
+}
+
+#[cfg(not(feature = "wasm"))]
+fn run_wasm(_args: RunWasmArgs) -> Result<ExitCode> {
+    bail!("run-wasm needs the executor; rebuild with `--features wasm`")
+}
+
+fn validate(args: ValidateArgs) -> Result<ExitCode> {
+    let instance = load_instance(&args.instance)?;
+    let solution = read_solution(&args.solution)?;
+
+    print_costs(&solution, &instance.map);
+    Ok(report_violations(
+        &instance,
+        &solution,
+        args.disappear_at_goal,
+    ))
+}
+
+fn render(args: RenderArgs) -> Result<ExitCode> {
+    let map = read_map(&args.map)?;
+    let solution = read_solution(&args.solution)?;
+    let options = RenderOptions {
+        cell_size: args.cell_size,
+        frame_delay_ms: args.delay,
+        ..RenderOptions::default()
+    };
+
+    let image = match args.output.extension().and_then(|e| e.to_str()) {
+        Some("svg") => mapf_viz::render_svg(&map, &solution, &options).into_bytes(),
+        Some("gif") => mapf_viz::render_gif(&map, &solution, &options)?,
+        _ => bail!(
+            "Unknown image format for {}; use .svg or .gif",
+            args.output.display()
+        ),
+    };
+    fs::write(&args.output, image)
+        .with_context(|| format!("Failed to write {}", args.output.display()))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff(args: DiffArgs) -> Result<ExitCode> {
+    let (a, b) = (read_solution(&args.a)?, read_solution(&args.b)?);
+    let map = args.map.as_deref().map(read_map).transpose()?;
+    let comparison = mapf_core::diff::diff(&a, &b);
+
+    // Leaderboard scores need the map for weights; without it, count actions
+    let ((cost_a, cost_b), (makespan_a, makespan_b), label) = match &map {
+        Some(map) => {
+            let (a, b) = (mapf_scoring::score(&a, map), mapf_scoring::score(&b, map));
+            ((a.cost, b.cost), (a.makespan, b.makespan), "cost:")
+        }
+        None => {
+            let (makespan_a, makespan_b) = comparison.makespan;
+            (
+                comparison.sum_of_costs,
+                (makespan_a as u64, makespan_b as u64),
+                "sum of costs:",
+            )
+        }
+    };
+    println!(
+        "{:<16} {} -> {} ({:+})",
+        label,
+        cost_a,
+        cost_b,
+        cost_b as i64 - cost_a as i64
+    );
+    println!(
+        "makespan:        {} -> {} ({:+})",
+        makespan_a,
+        makespan_b,
+        makespan_b as i64 - makespan_a as i64
+    );
+    if a.paths.len() != b.paths.len() {
+        println!(
+            "agents:          {} vs {}; comparing the first {}",
+            a.paths.len(),
+            b.paths.len(),
+            comparison.agents.len()
+        );
+    }
+    println!(
+        "changed agents:  {} of {}",
+        comparison.changed().count(),
+        comparison.agents.len()
+    );
+    for agent in comparison.changed() {
+        let spans: Vec<String> = agent
+            .divergences
+            .iter()
+            .map(|span| format!("{}..{}", span.start, span.end))
+            .collect();
+        println!(
+            "  agent {}: cost {} -> {} ({:+}), apart at t={}",
+            agent.agent,
+            agent.cost_a,
+            agent.cost_b,
+            agent.cost_delta(),
+            spans.join(", ")
+        );
+    }
+
+    if let (Some(output), Some(map)) = (&args.output, &map) {
+        let options = RenderOptions {
+            cell_size: args.cell_size,
+            ..RenderOptions::default()
+        };
+        fs::write(output, mapf_viz::render_diff_svg(map, &a, &b, &options))
+            .with_context(|| format!("Failed to write {}", output.display()))?;
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn convert(args: ConvertArgs) -> Result<ExitCode> {
+    let from = map_format(&args.input, args.from)?;
+    let to = map_format(&args.output, args.to)?;
+
+    let data = fs::read(&args.input)
+        .with_context(|| format!("Failed to read {}", args.input.display()))?;
+    let map = match from {
+        Format::Png => mapf_convert::png::decode(&data, args.threshold),
+        _ => mapf_convert::read(&data, from),
+    }
+    .with_context(|| format!("Failed to parse map {}", args.input.display()))?;
+
+    if map.is_weighted() && !to.has_costs() {
+        eprintln!(
+            "warning: {} can't hold cell costs; writing occupancy only",
+            args.output.display()
+        );
+    }
+    fs::write(&args.output, mapf_convert::write(&map, to)?)
+        .with_context(|| format!("Failed to write {}", args.output.display()))?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// `format` if given, otherwise the one `path`'s extension names.
+fn map_format(path: &FsPath, format: Option<MapFormat>) -> Result<Format> {
+    if let Some(format) = format {
+        return Ok(format.into());
+    }
+    match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(Format::from_extension)
+    {
+        Some(format) => Ok(format),
+        None => bail!(
+            "Unknown map format for {}; use .map, .json, .rle or .png, or pass --from/--to",
+            path.display()
+        ),
+    }
+}
+
+/// Print every rule `solution` breaks, with agents vanishing at their goal
+/// if `disappear_at_goal` is set; fails if there are any.
+fn report_violations(
+    instance: &Instance,
+    solution: &Solution,
+    disappear_at_goal: bool,
+) -> ExitCode {
+    let violations = validate::validate_with(
+        &instance.map,
+        &instance.starts,
+        &instance.goals,
+        solution,
+        disappear_at_goal,
+    );
+    if violations.is_empty() {
+        println!("result:          valid");
+        return ExitCode::SUCCESS;
+    }
+
+    println!("result:          {} violations", violations.len());
+    for violation in &violations {
+        println!("  [{}] {}", violation.kind.as_str(), violation.details);
+    }
+    ExitCode::FAILURE
+}
+
+/// Print the score the leaderboard would record for `solution`.
+fn print_costs(solution: &Solution, map: &GridMap) {
+    let score = mapf_scoring::score(solution, map);
+    println!("cost:            {}", score.cost);
+    println!("makespan:        {}", score.makespan);
+}
+
+fn write_solution(path: &FsPath, solution: &Solution) -> Result<()> {
+    let json = serde_json::to_string(solution)?;
+    fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn read_map(path: &FsPath) -> Result<GridMap> {
+    let map = if is_compressed(path) {
+        GridMap::parse_compressed(&read_bytes(path)?).map_err(anyhow::Error::from)
+    } else {
+        GridMap::parse_from_reader(open(path)?).map_err(anyhow::Error::from)
+    };
+    map.with_context(|| format!("Failed to parse map {}", path.display()))
+}
+
+fn read_scenario(path: &FsPath) -> Result<Scenario> {
+    let scenario = if is_compressed(path) {
+        Scenario::parse_compressed(&read_bytes(path)?).map_err(anyhow::Error::from)
+    } else {
+        Scenario::parse_from_reader(open(path)?).map_err(anyhow::Error::from)
+    };
+    scenario.with_context(|| format!("Failed to parse scenario {}", path.display()))
+}
+
+/// Whether `path` is a `.gz` file or a zip archive, as MovingAI ships them.
+fn is_compressed(path: &FsPath) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("gz") || e.eq_ignore_ascii_case("zip"))
+}
+
+fn read_bytes(path: &FsPath) -> Result<Vec<u8>> {
+    fs::read(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+fn read_solution(path: &FsPath) -> Result<Solution> {
+    serde_json::from_str(&read(path)?)
+        .with_context(|| format!("Failed to parse solution {}", path.display()))
+}
+
+fn read(path: &FsPath) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+}
+
+/// Buffered reader over a file, for parsers that stream it line by line.
+fn open(path: &FsPath) -> Result<BufReader<fs::File>> {
+    let file =
+        fs::File::open(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(BufReader::new(file))
+}
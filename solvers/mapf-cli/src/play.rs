@@ -0,0 +1,450 @@
+//! `play`: step through a solution on its map in the terminal, for quick
+//! debugging on servers without a browser.
+//!
+//! The viewer keeps all state in a [`Player`]; key presses update it and
+//! every frame is drawn from it. `--at` prints one frame as plain text
+//! instead, which also works when output isn't a terminal.
+
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use clap::Args;
+use mapf_core::validate::{check_collisions, Violation};
+use mapf_core::{Coordinate, GridMap, Solution};
+use ratatui::buffer::Buffer;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Widget, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::{read_map, read_solution};
+
+#[derive(Args)]
+pub struct PlayArgs {
+    /// MovingAI `.map` file
+    map: PathBuf,
+    /// Solution JSON, as written by `solve --output` or returned by the API
+    solution: PathBuf,
+    /// Timesteps per second while playing
+    #[arg(long, default_value_t = 4.0)]
+    speed: f64,
+    /// Select this agent to start with
+    #[arg(long)]
+    agent: Option<usize>,
+    /// Print the frame at this timestep as text and exit
+    #[arg(long)]
+    at: Option<usize>,
+}
+
+/// Labels for agents in text frames, one character each; agents past the
+/// last label reuse them.
+const LABELS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// Width of the side panel, in columns.
+const PANEL_WIDTH: u16 = 36;
+
+const KEYS: &[&str] = &[
+    "space     play / pause",
+    "<- ->     step one timestep",
+    "home end  first / last timestep",
+    "+ -       speed",
+    "tab n p   next / previous agent",
+    "0-9 enter select agent by number",
+    "esc       clear selection",
+    "w a s d   pan, with no agent selected",
+    "q         quit",
+];
+
+pub fn run(args: PlayArgs) -> Result<ExitCode> {
+    let map = read_map(&args.map)?;
+    let solution = read_solution(&args.solution)?;
+    if !args.speed.is_finite() || args.speed <= 0.0 {
+        bail!("--speed must be positive");
+    }
+    let mut player = Player::new(&map, &solution, args.speed);
+    if let Some(agent) = args.agent {
+        if agent >= solution.paths.len() {
+            bail!(
+                "Agent {} is out of range; the solution has {} agents",
+                agent,
+                solution.paths.len()
+            );
+        }
+        player.selected = Some(agent);
+    }
+
+    if let Some(t) = args.at {
+        player.t = t.min(player.last());
+        print!("{}", player.text_frame());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !io::stdout().is_terminal() {
+        bail!("play needs a terminal; use --at <TIMESTEP> to print a single frame");
+    }
+
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, &mut player);
+    ratatui::restore();
+    result.map(|()| ExitCode::SUCCESS)
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, player: &mut Player) -> Result<()> {
+    let mut last_step = Instant::now();
+    loop {
+        terminal.draw(|frame| draw(frame, player))?;
+
+        let interval = Duration::from_secs_f64(1.0 / player.speed);
+        let timeout = if player.playing {
+            interval.saturating_sub(last_step.elapsed())
+        } else {
+            Duration::from_secs(1)
+        };
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                let interrupt =
+                    key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+                if key.kind == KeyEventKind::Press && (interrupt || !player.handle(key.code)) {
+                    return Ok(());
+                }
+            }
+        }
+        if player.playing && last_step.elapsed() >= interval {
+            player.advance();
+            last_step = Instant::now();
+        }
+    }
+}
+
+/// Viewer state: the current timestep, playback and the selected agent.
+struct Player<'a> {
+    map: &'a GridMap,
+    solution: &'a Solution,
+    conflicts: Vec<Violation>,
+    /// Timesteps to show: the longest path's length
+    horizon: usize,
+    t: usize,
+    playing: bool,
+    /// Timesteps per second
+    speed: f64,
+    selected: Option<usize>,
+    /// Agent number being typed
+    input: String,
+    /// Map cell shown in the top-left corner
+    offset: (u32, u32),
+}
+
+impl<'a> Player<'a> {
+    fn new(map: &'a GridMap, solution: &'a Solution, speed: f64) -> Self {
+        Self {
+            map,
+            solution,
+            conflicts: check_collisions(&solution.paths),
+            horizon: solution
+                .paths
+                .iter()
+                .map(|p| p.steps.len())
+                .max()
+                .unwrap_or(0),
+            t: 0,
+            playing: false,
+            speed,
+            selected: None,
+            input: String::new(),
+            offset: (0, 0),
+        }
+    }
+
+    /// Last timestep.
+    fn last(&self) -> usize {
+        self.horizon.saturating_sub(1)
+    }
+
+    /// Move one timestep forward while playing, pausing at the end.
+    fn advance(&mut self) {
+        if self.t < self.last() {
+            self.t += 1;
+        } else {
+            self.playing = false;
+        }
+    }
+
+    /// Apply a key press; returns `false` to quit.
+    fn handle(&mut self, key: KeyCode) -> bool {
+        let agents = self.solution.paths.len();
+        match key {
+            KeyCode::Char('q') => return false,
+            KeyCode::Char(' ') => {
+                // Replay from the start once the end is reached
+                if !self.playing && self.t == self.last() {
+                    self.t = 0;
+                }
+                self.playing = !self.playing;
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.playing = false;
+                self.t = (self.t + 1).min(self.last());
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.playing = false;
+                self.t = self.t.saturating_sub(1);
+            }
+            KeyCode::Home => self.t = 0,
+            KeyCode::End => self.t = self.last(),
+            KeyCode::Char('+') | KeyCode::Char('=') => self.speed = (self.speed * 2.0).min(256.0),
+            KeyCode::Char('-') => self.speed = (self.speed / 2.0).max(0.25),
+            KeyCode::Tab | KeyCode::Char('n') if agents > 0 => {
+                self.selected = Some(self.selected.map_or(0, |a| (a + 1) % agents));
+            }
+            KeyCode::BackTab | KeyCode::Char('p') if agents > 0 => {
+                self.selected = Some(
+                    self.selected
+                        .map_or(agents - 1, |a| (a + agents - 1) % agents),
+                );
+            }
+            KeyCode::Char(digit @ '0'..='9') => self.input.push(digit),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Enter => {
+                if let Ok(agent) = self.input.parse::<usize>() {
+                    if agent < agents {
+                        self.selected = Some(agent);
+                    }
+                }
+                self.input.clear();
+            }
+            KeyCode::Esc => {
+                self.input.clear();
+                self.selected = None;
+            }
+            KeyCode::Char('w') => self.offset.1 = self.offset.1.saturating_sub(1),
+            KeyCode::Char('s') => {
+                self.offset.1 = (self.offset.1 + 1).min(self.map.height().saturating_sub(1))
+            }
+            KeyCode::Char('a') => self.offset.0 = self.offset.0.saturating_sub(1),
+            KeyCode::Char('d') => {
+                self.offset.0 = (self.offset.0 + 1).min(self.map.width().saturating_sub(1))
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Position of `agent` at the current timestep.
+    fn position(&self, agent: usize) -> Option<Coordinate> {
+        position_at(&self.solution.paths[agent].steps, self.t)
+    }
+
+    /// Occupant of every cell at the current timestep, row-major; the
+    /// lowest-numbered agent wins where several collide.
+    fn occupants(&self) -> Vec<Option<usize>> {
+        let width = self.map.width() as usize;
+        let mut cells = vec![None; width * self.map.height() as usize];
+        for agent in (0..self.solution.paths.len()).rev() {
+            if let Some(pos) = self.position(agent) {
+                if pos.x < self.map.width() && pos.y < self.map.height() {
+                    cells[pos.y as usize * width + pos.x as usize] = Some(agent);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Collisions at the current timestep, including swaps into the next.
+    fn conflicts_now(&self) -> impl Iterator<Item = &Violation> {
+        self.conflicts.iter().filter(|c| c.timestep == Some(self.t))
+    }
+
+    fn in_conflict(&self, cell: Coordinate) -> bool {
+        self.conflicts_now().any(|c| c.positions.contains(&cell))
+    }
+
+    /// The map at the current timestep as text: `@` blocked, `.` free,
+    /// agents by label and `!` on collisions, followed by the status panel.
+    fn text_frame(&self) -> String {
+        let occupants = self.occupants();
+        let width = self.map.width();
+        let mut out = String::new();
+        for y in 0..self.map.height() {
+            for x in 0..width {
+                let cell = Coordinate { x, y };
+                let c = if self.in_conflict(cell) {
+                    '!'
+                } else if let Some(agent) = occupants[(y * width + x) as usize] {
+                    LABELS[agent % LABELS.len()] as char
+                } else if self.map.is_passable(x, y) {
+                    '.'
+                } else {
+                    '@'
+                };
+                out.push(c);
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+        for line in self.status() {
+            let _ = writeln!(out, "{}", line);
+        }
+        out
+    }
+
+    /// Lines for the side panel: timestep, playback, the selected agent and
+    /// current collisions.
+    fn status(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "timestep {} of {}{}",
+                self.t,
+                self.last(),
+                if self.playing { "  playing" } else { "" }
+            ),
+            format!(
+                "agents: {}  collisions: {}  speed: {}/s",
+                self.solution.paths.len(),
+                self.conflicts.len(),
+                self.speed
+            ),
+        ];
+        if !self.input.is_empty() {
+            lines.push(format!("select agent: {}_", self.input));
+        }
+
+        if let Some(agent) = self.selected {
+            let steps = &self.solution.paths[agent].steps;
+            lines.push(String::new());
+            lines.push(format!("agent {}", agent));
+            if let (Some(start), Some(goal), Some(pos)) =
+                (steps.first(), steps.last(), self.position(agent))
+            {
+                // Arrival ignores trailing waits at the goal
+                let arrival = steps.len() - steps.iter().rev().take_while(|&c| c == goal).count();
+                let state = match steps.get(self.t + 1) {
+                    _ if self.t >= arrival => "at goal",
+                    Some(next) if *next != pos => "moving",
+                    _ => "waiting",
+                };
+                lines.push(format!("  at ({},{}), {}", pos.x, pos.y, state));
+                lines.push(format!("  start ({},{})", start.x, start.y));
+                lines.push(format!(
+                    "  goal ({},{}), arrives t={}",
+                    goal.x, goal.y, arrival
+                ));
+            } else {
+                lines.push("  empty path".to_string());
+            }
+        }
+
+        let now: Vec<&Violation> = self.conflicts_now().collect();
+        if !now.is_empty() {
+            lines.push(String::new());
+            for conflict in now {
+                lines.push(format!("{}: {}", conflict.kind.as_str(), conflict.details));
+            }
+        }
+        lines
+    }
+
+    /// Keep the selected agent in view.
+    fn follow(&mut self, columns: u32, rows: u32) {
+        let Some(pos) = self.selected.and_then(|agent| self.position(agent)) else {
+            return;
+        };
+        let center = |pos: u32, size: u32, span: u32| {
+            pos.saturating_sub(span / 2).min(size.saturating_sub(span))
+        };
+        self.offset = (
+            center(pos.x, self.map.width(), columns),
+            center(pos.y, self.map.height(), rows),
+        );
+    }
+}
+
+fn draw(frame: &mut Frame, player: &mut Player) {
+    let [map_area, panel_area] =
+        Layout::horizontal([Constraint::Min(0), Constraint::Length(PANEL_WIDTH)])
+            .areas(frame.area());
+
+    let title = format!(" t = {}/{} ", player.t, player.last());
+    let block = Block::bordered().title(title);
+    let inner = block.inner(map_area);
+    frame.render_widget(block, map_area);
+    // Two columns per cell keeps cells roughly square
+    player.follow(inner.width as u32 / 2, inner.height as u32);
+    frame.render_widget(MapView(player), inner);
+
+    let mut lines: Vec<Line> = player.status().into_iter().map(Line::from).collect();
+    lines.push(Line::from(""));
+    lines.extend(
+        KEYS.iter()
+            .map(|k| Line::styled(*k, Style::new().fg(Color::DarkGray))),
+    );
+    frame.render_widget(
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(Block::bordered().title(" mapf play ")),
+        panel_area,
+    );
+}
+
+/// The visible part of the map at the current timestep.
+struct MapView<'p, 'a>(&'p Player<'a>);
+
+impl Widget for MapView<'_, '_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let player = self.0;
+        let map = player.map;
+        let occupants = player.occupants();
+        let selected_path = player
+            .selected
+            .map(|agent| player.solution.paths[agent].steps.as_slice())
+            .unwrap_or_default();
+
+        for row in 0..area.height {
+            for column in 0..area.width / 2 {
+                let (x, y) = (
+                    player.offset.0 + column as u32,
+                    player.offset.1 + row as u32,
+                );
+                if x >= map.width() || y >= map.height() {
+                    continue;
+                }
+                let cell = Coordinate { x, y };
+                let (symbol, mut style) = match occupants[(y * map.width() + x) as usize] {
+                    Some(agent) => {
+                        let [r, g, b] = mapf_viz::agent_color(agent);
+                        let mut style = Style::new().fg(Color::Black).bg(Color::Rgb(r, g, b));
+                        if player.selected == Some(agent) {
+                            style = style.add_modifier(Modifier::BOLD | Modifier::REVERSED);
+                        }
+                        (format!("{:>2}", agent % 100), style)
+                    }
+                    None if !map.is_passable(x, y) => {
+                        ("  ".to_string(), Style::new().bg(Color::DarkGray))
+                    }
+                    None if selected_path.last() == Some(&cell) => {
+                        ("<>".to_string(), Style::new().fg(Color::White))
+                    }
+                    None if selected_path.contains(&cell) => {
+                        (" *".to_string(), Style::new().fg(Color::Gray))
+                    }
+                    None => (" .".to_string(), Style::new().fg(Color::DarkGray)),
+                };
+                if player.in_conflict(cell) {
+                    style = style.bg(Color::Red);
+                }
+                buf.set_string(area.x + column * 2, area.y + row, symbol, style);
+            }
+        }
+    }
+}
+
+/// Position of an agent at timestep `t`; agents stay at their last cell.
+fn position_at(steps: &[Coordinate], t: usize) -> Option<Coordinate> {
+    steps.get(t).or_else(|| steps.last()).copied()
+}
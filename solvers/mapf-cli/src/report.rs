@@ -0,0 +1,218 @@
+//! Markdown and HTML reports for `bench`, laid out like the online
+//! leaderboard.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use mapf_scoring::Score;
+
+/// Leaderboard score of a valid solution, or why there is none.
+pub type Outcome = Result<Score, String>;
+
+/// One solver run on one instance.
+pub struct Run {
+    pub solver: String,
+    pub map: String,
+    pub agents: usize,
+    pub outcome: Outcome,
+    pub time_ms: f64,
+}
+
+/// Totals over a group of runs.
+#[derive(Default)]
+struct Totals {
+    runs: usize,
+    solved: usize,
+    cost: u64,
+    makespan: u64,
+    time_ms: f64,
+}
+
+impl Totals {
+    fn add(&mut self, run: &Run) {
+        self.runs += 1;
+        self.time_ms += run.time_ms;
+        if let Ok(score) = run.outcome {
+            self.solved += 1;
+            self.cost += score.cost;
+            self.makespan += score.makespan;
+        }
+    }
+
+    fn solved(&self) -> String {
+        format!("{}/{}", self.solved, self.runs)
+    }
+
+    fn mean_time(&self) -> String {
+        format!("{:.1} ms", self.time_ms / self.runs.max(1) as f64)
+    }
+
+    /// Mean over solved runs only; `-` if none were solved.
+    fn mean(&self, total: u64) -> String {
+        if self.solved == 0 {
+            "-".to_string()
+        } else {
+            format!("{:.1}", total as f64 / self.solved as f64)
+        }
+    }
+}
+
+/// A table with right-aligned numeric columns.
+struct Table {
+    headers: &'static [&'static str],
+    /// Whether each column holds numbers
+    numeric: &'static [bool],
+    rows: Vec<Vec<String>>,
+}
+
+/// Leaderboard: solvers ranked by runs solved, then by total cost.
+fn leaderboard(runs: &[Run]) -> Table {
+    let mut totals: BTreeMap<&str, Totals> = BTreeMap::new();
+    for run in runs {
+        totals.entry(&run.solver).or_default().add(run);
+    }
+    let mut standings: Vec<_> = totals.into_iter().collect();
+    standings.sort_by(|(_, a), (_, b)| b.solved.cmp(&a.solved).then(a.cost.cmp(&b.cost)));
+
+    Table {
+        headers: &["Rank", "Solver", "Solved", "Total Cost", "Avg Time"],
+        numeric: &[true, false, true, true, true],
+        rows: standings
+            .iter()
+            .enumerate()
+            .map(|(rank, (solver, totals))| {
+                vec![
+                    (rank + 1).to_string(),
+                    solver.to_string(),
+                    totals.solved(),
+                    totals.cost.to_string(),
+                    totals.mean_time(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Per map and agent count, one row per solver.
+fn breakdown(runs: &[Run]) -> Table {
+    let mut totals: BTreeMap<(&str, usize, &str), Totals> = BTreeMap::new();
+    for run in runs {
+        totals
+            .entry((&run.map, run.agents, &run.solver))
+            .or_default()
+            .add(run);
+    }
+
+    Table {
+        headers: &[
+            "Map",
+            "Agents",
+            "Solver",
+            "Solved",
+            "Mean Cost",
+            "Mean Makespan",
+            "Avg Time",
+        ],
+        numeric: &[false, true, false, true, true, true, true],
+        rows: totals
+            .iter()
+            .map(|((map, agents, solver), totals)| {
+                vec![
+                    map.to_string(),
+                    agents.to_string(),
+                    solver.to_string(),
+                    totals.solved(),
+                    totals.mean(totals.cost),
+                    totals.mean(totals.makespan),
+                    totals.mean_time(),
+                ]
+            })
+            .collect(),
+    }
+}
+
+/// Report as Markdown.
+pub fn markdown(runs: &[Run]) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# MAPF Benchmark Report\n");
+    let _ = writeln!(
+        out,
+        "{} runs. Costs are leaderboard scores, summed over solved runs.\n",
+        runs.len()
+    );
+    for (title, table) in [
+        ("Leaderboard", leaderboard(runs)),
+        ("Results", breakdown(runs)),
+    ] {
+        let _ = writeln!(out, "## {}\n", title);
+        write_markdown_table(&mut out, &table);
+        let _ = writeln!(out);
+    }
+    out
+}
+
+fn write_markdown_table(out: &mut String, table: &Table) {
+    let _ = writeln!(out, "| {} |", table.headers.join(" | "));
+    let rule: Vec<&str> = table
+        .numeric
+        .iter()
+        .map(|&numeric| if numeric { "---:" } else { "---" })
+        .collect();
+    let _ = writeln!(out, "| {} |", rule.join(" | "));
+    for row in &table.rows {
+        let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+    }
+}
+
+/// Report as a standalone HTML page.
+pub fn html(runs: &[Run]) -> String {
+    let mut out = String::new();
+    out.push_str(concat!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>MAPF Benchmark Report</title>\n<style>\n",
+        "body { font-family: sans-serif; margin: 2em; }\n",
+        "table { border-collapse: collapse; margin-bottom: 2em; }\n",
+        "th, td { border: 1px solid #ccc; padding: 0.3em 0.8em; }\n",
+        "td.num { text-align: right; }\n",
+        "</style>\n</head>\n<body>\n<h1>MAPF Benchmark Report</h1>\n",
+    ));
+    let _ = writeln!(
+        out,
+        "<p>{} runs. Costs are leaderboard scores, summed over solved runs.</p>",
+        runs.len()
+    );
+    for (title, table) in [
+        ("Leaderboard", leaderboard(runs)),
+        ("Results", breakdown(runs)),
+    ] {
+        let _ = writeln!(out, "<h2>{}</h2>", title);
+        write_html_table(&mut out, &table);
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn write_html_table(out: &mut String, table: &Table) {
+    out.push_str("<table>\n<tr>");
+    for header in table.headers {
+        let _ = write!(out, "<th>{}</th>", header);
+    }
+    out.push_str("</tr>\n");
+    for row in &table.rows {
+        out.push_str("<tr>");
+        for (cell, &numeric) in row.iter().zip(table.numeric) {
+            let class = if numeric { " class=\"num\"" } else { "" };
+            let _ = write!(out, "<td{}>{}</td>", class, escape(cell));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+/// Escape text for use inside an HTML element.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
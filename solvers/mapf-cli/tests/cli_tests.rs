@@ -0,0 +1,353 @@
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn maps_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../maps/mapf-map")
+}
+
+fn instance() -> [PathBuf; 2] {
+    let maps = maps_dir();
+    [
+        maps.join("empty-8-8.map"),
+        maps.join("scen-even/empty-8-8-even-1.scen"),
+    ]
+}
+
+fn run(args: &[&str], instance: &[PathBuf], trailing: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_mapf-cli"))
+        .args(args)
+        .args(instance)
+        .args(trailing)
+        .output()
+        .expect("Failed to run mapf-cli")
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn solve_then_validate_round_trips() {
+    let solution = std::env::temp_dir().join(format!("mapf-cli-{}.json", std::process::id()));
+    let solution_arg = solution.to_str().unwrap();
+
+    let solved = run(
+        &["solve"],
+        &instance(),
+        &[
+            "--agents",
+            "3",
+            "--algorithm",
+            "centralized",
+            "--output",
+            solution_arg,
+        ],
+    );
+    assert!(solved.status.success(), "{}", stdout(&solved));
+    assert!(stdout(&solved).contains("cost:            "));
+    assert!(stdout(&solved).contains("makespan:"));
+
+    let checked = run(&["validate"], &instance(), &["--agents", "3", solution_arg]);
+    assert!(checked.status.success(), "{}", stdout(&checked));
+    assert!(stdout(&checked).contains("result:          valid"));
+
+    // The same solution has one path too many for two agents
+    let mismatched = run(&["validate"], &instance(), &["--agents", "2", solution_arg]);
+    assert!(!mismatched.status.success());
+    assert!(stdout(&mismatched).contains("[agent_count_mismatch]"));
+
+    for extension in ["svg", "gif"] {
+        let image = solution.with_extension(extension);
+        let map = instance()[0].clone();
+        let rendered = run(
+            &["render"],
+            &[map, solution.clone()],
+            &["--output", image.to_str().unwrap()],
+        );
+        assert!(
+            rendered.status.success(),
+            "{}",
+            String::from_utf8_lossy(&rendered.stderr)
+        );
+        assert!(std::fs::metadata(&image).unwrap().len() > 0);
+        std::fs::remove_file(image).ok();
+    }
+
+    std::fs::remove_file(solution).ok();
+}
+
+#[test]
+fn rejects_more_agents_than_the_scenario_has() {
+    let output = run(&["solve"], &instance(), &["--agents", "100000"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("requested"));
+}
+
+#[test]
+fn rejects_scenarios_for_another_map() {
+    let maps = maps_dir();
+    let instance = [
+        maps.join("empty-8-8.map"),
+        maps.join("scen-even/empty-16-16-even-1.scen"),
+    ];
+    let output = run(&["solve"], &instance, &["--agents", "2"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Scenario doesn't fit the map"));
+    assert!(stderr.contains("entry 0: declared size 16x16 doesn't match the 8x8 map"));
+    // Entries past --agents aren't reported
+    assert!(!stderr.contains("entry 2:"));
+}
+
+#[test]
+fn bench_reports_every_solver() {
+    let maps = [maps_dir()];
+    let output = run(
+        &["bench"],
+        &maps,
+        &[
+            "--maps",
+            "empty-8-8",
+            "--scenarios",
+            "2",
+            "--agents",
+            "1,2",
+            "--solver",
+            "prioritized",
+            "--solver",
+            "centralized",
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let report = stdout(&output);
+    assert!(report.contains("| Rank | Solver | Solved | Total Cost | Avg Time |"));
+    // Two scenarios at two sizes, every instance easy enough for both solvers
+    assert!(report.contains("| centralized | 4/4 |"));
+    assert!(report.contains("| empty-8-8 | 2 | prioritized | 2/2 |"));
+
+    let html = std::env::temp_dir().join(format!("mapf-cli-{}.html", std::process::id()));
+    let output = run(
+        &["bench"],
+        &maps,
+        &[
+            "--maps",
+            "empty-8-8",
+            "--scenarios",
+            "1",
+            "--agents",
+            "1",
+            "--solver",
+            "prioritized",
+            "--report",
+            html.to_str().unwrap(),
+        ],
+    );
+    assert!(output.status.success());
+    let page = std::fs::read_to_string(&html).unwrap();
+    assert!(page.starts_with("<!DOCTYPE html>"));
+    assert!(page.contains("<td>prioritized</td>"));
+    std::fs::remove_file(html).ok();
+}
+
+#[test]
+fn bench_rejects_unknown_solvers() {
+    let output = run(
+        &["bench"],
+        &[maps_dir()],
+        &["--agents", "1", "--solver", "magic"],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown solver"));
+}
+
+#[test]
+fn diff_compares_two_solvers() {
+    let dir = std::env::temp_dir();
+    let id = std::process::id();
+    let [map, _] = instance();
+    let mut solutions = Vec::new();
+    // Prioritized planning against optimal ECBS
+    for (algorithm, suboptimality) in [("prioritized", "1.2"), ("ecbs", "1")] {
+        let solution = dir.join(format!("mapf-cli-diff-{}-{}.json", id, algorithm));
+        let solved = run(
+            &["solve"],
+            &instance(),
+            &[
+                "--agents",
+                "6",
+                "--algorithm",
+                algorithm,
+                "--suboptimality",
+                suboptimality,
+                "--output",
+                solution.to_str().unwrap(),
+            ],
+        );
+        assert!(solved.status.success());
+        solutions.push(solution);
+    }
+
+    let image = dir.join(format!("mapf-cli-diff-{}.svg", id));
+    let output = run(
+        &["diff"],
+        &solutions,
+        &[
+            "--map",
+            map.to_str().unwrap(),
+            "--output",
+            image.to_str().unwrap(),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout(&output).contains("cost:            39 -> 37 (-2)"));
+    assert!(stdout(&output).contains("changed agents:  "));
+    assert!(std::fs::read_to_string(&image)
+        .unwrap()
+        .contains("class=\"b\""));
+
+    for file in solutions.into_iter().chain([image]) {
+        std::fs::remove_file(file).ok();
+    }
+}
+
+#[test]
+fn convert_round_trips_through_every_format() {
+    let dir = std::env::temp_dir();
+    let id = std::process::id();
+    let file = |name: &str| dir.join(format!("mapf-cli-convert-{}-{}", id, name));
+    let steps = [
+        (maps_dir().join("random-32-32-10.map"), file("a.map")),
+        (file("a.map"), file("b.json")),
+        (file("b.json"), file("c.png")),
+        (file("c.png"), file("d.bin")),
+        (file("d.bin"), file("e.map")),
+    ];
+    for (input, output) in &steps {
+        let to = if output.extension().unwrap() == "bin" {
+            &["--to", "rle"][..]
+        } else {
+            &[]
+        };
+        let from = if input.extension().unwrap() == "bin" {
+            &["--from", "rle"][..]
+        } else {
+            &[]
+        };
+        let converted = run(
+            &["convert"],
+            &[input.clone(), output.clone()],
+            &[to, from].concat(),
+        );
+        assert!(
+            converted.status.success(),
+            "{}",
+            String::from_utf8_lossy(&converted.stderr)
+        );
+    }
+
+    let first = std::fs::read_to_string(file("a.map")).unwrap();
+    assert!(first.starts_with("type octile\nheight 32\nwidth 32\nmap\n"));
+    assert_eq!(std::fs::read_to_string(file("e.map")).unwrap(), first);
+
+    for (_, output) in steps {
+        std::fs::remove_file(output).ok();
+    }
+}
+
+#[test]
+fn convert_needs_a_known_format() {
+    let output = run(
+        &["convert"],
+        &[maps_dir().join("empty-8-8.map"), PathBuf::from("map.txt")],
+        &[],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unknown map format"));
+}
+
+#[test]
+fn generate_writes_a_benchmark_directory() {
+    let dir = std::env::temp_dir().join(format!("mapf-cli-generate-{}", std::process::id()));
+    let output = run(
+        &["generate"],
+        std::slice::from_ref(&dir),
+        &[
+            "--seed",
+            "4",
+            "--scenarios",
+            "2",
+            "--agents",
+            "5",
+            "warehouse",
+            "--columns",
+            "2",
+            "--rows",
+            "2",
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let map = std::fs::read_to_string(dir.join("warehouse-24-12-4.map")).unwrap();
+    assert!(map.starts_with("type octile\nheight 12\nwidth 24\nmap\n"));
+    for n in 1..=2 {
+        let scen = dir.join(format!("scen-random/warehouse-24-12-4-random-{}.scen", n));
+        let scen = std::fs::read_to_string(scen).unwrap();
+        assert!(scen.starts_with("version 1\n"));
+        assert_eq!(scen.lines().count(), 1 + 5);
+        assert!(scen.contains("\twarehouse-24-12-4.map\t24\t12\t"));
+    }
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn play_prints_a_frame_without_a_terminal() {
+    let solution = std::env::temp_dir().join(format!("mapf-cli-play-{}.json", std::process::id()));
+    // Agents 0 and 1 swap cells; agent 2 walks down two cells
+    std::fs::write(
+        &solution,
+        r#"{"paths":[
+            {"steps":[{"x":0,"y":0},{"x":1,"y":0}]},
+            {"steps":[{"x":1,"y":0},{"x":0,"y":0}]},
+            {"steps":[{"x":3,"y":1},{"x":3,"y":2},{"x":3,"y":3}]}
+        ]}"#,
+    )
+    .unwrap();
+    let map = maps_dir().join("empty-8-8.map");
+    let files = [map, solution.clone()];
+
+    let output = run(&["play"], &files, &["--at", "0", "--agent", "2"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let text = stdout(&output);
+    assert!(text.starts_with("!!......\n...2....\n........\n"));
+    assert!(text.contains("timestep 0 of 2"));
+    assert!(text.contains("edge_collision: Agents 0 and 1 swap positions"));
+    assert!(text.contains("agent 2\n  at (3,1), moving\n"));
+    assert!(text.contains("arrives t=2"));
+
+    // Past the end, agents wait at their goals
+    let output = run(&["play"], &files, &["--at", "9"]);
+    assert!(stdout(&output).starts_with("10......\n........\n........\n...2....\n"));
+
+    // The viewer itself needs a terminal
+    let output = run(&["play"], &files, &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("needs a terminal"));
+
+    std::fs::remove_file(solution).ok();
+}
@@ -0,0 +1,67 @@
+//! The JSON map object of the arena API (`MapData` in the backend).
+//!
+//! ```json
+//! { "width": 3, "height": 1, "tiles": [1, 0, 1], "map_type": "octile", "costs": [1, 1, 4] }
+//! ```
+//!
+//! `tiles` is row-major, 1 = passable and 0 = blocked; `map_type` and
+//! `costs` are optional.
+
+use mapf_core::GridMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{cell_count, from_bytes, ConvertError};
+
+/// A map as the API sends and receives it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonMap {
+    pub width: u32,
+    pub height: u32,
+    pub tiles: Vec<u8>,
+    /// Declared MovingAI map type; `octile` if absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub map_type: Option<String>,
+    /// Per-cell entry costs for weighted maps, same layout as `tiles`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub costs: Option<Vec<u32>>,
+}
+
+impl JsonMap {
+    pub fn from_grid_map(map: &GridMap) -> Self {
+        Self {
+            width: map.width(),
+            height: map.height(),
+            tiles: map.to_bytes(),
+            map_type: Some(map.map_type().to_string()),
+            costs: map.costs().map(<[u32]>::to_vec),
+        }
+    }
+
+    pub fn to_grid_map(&self) -> Result<GridMap, ConvertError> {
+        cell_count(self.width, self.height)?;
+        let map = from_bytes(self.width, self.height, &self.tiles)?;
+        let map = match &self.map_type {
+            Some(map_type) => map.with_map_type(map_type.as_str()),
+            None => map,
+        };
+        match &self.costs {
+            Some(costs) => map
+                .with_costs(costs.clone())
+                .ok_or(ConvertError::SizeMismatch {
+                    what: "costs",
+                    got: costs.len(),
+                    width: self.width,
+                    height: self.height,
+                }),
+            None => Ok(map),
+        }
+    }
+}
+
+pub fn decode(data: &[u8]) -> Result<GridMap, ConvertError> {
+    serde_json::from_slice::<JsonMap>(data)?.to_grid_map()
+}
+
+pub fn encode(map: &GridMap) -> Result<Vec<u8>, ConvertError> {
+    Ok(serde_json::to_vec(&JsonMap::from_grid_map(map))?)
+}
@@ -0,0 +1,117 @@
+//! Conversion of grid maps between the formats maps arrive in.
+//!
+//! Reads and writes:
+//! - [`Format::MovingAi`]: `.map` text, the arena catalog's format
+//! - [`Format::Json`]: the `{ width, height, tiles, map_type, costs }` object
+//!   the API accepts
+//! - [`Format::Rle`]: compact run-length encoded bytes, see [`rle`]
+//! - [`Format::Png`]: occupancy images, one pixel per cell, see [`png`]
+//!
+//! Only JSON carries cell costs; the other formats hold occupancy alone.
+
+pub mod json;
+pub mod png;
+pub mod rle;
+
+use mapf_core::{GridMap, MapError};
+use thiserror::Error;
+
+/// Errors from reading or writing a map.
+#[derive(Debug, Error)]
+pub enum ConvertError {
+    #[error(transparent)]
+    MovingAi(#[from] MapError),
+
+    #[error("invalid JSON map: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("invalid RLE map: {0}")]
+    Rle(&'static str),
+
+    #[error("invalid PNG: {0}")]
+    PngDecode(#[from] ::png::DecodingError),
+
+    #[error("failed to encode PNG: {0}")]
+    PngEncode(#[from] ::png::EncodingError),
+
+    #[error("{what} length {got} doesn't match {width}x{height}")]
+    SizeMismatch {
+        what: &'static str,
+        got: usize,
+        width: u32,
+        height: u32,
+    },
+
+    #[error("map of {width}x{height} cells is too large")]
+    TooLarge { width: u32, height: u32 },
+}
+
+/// A map file format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    MovingAi,
+    Json,
+    Rle,
+    Png,
+}
+
+impl Format {
+    /// Format for a file extension: `map`, `json`, `rle` or `png`.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "map" => Some(Format::MovingAi),
+            "json" => Some(Format::Json),
+            "rle" => Some(Format::Rle),
+            "png" => Some(Format::Png),
+            _ => None,
+        }
+    }
+
+    /// Whether maps in this format keep their cell costs.
+    pub fn has_costs(self) -> bool {
+        self == Format::Json
+    }
+}
+
+/// Parse a map stored in `format`.
+pub fn read(data: &[u8], format: Format) -> Result<GridMap, ConvertError> {
+    match format {
+        Format::MovingAi => Ok(GridMap::parse(&String::from_utf8_lossy(data))?),
+        Format::Json => json::decode(data),
+        Format::Rle => rle::decode(data),
+        Format::Png => png::decode(data, png::DEFAULT_THRESHOLD),
+    }
+}
+
+/// Store `map` in `format`; cell costs are dropped unless the format
+/// [has them](Format::has_costs).
+pub fn write(map: &GridMap, format: Format) -> Result<Vec<u8>, ConvertError> {
+    match format {
+        Format::MovingAi => Ok(map.to_movingai().into_bytes()),
+        Format::Json => json::encode(map),
+        Format::Rle => Ok(rle::encode(map)),
+        Format::Png => png::encode(map),
+    }
+}
+
+/// Number of cells in a `width` x `height` map, if it fits in memory.
+fn cell_count(width: u32, height: u32) -> Result<usize, ConvertError> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .filter(|&cells| cells <= MAX_CELLS)
+        .ok_or(ConvertError::TooLarge { width, height })
+}
+
+/// Largest map accepted, well above any benchmark (MovingAI's largest are
+/// around 2 million cells).
+const MAX_CELLS: usize = 1 << 28;
+
+/// Map from row-major occupancy bytes, failing on length mismatch.
+fn from_bytes(width: u32, height: u32, tiles: &[u8]) -> Result<GridMap, ConvertError> {
+    GridMap::from_bytes(width, height, tiles).ok_or(ConvertError::SizeMismatch {
+        what: "tiles",
+        got: tiles.len(),
+        width,
+        height,
+    })
+}
@@ -0,0 +1,67 @@
+//! PNG occupancy images: one pixel per cell, dark pixels blocked.
+//!
+//! Written as 8-bit grayscale, white for passable and black for blocked.
+//! Any PNG is read: pixels are converted to luminance, composited over
+//! white where transparent, and cells darker than a threshold are blocked.
+
+use std::io::Cursor;
+
+use ::png::{BitDepth, ColorType, Decoder, Encoder, Transformations};
+use mapf_core::GridMap;
+
+use crate::{cell_count, from_bytes, ConvertError};
+
+/// Luminance (0-255) below which a pixel counts as an obstacle.
+pub const DEFAULT_THRESHOLD: u8 = 128;
+
+pub fn encode(map: &GridMap) -> Result<Vec<u8>, ConvertError> {
+    let pixels: Vec<u8> = map.to_bytes().iter().map(|&tile| tile * 255).collect();
+
+    let mut out = Vec::new();
+    let mut encoder = Encoder::new(&mut out, map.width(), map.height());
+    encoder.set_color(ColorType::Grayscale);
+    encoder.set_depth(BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixels)?;
+    writer.finish()?;
+    Ok(out)
+}
+
+/// Read an occupancy image, blocking cells with luminance below `threshold`.
+pub fn decode(data: &[u8], threshold: u8) -> Result<GridMap, ConvertError> {
+    let mut decoder = Decoder::new(Cursor::new(data));
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    cell_count(width, height)?;
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer)?;
+    let (color, _) = reader.output_color_type();
+    let samples = color.samples();
+
+    let tiles: Vec<u8> = buffer[..frame.buffer_size()]
+        .chunks_exact(frame.line_size)
+        .flat_map(|line| line[..width as usize * samples].chunks_exact(samples))
+        .map(|pixel| (luminance(pixel, color) >= threshold) as u8)
+        .collect();
+    from_bytes(width, height, &tiles)
+}
+
+/// Luminance of an 8-bit pixel, composited over white.
+fn luminance(pixel: &[u8], color: ColorType) -> u8 {
+    let (luma, alpha) = match color {
+        ColorType::Grayscale => (pixel[0] as u32, 255),
+        ColorType::GrayscaleAlpha => (pixel[0] as u32, pixel[1] as u32),
+        ColorType::Rgb => (rgb_luma(pixel), 255),
+        ColorType::Rgba => (rgb_luma(pixel), pixel[3] as u32),
+        // Expanded to RGB(A) by `normalize_to_color8`
+        ColorType::Indexed => (255, 255),
+    };
+    ((luma * alpha + 255 * (255 - alpha)) / 255) as u8
+}
+
+/// Rec. 601 luma of an RGB pixel.
+fn rgb_luma(pixel: &[u8]) -> u32 {
+    (299 * pixel[0] as u32 + 587 * pixel[1] as u32 + 114 * pixel[2] as u32) / 1000
+}
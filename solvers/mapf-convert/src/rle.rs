@@ -0,0 +1,90 @@
+//! Run-length encoded occupancy bytes, for shipping large maps compactly.
+//!
+//! Layout, all integers little-endian:
+//!
+//! ```text
+//! "MRLE"  width: u32  height: u32  run*
+//! ```
+//!
+//! Runs cover the cells in row-major order and alternate between passable
+//! and blocked, starting with passable (a map starting with an obstacle
+//! opens with an empty run). Each run length is an unsigned LEB128 varint,
+//! so open areas and walls cost a byte or two per run.
+
+use mapf_core::GridMap;
+
+use crate::{cell_count, from_bytes, ConvertError};
+
+const MAGIC: &[u8; 4] = b"MRLE";
+
+pub fn encode(map: &GridMap) -> Vec<u8> {
+    let mut out = MAGIC.to_vec();
+    out.extend_from_slice(&map.width().to_le_bytes());
+    out.extend_from_slice(&map.height().to_le_bytes());
+
+    let mut passable = true;
+    let mut run: u64 = 0;
+    for tile in map.to_bytes() {
+        if (tile != 0) != passable {
+            write_varint(&mut out, run);
+            passable = !passable;
+            run = 0;
+        }
+        run += 1;
+    }
+    if run > 0 {
+        write_varint(&mut out, run);
+    }
+    out
+}
+
+pub fn decode(data: &[u8]) -> Result<GridMap, ConvertError> {
+    let rest = data
+        .strip_prefix(MAGIC)
+        .ok_or(ConvertError::Rle("missing MRLE header"))?;
+    if rest.len() < 8 {
+        return Err(ConvertError::Rle("truncated header"));
+    }
+    let width = u32::from_le_bytes(rest[0..4].try_into().unwrap());
+    let height = u32::from_le_bytes(rest[4..8].try_into().unwrap());
+    let cells = cell_count(width, height)?;
+
+    let mut tiles = Vec::with_capacity(cells);
+    let mut rest = &rest[8..];
+    let mut passable = true;
+    while !rest.is_empty() {
+        let run = read_varint(&mut rest)?;
+        if run > (cells - tiles.len()) as u64 {
+            return Err(ConvertError::Rle("runs cover more cells than the map has"));
+        }
+        tiles.resize(tiles.len() + run as usize, passable as u8);
+        passable = !passable;
+    }
+    if tiles.len() < cells {
+        return Err(ConvertError::Rle("runs cover fewer cells than the map has"));
+    }
+    from_bytes(width, height, &tiles)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(data: &mut &[u8]) -> Result<u64, ConvertError> {
+    let mut value: u64 = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data
+            .split_first()
+            .ok_or(ConvertError::Rle("truncated run length"))?;
+        *data = rest;
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(ConvertError::Rle("run length too long"))
+}
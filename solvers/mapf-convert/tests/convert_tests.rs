@@ -0,0 +1,139 @@
+//! Tests for map format conversion.
+
+use mapf_convert::{json::JsonMap, png, read, rle, write, ConvertError, Format};
+use mapf_core::GridMap;
+use pretty_assertions::assert_eq;
+
+const MAZE: &str = "type octile\nheight 3\nwidth 4\nmap\n@@@@\n@..@\n....\n";
+
+fn maze() -> GridMap {
+    GridMap::parse(MAZE).unwrap()
+}
+
+/// Occupancy, type and costs, which `GridMap` doesn't compare itself.
+fn contents(map: &GridMap) -> (u32, u32, Vec<u8>, String, Option<Vec<u32>>) {
+    (
+        map.width(),
+        map.height(),
+        map.to_bytes(),
+        map.map_type().to_string(),
+        map.costs().map(<[u32]>::to_vec),
+    )
+}
+
+#[test]
+fn every_format_round_trips() {
+    for format in [Format::MovingAi, Format::Json, Format::Rle, Format::Png] {
+        let data = write(&maze(), format).unwrap();
+        let map = read(&data, format).unwrap();
+        assert_eq!(contents(&map), contents(&maze()), "{:?}", format);
+    }
+}
+
+#[test]
+fn formats_follow_extensions() {
+    assert_eq!(Format::from_extension("map"), Some(Format::MovingAi));
+    assert_eq!(Format::from_extension("PNG"), Some(Format::Png));
+    assert_eq!(Format::from_extension("txt"), None);
+}
+
+#[test]
+fn json_matches_the_api_and_keeps_costs() {
+    let map = GridMap::from_bytes(3, 1, &[1, 0, 1])
+        .unwrap()
+        .with_costs(vec![1, 1, 4])
+        .unwrap();
+    let json = String::from_utf8(write(&map, Format::Json).unwrap()).unwrap();
+    assert_eq!(
+        json,
+        r#"{"width":3,"height":1,"tiles":[1,0,1],"map_type":"octile","costs":[1,1,4]}"#
+    );
+    assert_eq!(
+        contents(&read(json.as_bytes(), Format::Json).unwrap()),
+        contents(&map)
+    );
+
+    // Type and costs are optional
+    let map = read(br#"{"width":2,"height":1,"tiles":[0,1]}"#, Format::Json).unwrap();
+    assert_eq!(map.map_type(), "octile");
+    assert!(!map.is_weighted());
+}
+
+#[test]
+fn json_rejects_mismatched_sizes() {
+    let map = JsonMap {
+        width: 2,
+        height: 2,
+        tiles: vec![1, 1, 1],
+        map_type: None,
+        costs: None,
+    };
+    assert!(matches!(
+        map.to_grid_map(),
+        Err(ConvertError::SizeMismatch {
+            what: "tiles",
+            got: 3,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn rle_stores_alternating_runs() {
+    let data = rle::encode(&maze());
+    let mut expected = b"MRLE".to_vec();
+    expected.extend_from_slice(&[4, 0, 0, 0, 3, 0, 0, 0]);
+    // Opens with an empty passable run: 0 . 5 @ . 2 . 1 @ . 4 .
+    expected.extend_from_slice(&[0, 5, 2, 1, 4]);
+    assert_eq!(data, expected);
+}
+
+#[test]
+fn rle_uses_varints_for_long_runs() {
+    let map = GridMap::from_bytes(300, 1, &[1; 300]).unwrap();
+    let data = rle::encode(&map);
+    assert_eq!(&data[12..], &[0xAC, 0x02]);
+    assert_eq!(rle::decode(&data).unwrap().to_bytes(), vec![1; 300]);
+}
+
+#[test]
+fn rle_rejects_bad_input() {
+    let mut data = rle::encode(&maze());
+    assert!(matches!(
+        rle::decode(&data[..data.len() - 1]),
+        Err(ConvertError::Rle(_))
+    ));
+    data.push(1);
+    assert!(matches!(rle::decode(&data), Err(ConvertError::Rle(_))));
+    assert!(matches!(rle::decode(b"GIF89a"), Err(ConvertError::Rle(_))));
+
+    let mut huge = b"MRLE".to_vec();
+    huge.extend_from_slice(&[0xFF; 8]);
+    assert!(matches!(
+        rle::decode(&huge),
+        Err(ConvertError::TooLarge { .. })
+    ));
+}
+
+#[test]
+fn png_threshold_decides_obstacles() {
+    // Gray cells are passable at the default threshold but not at a higher one
+    let mut data = Vec::new();
+    {
+        let mut encoder = ::png::Encoder::new(&mut data, 2, 1);
+        encoder.set_color(::png::ColorType::Rgba);
+        encoder.set_depth(::png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        // Mid-gray, then a transparent black pixel that shows the white behind it
+        writer
+            .write_image_data(&[160, 160, 160, 255, 0, 0, 0, 0])
+            .unwrap();
+    }
+    assert_eq!(
+        png::decode(&data, png::DEFAULT_THRESHOLD)
+            .unwrap()
+            .to_bytes(),
+        vec![1, 1]
+    );
+    assert_eq!(png::decode(&data, 200).unwrap().to_bytes(), vec![0, 1]);
+}
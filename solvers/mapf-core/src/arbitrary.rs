@@ -0,0 +1,135 @@
+//! Proptest strategies for core types (`proptest` feature).
+//!
+//! Generated values are small so properties run fast, and shrink towards
+//! smaller, emptier cases: maps towards fewer rows, columns and obstacles,
+//! instances towards fewer agents.
+//!
+//! ```
+//! use mapf_core::GridMap;
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn maps_survive_a_round_trip(map in any::<GridMap>()) {
+//!         let parsed = GridMap::parse(&map.to_movingai()).unwrap();
+//!         prop_assert_eq!(parsed.to_movingai(), map.to_movingai());
+//!     }
+//! }
+//! # maps_survive_a_round_trip();
+//! ```
+
+use proptest::prelude::*;
+use proptest::sample::subsequence;
+
+use crate::instance::Instance;
+use crate::map::{GridMap, Tile};
+use crate::scenario::ScenarioEntry;
+use crate::solution::Coordinate;
+
+/// Largest side of a generated map.
+const MAX_SIDE: u32 = 16;
+
+/// Most agents in a generated instance.
+const MAX_AGENTS: usize = 8;
+
+impl Arbitrary for Tile {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Mostly open ground, which is what tiles shrink to.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop_oneof![
+            12 => Just(Tile::Passable),
+            4 => Just(Tile::Blocked),
+            1 => Just(Tile::Swamp),
+            1 => Just(Tile::Water),
+            1 => Just(Tile::Trees),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for GridMap {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Unweighted octile maps of 1 to 16 cells a side.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (1..=MAX_SIDE, 1..=MAX_SIDE)
+            .prop_flat_map(|(width, height)| {
+                let cells = (width * height) as usize;
+                proptest::collection::vec(any::<Tile>(), cells)
+                    .prop_map(move |tiles| GridMap::from_tiles(width, height, tiles))
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ScenarioEntry {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Entries on a map of up to 256 cells a side, with start and goal
+    /// inside it and maybe annotations. The optimal length isn't
+    /// consistent with the cells.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (1..=256u32, 1..=256u32, "[a-z0-9-]{1,12}")
+            .prop_flat_map(|(width, height, name)| {
+                (
+                    (0..width, 0..height),
+                    (0..width, 0..height),
+                    0.0..512.0f64,
+                    0..64u32,
+                    0..32usize,
+                )
+                    .prop_map(
+                        move |(start, goal, optimal_length, bucket, start_time)| {
+                            ScenarioEntry::new(format!("{name}.map"), width, height)
+                                .with_start(start.0, start.1)
+                                .with_goal(goal.0, goal.1)
+                                .with_optimal_length(optimal_length)
+                                .with_bucket(bucket)
+                                .with_start_time(start_time)
+                        },
+                    )
+            })
+            .prop_flat_map(|entry| {
+                let name = proptest::option::of("[a-z0-9-]{1,8}");
+                (name.clone(), name, proptest::option::of(0..16u32)).prop_map(
+                    move |(id, group, priority)| ScenarioEntry {
+                        id,
+                        group,
+                        priority,
+                        ..entry.clone()
+                    },
+                )
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Instance {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Up to eight agents on an arbitrary map, starts and goals on distinct
+    /// passable cells. A goal may be unreachable from its start.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        any::<GridMap>()
+            .prop_flat_map(|map| {
+                let cells: Vec<Coordinate> = (0..map.height())
+                    .flat_map(|y| (0..map.width()).map(move |x| Coordinate { x, y }))
+                    .filter(|c| map.is_passable(c.x, c.y))
+                    .collect();
+                let max_agents = cells.len().min(MAX_AGENTS);
+                (0..=max_agents).prop_flat_map(move |agents| {
+                    (
+                        Just(map.clone()),
+                        subsequence(cells.clone(), agents).prop_shuffle(),
+                        subsequence(cells.clone(), agents).prop_shuffle(),
+                    )
+                })
+            })
+            .prop_map(|(map, starts, goals)| Instance::new(map, starts, goals))
+            .boxed()
+    }
+}
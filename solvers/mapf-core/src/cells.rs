@@ -0,0 +1,76 @@
+//! Tile storage for [`GridMap`](crate::GridMap).
+
+use crate::map::Tile;
+
+/// Row-major tiles of a map.
+///
+/// Maps made only of plain passable and blocked cells, like the city and
+/// game benchmarks without terrain, take one bit per cell; a map with swamp,
+/// water or trees falls back to one [`Tile`] per cell.
+#[derive(Debug, Clone)]
+pub(crate) enum Cells {
+    /// Bit `i % 64` of word `i / 64` is set when cell `i` is passable.
+    Bits {
+        words: Vec<u64>,
+        len: usize,
+    },
+    Tiles(Vec<Tile>),
+}
+
+impl Cells {
+    /// Store `tiles` compactly when none of them carry terrain.
+    pub(crate) fn from_tiles(tiles: Vec<Tile>) -> Self {
+        if tiles
+            .iter()
+            .all(|&t| matches!(t, Tile::Passable | Tile::Blocked))
+        {
+            Self::from_passable(tiles.len(), |i| tiles[i] == Tile::Passable)
+        } else {
+            Cells::Tiles(tiles)
+        }
+    }
+
+    /// Plain passable or blocked cells, passable where `passable(i)` is true.
+    pub(crate) fn from_passable(len: usize, passable: impl Fn(usize) -> bool) -> Self {
+        let mut words = vec![0u64; len.div_ceil(64)];
+        for i in (0..len).filter(|&i| passable(i)) {
+            words[i / 64] |= 1 << (i % 64);
+        }
+        Cells::Bits { words, len }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Cells::Bits { len, .. } => *len,
+            Cells::Tiles(tiles) => tiles.len(),
+        }
+    }
+
+    /// Tile of cell `i`; panics if out of range.
+    pub(crate) fn get(&self, i: usize) -> Tile {
+        match self {
+            Cells::Bits { .. } if self.is_passable(i) => Tile::Passable,
+            Cells::Bits { .. } => Tile::Blocked,
+            Cells::Tiles(tiles) => tiles[i],
+        }
+    }
+
+    /// Whether cell `i` can be entered; panics if out of range.
+    pub(crate) fn is_passable(&self, i: usize) -> bool {
+        match self {
+            Cells::Bits { words, len } => {
+                assert!(i < *len, "cell {i} out of range");
+                words[i / 64] & (1 << (i % 64)) != 0
+            }
+            Cells::Tiles(tiles) => tiles[i].is_passable(),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = Tile> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+
+    pub(crate) fn is_compact(&self) -> bool {
+        matches!(self, Cells::Bits { .. })
+    }
+}
@@ -0,0 +1,100 @@
+//! Lower-resolution views of a map for hierarchical search.
+
+use crate::map::{GridMap, Rect};
+use crate::solution::Coordinate;
+
+/// A map shrunk by [`GridMap::coarsen`], with the mapping between its
+/// cells and the `factor` x `factor` blocks of fine cells they cover.
+#[derive(Debug, Clone)]
+pub struct CoarseMap {
+    map: GridMap,
+    factor: u32,
+    fine_width: u32,
+    fine_height: u32,
+}
+
+impl CoarseMap {
+    /// The coarse map; its cells are plain passable or blocked, without
+    /// costs.
+    pub fn map(&self) -> &GridMap {
+        &self.map
+    }
+
+    /// Fine cells per coarse cell along each axis.
+    pub fn factor(&self) -> u32 {
+        self.factor
+    }
+
+    /// Coarse cell covering fine cell (x, y), or `None` if it is out of
+    /// bounds of the fine map.
+    pub fn to_coarse(&self, x: u32, y: u32) -> Option<Coordinate> {
+        (x < self.fine_width && y < self.fine_height).then(|| Coordinate {
+            x: x / self.factor,
+            y: y / self.factor,
+        })
+    }
+
+    /// Block of fine cells covered by coarse cell (x, y), or `None` if it
+    /// is out of bounds. Blocks on the right and bottom edges are cut to
+    /// the fine map.
+    pub fn to_fine(&self, x: u32, y: u32) -> Option<Rect> {
+        (x < self.map.width() && y < self.map.height())
+            .then(|| block(self.factor, self.fine_width, self.fine_height, x, y))
+    }
+}
+
+impl GridMap {
+    /// The map at `1 / factor` resolution, each coarse cell passable if at
+    /// least half of the fine cells it covers are. `None` if `factor` is 0.
+    pub fn coarsen(&self, factor: u32) -> Option<CoarseMap> {
+        self.coarsen_with_threshold(factor, 0.5)
+    }
+
+    /// Like [`coarsen`](Self::coarsen), with a coarse cell passable if at
+    /// least `threshold` (0 to 1) of its fine cells are, and at least one.
+    ///
+    /// A threshold of 0 keeps every block with any free cell, which never
+    /// cuts off a route and so suits admissible heuristics; 1 keeps only
+    /// fully free blocks, which suits conservative feasibility checks.
+    pub fn coarsen_with_threshold(&self, factor: u32, threshold: f64) -> Option<CoarseMap> {
+        if factor == 0 {
+            return None;
+        }
+        let (fine_width, fine_height) = (self.width(), self.height());
+        let width = fine_width.div_ceil(factor);
+        let height = fine_height.div_ceil(factor);
+
+        let mut bytes = Vec::with_capacity((width as usize) * (height as usize));
+        for y in 0..height {
+            for x in 0..width {
+                let block = block(factor, fine_width, fine_height, x, y);
+                let passable = (block.y..block.y + block.height)
+                    .flat_map(|fy| (block.x..block.x + block.width).map(move |fx| (fx, fy)))
+                    .filter(|&(fx, fy)| self.is_passable(fx, fy))
+                    .count();
+                let cells = (block.width * block.height) as f64;
+                bytes.push((passable > 0 && passable as f64 >= threshold * cells) as u8);
+            }
+        }
+        let map = GridMap::from_bytes(width, height, &bytes)
+            .expect("sized to the coarse map")
+            .with_map_type(self.map_type());
+        Some(CoarseMap {
+            map,
+            factor,
+            fine_width,
+            fine_height,
+        })
+    }
+}
+
+/// Fine cells covered by coarse cell (x, y), cut to the fine map.
+fn block(factor: u32, fine_width: u32, fine_height: u32, x: u32, y: u32) -> Rect {
+    let (fx, fy) = (x * factor, y * factor);
+    Rect {
+        x: fx,
+        y: fy,
+        width: factor.min(fine_width - fx),
+        height: factor.min(fine_height - fy),
+    }
+}
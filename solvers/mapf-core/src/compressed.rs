@@ -0,0 +1,132 @@
+//! Reading maps and scenarios straight from compressed files (`compressed`
+//! feature).
+//!
+//! The input format is detected from its first bytes, so plain text, gzip
+//! (`.map.gz`, `.scen.gz`) and zip archives such as the MovingAI bundles
+//! are all accepted. Inside a zip only the `.map` or `.scen` files are
+//! read, named without the folders they sit in.
+
+use std::io::{self, Cursor, Read};
+use std::path::Path;
+
+use flate2::read::MultiGzDecoder;
+use thiserror::Error;
+
+use crate::map::{GridMap, MapError};
+use crate::scenario::{Scenario, ScenarioError};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+/// Errors from reading a possibly compressed map or scenario.
+#[derive(Debug, Error)]
+pub enum CompressedError {
+    #[error("failed to decompress: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid zip archive: {0}")]
+    Archive(String),
+
+    #[error("archive has no .{extension} file")]
+    NotFound { extension: &'static str },
+
+    #[error("archive has {count} .{extension} files, expected one")]
+    Ambiguous {
+        extension: &'static str,
+        count: usize,
+    },
+
+    #[error("{name}: {source}")]
+    Map { name: String, source: MapError },
+
+    #[error("{name}: {source}")]
+    Scenario { name: String, source: ScenarioError },
+}
+
+impl GridMap {
+    /// Parse a map that may be gzipped or the only `.map` file in a zip.
+    pub fn parse_compressed(data: &[u8]) -> Result<Self, CompressedError> {
+        let (name, text) = single(files(data, "map")?, "map")?;
+        Self::parse(&text).map_err(|source| CompressedError::Map { name, source })
+    }
+
+    /// Parse every `.map` file in a zip archive, sorted by file name. Plain
+    /// or gzipped input gives one map with an empty name.
+    pub fn parse_archive(data: &[u8]) -> Result<Vec<(String, Self)>, CompressedError> {
+        files(data, "map")?
+            .into_iter()
+            .map(|(name, text)| match Self::parse(&text) {
+                Ok(map) => Ok((name, map)),
+                Err(source) => Err(CompressedError::Map { name, source }),
+            })
+            .collect()
+    }
+}
+
+impl Scenario {
+    /// Parse a scenario that may be gzipped or the only `.scen` file in a
+    /// zip.
+    pub fn parse_compressed(data: &[u8]) -> Result<Self, CompressedError> {
+        let (name, text) = single(files(data, "scen")?, "scen")?;
+        Self::parse(&text).map_err(|source| CompressedError::Scenario { name, source })
+    }
+
+    /// Parse every `.scen` file in a zip archive, sorted by file name.
+    /// Plain or gzipped input gives one scenario with an empty name.
+    pub fn parse_archive(data: &[u8]) -> Result<Vec<(String, Self)>, CompressedError> {
+        files(data, "scen")?
+            .into_iter()
+            .map(|(name, text)| match Self::parse(&text) {
+                Ok(scenario) => Ok((name, scenario)),
+                Err(source) => Err(CompressedError::Scenario { name, source }),
+            })
+            .collect()
+    }
+}
+
+/// Name and text of each file with `extension` in `data`.
+fn files(data: &[u8], extension: &'static str) -> Result<Vec<(String, String)>, CompressedError> {
+    let mut text = String::new();
+    if data.starts_with(ZIP_MAGIC) {
+        return unzip(data, extension);
+    } else if data.starts_with(GZIP_MAGIC) {
+        MultiGzDecoder::new(data).read_to_string(&mut text)?;
+    } else {
+        Cursor::new(data).read_to_string(&mut text)?;
+    }
+    Ok(vec![(String::new(), text)])
+}
+
+fn unzip(data: &[u8], extension: &'static str) -> Result<Vec<(String, String)>, CompressedError> {
+    let archive_error = |e: zip::result::ZipError| CompressedError::Archive(e.to_string());
+    let mut archive = zip::ZipArchive::new(Cursor::new(data)).map_err(archive_error)?;
+    let mut files = Vec::new();
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(archive_error)?;
+        let Some(name) = file
+            .enclosed_name()
+            .and_then(|p| p.file_name().and_then(|n| n.to_str()).map(str::to_owned))
+        else {
+            continue;
+        };
+        if file.is_dir() || Path::new(&name).extension().is_none_or(|e| e != extension) {
+            continue;
+        }
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        files.push((name, text));
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn single(
+    mut files: Vec<(String, String)>,
+    extension: &'static str,
+) -> Result<(String, String), CompressedError> {
+    match files.len() {
+        0 => Err(CompressedError::NotFound { extension }),
+        1 => Ok(files.remove(0)),
+        count => Err(CompressedError::Ambiguous { extension, count }),
+    }
+}
@@ -0,0 +1,114 @@
+//! Comparison of two solutions to the same instance.
+//!
+//! Explains why one submission beat another: which agents got cheaper or
+//! dearer, and when their paths part ways.
+
+use std::ops::Range;
+
+use crate::{Coordinate, Path, Solution};
+
+/// How one agent's path differs between two solutions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentDiff {
+    pub agent: usize,
+    /// Cost in the first solution, ignoring trailing waits at the goal
+    pub cost_a: u32,
+    /// Cost in the second solution, ignoring trailing waits at the goal
+    pub cost_b: u32,
+    /// Timestep spans during which the agent is on different cells
+    pub divergences: Vec<Range<usize>>,
+}
+
+impl AgentDiff {
+    /// `cost_b - cost_a`: negative when the second solution is cheaper.
+    pub fn cost_delta(&self) -> i64 {
+        self.cost_b as i64 - self.cost_a as i64
+    }
+
+    /// Whether the agent moves identically in both solutions.
+    pub fn is_same(&self) -> bool {
+        self.divergences.is_empty()
+    }
+
+    /// First timestep at which the paths differ.
+    pub fn first_divergence(&self) -> Option<usize> {
+        self.divergences.first().map(|span| span.start)
+    }
+}
+
+/// Per-agent comparison of two solutions, plus the totals.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionDiff {
+    /// One entry per agent present in both solutions, in agent order
+    pub agents: Vec<AgentDiff>,
+    pub sum_of_costs: (u64, u64),
+    pub makespan: (u32, u32),
+}
+
+impl SolutionDiff {
+    /// Agents whose paths differ.
+    pub fn changed(&self) -> impl Iterator<Item = &AgentDiff> {
+        self.agents.iter().filter(|agent| !agent.is_same())
+    }
+}
+
+/// Compare `a` with `b` agent by agent.
+///
+/// Costs count actions, as in [`Path::cost`], after dropping trailing waits,
+/// so paths that only differ in how long they idle at the goal compare
+/// equal. Agents stay at their last cell once their path ends. If the
+/// solutions have different numbers of paths, only the common agents are
+/// compared; the totals still cover every path.
+pub fn diff(a: &Solution, b: &Solution) -> SolutionDiff {
+    let (a, b) = (a.canonicalize(), b.canonicalize());
+
+    let agents = a
+        .paths
+        .iter()
+        .zip(&b.paths)
+        .enumerate()
+        .map(|(agent, (path_a, path_b))| AgentDiff {
+            agent,
+            cost_a: path_a.cost(),
+            cost_b: path_b.cost(),
+            divergences: divergences(path_a, path_b),
+        })
+        .collect();
+
+    SolutionDiff {
+        agents,
+        sum_of_costs: (sum_of_costs(&a), sum_of_costs(&b)),
+        makespan: (makespan(&a), makespan(&b)),
+    }
+}
+
+fn sum_of_costs(solution: &Solution) -> u64 {
+    solution.paths.iter().map(|p| p.cost() as u64).sum()
+}
+
+fn makespan(solution: &Solution) -> u32 {
+    solution.paths.iter().map(Path::cost).max().unwrap_or(0)
+}
+
+/// Spans of timesteps during which the two paths put the agent on
+/// different cells.
+fn divergences(a: &Path, b: &Path) -> Vec<Range<usize>> {
+    let horizon = a.steps.len().max(b.steps.len());
+    let mut spans: Vec<Range<usize>> = Vec::new();
+
+    for t in 0..horizon {
+        if position_at(a, t) == position_at(b, t) {
+            continue;
+        }
+        match spans.last_mut() {
+            Some(span) if span.end == t => span.end = t + 1,
+            _ => spans.push(t..t + 1),
+        }
+    }
+    spans
+}
+
+/// Position of an agent at timestep `t`; `None` for empty paths.
+fn position_at(path: &Path, t: usize) -> Option<&Coordinate> {
+    path.steps.get(t).or_else(|| path.steps.last())
+}
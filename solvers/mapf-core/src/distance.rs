@@ -0,0 +1,133 @@
+//! Exact distance-to-goal tables for heuristic search, and single
+//! shortest paths.
+
+use std::collections::VecDeque;
+
+use crate::map::GridMap;
+use crate::solution::Coordinate;
+
+/// Shortest 4-connected move counts from every cell to one goal, as
+/// computed by [`GridMap::distance_map`].
+///
+/// Distances count moves, ignoring the map's cost channel, so on weighted
+/// maps they are still a lower bound on the cost to go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMap {
+    goal: Coordinate,
+    width: u32,
+    height: u32,
+    /// Row-major; `u32::MAX` where the goal can't be reached
+    distances: Vec<u32>,
+}
+
+impl DistanceMap {
+    /// The cell distances are measured to.
+    pub fn goal(&self) -> Coordinate {
+        self.goal
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Moves needed from (x, y) to the goal, or `None` if the goal can't be
+    /// reached from there or (x, y) is out of bounds.
+    pub fn get(&self, x: u32, y: u32) -> Option<u32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let distance = self.distances[(y * self.width + x) as usize];
+        (distance != u32::MAX).then_some(distance)
+    }
+
+    /// Whether the goal can be reached from (x, y).
+    pub fn is_reachable(&self, x: u32, y: u32) -> bool {
+        self.get(x, y).is_some()
+    }
+}
+
+impl GridMap {
+    /// Breadth-first distances from every cell to `goal`, for an exact
+    /// single-agent heuristic with constant-time lookup.
+    ///
+    /// Nothing is reachable if `goal` is blocked or out of bounds.
+    pub fn distance_map(&self, goal: Coordinate) -> DistanceMap {
+        let (width, height) = (self.width(), self.height());
+        let mut distances = vec![u32::MAX; (width as usize) * (height as usize)];
+
+        if self.is_passable(goal.x, goal.y) {
+            distances[(goal.y * width + goal.x) as usize] = 0;
+            let mut queue = VecDeque::from([goal]);
+            while let Some(cell) = queue.pop_front() {
+                let next = distances[(cell.y * width + cell.x) as usize] + 1;
+                let neighbors = [
+                    (cell.y > 0).then(|| (cell.x, cell.y - 1)),
+                    (cell.x + 1 < width).then(|| (cell.x + 1, cell.y)),
+                    (cell.y + 1 < height).then(|| (cell.x, cell.y + 1)),
+                    (cell.x > 0).then(|| (cell.x - 1, cell.y)),
+                ];
+                for (x, y) in neighbors.into_iter().flatten() {
+                    let i = (y * width + x) as usize;
+                    if distances[i] == u32::MAX && self.is_passable(x, y) {
+                        distances[i] = next;
+                        queue.push_back(Coordinate { x, y });
+                    }
+                }
+            }
+        }
+
+        DistanceMap {
+            goal,
+            width,
+            height,
+            distances,
+        }
+    }
+
+    /// A shortest 4-connected path from `start` to `goal`, both included,
+    /// found by breadth-first search; its length minus one is the optimal
+    /// move count. Costs are ignored. `None` if either cell is blocked or
+    /// out of bounds, or `goal` can't be reached.
+    pub fn shortest_path(&self, start: (u32, u32), goal: (u32, u32)) -> Option<Vec<(u32, u32)>> {
+        if !self.is_passable(start.0, start.1) || !self.is_passable(goal.0, goal.1) {
+            return None;
+        }
+        let (width, height) = (self.width(), self.height());
+        let index = |(x, y): (u32, u32)| (y * width + x) as usize;
+
+        // Cell each one was first reached from; the start points at itself
+        let mut parents = vec![None; (width as usize) * (height as usize)];
+        parents[index(start)] = Some(start);
+        let mut queue = VecDeque::from([start]);
+        while let Some(cell) = queue.pop_front() {
+            if cell == goal {
+                let mut path = vec![goal];
+                let mut at = goal;
+                while at != start {
+                    at = parents[index(at)].expect("visited cells have a parent");
+                    path.push(at);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            let (x, y) = cell;
+            let neighbors = [
+                (y > 0).then(|| (x, y - 1)),
+                (x + 1 < width).then(|| (x + 1, y)),
+                (y + 1 < height).then(|| (x, y + 1)),
+                (x > 0).then(|| (x - 1, y)),
+            ];
+            for next in neighbors.into_iter().flatten() {
+                if parents[index(next)].is_none() && self.is_passable(next.0, next.1) {
+                    parents[index(next)] = Some(cell);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,224 @@
+//! MovingAI benchmark downloads (`download` feature).
+//!
+//! Fetches the official map and scenario archives once into a local cache
+//! directory and loads benchmarks from there:
+//!
+//! ```no_run
+//! use mapf_core::download::{BenchmarkCache, ScenarioSet};
+//!
+//! let benchmark = BenchmarkCache::new("benchmarks")
+//!     .load("random-32-32-20", ScenarioSet::Random)
+//!     .unwrap();
+//! println!("{} scenarios", benchmark.scenarios.len());
+//! ```
+
+use std::fs::{self, File};
+use std::io::{self, BufReader, Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use thiserror::Error;
+
+use crate::map::{GridMap, MapError};
+use crate::scenario::{Scenario, ScenarioError};
+
+/// Where the MovingAI MAPF benchmark archives live.
+pub const MOVINGAI_URL: &str = "https://movingai.com/benchmarks/mapf";
+
+/// Errors from fetching or loading a benchmark.
+#[derive(Debug, Error)]
+pub enum DownloadError {
+    #[error("failed to download {url}: {reason}")]
+    Http { url: String, reason: String },
+
+    #[error("invalid archive {url}: {reason}")]
+    Archive { url: String, reason: String },
+
+    #[error("no benchmark map named {0}")]
+    UnknownMap(String),
+
+    #[error("{path}: {source}")]
+    Map { path: PathBuf, source: MapError },
+
+    #[error("{path}: {source}")]
+    Scenario {
+        path: PathBuf,
+        source: ScenarioError,
+    },
+
+    #[error("cache I/O error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// The two scenario sets of the MovingAI MAPF benchmarks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScenarioSet {
+    /// Tasks drawn uniformly at random.
+    Random,
+    /// Tasks spread evenly over the difficulty buckets.
+    Even,
+}
+
+impl ScenarioSet {
+    fn name(self) -> &'static str {
+        match self {
+            ScenarioSet::Random => "random",
+            ScenarioSet::Even => "even",
+        }
+    }
+}
+
+/// A benchmark map with its scenario files, in file number order.
+#[derive(Debug, Clone)]
+pub struct Benchmark {
+    /// Map name, e.g. `random-32-32-20`.
+    pub name: String,
+    pub map: GridMap,
+    pub scenarios: Vec<Scenario>,
+}
+
+/// Local cache of the MovingAI benchmark archives.
+///
+/// Each archive is downloaded the first time a benchmark needs it and
+/// unpacked into its own directory below the cache; later loads only read
+/// the files.
+#[derive(Debug, Clone)]
+pub struct BenchmarkCache {
+    dir: PathBuf,
+    base_url: String,
+}
+
+impl BenchmarkCache {
+    /// A cache in `dir`, created on first download, fetching from
+    /// [`MOVINGAI_URL`].
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url: MOVINGAI_URL.to_string(),
+        }
+    }
+
+    /// Fetch the archives from a mirror instead, e.g. one inside a cluster
+    /// without internet access.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Load the map `name` (without `.map`) and its scenarios from `set`,
+    /// downloading the archives if they aren't cached yet.
+    pub fn load(&self, name: &str, set: ScenarioSet) -> Result<Benchmark, DownloadError> {
+        let map = self.map(name)?;
+        let scenarios = self.scenarios(name, set)?;
+        Ok(Benchmark {
+            name: name.to_string(),
+            map,
+            scenarios,
+        })
+    }
+
+    /// Load the map `name` (without `.map`).
+    pub fn map(&self, name: &str) -> Result<GridMap, DownloadError> {
+        let path = self.archive("mapf-map")?.join(format!("{name}.map"));
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(DownloadError::UnknownMap(name.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        GridMap::parse_from_reader(BufReader::new(file))
+            .map_err(|source| DownloadError::Map { path, source })
+    }
+
+    /// Load the scenarios of map `name` from `set`, e.g.
+    /// `random-32-32-20-random-1.scen` to `-25.scen`.
+    pub fn scenarios(&self, name: &str, set: ScenarioSet) -> Result<Vec<Scenario>, DownloadError> {
+        let dir = self.archive(&format!("mapf-scen-{}", set.name()))?;
+        let prefix = format!("{name}-{}-", set.name());
+
+        let mut files: Vec<(u32, PathBuf)> = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let number = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_prefix(&prefix))
+                .and_then(|n| n.strip_suffix(".scen"))
+                .and_then(|n| n.parse().ok());
+            if let Some(number) = number {
+                files.push((number, path));
+            }
+        }
+        if files.is_empty() {
+            return Err(DownloadError::UnknownMap(name.to_string()));
+        }
+        files.sort();
+
+        files
+            .into_iter()
+            .map(|(_, path)| {
+                let file = File::open(&path)?;
+                Scenario::parse_from_reader(BufReader::new(file))
+                    .map_err(|source| DownloadError::Scenario { path, source })
+            })
+            .collect()
+    }
+
+    /// Directory holding the unpacked `archive`, downloading it first if
+    /// needed.
+    fn archive(&self, archive: &str) -> Result<PathBuf, DownloadError> {
+        let dir = self.dir.join(archive);
+        if dir.is_dir() {
+            return Ok(dir);
+        }
+
+        let url = format!("{}/{archive}.zip", self.base_url);
+        let mut bytes = Vec::new();
+        ureq::get(&url)
+            .call()
+            .map_err(|e| DownloadError::Http {
+                url: url.clone(),
+                reason: e.to_string(),
+            })?
+            .into_reader()
+            .read_to_end(&mut bytes)?;
+
+        // Unpack next to the final directory and move it into place, so an
+        // interrupted download never looks like a complete archive
+        let partial = self.dir.join(format!("{archive}.partial"));
+        if partial.exists() {
+            fs::remove_dir_all(&partial)?;
+        }
+        fs::create_dir_all(&partial)?;
+        unpack(&bytes, &partial).map_err(|reason| DownloadError::Archive {
+            url: url.clone(),
+            reason,
+        })?;
+        fs::rename(&partial, &dir)?;
+        Ok(dir)
+    }
+}
+
+/// Write the `.map` and `.scen` files of a zip archive into `dir`, without
+/// the folders they sit in inside the archive.
+fn unpack(bytes: &[u8], dir: &Path) -> Result<(), String> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| e.to_string())?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        let Some(name) = file
+            .enclosed_name()
+            .and_then(|p| p.file_name().map(|n| n.to_owned()))
+        else {
+            continue;
+        };
+        let wanted = Path::new(&name)
+            .extension()
+            .is_some_and(|e| e == "map" || e == "scen");
+        if file.is_dir() || !wanted {
+            continue;
+        }
+        let mut out = File::create(dir.join(&name)).map_err(|e| e.to_string())?;
+        io::copy(&mut file, &mut out).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
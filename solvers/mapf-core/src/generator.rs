@@ -0,0 +1,199 @@
+//! Seeded random obstacle maps and scenarios.
+
+use std::collections::VecDeque;
+
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
+
+use crate::map::GridMap;
+use crate::scenario::{Scenario, ScenarioEntry};
+
+/// Neighbors of a cell in ring order, starting north and going clockwise.
+/// Consecutive entries share an edge, and even entries are the 4-neighbors.
+const RING: [(i64, i64); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+impl GridMap {
+    /// A `width` x `height` map with obstacles scattered at random, covering
+    /// about `obstacle_density` (clamped to `0..=1`) of the cells.
+    ///
+    /// The free cells always form one 4-connected region, so every start can
+    /// reach every goal; an obstacle that would split it is skipped, so dense
+    /// maps may end up with fewer obstacles than asked for, and at least one
+    /// cell stays free. The same arguments always give the same map.
+    pub fn random(width: u32, height: u32, obstacle_density: f64, seed: u64) -> Self {
+        let cells = (width as usize) * (height as usize);
+        let mut free = vec![1u8; cells];
+        let target = (obstacle_density.clamp(0.0, 1.0) * cells as f64).round() as usize;
+
+        let mut order: Vec<usize> = (0..cells).collect();
+        order.shuffle(&mut Pcg64::seed_from_u64(seed));
+
+        let mut placed = 0;
+        for cell in order {
+            if placed == target {
+                break;
+            }
+            if keeps_connected(&free, width, height, cell) {
+                free[cell] = 0;
+                placed += 1;
+            }
+        }
+
+        GridMap::from_bytes(width, height, &free).expect("size matches")
+    }
+}
+
+impl Scenario {
+    /// Sample `num_agents` tasks on `map`, in the style of the benchmarks'
+    /// `random` scenarios, or `None` if the map has fewer passable cells.
+    ///
+    /// Starts are distinct passable cells, as are goals, and every goal is
+    /// reachable from its start; an agent only starts on its own goal when no
+    /// other goal is left in its region. Optimal lengths are 4-connected
+    /// single-agent distances, and buckets group them by fours as in the
+    /// benchmark files. Entries have no map name; see
+    /// [`Scenario::with_map_name`]. The same arguments always give the same
+    /// scenario.
+    pub fn generate(map: &GridMap, num_agents: usize, seed: u64) -> Option<Self> {
+        let (width, height) = (map.width(), map.height());
+        let tiles = map.to_bytes();
+        let (labels, sizes) = regions(width, height, &tiles);
+
+        let mut free: Vec<usize> = (0..tiles.len()).filter(|&c| tiles[c] != 0).collect();
+        if num_agents > free.len() {
+            return None;
+        }
+
+        let mut rng = Pcg64::seed_from_u64(seed);
+        free.shuffle(&mut rng);
+        // Unused goal cells of each region, in random order
+        let mut goals: Vec<Vec<usize>> =
+            sizes.iter().map(|&size| Vec::with_capacity(size)).collect();
+        for &cell in &free {
+            goals[labels[cell]].push(cell);
+        }
+        for pool in &mut goals {
+            pool.shuffle(&mut rng);
+        }
+
+        let w = width as usize;
+        let entries = free[..num_agents]
+            .iter()
+            .map(|&start| {
+                // There are as many goals left in the region as starts to take them
+                let pool = &mut goals[labels[start]];
+                let mut goal = pool.pop().expect("region has a goal left");
+                if goal == start {
+                    if let Some(other) = pool.pop() {
+                        pool.push(goal);
+                        goal = other;
+                    }
+                }
+                let cell = |i: usize| ((i % w) as u32, (i / w) as u32);
+                let length = map
+                    .shortest_path(cell(start), cell(goal))
+                    .expect("goal is in the start's region")
+                    .len() as u32
+                    - 1;
+                ScenarioEntry::new("", width, height)
+                    .with_start((start % w) as u32, (start / w) as u32)
+                    .with_goal((goal % w) as u32, (goal / w) as u32)
+                    .with_optimal_length(length as f64)
+                    .with_bucket(length / 4)
+            })
+            .collect();
+        Some(Scenario::new(1, entries))
+    }
+}
+
+/// Whether blocking `cell` leaves the free cells around it connected.
+///
+/// Checks locally: the free 4-neighbors must all be linked through free
+/// cells of the surrounding ring. That is stricter than needed but keeps
+/// generation linear in the map size. A cell with no free 4-neighbor is the
+/// last free cell and is kept.
+fn keeps_connected(free: &[u8], width: u32, height: u32, cell: usize) -> bool {
+    let w = width as usize;
+    let (x, y) = ((cell % w) as i64, (cell / w) as i64);
+    let ring = RING.map(|(dx, dy)| {
+        let (nx, ny) = (x + dx, y + dy);
+        (0..width as i64).contains(&nx)
+            && (0..height as i64).contains(&ny)
+            && free[(ny * width as i64 + nx) as usize] == 1
+    });
+
+    // Count runs of free ring cells that include a 4-neighbor
+    let mut runs = 0;
+    for start in 0..8 {
+        if !ring[start] || ring[(start + 7) % 8] {
+            continue;
+        }
+        let mut i = start;
+        let mut cardinal = false;
+        while ring[i % 8] && i < start + 8 {
+            cardinal |= i % 2 == 0;
+            i += 1;
+        }
+        runs += cardinal as usize;
+    }
+    // A fully free ring has no run start but is one connected run
+    if ring.iter().all(|&f| f) {
+        runs = 1;
+    }
+    runs == 1
+}
+
+/// Label the 4-connected regions of passable cells.
+///
+/// Returns each cell's region (`usize::MAX` for blocked cells) and the size
+/// of every region.
+fn regions(width: u32, height: u32, tiles: &[u8]) -> (Vec<usize>, Vec<usize>) {
+    let mut labels = vec![usize::MAX; tiles.len()];
+    let mut sizes = Vec::new();
+    let mut queue = VecDeque::new();
+
+    for seed in 0..tiles.len() {
+        if tiles[seed] == 0 || labels[seed] != usize::MAX {
+            continue;
+        }
+        let region = sizes.len();
+        let mut size = 0;
+        labels[seed] = region;
+        queue.push_back(seed);
+        while let Some(cell) = queue.pop_front() {
+            size += 1;
+            for next in neighbors(width, height, cell) {
+                if tiles[next] != 0 && labels[next] == usize::MAX {
+                    labels[next] = region;
+                    queue.push_back(next);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+    (labels, sizes)
+}
+
+/// In-bounds 4-neighbors of a row-major cell index.
+fn neighbors(width: u32, height: u32, cell: usize) -> impl Iterator<Item = usize> {
+    let (w, h) = (width as usize, height as usize);
+    let (x, y) = (cell % w, cell / w);
+    [
+        (x > 0).then(|| cell - 1),
+        (x + 1 < w).then(|| cell + 1),
+        (y > 0).then(|| cell - w),
+        (y + 1 < h).then(|| cell + w),
+    ]
+    .into_iter()
+    .flatten()
+}
@@ -0,0 +1,96 @@
+//! Landmark (differential) heuristics.
+//!
+//! Distances from a few landmark cells bound the distance between any two
+//! cells by the triangle inequality: `|d(L, a) - d(L, b)| <= d(a, b)`. On
+//! mazes and other maps with long detours this is far tighter than the
+//! Manhattan distance, and it stays admissible.
+
+use crate::distance::DistanceMap;
+use crate::map::GridMap;
+use crate::solution::Coordinate;
+
+/// Distance maps from `k` landmarks, giving an admissible 4-connected
+/// heuristic between any two cells.
+#[derive(Debug, Clone)]
+pub struct Landmarks {
+    maps: Vec<DistanceMap>,
+}
+
+impl Landmarks {
+    /// Pick up to `k` landmarks spread over `map` and precompute their
+    /// distance maps.
+    ///
+    /// Landmarks are chosen farthest-first: each one is the passable cell
+    /// furthest from all landmarks so far, and cells in a region no
+    /// landmark reaches yet go first. Fewer than `k` are picked only if
+    /// the map has fewer passable cells. The choice is deterministic.
+    pub fn new(map: &GridMap, k: usize) -> Self {
+        let (width, height) = (map.width(), map.height());
+        let cells = || {
+            (0..height)
+                .flat_map(move |y| (0..width).map(move |x| Coordinate { x, y }))
+                .filter(|c| map.is_passable(c.x, c.y))
+        };
+        let Some(first) = cells().next() else {
+            return Self { maps: Vec::new() };
+        };
+
+        // Start from the far end of the first region rather than its corner
+        let from_first = map.distance_map(first);
+        let mut next = cells()
+            .max_by_key(|c| (from_first.get(c.x, c.y), std::cmp::Reverse((c.y, c.x))))
+            .unwrap_or(first);
+
+        // Moves to the nearest landmark; `u32::MAX` where none reaches
+        let mut nearest = vec![u32::MAX; (width as usize) * (height as usize)];
+        let mut maps = Vec::with_capacity(k);
+        while maps.len() < k {
+            let landmark = map.distance_map(next);
+            for c in cells() {
+                let i = (c.y * width + c.x) as usize;
+                nearest[i] = nearest[i].min(landmark.get(c.x, c.y).unwrap_or(u32::MAX));
+            }
+            maps.push(landmark);
+
+            match cells()
+                .map(|c| (nearest[(c.y * width + c.x) as usize], c))
+                .max_by_key(|&(d, c)| (d, std::cmp::Reverse((c.y, c.x))))
+            {
+                Some((d, c)) if d > 0 => next = c,
+                _ => break,
+            }
+        }
+        Self { maps }
+    }
+
+    /// Landmarks at the given cells, e.g. ones chosen by hand. Blocked or
+    /// out-of-bounds cells add nothing to the heuristic.
+    pub fn from_cells(map: &GridMap, cells: &[Coordinate]) -> Self {
+        Self {
+            maps: cells.iter().map(|&c| map.distance_map(c)).collect(),
+        }
+    }
+
+    /// The landmark cells, in the order they were picked.
+    pub fn cells(&self) -> impl Iterator<Item = Coordinate> + '_ {
+        self.maps.iter().map(DistanceMap::goal)
+    }
+
+    pub fn len(&self) -> usize {
+        self.maps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+
+    /// Lower bound on the moves from `a` to `b`: the best landmark bound,
+    /// and never less than the Manhattan distance.
+    pub fn h(&self, a: Coordinate, b: Coordinate) -> u32 {
+        let manhattan = a.x.abs_diff(b.x) + a.y.abs_diff(b.y);
+        self.maps
+            .iter()
+            .filter_map(|m| Some(m.get(a.x, a.y)?.abs_diff(m.get(b.x, b.y)?)))
+            .fold(manhattan, u32::max)
+    }
+}
@@ -0,0 +1,196 @@
+//! MAPF instances and stable content fingerprints.
+//!
+//! [`Instance`] is the one shape tools pass instances around in; with the
+//! `serde` feature it is the JSON object `{ map, starts, goals }`.
+//!
+//! Fingerprints are SHA-256 digests of a fixed binary layout, so they stay
+//! the same across platforms and releases and can key caches and
+//! deduplicate evaluations. The map type header and the map's name aren't
+//! part of the content. [`Instance::canonicalize`] goes further and ignores
+//! agent order, so reordered submissions of one instance match too.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::map::GridMap;
+use crate::scenario::{AgentsError, EntryError, Scenario};
+use crate::solution::Coordinate;
+
+/// Why a scenario can't be turned into an instance.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InstanceError {
+    #[error(transparent)]
+    Agents(#[from] AgentsError),
+
+    #[error(transparent)]
+    DoesNotFit(#[from] EntryError),
+}
+
+/// A map with one start and goal per agent.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Instance {
+    pub map: GridMap,
+    pub starts: Vec<Coordinate>,
+    pub goals: Vec<Coordinate>,
+}
+
+impl Instance {
+    pub fn new(map: GridMap, starts: Vec<Coordinate>, goals: Vec<Coordinate>) -> Self {
+        Self { map, starts, goals }
+    }
+
+    /// The first `agents` agents of `scenario` on `map`.
+    ///
+    /// Fails if the scenario has fewer agents, if two of them share a start
+    /// or goal, or if one of their entries doesn't fit the map; see
+    /// [`Scenario::validate_against`] for every misfit at once.
+    pub fn from_scenario(
+        map: GridMap,
+        scenario: &Scenario,
+        agents: usize,
+    ) -> Result<Self, InstanceError> {
+        let (starts, goals) = scenario.agents_n(agents)?;
+        if let Some(error) = scenario
+            .validate_against(&map)
+            .into_iter()
+            .find(|e| e.entry() < agents)
+        {
+            return Err(error.into());
+        }
+
+        let coordinates =
+            |positions: Vec<(u32, u32)>| positions.into_iter().map(Coordinate::from).collect();
+        Ok(Self::new(map, coordinates(starts), coordinates(goals)))
+    }
+
+    pub fn num_agents(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Lowercase hex SHA-256 of the map's content and the agents' starts and
+    /// goals in order, i.e. of [`encode`](Self::encode).
+    pub fn fingerprint(&self) -> String {
+        hex(&Sha256::digest(self.encode()))
+    }
+
+    /// Fixed binary layout of the instance, the map given by its digest:
+    /// a version tag, the map's SHA-256, the start and goal counts as
+    /// little-endian `u64`, then every start and goal as little-endian
+    /// `u32` x and y.
+    pub fn encode(&self) -> Vec<u8> {
+        let tag = b"mapf-instance-v1";
+        let mut out =
+            Vec::with_capacity(tag.len() + 48 + 8 * (self.starts.len() + self.goals.len()));
+        out.extend_from_slice(tag);
+        out.extend_from_slice(&map_digest(&self.map));
+        out.extend_from_slice(&(self.starts.len() as u64).to_le_bytes());
+        out.extend_from_slice(&(self.goals.len() as u64).to_le_bytes());
+        for c in self.starts.iter().chain(&self.goals) {
+            out.extend_from_slice(&c.x.to_le_bytes());
+            out.extend_from_slice(&c.y.to_le_bytes());
+        }
+        out
+    }
+
+    /// The instance in canonical form: agents whose goal can't be reached
+    /// from their start are dropped and the rest sorted by start, then goal
+    /// (row by row). Instances that differ only in agent order or in
+    /// hopeless agents have the same canonical encoding and fingerprint.
+    pub fn canonicalize(&self) -> CanonicalInstance {
+        // One region per connected area, so equal regions mean reachable
+        let areas = self.map.partition(1);
+        let (mut kept, unreachable): (Vec<usize>, Vec<usize>) =
+            (0..self.starts.len().min(self.goals.len())).partition(|&i| {
+                let (start, goal) = (self.starts[i], self.goals[i]);
+                areas
+                    .region(start.x, start.y)
+                    .is_some_and(|area| areas.region(goal.x, goal.y) == Some(area))
+            });
+        kept.sort_by_key(|&i| {
+            let (start, goal) = (self.starts[i], self.goals[i]);
+            (start.y, start.x, goal.y, goal.x, i)
+        });
+
+        CanonicalInstance {
+            instance: Self::new(
+                self.map.clone(),
+                kept.iter().map(|&i| self.starts[i]).collect(),
+                kept.iter().map(|&i| self.goals[i]).collect(),
+            ),
+            agents: kept,
+            unreachable,
+        }
+    }
+}
+
+/// An instance in canonical form, from [`Instance::canonicalize`], with
+/// the way back to the original agent numbering.
+#[derive(Debug, Clone)]
+pub struct CanonicalInstance {
+    pub instance: Instance,
+    /// Original index of each agent kept, in canonical order.
+    pub agents: Vec<usize>,
+    /// Original indices of the agents dropped as unreachable, ascending.
+    pub unreachable: Vec<usize>,
+}
+
+impl CanonicalInstance {
+    /// Canonical byte encoding; equal for the same canonical instance.
+    pub fn encode(&self) -> Vec<u8> {
+        self.instance.encode()
+    }
+
+    /// Fingerprint of the canonical instance.
+    pub fn fingerprint(&self) -> String {
+        self.instance.fingerprint()
+    }
+}
+
+/// Every agent of the scenario on the map.
+impl TryFrom<(GridMap, &Scenario)> for Instance {
+    type Error = InstanceError;
+
+    fn try_from((map, scenario): (GridMap, &Scenario)) -> Result<Self, Self::Error> {
+        Self::from_scenario(map, scenario, scenario.entries().len())
+    }
+}
+
+impl GridMap {
+    /// Lowercase hex SHA-256 of the map's size, tiles and cell costs.
+    ///
+    /// Maps with the same cells have the same fingerprint however they were
+    /// loaded or stored.
+    pub fn fingerprint(&self) -> String {
+        hex(&map_digest(self))
+    }
+}
+
+fn map_digest(map: &GridMap) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(b"mapf-map-v1");
+    hasher.update(map.width().to_le_bytes());
+    hasher.update(map.height().to_le_bytes());
+    for y in 0..map.height() {
+        let row: Vec<u8> = (0..map.width())
+            .map(|x| map.get(x, y).expect("in bounds").to_char() as u8)
+            .collect();
+        hasher.update(row);
+    }
+    match map.costs() {
+        Some(costs) => {
+            hasher.update([1]);
+            for cost in costs {
+                hasher.update(cost.to_le_bytes());
+            }
+        }
+        None => hasher.update([0]),
+    }
+    hasher.finalize().into()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
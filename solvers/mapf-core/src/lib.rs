@@ -1,10 +1,49 @@
 //! Core MAPF types and MovingAI format parser.
 //!
-//! Provides data structures for grid maps and scenarios,
-//! plus parsers for the MovingAI `.map` and `.scen` formats.
+//! Provides data structures for grid maps, scenarios and solutions,
+//! plus parsers for the MovingAI `.map` and `.scen` formats, basic
+//! solution checks ([`validate`]), solution comparison ([`diff`]),
+//! seeded random maps ([`GridMap::random`]), exact distance-to-goal
+//! tables ([`GridMap::distance_map`]), landmark heuristics
+//! ([`heuristics`]), free-space partitions ([`GridMap::partition`]) and
+//! dynamic obstacle schedules ([`DynamicObstacles`]). With the `download`
+//! feature, the `download` module fetches the MovingAI benchmarks into a
+//! local cache; the `png` feature reads and writes maps as images, and the
+//! `compressed` feature parses gzipped files and zip archives directly.
+//! The `proptest` feature implements `Arbitrary` for maps, scenario
+//! entries and instances.
 
+#[cfg(feature = "proptest")]
+mod arbitrary;
+mod cells;
+mod coarse;
+#[cfg(feature = "compressed")]
+mod compressed;
+pub mod diff;
+mod distance;
+#[cfg(feature = "download")]
+pub mod download;
+mod generator;
+pub mod heuristics;
+mod instance;
 mod map;
+mod obstacles;
+mod partition;
+#[cfg(feature = "png")]
+mod png;
 mod scenario;
+mod solution;
+pub mod validate;
 
-pub use map::{GridMap, MapError, Tile};
-pub use scenario::{Scenario, ScenarioEntry, ScenarioError};
+pub use coarse::CoarseMap;
+#[cfg(feature = "compressed")]
+pub use compressed::CompressedError;
+pub use distance::DistanceMap;
+pub use instance::{CanonicalInstance, Instance, InstanceError};
+pub use map::{GridMap, MapError, Rect, Tile};
+pub use obstacles::{DynamicObstacles, ObstacleError, TimedObstacle};
+pub use partition::Partition;
+#[cfg(feature = "png")]
+pub use png::{Palette, PngError};
+pub use scenario::{AgentsError, Endpoint, EntryError, Scenario, ScenarioEntry, ScenarioError};
+pub use solution::{Coordinate, Path, Solution};
@@ -1,39 +1,172 @@
 //! MovingAI `.map` format parser.
 
+use std::io::BufRead;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::cells::Cells;
+use crate::solution::{Coordinate, Path};
+
 /// A single cell in a grid map.
+///
+/// Terrain classes follow the MovingAI benchmarks: swamp is passable but
+/// slow, while water and trees can't be entered, like obstacles.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Tile {
-    /// Agent can traverse this cell.
+    /// Agent can traverse this cell (`.`, `G`).
     Passable,
-    /// Obstacle; agent cannot enter.
+    /// Passable swamp (`S`), costlier to cross than open ground.
+    Swamp,
+    /// Water (`W`); agent cannot enter.
+    Water,
+    /// Trees (`T`); agent cannot enter.
+    Trees,
+    /// Obstacle (`@`, `O`, anything else); agent cannot enter.
     Blocked,
 }
 
+impl Tile {
+    /// Whether an agent can enter this tile.
+    pub fn is_passable(self) -> bool {
+        matches!(self, Tile::Passable | Tile::Swamp)
+    }
+
+    /// Relative cost of entering this tile, or `None` if it can't be entered.
+    ///
+    /// Open ground costs 1 and swamp 3. Independent of a map's cost channel,
+    /// which is what validation and scoring use.
+    pub fn traversal_cost(self) -> Option<u32> {
+        match self {
+            Tile::Passable => Some(1),
+            Tile::Swamp => Some(3),
+            Tile::Water | Tile::Trees | Tile::Blocked => None,
+        }
+    }
+
+    /// MovingAI map character.
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Passable => '.',
+            Tile::Swamp => 'S',
+            Tile::Water => 'W',
+            Tile::Trees => 'T',
+            Tile::Blocked => '@',
+        }
+    }
+}
+
 /// Errors from parsing a MovingAI map file.
+///
+/// Each carries the 1-based line it was found on and that line's text, so
+/// problems in large map packs can be tracked down.
 #[derive(Debug, Error)]
 pub enum MapError {
-    #[error("missing header field: {0}")]
-    MissingHeader(&'static str),
+    #[error("missing header field {field}, header ended on line {line}: {text:?}")]
+    MissingHeader {
+        field: &'static str,
+        line: usize,
+        text: String,
+    },
+
+    #[error("invalid header value for {field} on line {line}: {text:?}")]
+    InvalidHeader {
+        field: &'static str,
+        value: String,
+        line: usize,
+        text: String,
+    },
+
+    #[error("dimension mismatch: expected {expected} rows, got {got} by line {line}")]
+    DimensionMismatch {
+        expected: u32,
+        got: u32,
+        line: usize,
+        text: String,
+    },
 
-    #[error("invalid header value for {field}: {value}")]
-    InvalidHeader { field: &'static str, value: String },
+    #[error("row {row} width mismatch on line {line}: expected {expected}, got {got}")]
+    RowWidthMismatch {
+        row: u32,
+        expected: u32,
+        got: u32,
+        line: usize,
+        text: String,
+    },
 
-    #[error("dimension mismatch: expected {expected} rows, got {got}")]
-    DimensionMismatch { expected: u32, got: u32 },
+    #[error("failed to read map at line {line}: {source}")]
+    Io { line: usize, source: std::io::Error },
+}
 
-    #[error("row {row} width mismatch: expected {expected}, got {got}")]
-    RowWidthMismatch { row: u32, expected: u32, got: u32 },
+/// A rectangle of cells, e.g. the area kept by [`GridMap::crop`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    /// Left column.
+    pub x: u32,
+    /// Top row.
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
 }
 
 /// A parsed grid map from the MovingAI format.
+///
+/// Maps without terrain classes are stored one bit per cell.
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(from = "GridMapData", into = "GridMapData")
+)]
 pub struct GridMap {
+    /// Declared map type from the `type` header (e.g. `octile`).
+    map_type: String,
     width: u32,
     height: u32,
     /// Row-major; index = y * width + x
+    tiles: Cells,
+    /// Per-cell entry costs for weighted maps, same layout as `tiles`
+    costs: Option<Vec<u32>>,
+}
+
+/// Serialized form of [`GridMap`], one tile per cell whatever the storage.
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct GridMapData {
+    map_type: String,
+    width: u32,
+    height: u32,
     tiles: Vec<Tile>,
+    #[serde(default)]
+    costs: Option<Vec<u32>>,
+}
+
+#[cfg(feature = "serde")]
+impl From<GridMapData> for GridMap {
+    fn from(data: GridMapData) -> Self {
+        Self {
+            map_type: data.map_type,
+            width: data.width,
+            height: data.height,
+            tiles: Cells::from_tiles(data.tiles),
+            costs: data.costs,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<GridMap> for GridMapData {
+    fn from(map: GridMap) -> Self {
+        Self {
+            tiles: map.tiles.iter().collect(),
+            map_type: map.map_type,
+            width: map.width,
+            height: map.height,
+            costs: map.costs,
+        }
+    }
 }
 
 impl GridMap {
@@ -48,44 +181,79 @@ impl GridMap {
     /// <N lines of M characters>
     /// ```
     pub fn parse(input: &str) -> Result<Self, MapError> {
-        let mut lines = input.lines();
+        Self::parse_lines(input.lines().map(Ok), false)
+    }
+
+    /// Parse a MovingAI `.map` file, tolerating the quirks of downloaded
+    /// benchmark files: header keys in any case or separated by tabs, blank
+    /// lines, trailing whitespace, and rows padded past the declared width
+    /// (the extra characters are dropped). Short rows and missing rows or
+    /// headers are still errors.
+    pub fn parse_lenient(input: &str) -> Result<Self, MapError> {
+        Self::parse_lines(input.lines().map(Ok), true)
+    }
+
+    /// Parse a MovingAI `.map` file line by line from `reader`, without
+    /// reading it all into memory first. Same format as [`GridMap::parse`].
+    pub fn parse_from_reader(reader: impl BufRead) -> Result<Self, MapError> {
+        Self::parse_lines(reader.lines(), false)
+    }
+
+    fn parse_lines<S: AsRef<str>>(
+        lines: impl Iterator<Item = std::io::Result<S>>,
+        lenient: bool,
+    ) -> Result<Self, MapError> {
+        // 1-based line numbers, and the last line read for errors
+        let mut lines = lines.enumerate().map(|(i, line)| match line {
+            Ok(text) => Ok((i + 1, text)),
+            Err(source) => Err(MapError::Io {
+                line: i + 1,
+                source,
+            }),
+        });
+        let mut last = (0, String::new());
 
         // Parse header
-        let mut map_type: Option<&str> = None;
+        let mut map_type: Option<String> = None;
         let mut height: Option<u32> = None;
         let mut width: Option<u32> = None;
 
         for line in lines.by_ref() {
-            let line = line.trim();
+            let (line_no, text) = line?;
+            last = (line_no, text.as_ref().to_string());
+            let normalized;
+            let line = if lenient {
+                normalized = normalize_header(text.as_ref());
+                normalized.as_str()
+            } else {
+                text.as_ref().trim()
+            };
             if line.eq_ignore_ascii_case("map") {
                 break;
             }
+            let invalid = |field, value: &str| MapError::InvalidHeader {
+                field,
+                value: value.to_string(),
+                line: line_no,
+                text: text.as_ref().to_string(),
+            };
             if let Some(rest) = line.strip_prefix("type ") {
-                map_type = Some(rest.trim());
+                map_type = Some(rest.trim().to_string());
             } else if let Some(rest) = line.strip_prefix("height ") {
-                height = Some(
-                    rest.trim()
-                        .parse()
-                        .map_err(|_| MapError::InvalidHeader {
-                            field: "height",
-                            value: rest.to_string(),
-                        })?,
-                );
+                height = Some(rest.trim().parse().map_err(|_| invalid("height", rest))?);
             } else if let Some(rest) = line.strip_prefix("width ") {
-                width = Some(
-                    rest.trim()
-                        .parse()
-                        .map_err(|_| MapError::InvalidHeader {
-                            field: "width",
-                            value: rest.to_string(),
-                        })?,
-                );
+                width = Some(rest.trim().parse().map_err(|_| invalid("width", rest))?);
             }
         }
 
-        let _map_type = map_type.ok_or(MapError::MissingHeader("type"))?;
-        let height = height.ok_or(MapError::MissingHeader("height"))?;
-        let width = width.ok_or(MapError::MissingHeader("width"))?;
+        let missing = |field| MapError::MissingHeader {
+            field,
+            line: last.0,
+            text: last.1.clone(),
+        };
+        let map_type = map_type.ok_or_else(|| missing("type"))?;
+        let height = height.ok_or_else(|| missing("height"))?;
+        let width = width.ok_or_else(|| missing("width"))?;
 
         // Parse grid rows
         let mut tiles = Vec::with_capacity((width * height) as usize);
@@ -95,38 +263,86 @@ impl GridMap {
             if row_count >= height {
                 break; // ignore trailing lines
             }
-            let chars: Vec<char> = line.chars().collect();
+            let (line_no, text) = line?;
+            let chars: Vec<char> = if lenient {
+                let row = text.as_ref().trim_end();
+                if row.is_empty() {
+                    continue;
+                }
+                row.chars().take(width as usize).collect()
+            } else {
+                text.as_ref().chars().collect()
+            };
             if chars.len() != width as usize {
                 return Err(MapError::RowWidthMismatch {
                     row: row_count,
                     expected: width,
                     got: chars.len() as u32,
+                    line: line_no,
+                    text: text.as_ref().to_string(),
                 });
             }
             for ch in chars {
                 tiles.push(Self::char_to_tile(ch));
             }
             row_count += 1;
+            last = (line_no, text.as_ref().to_string());
         }
 
         if row_count < height {
             return Err(MapError::DimensionMismatch {
                 expected: height,
                 got: row_count,
+                line: last.0,
+                text: last.1,
             });
         }
 
-        Ok(Self { width, height, tiles })
+        Ok(Self {
+            map_type,
+            width,
+            height,
+            tiles: Cells::from_tiles(tiles),
+            costs: None,
+        })
+    }
+
+    /// Write the map in MovingAI `.map` format, blocked cells as `@`.
+    ///
+    /// Terrain classes are kept; cell costs aren't part of the format and are
+    /// dropped.
+    pub fn to_movingai(&self) -> String {
+        format!(
+            "type {}\nheight {}\nwidth {}\nmap\n{}",
+            self.map_type,
+            self.height,
+            self.width,
+            self.to_ascii()
+        )
     }
 
     fn char_to_tile(ch: char) -> Tile {
         match ch {
-            '.' | 'G' | 'S' => Tile::Passable,
-            // Everything else is blocked (@ T O W etc.)
+            '.' | 'G' => Tile::Passable,
+            'S' => Tile::Swamp,
+            'W' => Tile::Water,
+            'T' => Tile::Trees,
+            // Everything else is blocked (@ O etc.)
             _ => Tile::Blocked,
         }
     }
 
+    /// Declared map type (`octile` for all MovingAI benchmarks).
+    pub fn map_type(&self) -> &str {
+        &self.map_type
+    }
+
+    /// Override the declared map type.
+    pub fn with_map_type(mut self, map_type: impl Into<String>) -> Self {
+        self.map_type = map_type.into();
+        self
+    }
+
     /// Width of the map (x dimension).
     pub fn width(&self) -> u32 {
         self.width
@@ -140,7 +356,7 @@ impl GridMap {
     /// Get tile at (x, y). Returns `None` if out of bounds.
     pub fn get(&self, x: u32, y: u32) -> Option<Tile> {
         if x < self.width && y < self.height {
-            Some(self.tiles[(y * self.width + x) as usize])
+            Some(self.tiles.get((y * self.width + x) as usize))
         } else {
             None
         }
@@ -148,29 +364,306 @@ impl GridMap {
 
     /// Check if cell (x, y) is passable (returns false for out-of-bounds).
     pub fn is_passable(&self, x: u32, y: u32) -> bool {
-        self.get(x, y) == Some(Tile::Passable)
+        x < self.width && y < self.height && self.tiles.is_passable((y * self.width + x) as usize)
+    }
+
+    /// Whether tiles are stored one bit per cell, which holds for maps
+    /// without swamp, water or trees.
+    pub fn is_compact(&self) -> bool {
+        self.tiles.is_compact()
+    }
+
+    /// Terrain cost of entering (x, y), or `None` if it can't be entered or
+    /// is out of bounds. See [`Tile::traversal_cost`].
+    pub fn tile_cost(&self, x: u32, y: u32) -> Option<u32> {
+        self.get(x, y).and_then(Tile::traversal_cost)
     }
 
     /// Convert to a flat byte array (row-major). 1 = passable, 0 = blocked.
     pub fn to_bytes(&self) -> Vec<u8> {
-        self.tiles
-            .iter()
-            .map(|t| match t {
-                Tile::Passable => 1,
-                Tile::Blocked => 0,
-            })
+        (0..self.tiles.len())
+            .map(|i| self.tiles.is_passable(i) as u8)
             .collect()
     }
 
     /// Create from raw bytes (row-major, 1 = passable, 0 = blocked).
+    /// The map type defaults to `octile`.
     pub fn from_bytes(width: u32, height: u32, data: &[u8]) -> Option<Self> {
         if data.len() != (width * height) as usize {
             return None;
         }
-        let tiles = data
+        Some(Self {
+            map_type: "octile".to_string(),
+            width,
+            height,
+            tiles: Cells::from_passable(data.len(), |i| data[i] != 0),
+            costs: None,
+        })
+    }
+
+    /// Parse just the grid rows of a MovingAI map, without the header, e.g.
+    /// for readable test fixtures:
+    ///
+    /// ```
+    /// use mapf_core::GridMap;
+    ///
+    /// let map = GridMap::from_ascii(
+    ///     "
+    ///     ...
+    ///     .@.
+    ///     ",
+    /// )
+    /// .unwrap();
+    /// assert_eq!((map.width(), map.height()), (3, 2));
+    /// assert!(!map.is_passable(1, 1));
+    /// ```
+    ///
+    /// Rows are trimmed and blank lines skipped, so indented raw strings
+    /// work. The map type is `octile`. Fails if the rows differ in width.
+    pub fn from_ascii(grid: &str) -> Result<Self, MapError> {
+        let mut width = None;
+        let mut tiles = Vec::new();
+        let mut height: u32 = 0;
+        for (i, line) in grid.lines().enumerate() {
+            let row = line.trim();
+            if row.is_empty() {
+                continue;
+            }
+            let chars = row.chars().count() as u32;
+            let expected = *width.get_or_insert(chars);
+            if chars != expected {
+                return Err(MapError::RowWidthMismatch {
+                    row: height,
+                    expected,
+                    got: chars,
+                    line: i + 1,
+                    text: line.to_string(),
+                });
+            }
+            tiles.extend(row.chars().map(Self::char_to_tile));
+            height += 1;
+        }
+        Ok(Self {
+            map_type: "octile".to_string(),
+            width: width.unwrap_or(0),
+            height,
+            tiles: Cells::from_tiles(tiles),
+            costs: None,
+        })
+    }
+
+    /// The grid rows in MovingAI characters, one line each, without the
+    /// header; the inverse of [`GridMap::from_ascii`].
+    pub fn to_ascii(&self) -> String {
+        let mut out = String::new();
+        let tiles: Vec<Tile> = self.tiles.iter().collect();
+        for row in tiles.chunks(self.width.max(1) as usize) {
+            out.extend(row.iter().map(|tile| tile.to_char()));
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Attach per-cell entry costs (row-major, same size as the map).
+    /// Returns `None` if the length doesn't match.
+    pub fn with_costs(mut self, costs: Vec<u32>) -> Option<Self> {
+        if costs.len() != self.tiles.len() {
+            return None;
+        }
+        self.costs = Some(costs);
+        Some(self)
+    }
+
+    /// Per-cell entry costs, or `None` on an unweighted map.
+    pub fn costs(&self) -> Option<&[u32]> {
+        self.costs.as_deref()
+    }
+
+    /// Whether the map carries a cost channel.
+    pub fn is_weighted(&self) -> bool {
+        self.costs.is_some()
+    }
+
+    /// Cost of an action ending on (x, y): the cell's entry cost, or 1 on
+    /// unweighted maps and out of bounds.
+    pub fn cell_cost(&self, x: u32, y: u32) -> u32 {
+        match &self.costs {
+            Some(costs) if x < self.width && y < self.height => {
+                costs[(y * self.width + x) as usize]
+            }
+            _ => 1,
+        }
+    }
+
+    /// Weighted cost of a path: every action (move or wait) costs the entry
+    /// cost of the cell it ends on. Equals `path.cost()` on unweighted maps.
+    pub fn path_cost(&self, path: &Path) -> u64 {
+        path.steps
             .iter()
-            .map(|&b| if b != 0 { Tile::Passable } else { Tile::Blocked })
+            .skip(1)
+            .map(|c| self.cell_cost(c.x, c.y) as u64)
+            .sum()
+    }
+
+    /// The cells inside `rect`, or `None` if it is empty or sticks out of
+    /// the map.
+    pub fn crop(&self, rect: Rect) -> Option<Self> {
+        let fits = |start: u32, len: u32, size: u32| {
+            len > 0 && start.checked_add(len).is_some_and(|end| end <= size)
+        };
+        if !fits(rect.x, rect.width, self.width) || !fits(rect.y, rect.height, self.height) {
+            return None;
+        }
+        Some(self.remap(rect.width, rect.height, |x, y| (rect.x + x, rect.y + y)))
+    }
+
+    /// The map turned a quarter clockwise: the left column becomes the top
+    /// row.
+    pub fn rotate90(&self) -> Self {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    /// The map mirrored left to right.
+    pub fn mirror_horizontal(&self) -> Self {
+        let width = self.width;
+        self.remap(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// The map mirrored top to bottom.
+    pub fn mirror_vertical(&self) -> Self {
+        let height = self.height;
+        self.remap(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+
+    /// The map with every cell blown up into a `factor` x `factor` block,
+    /// or `None` if `factor` is 0 or the result is too large.
+    pub fn scale(&self, factor: u32) -> Option<Self> {
+        let width = self.width.checked_mul(factor)?;
+        let height = self.height.checked_mul(factor)?;
+        if factor == 0 || width.checked_mul(height).is_none() {
+            return None;
+        }
+        Some(self.remap(width, height, |x, y| (x / factor, y / factor)))
+    }
+
+    /// The map inside a one-cell ring of blocked cells, so every cell
+    /// moves one step right and down. Border cells cost 1 on weighted maps.
+    pub fn with_border_walls(&self) -> Self {
+        let (width, height) = (self.width + 2, self.height + 2);
+        let inner = |x: u32, y: u32| {
+            (x > 0 && y > 0 && x <= self.width && y <= self.height)
+                .then(|| ((y - 1) * self.width + x - 1) as usize)
+        };
+        let cells = || (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)));
+        Self {
+            map_type: self.map_type.clone(),
+            width,
+            height,
+            tiles: Cells::from_tiles(
+                cells()
+                    .map(|(x, y)| inner(x, y).map_or(Tile::Blocked, |i| self.tiles.get(i)))
+                    .collect(),
+            ),
+            costs: self.costs.as_ref().map(|costs| {
+                cells()
+                    .map(|(x, y)| inner(x, y).map_or(1, |i| costs[i]))
+                    .collect()
+            }),
+        }
+    }
+
+    /// The map with every passable cell that can't be reached from `seed`
+    /// by 4-connected moves blocked, so stray pockets can't hold starts or
+    /// goals. `None` if `seed` is blocked or out of bounds.
+    pub fn fill_unreachable_from(&self, seed: Coordinate) -> Option<Self> {
+        if !self.is_passable(seed.x, seed.y) {
+            return None;
+        }
+        let distances = self.distance_map(seed);
+        Some(self.block_where(|x, y| !distances.is_reachable(x, y)))
+    }
+
+    /// The map with every passable cell that has no passable 4-neighbor
+    /// blocked.
+    pub fn remove_isolated_cells(&self) -> Self {
+        self.block_where(|x, y| {
+            ![
+                (y > 0).then(|| (x, y - 1)),
+                Some((x + 1, y)),
+                Some((x, y + 1)),
+                (x > 0).then(|| (x - 1, y)),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|(nx, ny)| self.is_passable(nx, ny))
+        })
+    }
+
+    /// The map with cell (x, y) made [`Tile::Blocked`] wherever `blocked`
+    /// holds; costs are kept.
+    fn block_where(&self, blocked: impl Fn(u32, u32) -> bool) -> Self {
+        let tiles = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let tile = self.tiles.get((y * self.width + x) as usize);
+                if tile.is_passable() && blocked(x, y) {
+                    Tile::Blocked
+                } else {
+                    tile
+                }
+            })
             .collect();
-        Some(Self { width, height, tiles })
+        Self {
+            map_type: self.map_type.clone(),
+            width: self.width,
+            height: self.height,
+            tiles: Cells::from_tiles(tiles),
+            costs: self.costs.clone(),
+        }
+    }
+
+    /// An octile map with `tiles` in row-major order; the caller checks the
+    /// length.
+    #[cfg(any(feature = "png", feature = "proptest"))]
+    pub(crate) fn from_tiles(width: u32, height: u32, tiles: Vec<Tile>) -> Self {
+        debug_assert_eq!(tiles.len(), (width as usize) * (height as usize));
+        Self {
+            map_type: "octile".to_string(),
+            width,
+            height,
+            tiles: Cells::from_tiles(tiles),
+            costs: None,
+        }
+    }
+
+    /// A `width` x `height` map whose cell (x, y) copies the tile and cost of
+    /// this map's cell `source(x, y)`.
+    fn remap(&self, width: u32, height: u32, source: impl Fn(u32, u32) -> (u32, u32)) -> Self {
+        let index = |x: u32, y: u32| {
+            let (sx, sy) = source(x, y);
+            (sy * self.width + sx) as usize
+        };
+        let cells = || (0..height).flat_map(move |y| (0..width).map(move |x| (x, y)));
+        Self {
+            map_type: self.map_type.clone(),
+            width,
+            height,
+            tiles: Cells::from_tiles(cells().map(|(x, y)| self.tiles.get(index(x, y))).collect()),
+            costs: self
+                .costs
+                .as_ref()
+                .map(|costs| cells().map(|(x, y)| costs[index(x, y)]).collect()),
+        }
+    }
+}
+
+/// A header line with its key lowercased and a single space before the
+/// value, e.g. `Height\t8` -> `height 8`.
+pub(crate) fn normalize_header(line: &str) -> String {
+    let line = line.trim();
+    match line.split_once(char::is_whitespace) {
+        Some((key, value)) => format!("{} {}", key.to_ascii_lowercase(), value.trim()),
+        None => line.to_string(),
     }
 }
@@ -0,0 +1,147 @@
+//! Dynamic obstacles: cells blocked for an interval of timesteps.
+//!
+//! Stored in map and scenario files as an extension section after the grid
+//! or the last entry, which MovingAI tools ignore:
+//!
+//! ```text
+//! obstacles
+//! x y from until
+//! x y from -
+//! ```
+//!
+//! Each line blocks cell (x, y) from timestep `from` until (excluding)
+//! `until`; `-` means the obstacle never disappears.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Header line opening the obstacle section.
+pub(crate) const SECTION_HEADER: &str = "obstacles";
+
+/// Errors from parsing an obstacle section.
+#[derive(Debug, Error)]
+pub enum ObstacleError {
+    #[error("malformed obstacle at line {line}: {reason}")]
+    Malformed { line: usize, reason: String },
+}
+
+/// An obstacle blocking cell (x, y) from timestep `from` until (excluding)
+/// `until`; `None` means it never disappears. Same shape as the API's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TimedObstacle {
+    pub x: u32,
+    pub y: u32,
+    pub from: usize,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub until: Option<usize>,
+}
+
+impl TimedObstacle {
+    /// Whether the obstacle is present at timestep `t`.
+    pub fn is_active_at(&self, t: usize) -> bool {
+        t >= self.from && self.until.is_none_or(|until| t < until)
+    }
+}
+
+/// A schedule of timed obstacles, indexed by cell for quick lookups.
+#[derive(Debug, Clone, Default)]
+pub struct DynamicObstacles {
+    obstacles: Vec<TimedObstacle>,
+    /// Indices into `obstacles` per cell
+    by_cell: HashMap<(u32, u32), Vec<usize>>,
+}
+
+impl DynamicObstacles {
+    pub fn new(obstacles: Vec<TimedObstacle>) -> Self {
+        let mut by_cell: HashMap<(u32, u32), Vec<usize>> = HashMap::new();
+        for (i, o) in obstacles.iter().enumerate() {
+            by_cell.entry((o.x, o.y)).or_default().push(i);
+        }
+        Self { obstacles, by_cell }
+    }
+
+    /// Read the obstacle section of a map or scenario file's content; a file
+    /// without one has no obstacles.
+    pub fn parse(input: &str) -> Result<Self, ObstacleError> {
+        let mut lines = input.lines().enumerate();
+        if !lines.any(|(_, line)| line.trim().eq_ignore_ascii_case(SECTION_HEADER)) {
+            return Ok(Self::default());
+        }
+
+        let mut obstacles = Vec::new();
+        for (line_no, line) in lines {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.is_empty() {
+                continue;
+            }
+            let malformed = |reason: String| ObstacleError::Malformed {
+                line: line_no + 1,
+                reason,
+            };
+            if parts.len() != 4 {
+                let reason = format!("expected 4 columns, got {}", parts.len());
+                return Err(malformed(reason));
+            }
+            let invalid =
+                |idx: usize, name: &str| malformed(format!("invalid {}: {}", name, parts[idx]));
+            let cell =
+                |idx: usize, name: &str| parts[idx].parse::<u32>().map_err(|_| invalid(idx, name));
+            let step = |idx: usize, name: &str| {
+                parts[idx].parse::<usize>().map_err(|_| invalid(idx, name))
+            };
+            let obstacle = TimedObstacle {
+                x: cell(0, "x")?,
+                y: cell(1, "y")?,
+                from: step(2, "from")?,
+                until: match parts[3] {
+                    "-" => None,
+                    _ => Some(step(3, "until")?),
+                },
+            };
+            if obstacle.until.is_some_and(|until| until <= obstacle.from) {
+                return Err(malformed("until must be after from".to_string()));
+            }
+            obstacles.push(obstacle);
+        }
+        Ok(Self::new(obstacles))
+    }
+
+    /// The obstacle section, header included, to append to a map or
+    /// scenario file.
+    pub fn to_section(&self) -> String {
+        let mut out = format!("{SECTION_HEADER}\n");
+        for o in &self.obstacles {
+            let until = o.until.map_or("-".to_string(), |t| t.to_string());
+            out.push_str(&format!("{} {} {} {}\n", o.x, o.y, o.from, until));
+        }
+        out
+    }
+
+    /// All obstacles, in the order given.
+    pub fn obstacles(&self) -> &[TimedObstacle] {
+        &self.obstacles
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.obstacles.is_empty()
+    }
+
+    /// Whether no obstacle blocks (x, y) at timestep `t`. The static map
+    /// is checked separately, with [`GridMap::is_passable`](crate::GridMap::is_passable).
+    pub fn is_passable_at(&self, x: u32, y: u32, t: usize) -> bool {
+        self.at(x, y).all(|o| !o.is_active_at(t))
+    }
+
+    /// Obstacles on cell (x, y).
+    pub fn at(&self, x: u32, y: u32) -> impl Iterator<Item = &TimedObstacle> + '_ {
+        self.by_cell
+            .get(&(x, y))
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.obstacles[i])
+    }
+}
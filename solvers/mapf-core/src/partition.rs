@@ -0,0 +1,222 @@
+//! Decomposition of a map's free space into contiguous regions.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, VecDeque};
+
+use crate::map::GridMap;
+use crate::solution::Coordinate;
+
+/// Label of blocked cells, which belong to no region.
+const NONE: u32 = u32::MAX;
+
+/// Free cells split into contiguous regions of similar size, with the
+/// regions that border each other, as computed by [`GridMap::partition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Partition {
+    width: u32,
+    height: u32,
+    /// Row-major region of each cell; `NONE` where blocked
+    labels: Vec<u32>,
+    sizes: Vec<usize>,
+    /// Sorted neighbors of each region
+    adjacency: Vec<Vec<u32>>,
+}
+
+impl Partition {
+    /// Number of regions.
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// Region of cell (x, y), or `None` if it is blocked or out of bounds.
+    pub fn region(&self, x: u32, y: u32) -> Option<u32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let label = self.labels[(y * self.width + x) as usize];
+        (label != NONE).then_some(label)
+    }
+
+    /// Number of cells in `region`.
+    pub fn size(&self, region: u32) -> usize {
+        self.sizes[region as usize]
+    }
+
+    /// Cells of `region`, row by row.
+    pub fn cells(&self, region: u32) -> impl Iterator<Item = Coordinate> + '_ {
+        let width = self.width;
+        self.labels
+            .iter()
+            .enumerate()
+            .filter(move |&(_, &label)| label == region)
+            .map(move |(i, _)| Coordinate {
+                x: i as u32 % width,
+                y: i as u32 / width,
+            })
+    }
+
+    /// Regions sharing an edge with `region`, ascending.
+    pub fn neighbors(&self, region: u32) -> &[u32] {
+        &self.adjacency[region as usize]
+    }
+}
+
+impl GridMap {
+    /// Split the passable cells into about `regions` contiguous regions of
+    /// similar size, for hierarchical search or for sharding agents.
+    ///
+    /// Each connected area gets regions in proportion to its size, and at
+    /// least one, so there may be more regions than asked for on maps with
+    /// many separate areas, and fewer when there are fewer free cells.
+    /// Regions are seeded far apart and grown one cell at a time, smallest
+    /// first, using 4-connected moves. The result is deterministic.
+    pub fn partition(&self, regions: u32) -> Partition {
+        let (width, height) = (self.width(), self.height());
+        let index = |c: Coordinate| (c.y * width + c.x) as usize;
+        let neighbors = |c: Coordinate| {
+            [
+                (c.y > 0).then(|| (c.x, c.y - 1)),
+                (c.x + 1 < width).then(|| (c.x + 1, c.y)),
+                (c.y + 1 < height).then(|| (c.x, c.y + 1)),
+                (c.x > 0).then(|| (c.x - 1, c.y)),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|(x, y)| Coordinate { x, y })
+            .filter(|c| self.is_passable(c.x, c.y))
+        };
+
+        // Breadth-first distances from `sources` within their area, in visit order
+        let flood = |sources: &[Coordinate]| {
+            let mut distances = vec![u32::MAX; (width as usize) * (height as usize)];
+            let mut order = Vec::new();
+            let mut queue: VecDeque<Coordinate> = sources.iter().copied().collect();
+            for &s in sources {
+                distances[index(s)] = 0;
+            }
+            while let Some(cell) = queue.pop_front() {
+                order.push(cell);
+                for next in neighbors(cell) {
+                    if distances[index(next)] == u32::MAX {
+                        distances[index(next)] = distances[index(cell)] + 1;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            (distances, order)
+        };
+
+        // Connected areas, each as its cells in visit order
+        let mut seen = vec![false; (width as usize) * (height as usize)];
+        let mut areas: Vec<Vec<Coordinate>> = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let cell = Coordinate { x, y };
+                if self.is_passable(x, y) && !seen[index(cell)] {
+                    let (_, area) = flood(&[cell]);
+                    for &c in &area {
+                        seen[index(c)] = true;
+                    }
+                    areas.push(area);
+                }
+            }
+        }
+
+        // One region per area, then more for whichever has the largest regions
+        let mut counts = vec![1; areas.len()];
+        let mut total = areas.len();
+        while total < regions as usize {
+            let Some(area) = (0..areas.len())
+                .filter(|&a| counts[a] < areas[a].len())
+                .max_by(|&a, &b| {
+                    (areas[a].len() * counts[b])
+                        .cmp(&(areas[b].len() * counts[a]))
+                        .then(b.cmp(&a))
+                })
+            else {
+                break;
+            };
+            counts[area] += 1;
+            total += 1;
+        }
+
+        // Seeds farthest-first: each is the cell furthest from those so far
+        let mut seeds = Vec::with_capacity(total);
+        for (area, &count) in areas.iter().zip(&counts) {
+            // Areas are in visit order, so the last cell is furthest from the first
+            let mut chosen = vec![*area.last().expect("area has cells")];
+            while chosen.len() < count {
+                let (distances, _) = flood(&chosen);
+                let next = area
+                    .iter()
+                    .copied()
+                    .max_by_key(|&c| (distances[index(c)], Reverse((c.y, c.x))))
+                    .expect("area has cells");
+                chosen.push(next);
+            }
+            seeds.extend(chosen);
+        }
+
+        // Grow all regions together, the smallest claiming the next cell
+        let mut labels = vec![NONE; (width as usize) * (height as usize)];
+        let mut sizes = vec![1; seeds.len()];
+        let mut frontiers: Vec<VecDeque<Coordinate>> = Vec::with_capacity(seeds.len());
+        let mut heap = BinaryHeap::new();
+        for (region, &seed) in seeds.iter().enumerate() {
+            labels[index(seed)] = region as u32;
+            frontiers.push(VecDeque::from([seed]));
+            heap.push(Reverse((1, region)));
+        }
+        while let Some(Reverse((_, region))) = heap.pop() {
+            let frontier = &mut frontiers[region];
+            while let Some(&cell) = frontier.front() {
+                match neighbors(cell).find(|&c| labels[index(c)] == NONE) {
+                    Some(next) => {
+                        labels[index(next)] = region as u32;
+                        frontier.push_back(next);
+                        sizes[region] += 1;
+                        heap.push(Reverse((sizes[region], region)));
+                        break;
+                    }
+                    None => {
+                        frontier.pop_front();
+                    }
+                }
+            }
+        }
+
+        let mut adjacency = vec![BTreeSet::new(); seeds.len()];
+        for y in 0..height {
+            for x in 0..width {
+                let a = labels[(y * width + x) as usize];
+                if a == NONE {
+                    continue;
+                }
+                for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                    if nx < width && ny < height {
+                        let b = labels[(ny * width + nx) as usize];
+                        if b != NONE && b != a {
+                            adjacency[a as usize].insert(b);
+                            adjacency[b as usize].insert(a);
+                        }
+                    }
+                }
+            }
+        }
+
+        Partition {
+            width,
+            height,
+            labels,
+            sizes,
+            adjacency: adjacency
+                .into_iter()
+                .map(|set| set.into_iter().collect())
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! PNG images of grid maps (`png` feature), one pixel per cell.
+//!
+//! Plain images are 8-bit grayscale, white for passable and black for
+//! blocked. A [`Palette`] gives terrain its own colors instead. Any PNG is
+//! read: transparent pixels are composited over white first.
+
+use std::io::Cursor;
+
+use ::png::{BitDepth, ColorType, Decoder, Encoder, Transformations};
+use thiserror::Error;
+
+use crate::map::{GridMap, Tile};
+
+/// Largest image read, well above any benchmark map.
+const MAX_CELLS: usize = 1 << 28;
+
+/// Luminance (0-255) below which a pixel is blocked in a plain image.
+const THRESHOLD: u8 = 128;
+
+/// Errors from reading or writing a map image.
+#[derive(Debug, Error)]
+pub enum PngError {
+    #[error("invalid PNG: {0}")]
+    Decode(#[from] ::png::DecodingError),
+
+    #[error("failed to encode PNG: {0}")]
+    Encode(#[from] ::png::EncodingError),
+
+    #[error("image of {width}x{height} pixels is too large")]
+    TooLarge { width: u32, height: u32 },
+}
+
+/// RGB color of each tile in a map image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub passable: [u8; 3],
+    pub swamp: [u8; 3],
+    pub water: [u8; 3],
+    pub trees: [u8; 3],
+    pub blocked: [u8; 3],
+}
+
+impl Default for Palette {
+    /// White ground, black obstacles, olive swamp, blue water and green trees.
+    fn default() -> Self {
+        Self {
+            passable: [255, 255, 255],
+            swamp: [128, 128, 0],
+            water: [30, 144, 255],
+            trees: [34, 139, 34],
+            blocked: [0, 0, 0],
+        }
+    }
+}
+
+impl Palette {
+    /// Color of `tile`.
+    pub fn color(&self, tile: Tile) -> [u8; 3] {
+        match tile {
+            Tile::Passable => self.passable,
+            Tile::Swamp => self.swamp,
+            Tile::Water => self.water,
+            Tile::Trees => self.trees,
+            Tile::Blocked => self.blocked,
+        }
+    }
+
+    /// Tile whose color is closest to `rgb`; ties go to the earlier tile
+    /// in passable, swamp, water, trees, blocked order.
+    pub fn nearest(&self, rgb: [u8; 3]) -> Tile {
+        let distance = |tile| {
+            let color = self.color(tile);
+            (0..3)
+                .map(|i| (color[i] as i32 - rgb[i] as i32).pow(2))
+                .sum::<i32>()
+        };
+        [
+            Tile::Passable,
+            Tile::Swamp,
+            Tile::Water,
+            Tile::Trees,
+            Tile::Blocked,
+        ]
+        .into_iter()
+        .min_by_key(|&tile| distance(tile))
+        .expect("palette has tiles")
+    }
+}
+
+impl GridMap {
+    /// Black-and-white image of the map: white where passable (including
+    /// swamp), black elsewhere.
+    pub fn to_png(&self) -> Result<Vec<u8>, PngError> {
+        let pixels: Vec<u8> = self.to_bytes().iter().map(|&tile| tile * 255).collect();
+        encode(self, ColorType::Grayscale, &pixels)
+    }
+
+    /// Color image of the map, each tile drawn in its `palette` color.
+    pub fn to_png_with_palette(&self, palette: &Palette) -> Result<Vec<u8>, PngError> {
+        let pixels: Vec<u8> = (0..self.height())
+            .flat_map(|y| (0..self.width()).map(move |x| (x, y)))
+            .flat_map(|(x, y)| palette.color(self.get(x, y).expect("in bounds")))
+            .collect();
+        encode(self, ColorType::Rgb, &pixels)
+    }
+
+    /// Read a black-and-white image: pixels darker than mid-gray are
+    /// blocked, the rest passable.
+    pub fn from_png(data: &[u8]) -> Result<Self, PngError> {
+        decode(data, |rgb| {
+            if luma(rgb) >= THRESHOLD {
+                Tile::Passable
+            } else {
+                Tile::Blocked
+            }
+        })
+    }
+
+    /// Read a color image, each pixel becoming the tile with the nearest
+    /// `palette` color, so maps drawn with other tools' shades still load.
+    pub fn from_png_with_palette(data: &[u8], palette: &Palette) -> Result<Self, PngError> {
+        decode(data, |rgb| palette.nearest(rgb))
+    }
+}
+
+fn encode(map: &GridMap, color: ColorType, pixels: &[u8]) -> Result<Vec<u8>, PngError> {
+    let mut out = Vec::new();
+    let mut encoder = Encoder::new(&mut out, map.width(), map.height());
+    encoder.set_color(color);
+    encoder.set_depth(BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()?;
+    Ok(out)
+}
+
+/// Decode an image, turning each pixel (as RGB over white) into a tile.
+fn decode(data: &[u8], tile: impl Fn([u8; 3]) -> Tile) -> Result<GridMap, PngError> {
+    let mut decoder = Decoder::new(Cursor::new(data));
+    decoder.set_transformations(Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let (width, height) = (reader.info().width, reader.info().height);
+    if (width as usize).saturating_mul(height as usize) > MAX_CELLS {
+        return Err(PngError::TooLarge { width, height });
+    }
+
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer)?;
+    let (color, _) = reader.output_color_type();
+    let samples = color.samples();
+
+    let tiles = buffer[..frame.buffer_size()]
+        .chunks_exact(frame.line_size)
+        .flat_map(|line| line[..width as usize * samples].chunks_exact(samples))
+        .map(|pixel| tile(rgb(pixel, color)))
+        .collect();
+    Ok(GridMap::from_tiles(width, height, tiles))
+}
+
+/// RGB of an 8-bit pixel, composited over white.
+fn rgb(pixel: &[u8], color: ColorType) -> [u8; 3] {
+    let (rgb, alpha) = match color {
+        ColorType::Grayscale => ([pixel[0]; 3], 255),
+        ColorType::GrayscaleAlpha => ([pixel[0]; 3], pixel[1]),
+        ColorType::Rgb => ([pixel[0], pixel[1], pixel[2]], 255),
+        ColorType::Rgba => ([pixel[0], pixel[1], pixel[2]], pixel[3]),
+        // Expanded to RGB(A) by `normalize_to_color8`
+        ColorType::Indexed => ([255; 3], 255),
+    };
+    let alpha = alpha as u32;
+    rgb.map(|c| ((c as u32 * alpha + 255 * (255 - alpha)) / 255) as u8)
+}
+
+/// Rec. 601 luma of an RGB pixel.
+fn luma(rgb: [u8; 3]) -> u8 {
+    ((299 * rgb[0] as u32 + 587 * rgb[1] as u32 + 114 * rgb[2] as u32) / 1000) as u8
+}
@@ -1,7 +1,13 @@
 //! MovingAI `.scen` (scenario) format parser.
 
+use std::collections::HashMap;
+use std::io::BufRead;
+
 use thiserror::Error;
 
+use crate::map::{normalize_header, GridMap};
+use crate::obstacles::SECTION_HEADER;
+
 /// Errors from parsing a MovingAI scenario file.
 #[derive(Debug, Error)]
 pub enum ScenarioError {
@@ -13,6 +19,82 @@ pub enum ScenarioError {
 
     #[error("malformed entry on line {line}: {reason}")]
     MalformedEntry { line: usize, reason: String },
+
+    #[error("failed to read scenario: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Start or goal of a scenario entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endpoint {
+    Start,
+    Goal,
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Endpoint::Start => "start",
+            Endpoint::Goal => "goal",
+        })
+    }
+}
+
+/// Why the first agents of a scenario can't be taken as an instance.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AgentsError {
+    #[error("scenario has {available} agents, {requested} requested")]
+    TooFew { requested: usize, available: usize },
+
+    #[error("agents {first} and {second} share the {endpoint} ({x},{y})")]
+    Collision {
+        first: usize,
+        second: usize,
+        endpoint: Endpoint,
+        x: u32,
+        y: u32,
+    },
+}
+
+/// A scenario entry that doesn't fit the map it is run on; `entry` is its
+/// index in the scenario.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum EntryError {
+    #[error("entry {entry}: declared size {declared_width}x{declared_height} doesn't match the {width}x{height} map")]
+    SizeMismatch {
+        entry: usize,
+        declared_width: u32,
+        declared_height: u32,
+        width: u32,
+        height: u32,
+    },
+
+    #[error("entry {entry}: {endpoint} ({x},{y}) is out of bounds")]
+    OutOfBounds {
+        entry: usize,
+        endpoint: Endpoint,
+        x: u32,
+        y: u32,
+    },
+
+    #[error("entry {entry}: {endpoint} ({x},{y}) is blocked")]
+    Blocked {
+        entry: usize,
+        endpoint: Endpoint,
+        x: u32,
+        y: u32,
+    },
+}
+
+impl EntryError {
+    /// Index of the offending entry.
+    pub fn entry(&self) -> usize {
+        match *self {
+            EntryError::SizeMismatch { entry, .. }
+            | EntryError::OutOfBounds { entry, .. }
+            | EntryError::Blocked { entry, .. } => entry,
+        }
+    }
 }
 
 /// A single entry (agent task) in a scenario file.
@@ -36,8 +118,142 @@ pub struct ScenarioEntry {
     pub goal_y: u32,
     /// Optimal path length (for validation/scoring).
     pub optimal_length: f64,
+    /// Timestep the agent appears at its start, for online and lifelong
+    /// MAPF; 0 for agents present from the beginning. Stored as an
+    /// optional tenth column.
+    pub start_time: usize,
+    /// Name of the agent, e.g. to match it across scenarios or in logs.
+    pub id: Option<String>,
+    /// Group or team the agent belongs to, e.g. for team scoring.
+    pub group: Option<String>,
+    /// Planning priority; higher goes first in prioritized planners.
+    pub priority: Option<u32>,
+}
+
+impl ScenarioEntry {
+    /// An entry on the named map, starting and ending at (0, 0) with bucket
+    /// and optimal length 0; set the rest with the `with_*` methods.
+    ///
+    /// ```
+    /// use mapf_core::ScenarioEntry;
+    ///
+    /// let entry = ScenarioEntry::new("empty-8-8.map", 8, 8)
+    ///     .with_start(0, 0)
+    ///     .with_goal(3, 4)
+    ///     .with_optimal_length(7.0)
+    ///     .with_bucket(1);
+    /// assert_eq!((entry.goal_x, entry.goal_y), (3, 4));
+    /// ```
+    pub fn new(map_name: impl Into<String>, map_width: u32, map_height: u32) -> Self {
+        Self {
+            bucket: 0,
+            map_name: map_name.into(),
+            map_width,
+            map_height,
+            start_x: 0,
+            start_y: 0,
+            goal_x: 0,
+            goal_y: 0,
+            optimal_length: 0.0,
+            start_time: 0,
+            id: None,
+            group: None,
+            priority: None,
+        }
+    }
+
+    /// Set the start cell.
+    pub fn with_start(mut self, x: u32, y: u32) -> Self {
+        self.start_x = x;
+        self.start_y = y;
+        self
+    }
+
+    /// Set the goal cell.
+    pub fn with_goal(mut self, x: u32, y: u32) -> Self {
+        self.goal_x = x;
+        self.goal_y = y;
+        self
+    }
+
+    /// Set the single-agent optimal path length.
+    pub fn with_optimal_length(mut self, optimal_length: f64) -> Self {
+        self.optimal_length = optimal_length;
+        self
+    }
+
+    /// Set the difficulty bucket.
+    pub fn with_bucket(mut self, bucket: u32) -> Self {
+        self.bucket = bucket;
+        self
+    }
+
+    /// Set the timestep the agent appears at.
+    pub fn with_start_time(mut self, start_time: usize) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    /// Set the agent's name. It is written as a column, so it must not
+    /// contain tabs or line breaks.
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set the agent's group or team; same restrictions as
+    /// [`with_id`](Self::with_id).
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Set the agent's planning priority.
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Whether the entry carries an id, group or priority.
+    pub fn is_annotated(&self) -> bool {
+        self.id.is_some() || self.group.is_some() || self.priority.is_some()
+    }
+
+    /// Read one `key=value` annotation column into the entry; the reason
+    /// if it isn't one.
+    fn annotate(&mut self, column: &str) -> Result<(), String> {
+        let Some((key, value)) = column.split_once('=') else {
+            return Err(format!("expected key=value annotation, got {column}"));
+        };
+        match key {
+            "id" => self.id = Some(value.to_string()),
+            "group" => self.group = Some(value.to_string()),
+            "priority" => {
+                let priority = value
+                    .parse()
+                    .map_err(|_| format!("invalid priority: {value}"))?;
+                self.priority = Some(priority);
+            }
+            _ => return Err(format!("unknown annotation: {key}")),
+        }
+        Ok(())
+    }
+
+    /// The entry's annotations as `key=value` columns.
+    fn annotations(&self) -> impl Iterator<Item = String> + '_ {
+        [
+            self.id.as_ref().map(|id| format!("id={id}")),
+            self.group.as_ref().map(|group| format!("group={group}")),
+            self.priority.map(|priority| format!("priority={priority}")),
+        ]
+        .into_iter()
+        .flatten()
+    }
 }
 
+/// A list of (x, y) positions, one per agent.
+type Positions = Vec<(u32, u32)>;
+
 /// A parsed MovingAI scenario file.
 #[derive(Debug, Clone)]
 pub struct Scenario {
@@ -51,25 +267,71 @@ impl Scenario {
     /// Expected format:
     /// ```text
     /// version N
-    /// bucket\tmap\twidth\theight\tstart_x\tstart_y\tgoal_x\tgoal_y\toptimal
+    /// bucket\tmap\twidth\theight\tstart_x\tstart_y\tgoal_x\tgoal_y\toptimal[\tstart_time][\tkey=value...]
     /// ...
     /// ```
+    ///
+    /// The optional tenth column is the agent's
+    /// [`start_time`](ScenarioEntry::start_time); without it agents start
+    /// at timestep 0. Any further columns annotate the agent with its
+    /// `id`, `group` or `priority`, e.g. `id=scout\tgroup=red\tpriority=2`.
+    ///
+    /// Older benchmark files separate the columns with spaces instead of
+    /// tabs; those parse too, as long as the map name has no spaces. An
+    /// `obstacles` section ends the entries; see [`DynamicObstacles`](crate::DynamicObstacles).
     pub fn parse(input: &str) -> Result<Self, ScenarioError> {
-        let mut lines = input.lines().enumerate();
+        Self::parse_lines(input.lines().map(Ok), false)
+    }
+
+    /// Parse a `.scen` file, tolerating the quirks of downloaded benchmark
+    /// files: a `Version` header in any case or written as `1.0`. Entries
+    /// with too few or invalid columns are still errors.
+    pub fn parse_lenient(input: &str) -> Result<Self, ScenarioError> {
+        Self::parse_lines(input.lines().map(Ok), true)
+    }
+
+    /// Parse a `.scen` file line by line from `reader`, without reading it
+    /// all into memory first. Same format as [`Scenario::parse`].
+    pub fn parse_from_reader(reader: impl BufRead) -> Result<Self, ScenarioError> {
+        Self::parse_lines(
+            reader.lines().map(|line| line.map_err(ScenarioError::Io)),
+            false,
+        )
+    }
+
+    fn parse_lines<S: AsRef<str>>(
+        lines: impl Iterator<Item = Result<S, ScenarioError>>,
+        lenient: bool,
+    ) -> Result<Self, ScenarioError> {
+        let mut lines = lines.enumerate();
 
         // First non-empty line should be "version N"
         let version = loop {
             match lines.next() {
                 Some((_, line)) => {
-                    let trimmed = line.trim();
+                    let line = line?;
+                    let normalized;
+                    let trimmed = if lenient {
+                        normalized = normalize_header(line.as_ref());
+                        normalized.as_str()
+                    } else {
+                        line.as_ref().trim()
+                    };
                     if trimmed.is_empty() {
                         continue;
                     }
                     if let Some(rest) = trimmed.strip_prefix("version ") {
+                        let rest = rest.trim();
+                        let lenient_version = || {
+                            let version = rest.parse::<f64>().ok()?;
+                            (version.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&version))
+                                .then_some(version as u32)
+                        };
                         break rest
-                            .trim()
                             .parse::<u32>()
-                            .map_err(|_| ScenarioError::InvalidVersion(rest.to_string()))?;
+                            .ok()
+                            .or_else(|| lenient.then(lenient_version).flatten())
+                            .ok_or_else(|| ScenarioError::InvalidVersion(rest.to_string()))?;
                     } else {
                         return Err(ScenarioError::MissingVersion);
                     }
@@ -81,11 +343,20 @@ impl Scenario {
         let mut entries = Vec::new();
 
         for (line_no, line) in lines {
-            let trimmed = line.trim();
+            let line = line?;
+            let trimmed = line.as_ref().trim();
             if trimmed.is_empty() {
                 continue;
             }
-            let parts: Vec<&str> = trimmed.split('\t').collect();
+            // Extension section, read by `DynamicObstacles::parse`
+            if trimmed.eq_ignore_ascii_case(SECTION_HEADER) {
+                break;
+            }
+            // Tabs first, so map names may contain spaces
+            let mut parts: Vec<&str> = trimmed.split('\t').collect();
+            if parts.len() < 9 {
+                parts = trimmed.split_whitespace().collect();
+            }
             if parts.len() < 9 {
                 return Err(ScenarioError::MalformedEntry {
                     line: line_no + 1,
@@ -107,7 +378,19 @@ impl Scenario {
                 })
             };
 
-            entries.push(ScenarioEntry {
+            let malformed = |reason| ScenarioError::MalformedEntry {
+                line: line_no + 1,
+                reason,
+            };
+            let mut extra = parts[9..].iter().peekable();
+            let start_time = match extra.next_if(|column| !column.contains('=')) {
+                Some(column) => column
+                    .parse()
+                    .map_err(|_| malformed(format!("invalid start_time: {column}")))?,
+                None => 0,
+            };
+
+            let mut entry = ScenarioEntry {
                 bucket: parse_u32(0, "bucket")?,
                 map_name: parts[1].to_string(),
                 map_width: parse_u32(2, "width")?,
@@ -117,12 +400,141 @@ impl Scenario {
                 goal_x: parse_u32(6, "goal_x")?,
                 goal_y: parse_u32(7, "goal_y")?,
                 optimal_length: parse_f64(8, "optimal_length")?,
-            });
+                start_time,
+                id: None,
+                group: None,
+                priority: None,
+            };
+            for column in extra {
+                entry.annotate(column).map_err(malformed)?;
+            }
+            entries.push(entry);
         }
 
         Ok(Self { version, entries })
     }
 
+    /// A scenario with the given entries, e.g. for generated instances.
+    pub fn new(version: u32, entries: Vec<ScenarioEntry>) -> Self {
+        Self { version, entries }
+    }
+
+    /// Write the scenario in MovingAI `.scen` format, optimal lengths with
+    /// eight decimals as in the benchmark files, so other benchmark tooling
+    /// can load it. Start times are written as a tenth column only if some
+    /// agent appears later than timestep 0 or is annotated, and annotations
+    /// after it.
+    pub fn to_movingai(&self) -> String {
+        let timed = self
+            .entries
+            .iter()
+            .any(|e| e.start_time > 0 || e.is_annotated());
+        let mut out = format!("version {}\n", self.version);
+        for e in &self.entries {
+            out.push_str(&format!(
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.8}",
+                e.bucket,
+                e.map_name,
+                e.map_width,
+                e.map_height,
+                e.start_x,
+                e.start_y,
+                e.goal_x,
+                e.goal_y,
+                e.optimal_length
+            ));
+            if timed {
+                out.push_str(&format!("\t{}", e.start_time));
+            }
+            for annotation in e.annotations() {
+                out.push('\t');
+                out.push_str(&annotation);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Set the map name of every entry, e.g. `random-32-32-20.map`.
+    pub fn with_map_name(mut self, map_name: &str) -> Self {
+        for entry in &mut self.entries {
+            entry.map_name = map_name.to_string();
+        }
+        self
+    }
+
+    /// Difficulty buckets present in the scenario, ascending.
+    pub fn buckets(&self) -> Vec<u32> {
+        let mut buckets: Vec<u32> = self.entries.iter().map(|e| e.bucket).collect();
+        buckets.sort_unstable();
+        buckets.dedup();
+        buckets
+    }
+
+    /// Entries of bucket `bucket`, in file order.
+    pub fn entries_in_bucket(&self, bucket: u32) -> impl Iterator<Item = &ScenarioEntry> + '_ {
+        self.entries.iter().filter(move |e| e.bucket == bucket)
+    }
+
+    /// A scenario with only the entries `keep` accepts, in file order.
+    ///
+    /// ```
+    /// # use mapf_core::{Scenario, ScenarioEntry};
+    /// let entries = (0..4)
+    ///     .map(|b| ScenarioEntry::new("m.map", 8, 8).with_bucket(b))
+    ///     .collect();
+    /// let scenario = Scenario::new(1, entries);
+    /// let hard = scenario.filter(|e| e.bucket >= 2);
+    /// assert_eq!(hard.buckets(), vec![2, 3]);
+    /// ```
+    pub fn filter(&self, mut keep: impl FnMut(&ScenarioEntry) -> bool) -> Scenario {
+        Scenario {
+            version: self.version,
+            entries: self.entries.iter().filter(|e| keep(e)).cloned().collect(),
+        }
+    }
+
+    /// Check every entry against `map`: the declared size must match, and
+    /// starts and goals must be passable cells inside it.
+    ///
+    /// Returns all problems in entry order, empty if the scenario fits.
+    pub fn validate_against(&self, map: &GridMap) -> Vec<EntryError> {
+        let (width, height) = (map.width(), map.height());
+        let mut errors = Vec::new();
+        for (entry, e) in self.entries.iter().enumerate() {
+            if (e.map_width, e.map_height) != (width, height) {
+                errors.push(EntryError::SizeMismatch {
+                    entry,
+                    declared_width: e.map_width,
+                    declared_height: e.map_height,
+                    width,
+                    height,
+                });
+            }
+            for (endpoint, x, y) in [
+                (Endpoint::Start, e.start_x, e.start_y),
+                (Endpoint::Goal, e.goal_x, e.goal_y),
+            ] {
+                if x >= width || y >= height {
+                    errors.push(EntryError::OutOfBounds {
+                        entry,
+                        endpoint,
+                        x,
+                        y,
+                    });
+                } else if !map.is_passable(x, y) {
+                    errors.push(EntryError::Blocked {
+                        entry,
+                        endpoint,
+                        x,
+                        y,
+                    });
+                }
+            }
+        }
+        errors
+    }
+
     /// Scenario file version number.
     pub fn version(&self) -> u32 {
         self.version
@@ -135,9 +547,79 @@ impl Scenario {
 
     /// Extract (start, goal) coordinate pairs for all agents.
     /// Returns (starts, goals) where each is a Vec of (x, y).
-    pub fn agents(&self) -> (Vec<(u32, u32)>, Vec<(u32, u32)>) {
+    pub fn agents(&self) -> (Positions, Positions) {
         let starts = self.entries.iter().map(|e| (e.start_x, e.start_y)).collect();
         let goals = self.entries.iter().map(|e| (e.goal_x, e.goal_y)).collect();
         (starts, goals)
     }
+
+    /// Starts and goals of the first `n` agents, the usual way benchmarks
+    /// scale the agent count.
+    ///
+    /// Fails if the scenario has fewer than `n` entries or if two of those
+    /// agents share a start or a goal.
+    pub fn agents_n(&self, n: usize) -> Result<(Positions, Positions), AgentsError> {
+        if n > self.entries.len() {
+            return Err(AgentsError::TooFew {
+                requested: n,
+                available: self.entries.len(),
+            });
+        }
+        let entries = &self.entries[..n];
+        let starts: Positions = entries.iter().map(|e| (e.start_x, e.start_y)).collect();
+        let goals: Positions = entries.iter().map(|e| (e.goal_x, e.goal_y)).collect();
+
+        for (endpoint, positions) in [(Endpoint::Start, &starts), (Endpoint::Goal, &goals)] {
+            let mut seen = HashMap::with_capacity(n);
+            for (second, &(x, y)) in positions.iter().enumerate() {
+                if let Some(&first) = seen.get(&(x, y)) {
+                    return Err(AgentsError::Collision {
+                        first,
+                        second,
+                        endpoint,
+                        x,
+                        y,
+                    });
+                }
+                seen.insert((x, y), second);
+            }
+        }
+        Ok((starts, goals))
+    }
+
+    /// Starts and goals of the first `k`, `2k`, `3k`, ... agents, for
+    /// sweeping the agent count as MAPF papers do. The last window is the
+    /// largest multiple of `k` the scenario has; `k = 0` yields nothing.
+    ///
+    /// Each window is [`agents_n`](Self::agents_n) of its size. A collision
+    /// is yielded once as an error and ends the iteration, since every
+    /// larger window contains it too.
+    ///
+    /// ```
+    /// # use mapf_core::{Scenario, ScenarioEntry};
+    /// let entries = (0..5)
+    ///     .map(|i| ScenarioEntry::new("m.map", 8, 8).with_start(i, 0).with_goal(i, 7))
+    ///     .collect();
+    /// let scenario = Scenario::new(1, entries);
+    /// let sizes: Vec<usize> = scenario
+    ///     .windows(2)
+    ///     .map(|w| w.unwrap().0.len())
+    ///     .collect();
+    /// assert_eq!(sizes, vec![2, 4]);
+    /// ```
+    pub fn windows(
+        &self,
+        k: usize,
+    ) -> impl Iterator<Item = Result<(Positions, Positions), AgentsError>> + '_ {
+        let sizes = (k > 0).then(|| (k..=self.entries.len()).step_by(k));
+        let mut done = false;
+        sizes.into_iter().flatten().map_while(move |n| {
+            if done {
+                return None;
+            }
+            let window = self.agents_n(n);
+            done = window.is_err();
+            Some(window)
+        })
+    }
 }
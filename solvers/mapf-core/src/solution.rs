@@ -0,0 +1,87 @@
+//! Solution types shared by solvers and the verification backend.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A 2D grid coordinate, the one every crate in the arena shares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Coordinate {
+    pub x: u32,
+    pub y: u32,
+}
+
+impl From<(u32, u32)> for Coordinate {
+    fn from((x, y): (u32, u32)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<Coordinate> for (u32, u32) {
+    fn from(c: Coordinate) -> Self {
+        (c.x, c.y)
+    }
+}
+
+/// A single agent's path, one coordinate per timestep (including start and goal).
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Path {
+    pub steps: Vec<Coordinate>,
+}
+
+impl Path {
+    /// Number of actions taken (moves and waits), i.e. `steps.len() - 1`.
+    pub fn cost(&self) -> u32 {
+        self.steps.len().saturating_sub(1) as u32
+    }
+
+    /// Copy of the path without explicit waits at its final cell.
+    ///
+    /// Agents implicitly stay at their last position, so trailing waits carry
+    /// no information.
+    pub fn trimmed(&self) -> Self {
+        let mut steps = self.steps.clone();
+        while steps.len() > 1 && steps[steps.len() - 1] == steps[steps.len() - 2] {
+            steps.pop();
+        }
+        Self { steps }
+    }
+
+    /// Copy of the path extended to `len` steps by staying at the last position.
+    /// Longer paths are returned unchanged.
+    pub fn padded(&self, len: usize) -> Self {
+        let mut steps = self.steps.clone();
+        if let Some(&last) = steps.last() {
+            steps.resize(len.max(steps.len()), last);
+        }
+        Self { steps }
+    }
+}
+
+/// A full MAPF solution: one path per agent, in input order.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Solution {
+    pub paths: Vec<Path>,
+}
+
+impl Solution {
+    /// Canonical form: every path trimmed of trailing waits.
+    ///
+    /// Equivalent solutions (differing only in explicit stays at the end)
+    /// canonicalize to the same value, so they hash and score identically.
+    pub fn canonicalize(&self) -> Self {
+        Self {
+            paths: self.paths.iter().map(Path::trimmed).collect(),
+        }
+    }
+
+    /// Copy with every path padded to the makespan, making implicit stays explicit.
+    pub fn padded(&self) -> Self {
+        let len = self.paths.iter().map(|p| p.steps.len()).max().unwrap_or(0);
+        Self {
+            paths: self.paths.iter().map(|p| p.padded(len)).collect(),
+        }
+    }
+}
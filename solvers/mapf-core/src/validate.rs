@@ -0,0 +1,402 @@
+//! Dependency-free solution checks for clients that can't reach the backend.
+//!
+//! Covers the basic MAPF rules (cardinal moves, bounds, obstacles, starts
+//! and goals, collisions) with the same error names, codes and messages as
+//! the backend validator, which remains authoritative for scoring.
+
+use std::collections::HashMap;
+
+use crate::{Coordinate, DynamicObstacles, GridMap, Path, Solution};
+
+/// Rule a solution breaks. Names and codes match the backend's
+/// `ValidationErrorType`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViolationKind {
+    EmptyPath,
+    DiagonalMove,
+    OutOfBounds,
+    BlockedCell,
+    DynamicObstacle,
+    InvalidStart,
+    InvalidGoal,
+    AgentCountMismatch,
+    VertexCollision,
+    EdgeCollision,
+}
+
+impl ViolationKind {
+    /// Stable numeric code: 1xx per-path, 2xx start/goal, 3xx inter-agent.
+    pub fn code(&self) -> u16 {
+        match self {
+            ViolationKind::EmptyPath => 100,
+            ViolationKind::DiagonalMove => 101,
+            ViolationKind::OutOfBounds => 102,
+            ViolationKind::BlockedCell => 103,
+            ViolationKind::DynamicObstacle => 106,
+            ViolationKind::InvalidStart => 200,
+            ViolationKind::InvalidGoal => 201,
+            ViolationKind::AgentCountMismatch => 203,
+            ViolationKind::VertexCollision => 300,
+            ViolationKind::EdgeCollision => 301,
+        }
+    }
+
+    /// Stable string code.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViolationKind::EmptyPath => "empty_path",
+            ViolationKind::DiagonalMove => "diagonal_move",
+            ViolationKind::OutOfBounds => "out_of_bounds",
+            ViolationKind::BlockedCell => "blocked_cell",
+            ViolationKind::DynamicObstacle => "dynamic_obstacle",
+            ViolationKind::InvalidStart => "invalid_start",
+            ViolationKind::InvalidGoal => "invalid_goal",
+            ViolationKind::AgentCountMismatch => "agent_count_mismatch",
+            ViolationKind::VertexCollision => "vertex_collision",
+            ViolationKind::EdgeCollision => "edge_collision",
+        }
+    }
+}
+
+/// One broken rule, with enough context to annotate it on the map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    /// Agents involved; the first is the one the violation is reported for
+    pub agents: Vec<usize>,
+    pub timestep: Option<usize>,
+    /// Cells involved: the offending cell, or `[from, to]` / `[actual, expected]` pairs
+    pub positions: Vec<Coordinate>,
+    pub details: String,
+}
+
+impl Violation {
+    fn new(kind: ViolationKind, agent: usize, timestep: Option<usize>, details: String) -> Self {
+        Self {
+            kind,
+            agents: vec![agent],
+            timestep,
+            positions: Vec::new(),
+            details,
+        }
+    }
+
+    fn with_agents(mut self, agents: Vec<usize>) -> Self {
+        self.agents = agents;
+        self
+    }
+
+    fn with_positions(mut self, positions: Vec<Coordinate>) -> Self {
+        self.positions = positions;
+        self
+    }
+}
+
+/// Run every check on `solution`; an empty result means it is valid.
+///
+/// Per-path violations come first, in agent order, followed by collisions
+/// in timestep order.
+pub fn validate(
+    map: &GridMap,
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+    solution: &Solution,
+) -> Vec<Violation> {
+    validate_with(map, starts, goals, solution, false)
+}
+
+/// Like [`validate`], with agents leaving the map after their last step if
+/// `disappear_at_goal` is set, so they no longer block anyone there.
+pub fn validate_with(
+    map: &GridMap,
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+    solution: &Solution,
+    disappear_at_goal: bool,
+) -> Vec<Violation> {
+    let paths = &solution.paths;
+    let mut violations = check_agent_counts(paths, starts, goals);
+
+    for (agent, path) in paths.iter().enumerate() {
+        violations.extend(check_cardinal(path, agent));
+        violations.extend(check_on_map(path, agent, map));
+    }
+    violations.extend(check_starts_and_goals(paths, starts, goals));
+    violations.extend(check_collisions_with(paths, disappear_at_goal));
+    violations
+}
+
+/// Check that there is exactly one path, start and goal per agent.
+pub fn check_agent_counts(
+    paths: &[Path],
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+) -> Vec<Violation> {
+    if paths.len() == starts.len() && starts.len() == goals.len() {
+        return Vec::new();
+    }
+    let agent = paths.len().min(starts.len()).min(goals.len());
+    vec![Violation::new(
+        ViolationKind::AgentCountMismatch,
+        agent,
+        None,
+        format!(
+            "Solution has {} paths for {} starts and {} goals",
+            paths.len(),
+            starts.len(),
+            goals.len()
+        ),
+    )]
+}
+
+/// Check that a path is non-empty and only moves to 4-neighbours or waits.
+pub fn check_cardinal(path: &Path, agent: usize) -> Vec<Violation> {
+    if path.steps.is_empty() {
+        return vec![Violation::new(
+            ViolationKind::EmptyPath,
+            agent,
+            None,
+            format!("Agent {} has empty path", agent),
+        )];
+    }
+
+    path.steps
+        .windows(2)
+        .enumerate()
+        .filter(|(_, step)| step[0].x.abs_diff(step[1].x) + step[0].y.abs_diff(step[1].y) > 1)
+        .map(|(t, step)| {
+            let (from, to) = (step[0], step[1]);
+            Violation::new(
+                ViolationKind::DiagonalMove,
+                agent,
+                Some(t),
+                format!(
+                    "Agent {} made diagonal move from ({},{}) to ({},{}) at timestep {}",
+                    agent, from.x, from.y, to.x, to.y, t
+                ),
+            )
+            .with_positions(vec![from, to])
+        })
+        .collect()
+}
+
+/// Check that a path stays within the map and off blocked cells.
+pub fn check_on_map(path: &Path, agent: usize, map: &GridMap) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (t, &pos) in path.steps.iter().enumerate() {
+        if pos.x >= map.width() || pos.y >= map.height() {
+            violations.push(
+                Violation::new(
+                    ViolationKind::OutOfBounds,
+                    agent,
+                    Some(t),
+                    format!(
+                        "Agent {} at ({},{}) is out of bounds at timestep {}",
+                        agent, pos.x, pos.y, t
+                    ),
+                )
+                .with_positions(vec![pos]),
+            );
+        } else if !map.is_passable(pos.x, pos.y) {
+            violations.push(
+                Violation::new(
+                    ViolationKind::BlockedCell,
+                    agent,
+                    Some(t),
+                    format!(
+                        "Agent {} at ({},{}) is on blocked cell at timestep {}",
+                        agent, pos.x, pos.y, t
+                    ),
+                )
+                .with_positions(vec![pos]),
+            );
+        }
+    }
+
+    violations
+}
+
+/// Check that a path never occupies a cell while a timed obstacle blocks
+/// it. The agent stays at its last cell once the path ends, so an obstacle
+/// appearing there later is reported too.
+pub fn check_dynamic_obstacles(
+    path: &Path,
+    agent: usize,
+    obstacles: &DynamicObstacles,
+) -> Vec<Violation> {
+    let hit = |pos: Coordinate, t: usize| {
+        Violation::new(
+            ViolationKind::DynamicObstacle,
+            agent,
+            Some(t),
+            format!(
+                "Agent {} at ({},{}) hits a dynamic obstacle at timestep {}",
+                agent, pos.x, pos.y, t
+            ),
+        )
+        .with_positions(vec![pos])
+    };
+
+    let mut violations: Vec<Violation> = path
+        .steps
+        .iter()
+        .enumerate()
+        .filter(|&(t, pos)| !obstacles.is_passable_at(pos.x, pos.y, t))
+        .map(|(t, &pos)| hit(pos, t))
+        .collect();
+
+    // Obstacles appearing on the final cell while the agent waits there
+    if let Some(&last) = path.steps.last() {
+        let end = path.steps.len();
+        let first_hit = obstacles
+            .at(last.x, last.y)
+            .map(|o| o.from.max(end))
+            .filter(|&t| !obstacles.is_passable_at(last.x, last.y, t))
+            .min();
+        if let Some(t) = first_hit {
+            violations.push(hit(last, t));
+        }
+    }
+    violations
+}
+
+/// Check that each path starts at its agent's start and ends at its goal.
+pub fn check_starts_and_goals(
+    paths: &[Path],
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    for (agent, path) in paths.iter().enumerate() {
+        // Empty paths and missing starts/goals are reported elsewhere
+        let (Some(&first), Some(&last)) = (path.steps.first(), path.steps.last()) else {
+            continue;
+        };
+        let (Some(&start), Some(&goal)) = (starts.get(agent), goals.get(agent)) else {
+            continue;
+        };
+
+        if first != start {
+            violations.push(
+                Violation::new(
+                    ViolationKind::InvalidStart,
+                    agent,
+                    Some(0),
+                    format!(
+                        "Agent {} path starts at ({},{}) but should start at ({},{})",
+                        agent, first.x, first.y, start.x, start.y
+                    ),
+                )
+                .with_positions(vec![first, start]),
+            );
+        }
+        if last != goal {
+            violations.push(
+                Violation::new(
+                    ViolationKind::InvalidGoal,
+                    agent,
+                    Some(path.steps.len() - 1),
+                    format!(
+                        "Agent {} path ends at ({},{}) but should end at ({},{})",
+                        agent, last.x, last.y, goal.x, goal.y
+                    ),
+                )
+                .with_positions(vec![last, goal]),
+            );
+        }
+    }
+
+    violations
+}
+
+/// Check for vertex collisions (two agents on one cell) and edge collisions
+/// (two agents swapping cells). Agents wait at their last cell once their
+/// path ends. Edge collisions are stamped with the timestep the agents leave
+/// from, as in the backend; `mapf-astar`'s conflicts use the same timesteps.
+pub fn check_collisions(paths: &[Path]) -> Vec<Violation> {
+    check_collisions_with(paths, false)
+}
+
+/// Like [`check_collisions`], with agents leaving the map after their last
+/// step instead if `disappear_at_goal` is set.
+pub fn check_collisions_with(paths: &[Path], disappear_at_goal: bool) -> Vec<Violation> {
+    let max_t = paths.iter().map(|p| p.steps.len()).max().unwrap_or(0);
+    let mut violations = Vec::new();
+
+    for t in 0..max_t {
+        // The lowest-indexed agent claims the cell; everyone else collides with it
+        let mut claimed: HashMap<Coordinate, usize> = HashMap::new();
+        for (agent, path) in paths.iter().enumerate() {
+            let Some(&pos) = position_at(path, t, disappear_at_goal) else {
+                continue;
+            };
+            match claimed.get(&pos) {
+                Some(&other) => violations.push(
+                    Violation::new(
+                        ViolationKind::VertexCollision,
+                        agent,
+                        Some(t),
+                        format!(
+                            "Agents {} and {} collide at ({},{}) at timestep {}",
+                            other, agent, pos.x, pos.y, t
+                        ),
+                    )
+                    .with_agents(vec![other, agent])
+                    .with_positions(vec![pos]),
+                ),
+                None => {
+                    claimed.insert(pos, agent);
+                }
+            }
+        }
+
+        if t + 1 == max_t {
+            break;
+        }
+        // Moves (from, to) -> agent, to find agents moving the opposite way
+        let mut moves: HashMap<(Coordinate, Coordinate), usize> = HashMap::new();
+        for (agent, path) in paths.iter().enumerate() {
+            let (Some(&from), Some(&to)) = (
+                position_at(path, t, disappear_at_goal),
+                position_at(path, t + 1, disappear_at_goal),
+            ) else {
+                continue;
+            };
+            if from == to {
+                continue;
+            }
+            if let Some(&other) = moves.get(&(to, from)) {
+                violations.push(
+                    Violation::new(
+                        ViolationKind::EdgeCollision,
+                        other,
+                        Some(t),
+                        format!(
+                            "Agents {} and {} swap positions between timesteps {} and {}",
+                            other,
+                            agent,
+                            t,
+                            t + 1
+                        ),
+                    )
+                    .with_agents(vec![other, agent])
+                    .with_positions(vec![to, from]),
+                );
+            }
+            moves.insert((from, to), agent);
+        }
+    }
+
+    violations
+}
+
+/// Position of an agent at timestep `t`; `None` for empty paths, and past
+/// the end of the path if agents disappear there.
+fn position_at(path: &Path, t: usize, disappear_at_goal: bool) -> Option<&Coordinate> {
+    match path.steps.get(t) {
+        None if disappear_at_goal => None,
+        step => step.or_else(|| path.steps.last()),
+    }
+}
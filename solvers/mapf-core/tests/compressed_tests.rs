@@ -0,0 +1,87 @@
+//! Tests for reading gzipped and zipped maps and scenarios.
+#![cfg(feature = "compressed")]
+
+use std::io::{Cursor, Write};
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use mapf_core::{CompressedError, GridMap, Scenario};
+
+const MAP: &str = "type octile\nheight 2\nwidth 3\nmap\n...\n.@.\n";
+const SCEN: &str = "version 1\n0\ttiny.map\t3\t2\t0\t0\t2\t1\t3\n";
+
+fn gzip(text: &str) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(text.as_bytes()).unwrap();
+    encoder.finish().unwrap()
+}
+
+/// A zip archive with the given files.
+fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(contents.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+#[test]
+fn parse_compressed_detects_format() {
+    let expected = GridMap::parse(MAP).unwrap().to_movingai();
+    for data in [
+        MAP.as_bytes().to_vec(),
+        gzip(MAP),
+        zip(&[("maps/tiny.map", MAP), ("README.txt", "not a map")]),
+    ] {
+        let map = GridMap::parse_compressed(&data).unwrap();
+        assert_eq!(map.to_movingai(), expected);
+    }
+
+    let scenario = Scenario::parse_compressed(&gzip(SCEN)).unwrap();
+    assert_eq!(scenario.entries().len(), 1);
+    assert_eq!(scenario.entries()[0].goal_x, 2);
+}
+
+#[test]
+fn parse_archive_reads_every_file() {
+    let other = SCEN.replace("\t2\t1\t3", "\t1\t1\t2");
+    let data = zip(&[
+        ("scen/tiny-2.scen", &other),
+        ("scen/tiny-1.scen", SCEN),
+        ("scen/tiny.map", MAP),
+    ]);
+    let scenarios = Scenario::parse_archive(&data).unwrap();
+    let names: Vec<&str> = scenarios.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["tiny-1.scen", "tiny-2.scen"]);
+    assert_eq!(scenarios[1].1.entries()[0].goal_x, 1);
+
+    assert!(matches!(
+        Scenario::parse_compressed(&data),
+        Err(CompressedError::Ambiguous {
+            extension: "scen",
+            count: 2
+        })
+    ));
+    assert!(matches!(
+        GridMap::parse_compressed(&zip(&[("notes.txt", "")])),
+        Err(CompressedError::NotFound { extension: "map" })
+    ));
+}
+
+#[test]
+fn parse_compressed_names_bad_file() {
+    let data = zip(&[("broken.map", "type octile\nheight 2\nmap\n")]);
+    let err = GridMap::parse_compressed(&data).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("broken.map: missing header field width"));
+
+    let truncated = &gzip(MAP)[..10];
+    assert!(matches!(
+        GridMap::parse_compressed(truncated),
+        Err(CompressedError::Io(_))
+    ));
+}
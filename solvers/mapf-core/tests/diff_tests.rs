@@ -0,0 +1,79 @@
+//! Tests for solution comparison.
+
+use mapf_core::diff::diff;
+use mapf_core::{Coordinate, Path, Solution};
+use pretty_assertions::assert_eq;
+
+fn path(cells: &[(u32, u32)]) -> Path {
+    Path {
+        steps: cells.iter().map(|&(x, y)| Coordinate { x, y }).collect(),
+    }
+}
+
+#[test]
+fn identical_solutions_have_no_changes() {
+    let solution = Solution {
+        paths: vec![path(&[(0, 0), (1, 0)]), path(&[(2, 2)])],
+    };
+    let result = diff(&solution, &solution);
+
+    assert_eq!(result.changed().count(), 0);
+    assert_eq!(result.sum_of_costs, (1, 1));
+    assert_eq!(result.makespan, (1, 1));
+}
+
+#[test]
+fn trailing_waits_do_not_count_as_differences() {
+    let a = Solution {
+        paths: vec![path(&[(0, 0), (1, 0)])],
+    };
+    let b = Solution {
+        paths: vec![path(&[(0, 0), (1, 0), (1, 0), (1, 0)])],
+    };
+    let result = diff(&a, &b);
+
+    assert!(result.agents[0].is_same());
+    assert_eq!(result.agents[0].cost_delta(), 0);
+}
+
+#[test]
+fn reports_cost_deltas_and_diverging_spans() {
+    // Agent 0 detours in `a` and goes straight in `b`; agent 1 is unchanged
+    let a = Solution {
+        paths: vec![
+            path(&[(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)]),
+            path(&[(5, 5)]),
+        ],
+    };
+    let b = Solution {
+        paths: vec![path(&[(0, 0), (1, 0), (2, 0)]), path(&[(5, 5)])],
+    };
+    let result = diff(&a, &b);
+
+    let agent = &result.agents[0];
+    assert_eq!((agent.cost_a, agent.cost_b), (4, 2));
+    assert_eq!(agent.cost_delta(), -2);
+    // Apart at t=1..4; together again once `b` waits at the goal
+    assert_eq!(agent.divergences, vec![1..4]);
+    assert_eq!(agent.first_divergence(), Some(1));
+
+    assert_eq!(
+        result.changed().map(|a| a.agent).collect::<Vec<_>>(),
+        vec![0]
+    );
+    assert_eq!(result.sum_of_costs, (4, 2));
+    assert_eq!(result.makespan, (4, 2));
+}
+
+#[test]
+fn separate_spans_for_paths_that_rejoin() {
+    let a = Solution {
+        paths: vec![path(&[(0, 0), (1, 0), (1, 0), (2, 0), (3, 0)])],
+    };
+    let b = Solution {
+        paths: vec![path(&[(0, 0), (0, 0), (1, 0), (2, 0), (2, 0), (3, 0)])],
+    };
+    let result = diff(&a, &b);
+
+    assert_eq!(result.agents[0].divergences, vec![1..2, 4..5]);
+}
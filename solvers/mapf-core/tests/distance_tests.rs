@@ -0,0 +1,93 @@
+//! Tests for BFS distance maps and shortest paths.
+
+use mapf_core::{Coordinate, GridMap};
+use pretty_assertions::assert_eq;
+
+/// 5x4 map with a wall to walk around and a walled-off corner:
+/// ```text
+/// .....
+/// .@@@.
+/// ...@@
+/// @@.@.
+/// ```
+fn map() -> GridMap {
+    GridMap::parse("type octile\nheight 4\nwidth 5\nmap\n.....\n.@@@.\n...@@\n@@.@.\n").unwrap()
+}
+
+#[test]
+fn distances_go_around_obstacles() {
+    let distances = map().distance_map(Coordinate { x: 4, y: 1 });
+    assert_eq!(distances.goal(), Coordinate { x: 4, y: 1 });
+    assert_eq!(distances.get(4, 1), Some(0));
+    assert_eq!(distances.get(0, 0), Some(5));
+    // Straight below the goal, but the wall forces the long way round
+    assert_eq!(distances.get(2, 2), Some(9));
+    assert_eq!(distances.get(2, 3), Some(10));
+}
+
+#[test]
+fn unreachable_and_blocked_cells_have_no_distance() {
+    let distances = map().distance_map(Coordinate { x: 0, y: 0 });
+    assert_eq!(distances.get(4, 3), None);
+    assert!(!distances.is_reachable(1, 1));
+    assert_eq!(distances.get(5, 0), None);
+
+    let nowhere = map().distance_map(Coordinate { x: 1, y: 1 });
+    assert!(!nowhere.is_reachable(0, 0));
+    let outside = map().distance_map(Coordinate { x: 9, y: 9 });
+    assert!(!outside.is_reachable(0, 0));
+}
+
+#[test]
+fn each_distance_is_one_more_than_the_closest_neighbor() {
+    let map = GridMap::random(24, 24, 0.3, 7);
+    let goal = (0..24 * 24)
+        .map(|i| Coordinate {
+            x: i % 24,
+            y: i / 24,
+        })
+        .find(|c| map.is_passable(c.x, c.y))
+        .unwrap();
+    let distances = map.distance_map(goal);
+
+    for y in 0..24 {
+        for x in 0..24 {
+            let Some(d) = distances.get(x, y) else {
+                assert!(!map.is_passable(x, y));
+                continue;
+            };
+            if d == 0 {
+                continue;
+            }
+            let best = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .filter_map(|(dx, dy)| {
+                    let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                    (nx >= 0 && ny >= 0)
+                        .then(|| distances.get(nx as u32, ny as u32))
+                        .flatten()
+                })
+                .min();
+            assert_eq!(best, Some(d - 1));
+        }
+    }
+}
+
+#[test]
+fn shortest_paths_are_optimal_unit_steps() {
+    let map = map();
+    let path = map.shortest_path((2, 3), (4, 1)).unwrap();
+    assert_eq!(path.first(), Some(&(2, 3)));
+    assert_eq!(path.last(), Some(&(4, 1)));
+    assert_eq!(path.len(), 11);
+    for step in path.windows(2) {
+        let ((ax, ay), (bx, by)) = (step[0], step[1]);
+        assert_eq!(ax.abs_diff(bx) + ay.abs_diff(by), 1);
+        assert!(map.is_passable(bx, by));
+    }
+
+    assert_eq!(map.shortest_path((0, 0), (0, 0)), Some(vec![(0, 0)]));
+    assert_eq!(map.shortest_path((0, 0), (4, 3)), None);
+    assert_eq!(map.shortest_path((1, 1), (0, 0)), None);
+    assert_eq!(map.shortest_path((0, 0), (9, 9)), None);
+}
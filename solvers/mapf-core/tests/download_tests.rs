@@ -0,0 +1,132 @@
+//! Tests for the benchmark cache, against a local stand-in for MovingAI.
+#![cfg(feature = "download")]
+
+use std::io::{BufRead, BufReader, Cursor, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use mapf_core::download::{BenchmarkCache, DownloadError, ScenarioSet};
+
+const MAP: &str = "type octile\nheight 2\nwidth 3\nmap\n...\n.@.\n";
+
+fn scen(goal_x: u32) -> String {
+    format!("version 1\n0\ttiny.map\t3\t2\t0\t0\t{goal_x}\t1\t3\n")
+}
+
+/// A zip archive with the given files.
+fn zip(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, contents) in files {
+        writer
+            .start_file(*name, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(contents.as_bytes()).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+/// Serve the benchmark archives over HTTP; returns the base URL and a
+/// counter of requests served.
+fn serve() -> (String, Arc<AtomicUsize>) {
+    let archives = [
+        ("/mapf-map.zip", zip(&[("tiny.map", MAP)])),
+        (
+            "/mapf-scen-random.zip",
+            zip(&[
+                ("scen-random/tiny-random-10.scen", &scen(2)),
+                ("scen-random/tiny-random-2.scen", &scen(0)),
+                ("scen-random/other-random-1.scen", &scen(1)),
+            ]),
+        ),
+    ];
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+
+    let served = requests.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = String::new();
+            let mut reader = BufReader::new(&stream);
+            reader.read_line(&mut request).unwrap();
+            // Drain the headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            served.fetch_add(1, Ordering::SeqCst);
+
+            let path = request.split_whitespace().nth(1).unwrap_or_default();
+            match archives.iter().find(|(p, _)| *p == path) {
+                Some((_, body)) => {
+                    write!(
+                        stream,
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len()
+                    )
+                    .unwrap();
+                    stream.write_all(body).unwrap();
+                }
+                None => {
+                    write!(
+                        stream,
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    });
+    (url, requests)
+}
+
+fn cache_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("mapf-core-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn load_downloads_once_then_reads_the_cache() {
+    let (url, requests) = serve();
+    let dir = cache_dir("download");
+    let cache = BenchmarkCache::new(&dir).with_base_url(&url);
+
+    let benchmark = cache.load("tiny", ScenarioSet::Random).unwrap();
+    assert_eq!(benchmark.name, "tiny");
+    assert_eq!((benchmark.map.width(), benchmark.map.height()), (3, 2));
+    assert!(!benchmark.map.is_passable(1, 1));
+    // In file number order, and only this map's files
+    let goals: Vec<u32> = benchmark
+        .scenarios
+        .iter()
+        .map(|s| s.entries()[0].goal_x)
+        .collect();
+    assert_eq!(goals, vec![0, 2]);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    cache.load("tiny", ScenarioSet::Random).unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+    assert!(matches!(
+        cache.map("missing"),
+        Err(DownloadError::UnknownMap(name)) if name == "missing"
+    ));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn failed_download_leaves_no_cache_behind() {
+    let (url, _) = serve();
+    let dir = cache_dir("download-missing");
+    let cache = BenchmarkCache::new(&dir).with_base_url(&url);
+
+    assert!(matches!(
+        cache.scenarios("tiny", ScenarioSet::Even),
+        Err(DownloadError::Http { .. })
+    ));
+    assert!(!dir.join("mapf-scen-even").exists());
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,134 @@
+//! Tests for random map generation.
+
+use std::collections::VecDeque;
+
+use mapf_core::{GridMap, Scenario};
+use pretty_assertions::assert_eq;
+
+/// Number of free cells and the size of the region around the first one.
+fn free_and_reachable(map: &GridMap) -> (usize, usize) {
+    let (w, h) = (map.width(), map.height());
+    let cells: Vec<(u32, u32)> = (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .filter(|&(x, y)| map.is_passable(x, y))
+        .collect();
+    let Some(&first) = cells.first() else {
+        return (0, 0);
+    };
+
+    let mut seen = vec![false; (w * h) as usize];
+    seen[(first.1 * w + first.0) as usize] = true;
+    let mut queue = VecDeque::from([first]);
+    let mut reached = 0;
+    while let Some((x, y)) = queue.pop_front() {
+        reached += 1;
+        let neighbors = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (nx, ny) in neighbors {
+            if map.is_passable(nx, ny) && !seen[(ny * w + nx) as usize] {
+                seen[(ny * w + nx) as usize] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    (cells.len(), reached)
+}
+
+#[test]
+fn random_maps_are_reproducible() {
+    let a = GridMap::random(32, 24, 0.2, 7);
+    assert_eq!(a.to_bytes(), GridMap::random(32, 24, 0.2, 7).to_bytes());
+    assert_ne!(a.to_bytes(), GridMap::random(32, 24, 0.2, 8).to_bytes());
+    assert_eq!((a.width(), a.height(), a.map_type()), (32, 24, "octile"));
+}
+
+#[test]
+fn random_maps_have_connected_free_space() {
+    for seed in 0..20 {
+        let map = GridMap::random(40, 30, 0.3, seed);
+        let (free, reached) = free_and_reachable(&map);
+        assert_eq!(reached, free, "seed {seed}");
+        // Close to the asked-for density at this level
+        assert_eq!(40 * 30 - free, 360, "seed {seed}");
+    }
+}
+
+#[test]
+fn dense_maps_stay_connected() {
+    let map = GridMap::random(20, 20, 0.9, 3);
+    let (free, reached) = free_and_reachable(&map);
+    assert_eq!(reached, free);
+    assert!(free >= 40);
+
+    // Some cells always stay free
+    let map = GridMap::random(5, 4, 1.0, 3);
+    let (free, reached) = free_and_reachable(&map);
+    assert_eq!(reached, free);
+    assert!(free >= 1);
+}
+
+#[test]
+fn zero_density_is_empty() {
+    let map = GridMap::random(6, 6, 0.0, 1);
+    assert!(map.to_bytes().iter().all(|&b| b == 1));
+    assert_eq!(GridMap::random(6, 6, -1.0, 1).to_bytes(), map.to_bytes());
+}
+
+#[test]
+fn generated_scenarios_have_reachable_tasks() {
+    let map = GridMap::random(24, 24, 0.25, 5);
+    let scen = Scenario::generate(&map, 40, 11).unwrap();
+    assert_eq!(scen.entries().len(), 40);
+    assert_eq!(
+        scen.entries(),
+        Scenario::generate(&map, 40, 11).unwrap().entries()
+    );
+
+    let (starts, goals) = scen.agents();
+    for cells in [&starts, &goals] {
+        let mut unique = cells.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), cells.len());
+        assert!(cells.iter().all(|&(x, y)| map.is_passable(x, y)));
+    }
+    for e in scen.entries() {
+        assert_eq!(
+            (e.map_width, e.map_height, e.map_name.as_str()),
+            (24, 24, "")
+        );
+        // Never shorter than the straight-line distance
+        let manhattan = e.start_x.abs_diff(e.goal_x) + e.start_y.abs_diff(e.goal_y);
+        assert!(e.optimal_length >= manhattan as f64);
+        assert_eq!(e.bucket, e.optimal_length as u32 / 4);
+    }
+}
+
+#[test]
+fn generated_lengths_go_around_walls() {
+    // A wall with a gap at the bottom: 0,0 -> 2,0 takes 6 steps
+    let map = GridMap::parse("type octile\nheight 3\nwidth 3\nmap\n.@.\n.@.\n...\n").unwrap();
+    let scen = Scenario::generate(&map, 7, 0)
+        .unwrap()
+        .with_map_name("u.map");
+    for e in scen.entries() {
+        let expected =
+            distance_on_u(e.start_x, e.start_y).abs_diff(distance_on_u(e.goal_x, e.goal_y));
+        assert_eq!(e.optimal_length, expected as f64);
+        assert_eq!(e.map_name, "u.map");
+    }
+    assert!(Scenario::generate(&map, 8, 0).is_none());
+}
+
+/// Position along the U-shaped corridor of `generated_lengths_go_around_walls`.
+fn distance_on_u(x: u32, y: u32) -> u32 {
+    match x {
+        0 => y,
+        1 => 3,
+        _ => 6 - y,
+    }
+}
@@ -0,0 +1,83 @@
+//! Tests for landmark heuristics.
+
+use mapf_core::heuristics::Landmarks;
+use mapf_core::{Coordinate, GridMap};
+
+/// Serpentine maze: one corridor winding down through the walls.
+/// ```text
+/// .......
+/// ######.
+/// .......
+/// .######
+/// .......
+/// ```
+fn maze() -> GridMap {
+    GridMap::parse(
+        "type octile\nheight 5\nwidth 7\nmap\n.......\n@@@@@@.\n.......\n.@@@@@@\n.......\n",
+    )
+    .unwrap()
+}
+
+fn passable(map: &GridMap) -> Vec<Coordinate> {
+    (0..map.height())
+        .flat_map(|y| (0..map.width()).map(move |x| Coordinate { x, y }))
+        .filter(|c| map.is_passable(c.x, c.y))
+        .collect()
+}
+
+#[test]
+fn landmark_heuristic_is_admissible_and_beats_manhattan() {
+    let map = maze();
+    let landmarks = Landmarks::new(&map, 2);
+    assert_eq!(landmarks.len(), 2);
+
+    let cells = passable(&map);
+    let mut tighter = 0;
+    for &a in &cells {
+        let exact = map.distance_map(a);
+        for &b in &cells {
+            let h = landmarks.h(a, b);
+            let manhattan = a.x.abs_diff(b.x) + a.y.abs_diff(b.y);
+            assert!(h >= manhattan);
+            assert!(h <= exact.get(b.x, b.y).unwrap(), "h({a:?}, {b:?}) = {h}");
+            tighter += (h > manhattan) as usize;
+        }
+    }
+    assert!(tighter > 0);
+
+    // The corridor's two ends are 22 moves apart, Manhattan says 10
+    let ends = (Coordinate { x: 0, y: 0 }, Coordinate { x: 6, y: 4 });
+    assert_eq!(landmarks.h(ends.0, ends.1), 22);
+}
+
+#[test]
+fn landmarks_spread_out_and_cover_every_region() {
+    // Two regions split by a wall column
+    let map = GridMap::parse("type octile\nheight 2\nwidth 5\nmap\n..@..\n..@..\n").unwrap();
+    let landmarks = Landmarks::new(&map, 3);
+    let cells: Vec<Coordinate> = landmarks.cells().collect();
+    assert_eq!(cells.len(), 3);
+    assert!(cells.iter().any(|c| c.x < 2));
+    assert!(cells.iter().any(|c| c.x > 2));
+
+    // Never more landmarks than passable cells
+    assert_eq!(Landmarks::new(&map, 100).len(), 8);
+    assert!(Landmarks::new(&map, 0).is_empty());
+}
+
+#[test]
+fn landmarks_from_cells_fall_back_to_manhattan() {
+    let map = maze();
+    let a = Coordinate { x: 0, y: 2 };
+    let b = Coordinate { x: 6, y: 2 };
+
+    assert_eq!(Landmarks::new(&map, 0).h(a, b), 6);
+    let blocked = Landmarks::from_cells(&map, &[Coordinate { x: 1, y: 1 }]);
+    assert_eq!(blocked.h(a, b), 6);
+    let corner = Landmarks::from_cells(&map, &[Coordinate { x: 0, y: 4 }]);
+    assert_eq!(
+        corner.cells().collect::<Vec<_>>(),
+        vec![Coordinate { x: 0, y: 4 }]
+    );
+    assert_eq!(corner.h(a, b), 6);
+}
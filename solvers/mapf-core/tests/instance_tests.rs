@@ -0,0 +1,121 @@
+//! Tests for instances and their fingerprints.
+
+use mapf_core::{
+    AgentsError, Coordinate, EntryError, GridMap, Instance, InstanceError, Scenario, ScenarioEntry,
+};
+use pretty_assertions::assert_eq;
+
+const MAP: &str = "type octile\nheight 2\nwidth 3\nmap\n.@.\n...\n";
+
+fn c(x: u32, y: u32) -> Coordinate {
+    Coordinate { x, y }
+}
+
+#[test]
+fn map_fingerprint_depends_on_content_only() {
+    let map = GridMap::parse(MAP).unwrap();
+    let fingerprint = map.fingerprint();
+    assert_eq!(fingerprint.len(), 64);
+    assert!(fingerprint.chars().all(|ch| ch.is_ascii_hexdigit()));
+
+    // Same cells however the map was made or labelled
+    let from_bytes = GridMap::from_bytes(3, 2, &[1, 0, 1, 1, 1, 1]).unwrap();
+    assert_eq!(from_bytes.fingerprint(), fingerprint);
+    assert_eq!(map.clone().with_map_type("tile").fingerprint(), fingerprint);
+
+    // Different cells, shape or costs
+    let other = GridMap::from_bytes(3, 2, &[1, 1, 1, 1, 1, 1]).unwrap();
+    assert_ne!(other.fingerprint(), fingerprint);
+    let transposed = GridMap::from_bytes(2, 3, &[1, 0, 1, 1, 1, 1]).unwrap();
+    assert_ne!(transposed.fingerprint(), fingerprint);
+    let weighted = map.clone().with_costs(vec![1; 6]).unwrap();
+    assert_ne!(weighted.fingerprint(), fingerprint);
+}
+
+#[test]
+fn map_fingerprint_is_stable() {
+    // Pinned so a change to the layout can't slip through unnoticed
+    assert_eq!(
+        GridMap::parse(MAP).unwrap().fingerprint(),
+        "6f71d9c33caf9a47af3ea925f505613c1db326d62df76bf4cd60c4e1dc0f8445"
+    );
+}
+
+#[test]
+fn instance_fingerprint_covers_starts_and_goals_in_order() {
+    let map = GridMap::parse(MAP).unwrap();
+    let instance = Instance::new(map.clone(), vec![c(0, 0), c(2, 0)], vec![c(2, 1), c(0, 1)]);
+    let fingerprint = instance.fingerprint();
+    assert_eq!(fingerprint, instance.clone().fingerprint());
+    assert_ne!(fingerprint, map.fingerprint());
+
+    let swapped = Instance::new(map.clone(), vec![c(2, 0), c(0, 0)], vec![c(0, 1), c(2, 1)]);
+    assert_ne!(swapped.fingerprint(), fingerprint);
+    let fewer = Instance::new(map.clone(), vec![c(0, 0)], vec![c(2, 1)]);
+    assert_ne!(fewer.fingerprint(), fingerprint);
+    // An agent moved from the goals to the starts
+    let shifted = Instance::new(map, vec![c(0, 0), c(2, 0), c(2, 1)], vec![c(0, 1)]);
+    assert_ne!(shifted.fingerprint(), fingerprint);
+}
+
+#[test]
+fn instance_from_scenario_takes_the_first_agents() {
+    let map = GridMap::parse(MAP).unwrap();
+    let entry = |start: (u32, u32), goal: (u32, u32)| {
+        ScenarioEntry::new("small.map", 3, 2)
+            .with_start(start.0, start.1)
+            .with_goal(goal.0, goal.1)
+    };
+    let scenario = Scenario::new(
+        1,
+        vec![
+            entry((0, 0), (2, 1)),
+            entry((2, 0), (0, 1)),
+            entry((1, 0), (1, 1)),
+        ],
+    );
+
+    let instance = Instance::from_scenario(map.clone(), &scenario, 2).unwrap();
+    assert_eq!(instance.num_agents(), 2);
+    assert_eq!(instance.starts, vec![c(0, 0), c(2, 0)]);
+    assert_eq!(instance.goals, vec![c(2, 1), c(0, 1)]);
+
+    // The third agent starts on the obstacle
+    assert!(matches!(
+        Instance::try_from((map.clone(), &scenario)),
+        Err(InstanceError::DoesNotFit(EntryError::Blocked {
+            entry: 2,
+            ..
+        }))
+    ));
+    assert!(matches!(
+        Instance::from_scenario(map, &scenario, 4),
+        Err(InstanceError::Agents(AgentsError::TooFew { .. }))
+    ));
+}
+
+#[test]
+fn canonical_form_ignores_agent_order_and_hopeless_agents() {
+    // Two columns split by a wall
+    let map = GridMap::parse("type octile\nheight 2\nwidth 3\nmap\n.@.\n.@.\n").unwrap();
+    let instance = Instance::new(
+        map.clone(),
+        vec![c(2, 0), c(0, 0), c(0, 1), c(1, 0)],
+        vec![c(2, 1), c(0, 1), c(2, 0), c(0, 0)],
+    );
+    let canonical = instance.canonicalize();
+    assert_eq!(canonical.agents, vec![1, 0]);
+    // Across the wall, and starting on it
+    assert_eq!(canonical.unreachable, vec![2, 3]);
+    assert_eq!(canonical.instance.starts, vec![c(0, 0), c(2, 0)]);
+    assert_eq!(canonical.instance.goals, vec![c(0, 1), c(2, 1)]);
+
+    let reordered = Instance::new(map, vec![c(0, 0), c(2, 0)], vec![c(0, 1), c(2, 1)]);
+    assert_ne!(reordered.fingerprint(), instance.fingerprint());
+    assert_eq!(reordered.canonicalize().encode(), canonical.encode());
+    assert_eq!(
+        reordered.canonicalize().fingerprint(),
+        canonical.fingerprint()
+    );
+    assert_eq!(canonical.fingerprint(), canonical.instance.fingerprint());
+}
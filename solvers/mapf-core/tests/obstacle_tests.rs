@@ -0,0 +1,94 @@
+//! Tests for dynamic obstacle schedules.
+
+use mapf_core::{DynamicObstacles, GridMap, ObstacleError, Scenario, TimedObstacle};
+use pretty_assertions::assert_eq;
+
+const MAP_WITH_OBSTACLES: &str = "type octile
+height 2
+width 3
+map
+...
+.@.
+obstacles
+0 0 2 4
+2 1 3 -
+";
+
+const SCEN_WITH_OBSTACLES: &str = "version 1
+0\tsmall.map\t3\t2\t0\t0\t2\t0\t2.00000000
+
+obstacles
+1 0 0 1
+";
+
+#[test]
+fn obstacle_section_follows_the_grid() {
+    let map = GridMap::parse(MAP_WITH_OBSTACLES).unwrap();
+    assert_eq!((map.width(), map.height()), (3, 2));
+
+    let obstacles = DynamicObstacles::parse(MAP_WITH_OBSTACLES).unwrap();
+    assert_eq!(
+        obstacles.obstacles(),
+        &[
+            TimedObstacle {
+                x: 0,
+                y: 0,
+                from: 2,
+                until: Some(4),
+            },
+            TimedObstacle {
+                x: 2,
+                y: 1,
+                from: 3,
+                until: None,
+            },
+        ]
+    );
+
+    assert!(obstacles.is_passable_at(0, 0, 1));
+    assert!(!obstacles.is_passable_at(0, 0, 2));
+    assert!(!obstacles.is_passable_at(0, 0, 3));
+    assert!(obstacles.is_passable_at(0, 0, 4));
+    assert!(!obstacles.is_passable_at(2, 1, 1_000));
+    assert!(obstacles.is_passable_at(1, 0, 3));
+    assert_eq!(obstacles.at(2, 1).count(), 1);
+}
+
+#[test]
+fn obstacle_section_ends_scenario_entries() {
+    let scen = Scenario::parse(SCEN_WITH_OBSTACLES).unwrap();
+    assert_eq!(scen.entries().len(), 1);
+
+    let obstacles = DynamicObstacles::parse(SCEN_WITH_OBSTACLES).unwrap();
+    assert_eq!(obstacles.obstacles().len(), 1);
+    assert!(!obstacles.is_passable_at(1, 0, 0));
+}
+
+#[test]
+fn obstacle_section_round_trip() {
+    let obstacles = DynamicObstacles::parse(MAP_WITH_OBSTACLES).unwrap();
+    let section = obstacles.to_section();
+    assert_eq!(section, "obstacles\n0 0 2 4\n2 1 3 -\n");
+    assert_eq!(
+        DynamicObstacles::parse(&section).unwrap().obstacles(),
+        obstacles.obstacles()
+    );
+
+    let none = DynamicObstacles::parse("type octile\nheight 1\nwidth 1\nmap\n.\n").unwrap();
+    assert!(none.is_empty());
+    assert!(none.is_passable_at(0, 0, 0));
+}
+
+#[test]
+fn malformed_obstacles_are_errors() {
+    for (input, line) in [
+        ("obstacles\n0 0 1\n", 2),
+        ("obstacles\n0 0 1 2\n0 x 1 2\n", 3),
+        ("obstacles\n0 0 3 3\n", 2),
+    ] {
+        match DynamicObstacles::parse(input) {
+            Err(ObstacleError::Malformed { line: got, .. }) => assert_eq!(got, line, "{input}"),
+            other => panic!("{input:?}: {other:?}"),
+        }
+    }
+}
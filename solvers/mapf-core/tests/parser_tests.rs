@@ -1,6 +1,9 @@
 //! Tests for MovingAI map and scenario parsing.
 
-use mapf_core::{GridMap, MapError, Scenario, ScenarioEntry, ScenarioError, Tile};
+use mapf_core::{
+    AgentsError, Coordinate, Endpoint, EntryError, GridMap, MapError, Path, Scenario,
+    ScenarioEntry, ScenarioError, Tile,
+};
 use pretty_assertions::assert_eq;
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -34,6 +37,7 @@ map
 #[test]
 fn parse_empty_8x8_map() {
     let map = GridMap::parse(EMPTY_8X8_MAP).expect("parse failed");
+    assert_eq!(map.map_type(), "octile");
     assert_eq!(map.width(), 8);
     assert_eq!(map.height(), 8);
     // All cells passable
@@ -77,7 +81,14 @@ fn map_is_passable() {
 fn map_parse_missing_header() {
     let bad = "map\n....";
     let err = GridMap::parse(bad).unwrap_err();
-    assert!(matches!(err, MapError::MissingHeader(_)));
+    assert!(matches!(
+        err,
+        MapError::MissingHeader {
+            field: "type",
+            line: 1,
+            ..
+        }
+    ));
 }
 
 #[test]
@@ -92,6 +103,31 @@ map
     assert!(matches!(err, MapError::DimensionMismatch { .. }));
 }
 
+#[test]
+fn map_errors_carry_line() {
+    let err = GridMap::parse("type octile\nheight 2\nwidth x4\nmap\n").unwrap_err();
+    match &err {
+        MapError::InvalidHeader {
+            field, line, text, ..
+        } => {
+            assert_eq!((*field, *line, text.as_str()), ("width", 3, "width x4"));
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+    assert_eq!(
+        err.to_string(),
+        "invalid header value for width on line 3: \"width x4\""
+    );
+
+    let err = GridMap::parse("type octile\nheight 3\nwidth 2\nmap\n..\n..\n").unwrap_err();
+    match err {
+        MapError::DimensionMismatch {
+            got, line, text, ..
+        } => assert_eq!((got, line, text.as_str()), (2, 6, "..")),
+        other => panic!("unexpected error: {other}"),
+    }
+}
+
 #[test]
 fn map_to_bytes_roundtrip() {
     let map = GridMap::parse(MAZE_SNIPPET).unwrap();
@@ -101,6 +137,163 @@ fn map_to_bytes_roundtrip() {
     assert!(bytes[0..6].iter().all(|&b| b == 0));
 }
 
+#[test]
+fn map_to_movingai_roundtrip() {
+    let map = GridMap::parse(MAZE_SNIPPET).unwrap();
+    assert_eq!(map.to_movingai(), MAZE_SNIPPET);
+}
+
+#[test]
+fn map_ascii_roundtrip() {
+    let map = GridMap::from_ascii(
+        "
+        @@@@@@
+        @....@
+        @.@@.@
+        @@@@@@
+        ",
+    )
+    .unwrap();
+    assert_eq!(map.to_movingai(), MAZE_SNIPPET);
+    assert_eq!(map.to_ascii(), MAZE_SNIPPET.split_once("map\n").unwrap().1);
+    assert_eq!(
+        GridMap::from_ascii(&map.to_ascii()).unwrap().to_bytes(),
+        map.to_bytes()
+    );
+
+    let err = GridMap::from_ascii("...\n..\n").unwrap_err();
+    assert!(matches!(
+        err,
+        MapError::RowWidthMismatch {
+            row: 1,
+            line: 2,
+            ..
+        }
+    ));
+    assert_eq!(GridMap::from_ascii("").unwrap().width(), 0);
+}
+
+#[test]
+fn map_parse_from_reader() {
+    let map = GridMap::parse_from_reader(MAZE_SNIPPET.as_bytes()).unwrap();
+    assert_eq!(map.to_movingai(), MAZE_SNIPPET);
+
+    // Windows line endings, as in some benchmark downloads
+    let crlf = MAZE_SNIPPET.replace('\n', "\r\n");
+    let map = GridMap::parse_from_reader(crlf.as_bytes()).unwrap();
+    assert_eq!(map.to_movingai(), MAZE_SNIPPET);
+
+    let err = GridMap::parse_from_reader(&b"type octile\nheight 1\nwidth 1\nmap\n\xff\n"[..])
+        .unwrap_err();
+    assert!(matches!(err, MapError::Io { line: 5, .. }));
+}
+
+#[test]
+fn map_parse_lenient() {
+    let messy = "Type octile\r\nheight\t4\r\nWIDTH  6\r\nmap\r\n\
+                 @@@@@@  \r\n@....@\r\n\r\n@.@@.@@@\r\n@@@@@@\t\r\n";
+    assert!(GridMap::parse(messy).is_err());
+    let map = GridMap::parse_lenient(messy).unwrap();
+    assert_eq!(map.to_movingai(), MAZE_SNIPPET);
+
+    // Structural problems are still reported
+    let short_row = "type octile\nheight 2\nwidth 3\nmap\n...\n..\n";
+    assert!(matches!(
+        GridMap::parse_lenient(short_row),
+        Err(MapError::RowWidthMismatch {
+            row: 1,
+            line: 6,
+            ..
+        })
+    ));
+    let missing_row = "type octile\nheight 2\nwidth 3\nmap\n...\n";
+    assert!(matches!(
+        GridMap::parse_lenient(missing_row),
+        Err(MapError::DimensionMismatch { .. })
+    ));
+}
+
+#[test]
+fn map_terrain_classes() {
+    let input = "type octile\nheight 1\nwidth 6\nmap\n.GSWTO\n";
+    let map = GridMap::parse(input).unwrap();
+
+    let tiles: Vec<_> = (0..6).map(|x| map.get(x, 0).unwrap()).collect();
+    assert_eq!(
+        tiles,
+        vec![
+            Tile::Passable,
+            Tile::Passable,
+            Tile::Swamp,
+            Tile::Water,
+            Tile::Trees,
+            Tile::Blocked
+        ]
+    );
+    let passable: Vec<_> = (0..6).map(|x| map.is_passable(x, 0)).collect();
+    assert_eq!(passable, vec![true, true, true, false, false, false]);
+    assert_eq!(map.to_bytes(), vec![1, 1, 1, 0, 0, 0]);
+
+    assert_eq!(map.tile_cost(0, 0), Some(1));
+    assert_eq!(map.tile_cost(2, 0), Some(3));
+    assert_eq!(map.tile_cost(3, 0), None);
+    assert_eq!(map.tile_cost(6, 0), None);
+    // Terrain is separate from the cost channel
+    assert!(!map.is_weighted());
+
+    assert!(map.to_movingai().ends_with("map\n..SWT@\n"));
+}
+
+#[test]
+fn plain_maps_are_stored_compactly() {
+    let map = GridMap::parse(MAZE_SNIPPET).unwrap();
+    assert!(map.is_compact());
+    assert!(GridMap::from_bytes(65, 1, &[1; 65]).unwrap().is_compact());
+    assert!(map.rotate90().is_compact());
+
+    let terrain = GridMap::parse("type octile\nheight 1\nwidth 2\nmap\n.T\n").unwrap();
+    assert!(!terrain.is_compact());
+    assert_eq!(terrain.get(1, 0), Some(Tile::Trees));
+
+    // Same answers from either storage, including across word boundaries
+    let bytes: Vec<u8> = (0..130).map(|i| (i % 3 != 0) as u8).collect();
+    let map = GridMap::from_bytes(13, 10, &bytes).unwrap();
+    assert_eq!(map.to_bytes(), bytes);
+    for (i, &b) in bytes.iter().enumerate() {
+        let (x, y) = (i as u32 % 13, i as u32 / 13);
+        assert_eq!(map.is_passable(x, y), b == 1);
+        let tile = if b == 1 {
+            Tile::Passable
+        } else {
+            Tile::Blocked
+        };
+        assert_eq!(map.get(x, y), Some(tile));
+    }
+    assert!(!map.is_passable(13, 0));
+    assert_eq!(map.get(0, 10), None);
+}
+
+#[test]
+fn map_cost_channel() {
+    let map = GridMap::from_bytes(3, 1, &[1, 1, 1]).unwrap();
+    assert!(!map.is_weighted());
+    assert!(map.clone().with_costs(vec![1, 2]).is_none());
+
+    let map = map.with_costs(vec![1, 5, 2]).unwrap();
+    assert_eq!(map.cell_cost(1, 0), 5);
+    assert_eq!(map.cell_cost(3, 0), 1);
+
+    let path = Path {
+        steps: vec![
+            Coordinate { x: 0, y: 0 },
+            Coordinate { x: 1, y: 0 },
+            Coordinate { x: 2, y: 0 },
+            Coordinate { x: 2, y: 0 },
+        ],
+    };
+    assert_eq!(map.path_cost(&path), 5 + 2 + 2);
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Scenario tests
 // ─────────────────────────────────────────────────────────────────────────────
@@ -132,6 +325,33 @@ fn parse_simple_scenario() {
     assert_eq!(e1.goal_y, 6);
 }
 
+#[test]
+fn scenario_to_movingai_roundtrip() {
+    let scen = Scenario::parse(SIMPLE_SCEN).unwrap();
+    let written = Scenario::new(scen.version(), scen.entries().to_vec()).to_movingai();
+    assert_eq!(written, SIMPLE_SCEN);
+}
+
+#[test]
+fn scenario_entry_builder() {
+    let entries = vec![
+        ScenarioEntry::new("empty-8-8.map", 8, 8)
+            .with_start(1, 2)
+            .with_goal(5, 2)
+            .with_optimal_length(4.0)
+            .with_bucket(1),
+        ScenarioEntry::new("empty-8-8.map", 8, 8).with_goal(0, 1),
+    ];
+    let written = Scenario::new(1, entries.clone()).to_movingai();
+    assert_eq!(
+        written,
+        "version 1\n\
+         1\tempty-8-8.map\t8\t8\t1\t2\t5\t2\t4.00000000\n\
+         0\tempty-8-8.map\t8\t8\t0\t0\t0\t1\t0.00000000\n"
+    );
+    assert_eq!(Scenario::parse(&written).unwrap().entries(), &entries[..]);
+}
+
 #[test]
 fn scenario_parse_missing_version() {
     let bad = "0\tempty.map\t8\t8\t0\t0\t1\t0\t1.0";
@@ -162,3 +382,268 @@ fn scenario_agents_extraction() {
     assert_eq!(starts[0], (0, 0));
     assert_eq!(goals[0], (1, 0));
 }
+
+#[test]
+fn scenario_parse_from_reader() {
+    let scen = Scenario::parse_from_reader(SIMPLE_SCEN.as_bytes()).unwrap();
+    assert_eq!(
+        scen.entries(),
+        Scenario::parse(SIMPLE_SCEN).unwrap().entries()
+    );
+
+    let err = Scenario::parse_from_reader(&b"version 1\n\xff"[..]).unwrap_err();
+    assert!(matches!(err, ScenarioError::Io(_)));
+}
+
+#[test]
+fn scenario_start_times() {
+    let plain = "version 1\n0\tm.map\t8\t8\t0\t0\t1\t0\t1.00000000\n";
+    let scen = Scenario::parse(plain).unwrap();
+    assert_eq!(scen.entries()[0].start_time, 0);
+    // No tenth column unless an agent appears late
+    assert_eq!(scen.to_movingai(), plain);
+
+    let timed = "version 1\n\
+                 0\tm.map\t8\t8\t0\t0\t1\t0\t1.00000000\t0\n\
+                 0\tm.map\t8\t8\t2\t2\t3\t2\t1.00000000\t12\n";
+    let scen = Scenario::parse(timed).unwrap();
+    let times: Vec<usize> = scen.entries().iter().map(|e| e.start_time).collect();
+    assert_eq!(times, vec![0, 12]);
+    assert_eq!(scen.to_movingai(), timed);
+    assert_eq!(
+        scen.entries()[1],
+        ScenarioEntry::new("m.map", 8, 8)
+            .with_start(2, 2)
+            .with_goal(3, 2)
+            .with_optimal_length(1.0)
+            .with_start_time(12)
+    );
+
+    let err = Scenario::parse("version 1\n0\tm.map\t8\t8\t0\t0\t1\t0\t1\tsoon\n").unwrap_err();
+    assert!(matches!(err, ScenarioError::MalformedEntry { line: 2, .. }));
+}
+
+#[test]
+fn scenario_annotations() {
+    let annotated = "version 1\n\
+                     0\tm.map\t8\t8\t0\t0\t1\t0\t1.00000000\t0\tid=scout\tgroup=red\tpriority=2\n\
+                     0\tm.map\t8\t8\t2\t2\t3\t2\t1.00000000\t4\n";
+    let scen = Scenario::parse(annotated).unwrap();
+    assert_eq!(
+        scen.entries()[0],
+        ScenarioEntry::new("m.map", 8, 8)
+            .with_goal(1, 0)
+            .with_optimal_length(1.0)
+            .with_id("scout")
+            .with_group("red")
+            .with_priority(2)
+    );
+    assert!(!scen.entries()[1].is_annotated());
+    assert_eq!(scen.to_movingai(), annotated);
+
+    // Annotations may follow the ninth column directly
+    let scen = Scenario::parse("version 1\n0\tm.map\t8\t8\t0\t0\t1\t0\t1\tgroup=blue\n").unwrap();
+    assert_eq!(scen.entries()[0].start_time, 0);
+    assert_eq!(scen.entries()[0].group.as_deref(), Some("blue"));
+
+    for bad in ["rank=1", "priority=high", "scout"] {
+        let input = format!("version 1\n0\tm.map\t8\t8\t0\t0\t1\t0\t1\t0\t{bad}\n");
+        let err = Scenario::parse(&input).unwrap_err();
+        assert!(matches!(err, ScenarioError::MalformedEntry { line: 2, .. }));
+    }
+}
+
+#[test]
+fn scenario_parse_space_separated() {
+    // Layout of the older MovingAI files, including padded columns
+    let scen = Scenario::parse(
+        "version 1\n\
+         0 Berlin_1_256.map 256 256 191 66 190 64 2.82842712\n\
+         1  Berlin_1_256.map  256  256  12 201  16 197  5.65685425\n",
+    )
+    .unwrap();
+    assert_eq!(
+        scen.entries(),
+        &[
+            ScenarioEntry::new("Berlin_1_256.map", 256, 256)
+                .with_start(191, 66)
+                .with_goal(190, 64)
+                .with_optimal_length(2.82842712),
+            ScenarioEntry::new("Berlin_1_256.map", 256, 256)
+                .with_start(12, 201)
+                .with_goal(16, 197)
+                .with_optimal_length(5.65685425)
+                .with_bucket(1),
+        ]
+    );
+
+    // Tab-separated names keep their spaces
+    let scen = Scenario::parse("version 1\n0\tmy map.map\t8\t8\t0\t0\t1\t0\t1\n").unwrap();
+    assert_eq!(scen.entries()[0].map_name, "my map.map");
+}
+
+#[test]
+fn scenario_parse_lenient() {
+    let messy = "Version 1.0\r\n0 empty-8-8.map 8 8 0 0 1 0 1\r\n\
+                 0\tempty-8-8.map\t8\t8\t5\t3\t5\t6\t3.00000000  \r\n";
+    assert!(Scenario::parse(messy).is_err());
+    let scen = Scenario::parse_lenient(messy).unwrap();
+    assert_eq!(scen.version(), 1);
+    assert_eq!(
+        scen.entries(),
+        Scenario::parse(SIMPLE_SCEN).unwrap().entries()
+    );
+
+    assert!(matches!(
+        Scenario::parse_lenient("version 1.5\n"),
+        Err(ScenarioError::InvalidVersion(_))
+    ));
+    assert!(matches!(
+        Scenario::parse_lenient("version 1\n0 a.map 8 8 0 0\n"),
+        Err(ScenarioError::MalformedEntry { line: 2, .. })
+    ));
+}
+
+#[test]
+fn scenario_validate_against_map() {
+    let map = GridMap::parse(MAZE_SNIPPET).unwrap();
+    let fits = ScenarioEntry::new("maze.map", 6, 4)
+        .with_start(1, 1)
+        .with_goal(4, 2);
+    let scen = Scenario::new(
+        1,
+        vec![
+            fits.clone(),
+            ScenarioEntry::new("maze.map", 8, 8)
+                .with_start(1, 1)
+                .with_goal(4, 1),
+            fits.clone().with_start(2, 2).with_goal(6, 1),
+        ],
+    );
+
+    assert!(Scenario::new(1, vec![fits])
+        .validate_against(&map)
+        .is_empty());
+    let errors = scen.validate_against(&map);
+    assert_eq!(
+        errors,
+        vec![
+            EntryError::SizeMismatch {
+                entry: 1,
+                declared_width: 8,
+                declared_height: 8,
+                width: 6,
+                height: 4,
+            },
+            EntryError::Blocked {
+                entry: 2,
+                endpoint: Endpoint::Start,
+                x: 2,
+                y: 2,
+            },
+            EntryError::OutOfBounds {
+                entry: 2,
+                endpoint: Endpoint::Goal,
+                x: 6,
+                y: 1,
+            },
+        ]
+    );
+    assert_eq!(errors[1].entry(), 2);
+    assert_eq!(errors[1].to_string(), "entry 2: start (2,2) is blocked");
+}
+
+#[test]
+fn scenario_buckets_and_filter() {
+    let entry = |bucket, x| {
+        ScenarioEntry::new("empty-8-8.map", 8, 8)
+            .with_start(x, 0)
+            .with_bucket(bucket)
+    };
+    let scen = Scenario::new(3, vec![entry(2, 0), entry(0, 1), entry(2, 2), entry(5, 3)]);
+
+    assert_eq!(scen.buckets(), vec![0, 2, 5]);
+    let starts: Vec<u32> = scen.entries_in_bucket(2).map(|e| e.start_x).collect();
+    assert_eq!(starts, vec![0, 2]);
+    assert_eq!(scen.entries_in_bucket(1).count(), 0);
+
+    let easy = scen.filter(|e| e.bucket < 3);
+    assert_eq!(easy.version(), 3);
+    assert_eq!(easy.entries(), &scen.entries()[..3]);
+    assert!(scen.filter(|_| false).entries().is_empty());
+}
+
+#[test]
+fn scenario_agents_n() {
+    let entry = |start: (u32, u32), goal: (u32, u32)| {
+        ScenarioEntry::new("empty-8-8.map", 8, 8)
+            .with_start(start.0, start.1)
+            .with_goal(goal.0, goal.1)
+    };
+    let scen = Scenario::new(
+        1,
+        vec![
+            entry((0, 0), (7, 7)),
+            entry((1, 0), (6, 7)),
+            entry((2, 0), (7, 7)),
+            entry((0, 0), (5, 7)),
+        ],
+    );
+
+    let (starts, goals) = scen.agents_n(2).unwrap();
+    assert_eq!(starts, vec![(0, 0), (1, 0)]);
+    assert_eq!(goals, vec![(7, 7), (6, 7)]);
+    assert_eq!(scen.agents_n(0).unwrap(), (vec![], vec![]));
+
+    assert_eq!(
+        scen.agents_n(4),
+        Err(AgentsError::Collision {
+            first: 0,
+            second: 3,
+            endpoint: Endpoint::Start,
+            x: 0,
+            y: 0,
+        })
+    );
+    let err = scen.agents_n(3).unwrap_err();
+    assert_eq!(err.to_string(), "agents 0 and 2 share the goal (7,7)");
+    assert_eq!(
+        scen.agents_n(5),
+        Err(AgentsError::TooFew {
+            requested: 5,
+            available: 4,
+        })
+    );
+}
+
+#[test]
+fn scenario_windows() {
+    let entry = |i: u32| {
+        ScenarioEntry::new("empty-8-8.map", 8, 8)
+            .with_start(i % 8, 0)
+            .with_goal(i % 8, 7)
+    };
+    let scen = Scenario::new(1, (0..11).map(entry).collect());
+
+    let windows: Vec<_> = scen.windows(3).collect();
+    assert_eq!(windows.len(), 3);
+    let (starts, goals) = windows[1].clone().unwrap();
+    assert_eq!(starts, vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]);
+    assert_eq!(goals[5], (5, 7));
+    // Agent 8 starts where agent 0 does: the window of 9 fails, then stops
+    assert_eq!(
+        windows[2],
+        Err(AgentsError::Collision {
+            first: 0,
+            second: 8,
+            endpoint: Endpoint::Start,
+            x: 0,
+            y: 0,
+        })
+    );
+
+    let sizes: Vec<usize> = scen.windows(4).map(|w| w.unwrap().0.len()).collect();
+    assert_eq!(sizes, vec![4, 8]);
+    assert_eq!(scen.windows(0).count(), 0);
+    assert_eq!(scen.windows(12).count(), 0);
+}
@@ -0,0 +1,107 @@
+//! Tests for free-space partitions.
+
+use std::collections::{HashSet, VecDeque};
+
+use mapf_core::{Coordinate, GridMap, Partition};
+use pretty_assertions::assert_eq;
+
+fn empty(size: u32) -> GridMap {
+    let row = ".".repeat(size as usize);
+    let rows = vec![row; size as usize].join("\n");
+    GridMap::parse(&format!(
+        "type octile\nheight {size}\nwidth {size}\nmap\n{rows}\n"
+    ))
+    .unwrap()
+}
+
+/// Whether the cells of `region` form one 4-connected piece.
+fn is_contiguous(partition: &Partition, region: u32) -> bool {
+    let cells: HashSet<Coordinate> = partition.cells(region).collect();
+    let Some(&start) = cells.iter().next() else {
+        return false;
+    };
+    let mut seen = HashSet::from([start]);
+    let mut queue = VecDeque::from([start]);
+    while let Some(c) = queue.pop_front() {
+        let steps = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+        for (dx, dy) in steps {
+            let next = Coordinate {
+                x: c.x.wrapping_add_signed(dx),
+                y: c.y.wrapping_add_signed(dy),
+            };
+            if cells.contains(&next) && seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    seen.len() == cells.len()
+}
+
+#[test]
+fn regions_are_balanced_and_contiguous() {
+    let map = empty(16);
+    let partition = map.partition(4);
+    assert_eq!(partition.len(), 4);
+
+    let sizes: Vec<usize> = (0..4).map(|r| partition.size(r)).collect();
+    assert_eq!(sizes.iter().sum::<usize>(), 256);
+    for (region, &size) in sizes.iter().enumerate() {
+        assert!(
+            (48..=80).contains(&size),
+            "region {region} has {size} cells"
+        );
+        assert!(is_contiguous(&partition, region as u32));
+        assert_eq!(partition.cells(region as u32).count(), size);
+    }
+}
+
+#[test]
+fn adjacency_matches_shared_edges() {
+    let map = empty(12);
+    let partition = map.partition(5);
+    let mut expected = vec![Vec::new(); partition.len()];
+    for y in 0..12 {
+        for x in 0..12 {
+            let a = partition.region(x, y).unwrap();
+            for (nx, ny) in [(x + 1, y), (x, y + 1)] {
+                if let Some(b) = partition.region(nx, ny).filter(|&b| b != a) {
+                    expected[a as usize].push(b);
+                    expected[b as usize].push(a);
+                }
+            }
+        }
+    }
+    for (region, mut neighbors) in expected.into_iter().enumerate() {
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        assert!(!neighbors.is_empty());
+        assert_eq!(partition.neighbors(region as u32), neighbors.as_slice());
+    }
+}
+
+#[test]
+fn separate_areas_get_their_own_regions() {
+    // A 6-cell area on the left, a 2-cell one on the right
+    let map = GridMap::parse("type octile\nheight 2\nwidth 5\nmap\n...@.\n...@.\n").unwrap();
+    assert_eq!(map.partition(1).len(), 2);
+
+    let partition = map.partition(4);
+    assert_eq!(partition.len(), 4);
+    let right = partition.region(4, 0).unwrap();
+    assert_eq!(partition.region(4, 1), Some(right));
+    assert_eq!(partition.size(right), 2);
+    assert!(partition.neighbors(right).is_empty());
+    assert_eq!(partition.region(3, 0), None);
+    assert_eq!(partition.region(5, 0), None);
+}
+
+#[test]
+fn no_more_regions_than_free_cells() {
+    let map = GridMap::parse("type octile\nheight 1\nwidth 4\nmap\n.@..\n").unwrap();
+    let partition = map.partition(10);
+    assert_eq!(partition.len(), 3);
+    assert!((0..3).all(|r| partition.size(r) == 1));
+
+    let blocked = GridMap::parse("type octile\nheight 1\nwidth 2\nmap\n@@\n").unwrap();
+    assert!(blocked.partition(3).is_empty());
+}
@@ -0,0 +1,49 @@
+//! Tests for PNG map images.
+#![cfg(feature = "png")]
+
+use mapf_core::{GridMap, Palette, Tile};
+use pretty_assertions::assert_eq;
+
+const TERRAIN: &str = "type octile\nheight 2\nwidth 4\nmap\n.@SW\nT..@\n";
+
+#[test]
+fn black_and_white_round_trip() {
+    let map = GridMap::parse(TERRAIN).unwrap();
+    let image = map.to_png().unwrap();
+    assert_eq!(&image[1..4], b"PNG");
+
+    let read = GridMap::from_png(&image).unwrap();
+    assert_eq!((read.width(), read.height()), (4, 2));
+    assert_eq!(read.to_bytes(), map.to_bytes());
+    assert!(read.is_compact());
+}
+
+#[test]
+fn palette_round_trip_keeps_terrain() {
+    let map = GridMap::parse(TERRAIN).unwrap();
+    let palette = Palette::default();
+    let image = map.to_png_with_palette(&palette).unwrap();
+
+    let read = GridMap::from_png_with_palette(&image, &palette).unwrap();
+    assert_eq!(read.to_movingai(), map.to_movingai());
+
+    // Without the palette, the darker terrain colors read as obstacles
+    let plain = GridMap::from_png(&image).unwrap();
+    assert_eq!(plain.get(2, 0), Some(Tile::Blocked));
+    assert_eq!(plain.get(3, 0), Some(Tile::Blocked));
+    assert_eq!(plain.get(1, 1), Some(Tile::Passable));
+}
+
+#[test]
+fn nearest_palette_color_wins() {
+    let palette = Palette::default();
+    assert_eq!(palette.nearest([250, 250, 240]), Tile::Passable);
+    assert_eq!(palette.nearest([20, 20, 30]), Tile::Blocked);
+    assert_eq!(palette.nearest([0, 120, 255]), Tile::Water);
+    assert_eq!(palette.color(Tile::Trees), palette.trees);
+}
+
+#[test]
+fn rejects_non_png_data() {
+    assert!(GridMap::from_png(b"not an image").is_err());
+}
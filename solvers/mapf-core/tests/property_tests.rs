@@ -0,0 +1,47 @@
+//! Property tests over generated maps, scenarios and instances.
+#![cfg(feature = "proptest")]
+
+use mapf_core::{GridMap, Instance, Scenario, ScenarioEntry};
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn maps_round_trip_through_movingai(map in any::<GridMap>()) {
+        let parsed = GridMap::parse(&map.to_movingai()).unwrap();
+        prop_assert_eq!(parsed.fingerprint(), map.fingerprint());
+    }
+
+    #[test]
+    fn scenarios_round_trip_through_movingai(
+        entries in proptest::collection::vec(any::<ScenarioEntry>(), 0..16),
+    ) {
+        let written = Scenario::new(1, entries.clone()).to_movingai();
+        let parsed = Scenario::parse(&written).unwrap();
+        prop_assert_eq!(parsed.to_movingai(), written);
+        for (parsed, entry) in parsed.entries().iter().zip(&entries) {
+            prop_assert_eq!((parsed.start_x, parsed.start_y), (entry.start_x, entry.start_y));
+            prop_assert_eq!(parsed.start_time, entry.start_time);
+            prop_assert_eq!(&parsed.id, &entry.id);
+            prop_assert_eq!(&parsed.group, &entry.group);
+            prop_assert_eq!(parsed.priority, entry.priority);
+        }
+    }
+
+    #[test]
+    fn generated_instances_fit_their_map(instance in any::<Instance>()) {
+        prop_assert_eq!(instance.starts.len(), instance.goals.len());
+        for c in instance.starts.iter().chain(&instance.goals) {
+            prop_assert!(instance.map.is_passable(c.x, c.y));
+        }
+    }
+
+    #[test]
+    fn canonical_form_is_idempotent(instance in any::<Instance>()) {
+        let canonical = instance.canonicalize();
+        prop_assert_eq!(canonical.instance.canonicalize().encode(), canonical.encode());
+        prop_assert_eq!(
+            canonical.agents.len() + canonical.unreachable.len(),
+            instance.num_agents()
+        );
+    }
+}
@@ -0,0 +1,42 @@
+//! Tests for solution canonicalization.
+
+use mapf_core::{Coordinate, Path, Solution};
+use pretty_assertions::assert_eq;
+
+fn path(cells: &[(u32, u32)]) -> Path {
+    Path {
+        steps: cells.iter().map(|&(x, y)| Coordinate { x, y }).collect(),
+    }
+}
+
+#[test]
+fn canonicalize_trims_trailing_waits() {
+    let solution = Solution {
+        paths: vec![
+            path(&[(0, 0), (1, 0), (1, 0), (1, 0)]),
+            path(&[(2, 2), (2, 2)]),
+            path(&[]),
+        ],
+    };
+    let canonical = solution.canonicalize();
+    assert_eq!(canonical.paths[0], path(&[(0, 0), (1, 0)]));
+    assert_eq!(canonical.paths[1], path(&[(2, 2)]));
+    assert_eq!(canonical.paths[2], path(&[]));
+}
+
+#[test]
+fn canonicalize_keeps_intermediate_waits() {
+    let p = path(&[(0, 0), (0, 0), (1, 0)]);
+    assert_eq!(p.trimmed(), p);
+}
+
+#[test]
+fn equivalent_solutions_canonicalize_equal() {
+    let a = Solution {
+        paths: vec![path(&[(0, 0), (1, 0)]), path(&[(3, 0), (2, 0), (2, 1)])],
+    };
+    let padded = a.padded();
+    assert_eq!(padded.paths[0], path(&[(0, 0), (1, 0), (1, 0)]));
+    assert_ne!(padded, a);
+    assert_eq!(padded.canonicalize(), a.canonicalize());
+}
@@ -0,0 +1,146 @@
+//! Tests for geometric map transforms and sanitation.
+
+use mapf_core::{Coordinate, GridMap, Rect, Tile};
+use pretty_assertions::assert_eq;
+
+/// 3x2 map:
+/// ```text
+/// .@S
+/// ..T
+/// ```
+fn map() -> GridMap {
+    GridMap::parse("type octile\nheight 2\nwidth 3\nmap\n.@S\n..T\n").unwrap()
+}
+
+/// Map rows after the header.
+fn rows(map: &GridMap) -> Vec<String> {
+    map.to_ascii().lines().map(String::from).collect()
+}
+
+#[test]
+fn crop_keeps_the_rectangle() {
+    let rect = Rect {
+        x: 1,
+        y: 0,
+        width: 2,
+        height: 2,
+    };
+    let cropped = map().crop(rect).unwrap();
+    assert_eq!((cropped.width(), cropped.height()), (2, 2));
+    assert_eq!(rows(&cropped), vec!["@S", ".T"]);
+
+    assert!(map().crop(Rect { width: 3, ..rect }).is_none());
+    assert!(map().crop(Rect { height: 0, ..rect }).is_none());
+    assert!(map()
+        .crop(Rect {
+            x: u32::MAX,
+            ..rect
+        })
+        .is_none());
+}
+
+#[test]
+fn rotate_turns_clockwise() {
+    let rotated = map().rotate90();
+    assert_eq!((rotated.width(), rotated.height()), (2, 3));
+    assert_eq!(rows(&rotated), vec!["..", ".@", "TS"]);
+
+    let full_turn = rotated.rotate90().rotate90().rotate90();
+    assert_eq!(full_turn.to_movingai(), map().to_movingai());
+}
+
+#[test]
+fn mirrors_flip_each_axis() {
+    assert_eq!(rows(&map().mirror_horizontal()), vec!["S@.", "T.."]);
+    assert_eq!(rows(&map().mirror_vertical()), vec!["..T", ".@S"]);
+}
+
+#[test]
+fn scale_blows_up_cells() {
+    let scaled = map().scale(2).unwrap();
+    assert_eq!((scaled.width(), scaled.height()), (6, 4));
+    assert_eq!(rows(&scaled), vec!["..@@SS", "..@@SS", "....TT", "....TT"]);
+    assert_eq!(scaled.get(5, 1), Some(Tile::Swamp));
+
+    assert!(map().scale(0).is_none());
+    assert!(map().scale(u32::MAX).is_none());
+}
+
+#[test]
+fn transforms_carry_costs() {
+    let weighted = map().with_costs(vec![1, 2, 3, 4, 5, 6]).unwrap();
+    assert_eq!(weighted.rotate90().costs(), Some(&[4, 1, 5, 2, 6, 3][..]));
+    assert_eq!(
+        weighted.mirror_horizontal().costs(),
+        Some(&[3, 2, 1, 6, 5, 4][..])
+    );
+    assert_eq!(weighted.scale(2).unwrap().cell_cost(3, 3), 5);
+    assert_eq!(weighted.rotate90().map_type(), "octile");
+}
+
+#[test]
+fn coarsen_merges_blocks() {
+    let fine =
+        GridMap::parse("type octile\nheight 3\nwidth 5\nmap\n..@@.\n.@@@.\n....@\n").unwrap();
+    let coarse = fine.coarsen(2).unwrap();
+    assert_eq!(coarse.factor(), 2);
+    assert_eq!(rows(coarse.map()), vec![".@.", "..@"]);
+
+    // The top-left block is only three quarters free
+    let strict = fine.coarsen_with_threshold(2, 1.0).unwrap();
+    assert_eq!(rows(strict.map()), vec!["@@.", "..@"]);
+
+    assert!(fine.coarsen(0).is_none());
+    assert_eq!(rows(fine.coarsen(1).unwrap().map()), rows(&fine));
+}
+
+#[test]
+fn coarsen_maps_between_resolutions() {
+    let coarse = map().coarsen(2).unwrap();
+    assert_eq!(coarse.to_coarse(2, 1), Some(Coordinate { x: 1, y: 0 }));
+    assert_eq!(coarse.to_coarse(3, 0), None);
+    assert_eq!(
+        coarse.to_fine(1, 0),
+        Some(Rect {
+            x: 2,
+            y: 0,
+            width: 1,
+            height: 2,
+        })
+    );
+    assert_eq!(coarse.to_fine(0, 1), None);
+}
+
+#[test]
+fn border_walls_surround_the_map() {
+    let walled = map().with_border_walls();
+    assert_eq!(rows(&walled), vec!["@@@@@", "@.@S@", "@..T@", "@@@@@"]);
+
+    let weighted = map().with_costs(vec![1, 0, 3, 2, 5, 0]).unwrap();
+    assert_eq!(weighted.with_border_walls().cell_cost(2, 2), 5);
+    assert_eq!(weighted.with_border_walls().cell_cost(0, 0), 1);
+}
+
+#[test]
+fn sanitizing_blocks_stray_pockets() {
+    let map = GridMap::from_ascii(
+        "
+        ..@..
+        ..@@.
+        @@@.@
+        .@@@S
+        ",
+    )
+    .unwrap();
+
+    let seed = |x, y| map.fill_unreachable_from(Coordinate { x, y });
+    assert_eq!(
+        rows(&seed(0, 0).unwrap()),
+        vec!["..@@@", "..@@@", "@@@@@", "@@@@@"]
+    );
+    assert!(seed(2, 0).is_none());
+    assert!(seed(9, 0).is_none());
+
+    let cleaned = map.remove_isolated_cells();
+    assert_eq!(rows(&cleaned), vec!["..@..", "..@@.", "@@@@@", "@@@@@"]);
+}
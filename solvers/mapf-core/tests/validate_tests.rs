@@ -0,0 +1,180 @@
+//! Tests for the client-side solution checks.
+
+use mapf_core::validate::{
+    check_collisions_with, check_dynamic_obstacles, validate, validate_with, ViolationKind,
+};
+use mapf_core::{Coordinate, DynamicObstacles, GridMap, Path, Solution, TimedObstacle};
+use pretty_assertions::assert_eq;
+
+fn path(cells: &[(u32, u32)]) -> Path {
+    Path {
+        steps: cells.iter().map(|&(x, y)| Coordinate { x, y }).collect(),
+    }
+}
+
+fn cells(cells: &[(u32, u32)]) -> Vec<Coordinate> {
+    cells.iter().map(|&(x, y)| Coordinate { x, y }).collect()
+}
+
+/// 3x3 grid with the center blocked
+fn ring_3x3() -> GridMap {
+    GridMap::from_ascii("...\n.@.\n...").unwrap()
+}
+
+fn kinds(
+    map: &GridMap,
+    starts: &[(u32, u32)],
+    goals: &[(u32, u32)],
+    paths: Vec<Path>,
+) -> Vec<ViolationKind> {
+    validate(map, &cells(starts), &cells(goals), &Solution { paths })
+        .into_iter()
+        .map(|v| v.kind)
+        .collect()
+}
+
+#[test]
+fn valid_solution_has_no_violations() {
+    let paths = vec![
+        path(&[(0, 0), (1, 0), (2, 0)]),
+        path(&[(2, 2), (2, 2), (2, 1)]),
+    ];
+    assert_eq!(
+        kinds(&ring_3x3(), &[(0, 0), (2, 2)], &[(2, 0), (2, 1)], paths),
+        vec![]
+    );
+}
+
+#[test]
+fn per_path_violations() {
+    let map = ring_3x3();
+    assert_eq!(
+        kinds(&map, &[(0, 0)], &[(1, 1)], vec![path(&[(0, 0), (1, 1)])]),
+        vec![ViolationKind::DiagonalMove, ViolationKind::BlockedCell]
+    );
+    assert_eq!(
+        kinds(
+            &map,
+            &[(0, 0)],
+            &[(3, 0)],
+            vec![path(&[(1, 0), (2, 0), (3, 0)])]
+        ),
+        vec![ViolationKind::OutOfBounds, ViolationKind::InvalidStart]
+    );
+    assert_eq!(
+        kinds(&map, &[(0, 0)], &[(2, 0)], vec![path(&[(0, 0), (1, 0)])]),
+        vec![ViolationKind::InvalidGoal]
+    );
+    assert_eq!(
+        kinds(&map, &[(0, 0), (2, 2)], &[(0, 0), (2, 2)], vec![path(&[])]),
+        vec![ViolationKind::AgentCountMismatch, ViolationKind::EmptyPath]
+    );
+}
+
+#[test]
+fn collisions_include_finished_agents() {
+    let map = ring_3x3();
+    // Agent 1 finishes at (1,0) and waits there as agent 0 passes through
+    let violations = validate(
+        &map,
+        &cells(&[(0, 0), (1, 0)]),
+        &cells(&[(2, 0), (1, 0)]),
+        &Solution {
+            paths: vec![path(&[(0, 0), (1, 0), (2, 0)]), path(&[(1, 0)])],
+        },
+    );
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind, ViolationKind::VertexCollision);
+    assert_eq!(violations[0].agents, vec![0, 1]);
+    assert_eq!(violations[0].timestep, Some(1));
+    assert_eq!(violations[0].positions, cells(&[(1, 0)]));
+    assert_eq!(
+        violations[0].details,
+        "Agents 0 and 1 collide at (1,0) at timestep 1"
+    );
+}
+
+#[test]
+fn finished_agents_can_disappear() {
+    let map = ring_3x3();
+    let (starts, goals) = (cells(&[(0, 0), (1, 0)]), cells(&[(2, 0), (1, 0)]));
+    let passing = path(&[(0, 0), (1, 0), (2, 0)]);
+    // Agent 1 leaves the map at timestep 0, before agent 0 gets there
+    let gone = Solution {
+        paths: vec![passing.clone(), path(&[(1, 0)])],
+    };
+    assert_eq!(validate_with(&map, &starts, &goals, &gone, true), vec![]);
+    assert_eq!(validate(&map, &starts, &goals, &gone).len(), 1);
+
+    // Still there at timestep 1
+    let waiting = vec![passing, path(&[(1, 0), (1, 0)])];
+    let violations = check_collisions_with(&waiting, true);
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind, ViolationKind::VertexCollision);
+    assert_eq!(violations[0].timestep, Some(1));
+}
+
+#[test]
+fn swaps_are_edge_collisions() {
+    let violations = validate(
+        &ring_3x3(),
+        &cells(&[(0, 0), (1, 0)]),
+        &cells(&[(1, 0), (0, 0)]),
+        &Solution {
+            paths: vec![path(&[(0, 0), (1, 0)]), path(&[(1, 0), (0, 0)])],
+        },
+    );
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind, ViolationKind::EdgeCollision);
+    assert_eq!(violations[0].kind.code(), 301);
+    assert_eq!(violations[0].agents, vec![0, 1]);
+    assert_eq!(violations[0].positions, cells(&[(0, 0), (1, 0)]));
+}
+
+#[test]
+fn dynamic_obstacles_block_cells_while_active() {
+    let obstacles = DynamicObstacles::new(vec![
+        TimedObstacle {
+            x: 1,
+            y: 0,
+            from: 1,
+            until: Some(2),
+        },
+        TimedObstacle {
+            x: 2,
+            y: 0,
+            from: 5,
+            until: None,
+        },
+    ]);
+
+    // Steps onto (1,0) at t=2, just after the obstacle left
+    let ok = check_dynamic_obstacles(&path(&[(0, 0), (0, 0), (1, 0)]), 0, &obstacles);
+    assert!(ok.is_empty());
+
+    let hits = check_dynamic_obstacles(&path(&[(0, 0), (1, 0), (2, 0)]), 3, &obstacles);
+    let found: Vec<_> = hits
+        .iter()
+        .map(|v| (v.kind, v.agents.clone(), v.timestep, v.positions.clone()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                ViolationKind::DynamicObstacle,
+                vec![3],
+                Some(1),
+                cells(&[(1, 0)])
+            ),
+            // Waiting at the goal when the permanent obstacle appears
+            (
+                ViolationKind::DynamicObstacle,
+                vec![3],
+                Some(5),
+                cells(&[(2, 0)])
+            ),
+        ]
+    );
+    assert_eq!(hits[0].kind.code(), 106);
+    assert_eq!(hits[0].kind.as_str(), "dynamic_obstacle");
+}
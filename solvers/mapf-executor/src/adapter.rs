@@ -0,0 +1,483 @@
+//! Adapters for every shape of the solver interface the arena has published
+//! Calls components with the `solve` signature they were built against and
+//! translates results to current types, so old submissions survive upgrades
+
+use anyhow::{anyhow, Context, Result};
+use wasmtime::component::{
+    ComponentExportIndex, ComponentNamedList, ComponentType, Instance, Lift, Lower, TypedFunc,
+};
+use wasmtime::Store;
+
+use crate::validation::{Coordinate, GridMap, Path, Solution, TimedObstacle};
+
+/// Interface version of record-based components without a `wit-version` export
+const WIT_VERSION_LEGACY: u32 = 1;
+/// Interface version that added `deadline-ms`
+const WIT_VERSION_DEADLINE: u32 = 2;
+/// Interface version that added terrain `costs`
+const WIT_VERSION_COSTS: u32 = 3;
+/// Interface version that added `obstacles`
+const WIT_VERSION_OBSTACLES: u32 = 4;
+/// Interface version that added `waypoints`
+const WIT_VERSION_WAYPOINTS: u32 = 5;
+/// Interface version that added `seed`
+const WIT_VERSION_SEED: u32 = 6;
+/// Newest interface version the adapters can drive (adds `low-level-calls`
+/// to `stats`)
+const WIT_VERSION_CURRENT: u32 = 7;
+
+/// Instance export the `mapf-solver` world puts its functions in
+const SOLVER_INTERFACE: &str = "mapf:solver/solver@0.1.0";
+
+/// WIT `coordinate` record
+#[derive(ComponentType, Lift, Lower)]
+#[component(record)]
+struct WitCoordinate {
+    x: u32,
+    y: u32,
+}
+
+impl From<&Coordinate> for WitCoordinate {
+    fn from(c: &Coordinate) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+impl From<WitCoordinate> for Coordinate {
+    fn from(c: WitCoordinate) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+/// WIT `timed-obstacle` record
+#[derive(ComponentType, Lower)]
+#[component(record)]
+struct WitTimedObstacle {
+    cell: WitCoordinate,
+    appears: u32,
+    disappears: Option<u32>,
+}
+
+impl From<&TimedObstacle> for WitTimedObstacle {
+    fn from(obstacle: &TimedObstacle) -> Self {
+        Self {
+            cell: WitCoordinate {
+                x: obstacle.x,
+                y: obstacle.y,
+            },
+            appears: obstacle.from as u32,
+            disappears: obstacle.until.map(|t| t as u32),
+        }
+    }
+}
+
+/// WIT `path` record
+#[derive(ComponentType, Lift)]
+#[component(record)]
+struct WitPath {
+    steps: Vec<WitCoordinate>,
+}
+
+/// WIT `solution` record
+#[derive(ComponentType, Lift)]
+#[component(record)]
+struct WitSolution {
+    paths: Vec<WitPath>,
+    cost: u64,
+}
+
+/// Return type of the record-based `solve` functions
+type SolveResult = (Result<WitSolution, String>,);
+
+/// Coordinate of the pre-record interface: `tuple<s32, s32>`
+type TupleCoordinate = (i32, i32);
+
+/// Return type of the pre-record `solve`: bare paths, no declared cost
+type TupleSolveResult = (Result<Vec<Vec<TupleCoordinate>>, String>,);
+
+/// Parameters of the version 1 `solve`
+type LegacyParams = (Vec<u8>, u32, u32, Vec<WitCoordinate>, Vec<WitCoordinate>);
+
+/// Parameters of the pre-record `solve`
+type TupleParams = (
+    Vec<u8>,
+    u32,
+    u32,
+    Vec<TupleCoordinate>,
+    Vec<TupleCoordinate>,
+);
+
+/// Inputs of one solve call, in terms of the current interface
+pub struct SolveArgs<'a> {
+    pub map: &'a GridMap,
+    pub starts: &'a [Coordinate],
+    pub goals: &'a [Coordinate],
+    pub waypoints: &'a [Vec<Coordinate>],
+    pub obstacles: &'a [TimedObstacle],
+    pub seed: u64,
+    pub deadline_ms: u64,
+}
+
+/// A solution returned by the guest, translated to current types
+pub struct GuestSolution {
+    pub solution: Solution,
+    /// Cost the solver claims; shapes without `cost` declare none
+    pub declared_cost: Option<u64>,
+}
+
+impl From<WitSolution> for GuestSolution {
+    fn from(wit_solution: WitSolution) -> Self {
+        Self {
+            solution: Solution {
+                paths: wit_solution
+                    .paths
+                    .into_iter()
+                    .map(|path| Path {
+                        steps: path.steps.into_iter().map(Coordinate::from).collect(),
+                    })
+                    .collect(),
+            },
+            declared_cost: Some(wit_solution.cost),
+        }
+    }
+}
+
+impl GuestSolution {
+    fn from_tuples(paths: Vec<Vec<TupleCoordinate>>) -> Self {
+        Self {
+            solution: Solution {
+                paths: paths
+                    .into_iter()
+                    .map(|steps| Path {
+                        // Negative coordinates wrap and fail bounds checks
+                        steps: steps
+                            .into_iter()
+                            .map(|(x, y)| Coordinate {
+                                x: x as u32,
+                                y: y as u32,
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            },
+            declared_cost: None,
+        }
+    }
+}
+
+/// Result of a `solve` call: a trap, or the solver's own result
+pub type SolveOutcome = wasmtime::Result<Result<GuestSolution, String>>;
+
+/// Shape of the solver interface a component was built against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    /// Pre-record interface: coordinates as `tuple<s32, s32>`, paths as
+    /// bare lists and no declared cost
+    Tuples,
+    /// Record-based interface of the given version
+    Records(u32),
+}
+
+impl Shape {
+    /// Recognize the component's interface: from its `wit-version` export
+    /// if it has one, otherwise from the signature of `solve`.
+    pub async fn detect<T: Send>(instance: &Instance, store: &mut Store<T>) -> Result<Self> {
+        if let Ok(wit_version) = typed_func::<T, (), (u32,)>(instance, store, "wit-version") {
+            let (version,) = wit_version
+                .call_async(&mut *store, ())
+                .await
+                .context("Failed to query interface version")?;
+            wit_version.post_return_async(&mut *store).await?;
+            if !(WIT_VERSION_LEGACY..=WIT_VERSION_CURRENT).contains(&version) {
+                return Err(anyhow!(
+                    "Solver targets WIT version {}, but this server supports versions {} to {}",
+                    version,
+                    WIT_VERSION_LEGACY,
+                    WIT_VERSION_CURRENT
+                ));
+            }
+            return Ok(Shape::Records(version));
+        }
+
+        if typed_func::<T, LegacyParams, SolveResult>(instance, store, "solve").is_ok() {
+            Ok(Shape::Records(WIT_VERSION_LEGACY))
+        } else if typed_func::<T, TupleParams, TupleSolveResult>(instance, store, "solve").is_ok() {
+            Ok(Shape::Tuples)
+        } else {
+            Err(anyhow!(
+                "Solver's solve export matches no supported interface shape"
+            ))
+        }
+    }
+
+    /// Whether the shape's `get-stats` record has `low-level-calls`.
+    pub fn reports_low_level_calls(self) -> bool {
+        matches!(self, Shape::Records(version) if version > WIT_VERSION_SEED)
+    }
+
+    /// Reject instances this shape has no way to pass to the solver.
+    pub fn check_supports(self, args: &SolveArgs<'_>) -> Result<()> {
+        let (version, name) = match self {
+            Shape::Tuples => (0, "the tuple-based interface".to_string()),
+            Shape::Records(version) => (version, format!("WIT version {}", version)),
+        };
+        if args.map.is_weighted() && version < WIT_VERSION_COSTS {
+            return Err(anyhow!(
+                "Solver targets {}, which predates terrain costs; weighted maps need version {}",
+                name,
+                WIT_VERSION_COSTS
+            ));
+        }
+        if !args.obstacles.is_empty() && version < WIT_VERSION_OBSTACLES {
+            return Err(anyhow!(
+                "Solver targets {}, which predates dynamic obstacles; dynamic environments need version {}",
+                name,
+                WIT_VERSION_OBSTACLES
+            ));
+        }
+        if args.waypoints.iter().any(|w| !w.is_empty()) && version < WIT_VERSION_WAYPOINTS {
+            return Err(anyhow!(
+                "Solver targets {}, which predates waypoints; multi-goal tasks need version {}",
+                name,
+                WIT_VERSION_WAYPOINTS
+            ));
+        }
+        Ok(())
+    }
+
+    /// Call `solve` with this shape's signature.
+    ///
+    /// Also reports whether the call returned normally; a trapped instance
+    /// can't be called again.
+    pub async fn solve<T: Send>(
+        self,
+        instance: &Instance,
+        store: &mut Store<T>,
+        args: SolveArgs<'_>,
+    ) -> Result<(SolveOutcome, bool)> {
+        let map_data = args.map.to_bytes();
+        let (width, height) = (args.map.width(), args.map.height());
+        let coordinates =
+            |cs: &[Coordinate]| cs.iter().map(WitCoordinate::from).collect::<Vec<_>>();
+        let starts = coordinates(args.starts);
+        let goals = coordinates(args.goals);
+        let costs = || args.map.costs().map(<[u32]>::to_vec);
+        let waypoints = || {
+            args.waypoints
+                .iter()
+                .map(|w| coordinates(w))
+                .collect::<Vec<_>>()
+        };
+        let obstacles = || {
+            args.obstacles
+                .iter()
+                .map(WitTimedObstacle::from)
+                .collect::<Vec<_>>()
+        };
+
+        let (result, returned) = match self {
+            Shape::Tuples => {
+                // Based on the pre-record interface: solve(map-data, width, height, starts, goals) -> result<list<list<tuple<s32, s32>>>, string>
+                let tuples = |cs: &[Coordinate]| {
+                    cs.iter()
+                        .map(|c| (c.x as i32, c.y as i32))
+                        .collect::<Vec<_>>()
+                };
+                let params = (
+                    map_data,
+                    width,
+                    height,
+                    tuples(args.starts),
+                    tuples(args.goals),
+                );
+                let (result, returned) =
+                    call::<_, TupleParams, TupleSolveResult>(instance, store, params).await?;
+                let result = result.map(|(r,)| r.map(GuestSolution::from_tuples));
+                return Ok((result, returned));
+            }
+            Shape::Records(WIT_VERSION_LEGACY) => {
+                // Based on mapf-solver.wit v1: solve(map-data, width, height, starts, goals) -> result<solution, string>
+                let params = (map_data, width, height, starts, goals);
+                call::<_, LegacyParams, SolveResult>(instance, store, params).await?
+            }
+            Shape::Records(WIT_VERSION_DEADLINE) => {
+                // Based on mapf-solver.wit v2: solve(map-data, width, height, starts, goals, deadline-ms) -> result<solution, string>
+                let params = (map_data, width, height, starts, goals, args.deadline_ms);
+                call::<_, _, SolveResult>(instance, store, params).await?
+            }
+            Shape::Records(WIT_VERSION_COSTS) => {
+                // Based on mapf-solver.wit v3: solve(map-data, width, height, costs, starts, goals, deadline-ms) -> result<solution, string>
+                let params = (
+                    map_data,
+                    width,
+                    height,
+                    costs(),
+                    starts,
+                    goals,
+                    args.deadline_ms,
+                );
+                call::<_, _, SolveResult>(instance, store, params).await?
+            }
+            Shape::Records(WIT_VERSION_OBSTACLES) => {
+                // Based on mapf-solver.wit v4: solve(map-data, width, height, costs, starts, goals, obstacles, deadline-ms) -> result<solution, string>
+                let params = (
+                    map_data,
+                    width,
+                    height,
+                    costs(),
+                    starts,
+                    goals,
+                    obstacles(),
+                    args.deadline_ms,
+                );
+                call::<_, _, SolveResult>(instance, store, params).await?
+            }
+            Shape::Records(WIT_VERSION_WAYPOINTS) => {
+                // Based on mapf-solver.wit v5: solve(map-data, width, height, costs, starts, goals, waypoints, obstacles, deadline-ms) -> result<solution, string>
+                let params = (
+                    map_data,
+                    width,
+                    height,
+                    costs(),
+                    starts,
+                    goals,
+                    waypoints(),
+                    obstacles(),
+                    args.deadline_ms,
+                );
+                call::<_, _, SolveResult>(instance, store, params).await?
+            }
+            Shape::Records(_) => {
+                // Based on mapf-solver.wit: solve(map-data, width, height, costs, starts, goals, waypoints, obstacles, seed, deadline-ms) -> result<solution, string>
+                let params = (
+                    map_data,
+                    width,
+                    height,
+                    costs(),
+                    starts,
+                    goals,
+                    waypoints(),
+                    obstacles(),
+                    args.seed,
+                    args.deadline_ms,
+                );
+                call::<_, _, SolveResult>(instance, store, params).await?
+            }
+        };
+        Ok((result.map(|(r,)| r.map(GuestSolution::from)), returned))
+    }
+}
+
+/// Call the guest's `solve` with the given signature and clean up after it.
+async fn call<T, P, R>(
+    instance: &Instance,
+    store: &mut Store<T>,
+    params: P,
+) -> Result<(wasmtime::Result<R>, bool)>
+where
+    T: Send,
+    P: ComponentNamedList + Lower + Send + Sync,
+    R: ComponentNamedList + Lift + Send + Sync,
+{
+    let solve_fn =
+        typed_func::<T, P, R>(instance, store, "solve").context("Failed to get solve function")?;
+    let result = solve_fn.call_async(&mut *store, params).await;
+    let returned = result.is_ok() && solve_fn.post_return_async(&mut *store).await.is_ok();
+    Ok((result, returned))
+}
+
+/// Find a guest function by name.
+///
+/// Solver functions live inside the [`SOLVER_INTERFACE`] instance export;
+/// components that export them at the root are still found there.
+fn export_index<T>(
+    instance: &Instance,
+    store: &mut Store<T>,
+    name: &str,
+) -> Option<ComponentExportIndex> {
+    let interface = instance.get_export(&mut *store, None, SOLVER_INTERFACE);
+    interface
+        .and_then(|interface| instance.get_export(&mut *store, Some(&interface), name))
+        .or_else(|| instance.get_export(&mut *store, None, name))
+}
+
+/// Look up a guest function of the solver interface with the given signature.
+pub(crate) fn typed_func<T, P, R>(
+    instance: &Instance,
+    store: &mut Store<T>,
+    name: &str,
+) -> Result<TypedFunc<P, R>>
+where
+    P: ComponentNamedList + Lower,
+    R: ComponentNamedList + Lift,
+{
+    let index = export_index(instance, store, name)
+        .ok_or_else(|| anyhow!("Solver does not export `{}`", name))?;
+    instance.get_typed_func::<P, R>(&mut *store, &index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_older_shapes_refuse_newer_features() {
+        let map = GridMap::from_bytes(2, 1, &[1, 1]).unwrap();
+        let weighted = map.clone().with_costs(vec![1, 3]).unwrap();
+        let c = |x| Coordinate { x, y: 0 };
+        let waypoints = vec![vec![c(1)]];
+        let args = |map| SolveArgs {
+            map,
+            starts: &[],
+            goals: &[],
+            waypoints: &[],
+            obstacles: &[],
+            seed: 0,
+            deadline_ms: 0,
+        };
+
+        assert!(Shape::Tuples.check_supports(&args(&map)).is_ok());
+        assert!(Shape::Tuples.check_supports(&args(&weighted)).is_err());
+        assert!(Shape::Records(WIT_VERSION_COSTS)
+            .check_supports(&args(&weighted))
+            .is_ok());
+
+        let multi_goal = SolveArgs {
+            waypoints: &waypoints,
+            ..args(&map)
+        };
+        assert!(Shape::Records(WIT_VERSION_OBSTACLES)
+            .check_supports(&multi_goal)
+            .is_err());
+        assert!(Shape::Records(WIT_VERSION_CURRENT)
+            .check_supports(&multi_goal)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_detect_finds_functions_in_the_interface_export() {
+        // `export solver;` nests the functions in an instance export
+        let wat = r#"
+            (component
+                (core module $m
+                    (func (export "wit-version") (result i32) i32.const 7))
+                (core instance $i (instantiate $m))
+                (func $version (result u32) (canon lift (core func $i "wit-version")))
+                (instance $solver (export "wit-version" (func $version)))
+                (export "mapf:solver/solver@0.1.0" (instance $solver)))
+        "#;
+        let mut config = wasmtime::Config::new();
+        config.wasm_component_model(true);
+        config.async_support(true);
+        let engine = wasmtime::Engine::new(&config).unwrap();
+        let component = wasmtime::component::Component::new(&engine, wat).unwrap();
+        let mut store = Store::new(&engine, ());
+        let instance = wasmtime::component::Linker::new(&engine)
+            .instantiate_async(&mut store, &component)
+            .await
+            .unwrap();
+
+        let shape = Shape::detect(&instance, &mut store).await.unwrap();
+        assert_eq!(shape, Shape::Records(WIT_VERSION_CURRENT));
+    }
+}
@@ -0,0 +1,520 @@
+/// WASM Component Model executor using wasmtime
+/// Loads and executes MAPF solvers with instruction counting and timeout
+
+use anyhow::{Context, Result};
+use std::time::{Duration, Instant};
+use wasmtime::component::{Component, ComponentType, Lift, Linker, ResourceTable};
+use wasmtime::StoreContextMut;
+use wasmtime::*;
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
+
+use crate::adapter::{typed_func, Shape, SolveArgs};
+use crate::validation::{Coordinate, GridMap, Solution, TimedObstacle};
+
+/// Stats from solver execution
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SolverStats {
+    pub instruction_count: Option<u64>,
+    pub execution_time_ms: u64,
+    pub fuel_consumed: Option<u64>,
+    /// Seed the solver ran with; rerunning with it reproduces the result
+    pub seed: u64,
+    /// Search statistics reported by the solver itself (WIT `get-stats`)
+    pub guest: Option<GuestStats>,
+}
+
+/// Solver-reported search statistics. Unverified; for display and analysis only.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GuestStats {
+    pub nodes_expanded: u64,
+    pub nodes_generated: u64,
+    /// 0 if unknown, as for solvers built before WIT version 7
+    pub low_level_calls: u64,
+    pub peak_memory_bytes: u64,
+    pub time_us: u64,
+    pub agent_costs: Vec<u64>,
+}
+
+/// Result from solver execution
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SolverResult {
+    pub solution: Option<Solution>,
+    /// Cost the solver claims for its solution (WIT `solution.cost`)
+    pub declared_cost: Option<u64>,
+    pub error: Option<String>,
+    pub stats: SolverStats,
+    /// Messages the solver logged through the host `log` import
+    pub log: SolverLog,
+}
+
+/// Size cap on a run's log, counting message bytes plus a per-entry overhead
+const LOG_CAPACITY_BYTES: usize = 64 * 1024;
+
+/// Bytes each entry counts against the cap on top of its message
+const LOG_ENTRY_OVERHEAD_BYTES: usize = 64;
+
+/// A message logged by the solver
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LogEntry {
+    pub level: String,
+    pub message: String,
+}
+
+/// Size-capped log buffer filled by the guest's `log` calls
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SolverLog {
+    pub entries: Vec<LogEntry>,
+    /// Messages were dropped because the buffer was full
+    pub truncated: bool,
+    #[serde(skip)]
+    bytes: usize,
+}
+
+impl SolverLog {
+    /// Append a message, dropping it once the buffer is full.
+    fn push(&mut self, level: &str, message: String) {
+        let size = LOG_ENTRY_OVERHEAD_BYTES + message.len();
+        if self.bytes + size > LOG_CAPACITY_BYTES {
+            self.truncated = true;
+            return;
+        }
+        self.bytes += size;
+        self.entries.push(LogEntry {
+            level: level.to_string(),
+            message,
+        });
+    }
+}
+
+/// WIT `level` enum of the `logging` interface
+#[derive(ComponentType, Lift)]
+#[component(enum)]
+#[repr(u8)]
+enum WitLogLevel {
+    #[component(name = "trace")]
+    Trace,
+    #[component(name = "debug")]
+    Debug,
+    #[component(name = "info")]
+    Info,
+    #[component(name = "warn")]
+    Warn,
+    #[component(name = "error")]
+    Error,
+}
+
+impl WitLogLevel {
+    fn as_str(&self) -> &'static str {
+        match self {
+            WitLogLevel::Trace => "trace",
+            WitLogLevel::Debug => "debug",
+            WitLogLevel::Info => "info",
+            WitLogLevel::Warn => "warn",
+            WitLogLevel::Error => "error",
+        }
+    }
+}
+
+/// Time a guest may overrun its deadline before it is killed, so solvers
+/// honouring the deadline contract can still return their best solution
+const DEADLINE_GRACE: Duration = Duration::from_millis(500);
+
+/// Linear memory a guest may grow to; growing past it traps
+const MEMORY_LIMIT_BYTES: usize = 512 << 20;
+
+/// WIT `stats` record
+#[derive(ComponentType, Lift)]
+#[component(record)]
+struct WitStats {
+    #[component(name = "nodes-expanded")]
+    nodes_expanded: u64,
+    #[component(name = "nodes-generated")]
+    nodes_generated: u64,
+    #[component(name = "low-level-calls")]
+    low_level_calls: u64,
+    #[component(name = "peak-memory-bytes")]
+    peak_memory_bytes: u64,
+    #[component(name = "time-us")]
+    time_us: u64,
+    #[component(name = "agent-costs")]
+    agent_costs: Vec<u64>,
+}
+
+impl From<WitStats> for GuestStats {
+    fn from(stats: WitStats) -> Self {
+        Self {
+            nodes_expanded: stats.nodes_expanded,
+            nodes_generated: stats.nodes_generated,
+            low_level_calls: stats.low_level_calls,
+            peak_memory_bytes: stats.peak_memory_bytes,
+            time_us: stats.time_us,
+            agent_costs: stats.agent_costs,
+        }
+    }
+}
+
+/// WIT `stats` record before version 7, without `low-level-calls`
+#[derive(ComponentType, Lift)]
+#[component(record)]
+struct WitStatsV6 {
+    #[component(name = "nodes-expanded")]
+    nodes_expanded: u64,
+    #[component(name = "nodes-generated")]
+    nodes_generated: u64,
+    #[component(name = "peak-memory-bytes")]
+    peak_memory_bytes: u64,
+    #[component(name = "time-us")]
+    time_us: u64,
+    #[component(name = "agent-costs")]
+    agent_costs: Vec<u64>,
+}
+
+impl From<WitStatsV6> for GuestStats {
+    fn from(stats: WitStatsV6) -> Self {
+        Self {
+            nodes_expanded: stats.nodes_expanded,
+            nodes_generated: stats.nodes_generated,
+            low_level_calls: 0,
+            peak_memory_bytes: stats.peak_memory_bytes,
+            time_us: stats.time_us,
+            agent_costs: stats.agent_costs,
+        }
+    }
+}
+
+/// WASM executor with sandboxing and resource limits
+pub struct WasmExecutor {
+    engine: Engine,
+    timeout: Duration,
+    fuel_limit: u64,
+    seed: u64,
+}
+
+impl WasmExecutor {
+    pub fn new(timeout_secs: u64, instruction_limit: u64) -> Result<Self> {
+        // Configure engine with fuel metering for instruction counting
+        let mut config = Config::new();
+        config.wasm_component_model(true);
+        // The host drives guests with `instantiate_async` and `call_async`
+        config.async_support(true);
+        config.consume_fuel(true);
+        config.epoch_interruption(true);
+
+        let engine = Engine::new(&config)?;
+
+        Ok(Self {
+            engine,
+            timeout: Duration::from_secs(timeout_secs),
+            fuel_limit: instruction_limit,
+            seed: 0,
+        })
+    }
+
+    /// Seed passed to solvers; defaults to 0.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Execute a WASM solver component
+    pub async fn execute(
+        &self,
+        wasm_bytes: &[u8],
+        map: &GridMap,
+        starts: &[Coordinate],
+        goals: &[Coordinate],
+        waypoints: &[Vec<Coordinate>],
+        obstacles: &[TimedObstacle],
+    ) -> Result<SolverResult> {
+        let start_time = Instant::now();
+
+        // Create store with fuel
+        let mut store = Store::new(&self.engine, ServerWasiState::new()?);
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(self.fuel_limit)?;
+        store.set_epoch_deadline(1);
+
+        // Start epoch thread as a hard stop once the deadline and grace have passed
+        let engine = self.engine.clone();
+        let timeout = self.timeout;
+        std::thread::spawn(move || {
+            std::thread::sleep(timeout + DEADLINE_GRACE);
+            engine.increment_epoch();
+        });
+
+        // Load component
+        let component = Component::from_binary(&self.engine, wasm_bytes)
+            .context("Failed to load WASM component")?;
+
+        // Create linker and add WASI
+        let mut linker = Linker::new(&self.engine);
+        wasmtime_wasi::add_to_linker_async(&mut linker)?;
+
+        // Guest logging goes to a capped buffer returned with the result
+        linker.instance("mapf:solver/logging@0.1.0")?.func_wrap(
+            "log",
+            |mut caller: StoreContextMut<'_, ServerWasiState>,
+             (level, message): (WitLogLevel, String)| {
+                caller.data_mut().log.push(level.as_str(), message);
+                Ok(())
+            },
+        )?;
+
+        // Instantiate component
+        let instance = linker
+            .instantiate_async(&mut store, &component)
+            .await
+            .context("Failed to instantiate component")?;
+
+        // Recognize the interface shape before picking the solve signature
+        let shape = Shape::detect(&instance, &mut store).await?;
+        let args = SolveArgs {
+            map,
+            starts,
+            goals,
+            waypoints,
+            obstacles,
+            seed: self.seed,
+            // Whatever remains of the time budget
+            deadline_ms: timeout.saturating_sub(start_time.elapsed()).as_millis() as u64,
+        };
+        shape.check_supports(&args)?;
+        let (result, returned) = shape.solve(&instance, &mut store, args).await?;
+
+        let execution_time_ms = start_time.elapsed().as_millis() as u64;
+        let fuel_consumed = self.fuel_limit - store.get_fuel().unwrap_or(0);
+
+        // Stats are best-effort: a trapped instance can't be called again
+        let guest = if returned {
+            Self::guest_stats(&instance, &mut store, shape).await
+        } else {
+            None
+        };
+        let log = std::mem::take(&mut store.data_mut().log);
+
+        // Handle result
+        match result {
+            Ok(Ok(guest_solution)) => Ok(SolverResult {
+                solution: Some(guest_solution.solution),
+                declared_cost: guest_solution.declared_cost,
+                error: None,
+                stats: SolverStats {
+                    instruction_count: Some(fuel_consumed),
+                    execution_time_ms,
+                    fuel_consumed: Some(fuel_consumed),
+                    seed: self.seed,
+                    guest,
+                },
+                log,
+            }),
+            Ok(Err(err_msg)) => Ok(SolverResult {
+                solution: None,
+                declared_cost: None,
+                error: Some(err_msg),
+                stats: SolverStats {
+                    instruction_count: Some(fuel_consumed),
+                    execution_time_ms,
+                    fuel_consumed: Some(fuel_consumed),
+                    seed: self.seed,
+                    guest,
+                },
+                log,
+            }),
+            Err(e) => {
+                let error_msg = if e.to_string().contains("epoch") {
+                    format!("Solver timeout after {}s", timeout.as_secs())
+                } else if e.to_string().contains("fuel") {
+                    "Solver exceeded instruction limit".to_string()
+                } else {
+                    format!("Execution error: {}", e)
+                };
+
+                Ok(SolverResult {
+                    solution: None,
+                    declared_cost: None,
+                    error: Some(error_msg),
+                    stats: SolverStats {
+                        instruction_count: Some(fuel_consumed),
+                        execution_time_ms,
+                        fuel_consumed: Some(fuel_consumed),
+                        seed: self.seed,
+                        guest: None,
+                    },
+                    log,
+                })
+            }
+        }
+    }
+}
+
+impl WasmExecutor {
+    /// Call the guest's `get-stats`, ignoring solvers that don't report any.
+    async fn guest_stats(
+        instance: &wasmtime::component::Instance,
+        store: &mut Store<ServerWasiState>,
+        shape: Shape,
+    ) -> Option<GuestStats> {
+        if shape.reports_low_level_calls() {
+            Self::get_stats::<WitStats>(instance, store).await
+        } else {
+            Self::get_stats::<WitStatsV6>(instance, store).await
+        }
+    }
+
+    /// Call `get-stats` returning the stats record `S`.
+    async fn get_stats<S>(
+        instance: &wasmtime::component::Instance,
+        store: &mut Store<ServerWasiState>,
+    ) -> Option<GuestStats>
+    where
+        S: ComponentType + Lift + Send + Sync + 'static,
+        GuestStats: From<S>,
+    {
+        let get_stats = typed_func::<_, (), (Option<S>,)>(instance, store, "get-stats").ok()?;
+        let (stats,) = get_stats.call_async(&mut *store, ()).await.ok()?;
+        get_stats.post_return_async(&mut *store).await.ok()?;
+        stats.map(GuestStats::from)
+    }
+}
+
+/// WASI state for the component
+struct ServerWasiState {
+    ctx: WasiCtx,
+    /// Resources handed to the guest, such as its stdio streams
+    table: ResourceTable,
+    limits: StoreLimits,
+    log: SolverLog,
+}
+
+impl ServerWasiState {
+    fn new() -> Result<Self> {
+        let ctx = WasiCtxBuilder::new()
+            .inherit_stdio()
+            .build();
+        Ok(Self {
+            ctx,
+            table: ResourceTable::new(),
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MEMORY_LIMIT_BYTES)
+                .trap_on_grow_failure(true)
+                .build(),
+            log: SolverLog::default(),
+        })
+    }
+}
+
+impl WasiView for ServerWasiState {
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.ctx
+    }
+
+    fn table(&mut self) -> &mut ResourceTable {
+        &mut self.table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_executor_creation() {
+        let executor = WasmExecutor::new(30, 10_000_000_000);
+        assert!(executor.is_ok());
+    }
+
+    #[test]
+    fn test_solver_log_is_capped() {
+        let mut log = SolverLog::default();
+        let message = "x".repeat(LOG_CAPACITY_BYTES / 2 - LOG_ENTRY_OVERHEAD_BYTES);
+
+        log.push("info", message.clone());
+        log.push("info", message.clone());
+        assert!(!log.truncated);
+
+        log.push("warn", message);
+        assert_eq!(log.entries.len(), 2);
+        assert!(log.truncated);
+    }
+
+    #[test]
+    fn test_solver_log_caps_empty_messages() {
+        let mut log = SolverLog::default();
+        for _ in 0..1_000_000 {
+            log.push("info", String::new());
+        }
+        assert_eq!(
+            log.entries.len(),
+            LOG_CAPACITY_BYTES / LOG_ENTRY_OVERHEAD_BYTES
+        );
+        assert!(log.truncated);
+    }
+
+    /// The test component, built by `task build:test-component`.
+    fn test_component() -> Vec<u8> {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../target/wasm32-wasip2/release/mapf_test_component.wasm"
+        );
+        std::fs::read(path)
+            .unwrap_or_else(|_| panic!("{path} not built; run `task build:test-component`"))
+    }
+
+    /// Run the test component on a 3x1 corridor; the seed picks its behavior.
+    async fn run_test_component(
+        wasm: &[u8],
+        seed: u64,
+        start: Coordinate,
+        goal: Coordinate,
+    ) -> (GridMap, SolverResult) {
+        let map = GridMap::from_bytes(3, 1, &[1; 3]).unwrap();
+        let executor = WasmExecutor::new(10, 1_000_000_000).unwrap().with_seed(seed);
+        let result = executor
+            .execute(wasm, &map, &[start], &[goal], &[], &[])
+            .await
+            .unwrap();
+        (map, result)
+    }
+
+    #[tokio::test]
+    #[ignore = "needs the test component; run `task build:test-component` first"]
+    async fn test_component_misbehaviors() {
+        use crate::validation::{check_declared_cost, validate_solution};
+
+        let wasm = test_component();
+        let (left, right) = (Coordinate { x: 0, y: 0 }, Coordinate { x: 2, y: 0 });
+
+        // Normal: waiting on a start that is the goal is a valid solution
+        let (map, result) = run_test_component(&wasm, 0, left, left).await;
+        let solution = result.solution.unwrap();
+        assert!(validate_solution(&solution, &map, &[left], &[left]).valid);
+        assert!(check_declared_cost(&solution, &map, result.declared_cost.unwrap()).is_empty());
+        assert_eq!(result.stats.seed, 0);
+
+        // Invalid paths: the teleport is caught by validation
+        let (map, result) = run_test_component(&wasm, 1, left, right).await;
+        let solution = result.solution.unwrap();
+        assert!(!validate_solution(&solution, &map, &[left], &[right]).valid);
+
+        // Infinite loop runs out of fuel; the huge allocation hits the memory
+        // limit and the panic traps, both well within it
+        let expected = [
+            (2, "Solver exceeded instruction limit"),
+            (3, "Execution error"),
+            (4, "Execution error"),
+        ];
+        for (seed, error) in expected {
+            let (_, result) = run_test_component(&wasm, seed, left, left).await;
+            assert!(result.solution.is_none(), "seed {seed}");
+            let message = result.error.unwrap();
+            assert!(message.starts_with(error), "seed {seed}: {message}");
+        }
+
+        // Wrong cost: valid paths, but the declared cost is flagged
+        let (map, result) = run_test_component(&wasm, 5, left, left).await;
+        let solution = result.solution.unwrap();
+        assert!(validate_solution(&solution, &map, &[left], &[left]).valid);
+        assert_eq!(result.declared_cost, Some(u64::MAX));
+        assert!(!check_declared_cost(&solution, &map, u64::MAX).is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+//! Solver execution and verification, shared by the server and `mapf-cli`.
+//!
+//! Everything needed to run a submitted component and check its output
+//! without a database or HTTP stack.
+
+pub mod adapter;
+pub mod executor;
+pub mod validation;
@@ -0,0 +1,2326 @@
+/// Path validation for MAPF solutions - Rust port of validation.ts
+/// Ensures solvers follow the rules: cardinal moves only, no collisions
+
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub use mapf_core::{Coordinate, GridMap, Path, Solution};
+
+/// Kind of validation error.
+///
+/// The serialized name and numeric code of each variant are stable and must
+/// never be reused; see `backend/schema/validation_result.schema.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationErrorType {
+    DiagonalMove,
+    OutOfBounds,
+    BlockedCell,
+    InvalidStart,
+    InvalidGoal,
+    VertexCollision,
+    EdgeCollision,
+    EmptyPath,
+    BelowOptimal,
+    PathTooLong,
+    CornerCutting,
+    AgentCountMismatch,
+    DuplicateStart,
+    DuplicateGoal,
+    DynamicObstacle,
+    MissedWaypoint,
+}
+
+impl ValidationErrorType {
+    /// Stable numeric code: 1xx per-path, 2xx start/goal/cost, 3xx inter-agent.
+    pub fn code(&self) -> u16 {
+        match self {
+            ValidationErrorType::EmptyPath => 100,
+            ValidationErrorType::DiagonalMove => 101,
+            ValidationErrorType::OutOfBounds => 102,
+            ValidationErrorType::BlockedCell => 103,
+            ValidationErrorType::PathTooLong => 104,
+            ValidationErrorType::CornerCutting => 105,
+            ValidationErrorType::DynamicObstacle => 106,
+            ValidationErrorType::InvalidStart => 200,
+            ValidationErrorType::InvalidGoal => 201,
+            ValidationErrorType::BelowOptimal => 202,
+            ValidationErrorType::AgentCountMismatch => 203,
+            ValidationErrorType::DuplicateStart => 204,
+            ValidationErrorType::DuplicateGoal => 205,
+            ValidationErrorType::MissedWaypoint => 206,
+            ValidationErrorType::VertexCollision => 300,
+            ValidationErrorType::EdgeCollision => 301,
+        }
+    }
+
+    /// Stable string code (same as the serialized `type`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValidationErrorType::EmptyPath => "empty_path",
+            ValidationErrorType::DiagonalMove => "diagonal_move",
+            ValidationErrorType::OutOfBounds => "out_of_bounds",
+            ValidationErrorType::BlockedCell => "blocked_cell",
+            ValidationErrorType::PathTooLong => "path_too_long",
+            ValidationErrorType::CornerCutting => "corner_cutting",
+            ValidationErrorType::DynamicObstacle => "dynamic_obstacle",
+            ValidationErrorType::InvalidStart => "invalid_start",
+            ValidationErrorType::InvalidGoal => "invalid_goal",
+            ValidationErrorType::BelowOptimal => "below_optimal",
+            ValidationErrorType::AgentCountMismatch => "agent_count_mismatch",
+            ValidationErrorType::DuplicateStart => "duplicate_start",
+            ValidationErrorType::DuplicateGoal => "duplicate_goal",
+            ValidationErrorType::MissedWaypoint => "missed_waypoint",
+            ValidationErrorType::VertexCollision => "vertex_collision",
+            ValidationErrorType::EdgeCollision => "edge_collision",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationError {
+    #[serde(rename = "type")]
+    pub error_type: ValidationErrorType,
+    /// Stable numeric code of `error_type`.
+    pub code: u16,
+    pub agent_index: usize,
+    /// All agents involved, in the order referenced by `details`.
+    pub agents: Vec<usize>,
+    pub timestep: Option<usize>,
+    /// Cells involved: the offending cell, or `[from, to]` / `[actual, expected]` pairs.
+    pub positions: Vec<Coordinate>,
+    pub details: String,
+}
+
+impl ValidationError {
+    /// Create an error involving a single agent and no positions.
+    pub fn new(
+        error_type: ValidationErrorType,
+        agent_index: usize,
+        timestep: Option<usize>,
+        details: String,
+    ) -> Self {
+        Self {
+            code: error_type.code(),
+            error_type,
+            agent_index,
+            agents: vec![agent_index],
+            timestep,
+            positions: Vec::new(),
+            details,
+        }
+    }
+
+    /// Set the involved agents.
+    pub fn with_agents(mut self, agents: Vec<usize>) -> Self {
+        self.agents = agents;
+        self
+    }
+
+    /// Set the involved cells.
+    pub fn with_positions(mut self, positions: Vec<Coordinate>) -> Self {
+        self.positions = positions;
+        self
+    }
+}
+
+/// Kind of non-fatal diagnostic. Codes are stable like `ValidationErrorType`'s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationWarningType {
+    /// Explicit waits at the goal after the agent has finished.
+    TrailingWait,
+    /// The agent returns to a cell it already left.
+    Detour,
+    /// The agent reaches its goal, leaves, and comes back.
+    GoalRevisit,
+    /// The solver-declared cost matches no independently computed metric.
+    CostMismatch,
+}
+
+impl ValidationWarningType {
+    /// Stable numeric code (9xx).
+    pub fn code(&self) -> u16 {
+        match self {
+            ValidationWarningType::TrailingWait => 900,
+            ValidationWarningType::Detour => 901,
+            ValidationWarningType::GoalRevisit => 902,
+            ValidationWarningType::CostMismatch => 903,
+        }
+    }
+
+    /// Stable string code (same as the serialized `type`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ValidationWarningType::TrailingWait => "trailing_wait",
+            ValidationWarningType::Detour => "detour",
+            ValidationWarningType::GoalRevisit => "goal_revisit",
+            ValidationWarningType::CostMismatch => "cost_mismatch",
+        }
+    }
+}
+
+/// A diagnostic that doesn't invalidate the solution but hints at solver inefficiency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationWarning {
+    #[serde(rename = "type")]
+    pub warning_type: ValidationWarningType,
+    /// Stable numeric code of `warning_type`.
+    pub code: u16,
+    pub agent_index: usize,
+    pub timestep: Option<usize>,
+    pub details: String,
+}
+
+impl ValidationWarning {
+    pub fn new(
+        warning_type: ValidationWarningType,
+        agent_index: usize,
+        timestep: Option<usize>,
+        details: String,
+    ) -> Self {
+        Self {
+            code: warning_type.code(),
+            warning_type,
+            agent_index,
+            timestep,
+            details,
+        }
+    }
+}
+
+/// Validation outcome for a single agent, for partial-credit scoring.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentStatus {
+    pub agent_index: usize,
+    /// No error involves this agent.
+    pub valid: bool,
+    /// Path cost in moves, ignoring trailing waits.
+    pub cost: u32,
+    /// First error involving this agent, if any.
+    pub first_error: Option<ValidationError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationResult {
+    pub valid: bool,
+    pub errors: Vec<ValidationError>,
+    /// Non-fatal diagnostics; never affect `valid`.
+    #[serde(default)]
+    pub warnings: Vec<ValidationWarning>,
+    /// Per-agent summary, one entry per path (empty in fail-fast mode).
+    #[serde(default)]
+    pub agents: Vec<AgentStatus>,
+}
+
+impl ValidationResult {
+    /// Invalid result keeping only the first of the collected errors.
+    fn first_error(mut errors: Vec<ValidationError>) -> Self {
+        errors.truncate(1);
+        Self {
+            valid: false,
+            errors,
+            warnings: Vec::new(),
+            agents: Vec::new(),
+        }
+    }
+}
+
+/// Default horizon as a multiple of `width + height`.
+pub const DEFAULT_HORIZON_FACTOR: usize = 16;
+
+/// Agent count above which vertex collisions are checked with per-timestep bitsets.
+pub const BITSET_AGENT_THRESHOLD: usize = 1000;
+
+/// An obstacle that blocks cell (x, y) from timestep `from` until (excluding) `until`.
+/// `until: None` means the obstacle never disappears.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedObstacle {
+    pub x: u32,
+    pub y: u32,
+    pub from: usize,
+    #[serde(default)]
+    pub until: Option<usize>,
+}
+
+impl TimedObstacle {
+    /// Whether the obstacle is present at timestep `t`.
+    pub fn is_active_at(&self, t: usize) -> bool {
+        t >= self.from && self.until.is_none_or(|until| t < until)
+    }
+}
+
+/// Movement and goal rules of the MAPF variant a solution is judged by.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleSet {
+    /// Permit diagonal moves on maps declared as `octile`.
+    pub allow_diagonals: bool,
+    /// Agents are removed from the grid once their path ends at the goal, so
+    /// other agents may pass through it later ("disappear at target").
+    pub disappear_at_goal: bool,
+}
+
+/// Options controlling how a solution is validated.
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// Explicit per-instance limit on path cost. Overrides `horizon_factor` when set.
+    pub max_path_length: Option<usize>,
+    /// Limit on path cost as a multiple of `width + height`.
+    pub horizon_factor: usize,
+    /// Stop at the first failing check and report a single example error.
+    pub fail_fast: bool,
+    /// Rules of the MAPF variant.
+    pub rules: RuleSet,
+    /// Appearing/disappearing obstacles on top of the static map.
+    pub obstacles: Vec<TimedObstacle>,
+    /// Per-agent cells to visit in order before the goal (pickup-and-delivery).
+    /// Agents without an entry have none.
+    pub waypoints: Vec<Vec<Coordinate>>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            max_path_length: None,
+            horizon_factor: DEFAULT_HORIZON_FACTOR,
+            fail_fast: false,
+            rules: RuleSet::default(),
+            obstacles: Vec::new(),
+            waypoints: Vec::new(),
+        }
+    }
+}
+
+impl ValidationOptions {
+    /// Whether diagonal moves are legal on the given map.
+    pub fn diagonals_allowed(&self, map: &GridMap) -> bool {
+        self.rules.allow_diagonals && map.map_type().eq_ignore_ascii_case("octile")
+    }
+
+    /// Maximum path cost allowed on the given map.
+    pub fn horizon(&self, map: &GridMap) -> usize {
+        self.max_path_length
+            .unwrap_or(self.horizon_factor * (map.width() + map.height()) as usize)
+    }
+}
+
+/// Check if a move is cardinal (N/S/E/W only, no diagonals).
+pub fn is_cardinal_move(from: &Coordinate, to: &Coordinate) -> bool {
+    let dx = to.x.abs_diff(from.x);
+    let dy = to.y.abs_diff(from.y);
+    // Valid: (1,0), (0,1), or (0,0) for wait
+    (dx == 1 && dy == 0) || (dx == 0 && dy == 1) || (dx == 0 && dy == 0)
+}
+
+/// Validate that a single path uses only cardinal moves.
+pub fn validate_path_cardinal(path: &Path, agent_index: usize) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if path.steps.is_empty() {
+        errors.push(ValidationError::new(
+            ValidationErrorType::EmptyPath,
+            agent_index,
+            None,
+            format!("Agent {} has empty path", agent_index),
+        ));
+        return errors;
+    }
+
+    for t in 0..path.steps.len() - 1 {
+        let from = &path.steps[t];
+        let to = &path.steps[t + 1];
+
+        if !is_cardinal_move(from, to) {
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::DiagonalMove,
+                    agent_index,
+                    Some(t),
+                    format!(
+                        "Agent {} made diagonal move from ({},{}) to ({},{}) at timestep {}",
+                        agent_index, from.x, from.y, to.x, to.y, t
+                    ),
+                )
+                .with_positions(vec![*from, *to]),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Validate that a single path uses only octile moves (cardinal, diagonal or wait).
+///
+/// Diagonal moves may not cut corners: both orthogonally adjacent cells they
+/// pass between must be passable.
+pub fn validate_path_octile(path: &Path, agent_index: usize, map: &GridMap) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if path.steps.is_empty() {
+        errors.push(ValidationError::new(
+            ValidationErrorType::EmptyPath,
+            agent_index,
+            None,
+            format!("Agent {} has empty path", agent_index),
+        ));
+        return errors;
+    }
+
+    for t in 0..path.steps.len() - 1 {
+        let from = &path.steps[t];
+        let to = &path.steps[t + 1];
+        let dx = to.x.abs_diff(from.x);
+        let dy = to.y.abs_diff(from.y);
+
+        if dx > 1 || dy > 1 {
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::DiagonalMove,
+                    agent_index,
+                    Some(t),
+                    format!(
+                        "Agent {} made invalid move from ({},{}) to ({},{}) at timestep {}",
+                        agent_index, from.x, from.y, to.x, to.y, t
+                    ),
+                )
+                .with_positions(vec![*from, *to]),
+            );
+        } else if dx == 1
+            && dy == 1
+            && !(map.is_passable(to.x, from.y) && map.is_passable(from.x, to.y))
+        {
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::CornerCutting,
+                    agent_index,
+                    Some(t),
+                    format!(
+                        "Agent {} cut a corner moving from ({},{}) to ({},{}) at timestep {}",
+                        agent_index, from.x, from.y, to.x, to.y, t
+                    ),
+                )
+                .with_positions(vec![*from, *to]),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Validate that a path stays within map bounds and on passable cells.
+pub fn validate_path_on_map(
+    path: &Path,
+    agent_index: usize,
+    map: &GridMap,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for (t, pos) in path.steps.iter().enumerate() {
+        // Bounds check
+        if pos.x >= map.width() || pos.y >= map.height() {
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::OutOfBounds,
+                    agent_index,
+                    Some(t),
+                    format!(
+                        "Agent {} at ({},{}) is out of bounds at timestep {}",
+                        agent_index, pos.x, pos.y, t
+                    ),
+                )
+                .with_positions(vec![*pos]),
+            );
+            continue;
+        }
+
+        // Passable check
+        if !map.is_passable(pos.x, pos.y) {
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::BlockedCell,
+                    agent_index,
+                    Some(t),
+                    format!(
+                        "Agent {} at ({},{}) is on blocked cell at timestep {}",
+                        agent_index, pos.x, pos.y, t
+                    ),
+                )
+                .with_positions(vec![*pos]),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Validate that a path never occupies a cell while a timed obstacle blocks it.
+///
+/// The agent is assumed to stay at its last position forever, so obstacles
+/// appearing on its final cell after the path ends are reported too.
+pub fn validate_path_dynamic(
+    path: &Path,
+    agent_index: usize,
+    obstacles: &[TimedObstacle],
+) -> Vec<ValidationError> {
+    validate_path_dynamic_with(path, agent_index, obstacles, &RuleSet::default())
+}
+
+/// Like [`validate_path_dynamic`], but an agent that disappears at its goal
+/// is not affected by obstacles appearing there afterwards.
+pub fn validate_path_dynamic_with(
+    path: &Path,
+    agent_index: usize,
+    obstacles: &[TimedObstacle],
+    rules: &RuleSet,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let Some(last) = path.steps.last() else {
+        return errors;
+    };
+
+    let mut by_cell: HashMap<Coordinate, Vec<&TimedObstacle>> = HashMap::new();
+    for obstacle in obstacles {
+        by_cell
+            .entry(Coordinate {
+                x: obstacle.x,
+                y: obstacle.y,
+            })
+            .or_default()
+            .push(obstacle);
+    }
+
+    let mut blocked_at = |pos: &Coordinate, t: usize| {
+        errors.push(
+            ValidationError::new(
+                ValidationErrorType::DynamicObstacle,
+                agent_index,
+                Some(t),
+                format!(
+                    "Agent {} at ({},{}) hits a dynamic obstacle at timestep {}",
+                    agent_index, pos.x, pos.y, t
+                ),
+            )
+            .with_positions(vec![*pos]),
+        );
+    };
+
+    for (t, pos) in path.steps.iter().enumerate() {
+        let Some(cell_obstacles) = by_cell.get(pos) else {
+            continue;
+        };
+        if cell_obstacles.iter().any(|o| o.is_active_at(t)) {
+            blocked_at(pos, t);
+        }
+    }
+
+    // Obstacles appearing on the final cell while the agent waits there
+    let end = path.steps.len();
+    if rules.disappear_at_goal {
+        return errors;
+    }
+    if let Some(cell_obstacles) = by_cell.get(last) {
+        let first_hit = cell_obstacles
+            .iter()
+            .map(|o| o.from.max(end))
+            .filter(|&t| cell_obstacles.iter().any(|o| o.is_active_at(t)))
+            .min();
+        if let Some(t) = first_hit {
+            blocked_at(last, t);
+        }
+    }
+
+    errors
+}
+
+/// Position of an agent at timestep `t`; agents stay at their last position after
+/// their path ends. Returns `None` for empty paths.
+pub fn position_at(path: &Path, t: usize) -> Option<&Coordinate> {
+    path.steps.get(t).or_else(|| path.steps.last())
+}
+
+/// Packed cell key used for sort-based lookups.
+fn cell_key(pos: &Coordinate) -> u64 {
+    (pos.x as u64) << 32 | pos.y as u64
+}
+
+/// First timestep from which an agent never leaves its final cell.
+fn parked_from(path: &Path) -> usize {
+    let Some(last) = path.steps.last() else {
+        return 0;
+    };
+    path.steps.iter().rposition(|p| p != last).map_or(0, |i| i + 1)
+}
+
+/// Validate that paths don't have vertex collisions (two agents at same cell at same time).
+///
+/// Agents that have permanently reached their final cell are treated as static
+/// occupancy and only looked up on cells that moving agents visit, so finished
+/// agents cost nothing per timestep. Timesteps are checked in parallel; within
+/// a timestep, occupants are sorted by cell so collisions are found without hashing.
+pub fn validate_no_vertex_collisions(paths: &[Path]) -> Vec<ValidationError> {
+    validate_no_vertex_collisions_with(paths, &RuleSet::default())
+}
+
+/// Like [`validate_no_vertex_collisions`], under the given rules.
+///
+/// When agents disappear at their goal, nobody is parked: each agent occupies
+/// cells only while its path lasts.
+pub fn validate_no_vertex_collisions_with(paths: &[Path], rules: &RuleSet) -> Vec<ValidationError> {
+    // Find max timestep
+    let max_t = paths.iter().map(|p| p.steps.len()).max().unwrap_or(0);
+
+    let parked: Vec<usize> = if rules.disappear_at_goal {
+        paths.iter().map(|p| p.steps.len()).collect()
+    } else {
+        paths.iter().map(parked_from).collect()
+    };
+
+    // Final cell -> (parked from, agent)
+    let mut parked_at: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+    if !rules.disappear_at_goal {
+        for (agent, path) in paths.iter().enumerate() {
+            if let Some(last) = path.steps.last() {
+                parked_at
+                    .entry(cell_key(last))
+                    .or_default()
+                    .push((parked[agent], agent));
+            }
+        }
+    }
+
+    // Cells where several agents end up; these can collide with no mover around
+    let shared: Vec<u64> = parked_at
+        .iter()
+        .filter(|(_, agents)| agents.len() > 1)
+        .map(|(&key, _)| key)
+        .collect();
+
+    (0..max_t)
+        .into_par_iter()
+        .map(|t| vertex_collisions_at(paths, &parked, &parked_at, &shared, t))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn vertex_collisions_at(
+    paths: &[Path],
+    parked: &[usize],
+    parked_at: &HashMap<u64, Vec<(usize, usize)>>,
+    shared: &[u64],
+    t: usize,
+) -> Vec<ValidationError> {
+    // Agents still moving at t
+    let mut occupants: Vec<(u64, usize)> = paths
+        .iter()
+        .enumerate()
+        .filter(|&(agent, _)| parked[agent] > t)
+        .filter_map(|(agent, path)| position_at(path, t).map(|pos| (cell_key(pos), agent)))
+        .collect();
+
+    // Parked agents on cells that matter at t
+    let cells: Vec<u64> = occupants
+        .iter()
+        .map(|&(key, _)| key)
+        .chain(shared.iter().copied())
+        .collect();
+    for key in cells {
+        if let Some(agents) = parked_at.get(&key) {
+            occupants.extend(
+                agents
+                    .iter()
+                    .filter(|&&(from, _)| from <= t)
+                    .map(|&(_, agent)| (key, agent)),
+            );
+        }
+    }
+    occupants.sort_unstable();
+    occupants.dedup();
+
+    let mut errors = Vec::new();
+    let mut start = 0;
+    while start < occupants.len() {
+        let key = occupants[start].0;
+        let end = start + occupants[start..].iter().take_while(|o| o.0 == key).count();
+
+        // The lowest-indexed agent claims the cell; everyone else collides with it
+        let other_agent = occupants[start].1;
+        for &(_, agent) in &occupants[start + 1..end] {
+            let pos = position_at(&paths[agent], t).unwrap();
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::VertexCollision,
+                    agent,
+                    Some(t),
+                    format!(
+                        "Agents {} and {} collide at ({},{}) at timestep {}",
+                        other_agent, agent, pos.x, pos.y, t
+                    ),
+                )
+                .with_agents(vec![other_agent, agent])
+                .with_positions(vec![*pos]),
+            );
+        }
+        start = end;
+    }
+
+    errors.sort_by_key(|e| e.agent_index);
+    errors
+}
+
+/// Validate vertex collisions using per-timestep bitsets over cell indices.
+///
+/// Each timestep marks occupied cells in a bitset of `width * height` bits and
+/// records cells marked twice in a second one; the agents on those cells are
+/// only looked up when a collision exists. Cost is linear in agents per
+/// timestep with no hashing or sorting, which wins for thousands of agents.
+/// Reports the same errors as [`validate_no_vertex_collisions_with`]; every
+/// position must lie within the map.
+pub fn validate_no_vertex_collisions_bitset(
+    paths: &[Path],
+    width: u32,
+    height: u32,
+    rules: &RuleSet,
+) -> Vec<ValidationError> {
+    let max_t = paths.iter().map(|p| p.steps.len()).max().unwrap_or(0);
+    let words = (width as usize * height as usize).div_ceil(64);
+
+    (0..max_t)
+        .into_par_iter()
+        .map_init(
+            || (vec![0u64; words], vec![0u64; words]),
+            |(seen, shared), t| {
+                vertex_collisions_bitset_at(paths, width, rules, seen, shared, t)
+            },
+        )
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn vertex_collisions_bitset_at(
+    paths: &[Path],
+    width: u32,
+    rules: &RuleSet,
+    seen: &mut [u64],
+    shared: &mut [u64],
+    t: usize,
+) -> Vec<ValidationError> {
+    let mut occupants = Vec::with_capacity(paths.len());
+    let mut any_shared = false;
+    for (agent, path) in paths.iter().enumerate() {
+        if rules.disappear_at_goal && t >= path.steps.len() {
+            continue;
+        }
+        let Some(pos) = position_at(path, t) else { continue };
+        let cell = (pos.y * width + pos.x) as usize;
+        let (word, bit) = (cell / 64, 1u64 << (cell % 64));
+        if seen[word] & bit != 0 {
+            shared[word] |= bit;
+            any_shared = true;
+        }
+        seen[word] |= bit;
+        occupants.push((cell, agent));
+    }
+
+    let mut errors = Vec::new();
+    if any_shared {
+        // Occupants arrive in agent order, so the first one claims the cell
+        let mut claimed: HashMap<usize, usize> = HashMap::new();
+        for &(cell, agent) in &occupants {
+            if shared[cell / 64] & (1u64 << (cell % 64)) == 0 {
+                continue;
+            }
+            let other_agent = *claimed.entry(cell).or_insert(agent);
+            if other_agent == agent {
+                continue;
+            }
+            let pos = position_at(&paths[agent], t).unwrap();
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::VertexCollision,
+                    agent,
+                    Some(t),
+                    format!(
+                        "Agents {} and {} collide at ({},{}) at timestep {}",
+                        other_agent, agent, pos.x, pos.y, t
+                    ),
+                )
+                .with_agents(vec![other_agent, agent])
+                .with_positions(vec![*pos]),
+            );
+        }
+    }
+
+    // Reset only the words touched at this timestep
+    for &(cell, _) in &occupants {
+        seen[cell / 64] = 0;
+        shared[cell / 64] = 0;
+    }
+
+    errors
+}
+
+/// Validate that paths don't have edge collisions (two agents swapping positions).
+///
+/// Timesteps are checked in parallel; within a timestep, moves are sorted by
+/// (from, to) so each agent's reverse move is found by binary search. Waits
+/// never swap; two agents waiting on one cell are a vertex collision.
+pub fn validate_no_edge_collisions(paths: &[Path]) -> Vec<ValidationError> {
+    validate_no_edge_collisions_with(paths, &RuleSet::default())
+}
+
+/// Like [`validate_no_edge_collisions`], under the given rules.
+///
+/// When agents disappear at their goal, an agent makes no moves once its
+/// path has ended.
+pub fn validate_no_edge_collisions_with(paths: &[Path], rules: &RuleSet) -> Vec<ValidationError> {
+    // Find max timestep
+    let max_t = paths.iter().map(|p| p.steps.len()).max().unwrap_or(0);
+
+    (0..max_t.saturating_sub(1))
+        .into_par_iter()
+        .map(|t| edge_collisions_at(paths, rules, t))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+fn edge_collisions_at(paths: &[Path], rules: &RuleSet, t: usize) -> Vec<ValidationError> {
+    let moves: Vec<Option<(u64, u64)>> = paths
+        .iter()
+        .map(|path| {
+            if rules.disappear_at_goal && t + 1 >= path.steps.len() {
+                return None;
+            }
+            let from = cell_key(position_at(path, t)?);
+            let to = cell_key(position_at(path, t + 1)?);
+            (from != to).then_some((from, to))
+        })
+        .collect();
+
+    let mut sorted: Vec<(u64, u64, usize)> = moves
+        .iter()
+        .enumerate()
+        .filter_map(|(agent, m)| m.map(|(from, to)| (from, to, agent)))
+        .collect();
+    sorted.sort_unstable();
+
+    let mut errors = Vec::new();
+    for (i, m) in moves.iter().enumerate() {
+        let Some((from, to)) = *m else { continue };
+
+        // Agents j > i moving the opposite way
+        let lo = sorted.partition_point(|&e| e < (to, from, i + 1));
+        let hi = sorted.partition_point(|&e| (e.0, e.1) <= (to, from));
+        for &(_, _, j) in &sorted[lo..hi] {
+            let from_pos = position_at(&paths[i], t).unwrap();
+            let to_pos = position_at(&paths[i], t + 1).unwrap();
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::EdgeCollision,
+                    i,
+                    Some(t),
+                    format!(
+                        "Agents {} and {} swap positions between timesteps {} and {}",
+                        i,
+                        j,
+                        t,
+                        t + 1
+                    ),
+                )
+                .with_agents(vec![i, j])
+                .with_positions(vec![*from_pos, *to_pos]),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Validate that there is exactly one path, start and goal per agent.
+pub fn validate_agent_counts(
+    paths: &[Path],
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if paths.len() != starts.len() || starts.len() != goals.len() {
+        let agent_index = paths.len().min(starts.len()).min(goals.len());
+        errors.push(ValidationError::new(
+            ValidationErrorType::AgentCountMismatch,
+            agent_index,
+            None,
+            format!(
+                "Solution has {} paths for {} starts and {} goals",
+                paths.len(),
+                starts.len(),
+                goals.len()
+            ),
+        ));
+    }
+
+    errors
+}
+
+/// Validate that no two agents share a start, and no two agents share a goal.
+pub fn validate_distinct_endpoints(
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for (error_type, label, cells) in [
+        (ValidationErrorType::DuplicateStart, "start", starts),
+        (ValidationErrorType::DuplicateGoal, "goal", goals),
+    ] {
+        let mut first_at: HashMap<Coordinate, usize> = HashMap::new();
+        for (agent, cell) in cells.iter().enumerate() {
+            if let Some(&other_agent) = first_at.get(cell) {
+                errors.push(
+                    ValidationError::new(
+                        error_type.clone(),
+                        agent,
+                        None,
+                        format!(
+                            "Agents {} and {} share the {} ({},{})",
+                            other_agent, agent, label, cell.x, cell.y
+                        ),
+                    )
+                    .with_agents(vec![other_agent, agent])
+                    .with_positions(vec![*cell]),
+                );
+            } else {
+                first_at.insert(*cell, agent);
+            }
+        }
+    }
+
+    errors
+}
+
+/// Validate that paths start and end at the correct positions.
+pub fn validate_starts_and_goals(
+    paths: &[Path],
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    for (i, path) in paths.iter().enumerate() {
+        if path.steps.is_empty() {
+            continue;
+        }
+
+        // Missing starts/goals are reported by `validate_agent_counts`
+        let (Some(expected_start), Some(expected_goal)) = (starts.get(i), goals.get(i)) else {
+            continue;
+        };
+        let path_start = &path.steps[0];
+        let path_end = &path.steps[path.steps.len() - 1];
+
+        if path_start.x != expected_start.x || path_start.y != expected_start.y {
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::InvalidStart,
+                    i,
+                    Some(0),
+                    format!(
+                        "Agent {} path starts at ({},{}) but should start at ({},{})",
+                        i, path_start.x, path_start.y, expected_start.x, expected_start.y
+                    ),
+                )
+                .with_positions(vec![*path_start, *expected_start]),
+            );
+        }
+
+        if path_end.x != expected_goal.x || path_end.y != expected_goal.y {
+            errors.push(
+                ValidationError::new(
+                    ValidationErrorType::InvalidGoal,
+                    i,
+                    Some(path.steps.len() - 1),
+                    format!(
+                        "Agent {} path ends at ({},{}) but should end at ({},{})",
+                        i, path_end.x, path_end.y, expected_goal.x, expected_goal.y
+                    ),
+                )
+                .with_positions(vec![*path_end, *expected_goal]),
+            );
+        }
+    }
+
+    errors
+}
+
+/// Validate that a path visits its agent's waypoints in order.
+///
+/// A waypoint counts as visited at the first timestep the agent stands on it
+/// after visiting the previous one; only the first missed one is reported.
+pub fn validate_waypoints(
+    path: &Path,
+    agent_index: usize,
+    waypoints: &[Coordinate],
+) -> Vec<ValidationError> {
+    let mut visited = 0;
+    for pos in &path.steps {
+        visited = advance_waypoints(waypoints, visited, pos);
+    }
+    match waypoints.get(visited) {
+        Some(waypoint) => vec![missed_waypoint(agent_index, visited, waypoint)],
+        None => Vec::new(),
+    }
+}
+
+/// Number of waypoints visited once the agent stands on `pos`, given
+/// `visited` were visited before.
+fn advance_waypoints(waypoints: &[Coordinate], mut visited: usize, pos: &Coordinate) -> usize {
+    while waypoints.get(visited) == Some(pos) {
+        visited += 1;
+    }
+    visited
+}
+
+fn missed_waypoint(agent_index: usize, index: usize, waypoint: &Coordinate) -> ValidationError {
+    ValidationError::new(
+        ValidationErrorType::MissedWaypoint,
+        agent_index,
+        None,
+        format!(
+            "Agent {} never visits waypoint {} at ({},{}) in order",
+            agent_index, index, waypoint.x, waypoint.y
+        ),
+    )
+    .with_positions(vec![*waypoint])
+}
+
+/// Validate that a path does not exceed the allowed horizon.
+///
+/// Keeps absurdly long (but otherwise legal) plans out of scoring and storage.
+pub fn validate_path_length(
+    path: &Path,
+    agent_index: usize,
+    max_length: usize,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    let cost = path.steps.len().saturating_sub(1);
+    if cost > max_length {
+        errors.push(ValidationError::new(
+            ValidationErrorType::PathTooLong,
+            agent_index,
+            Some(max_length + 1),
+            format!(
+                "Agent {} path has {} moves, exceeding the limit of {}",
+                agent_index, cost, max_length
+            ),
+        ));
+    }
+
+    errors
+}
+
+/// Validate that no agent's path is cheaper than the scenario's optimal length.
+///
+/// `optimal_lengths` are the single-agent lower bounds from the `.scen` file
+/// (one per agent). A path cheaper than its lower bound cannot be produced by
+/// a legal plan, so it indicates a cheating or corrupted submission. A
+/// different number of paths and baselines is an `AgentCountMismatch`.
+pub fn validate_against_baselines(paths: &[Path], optimal_lengths: &[f64]) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if paths.len() != optimal_lengths.len() {
+        errors.push(ValidationError::new(
+            ValidationErrorType::AgentCountMismatch,
+            paths.len().min(optimal_lengths.len()),
+            None,
+            format!(
+                "Solution has {} paths for {} scenario baselines",
+                paths.len(),
+                optimal_lengths.len()
+            ),
+        ));
+    }
+
+    for (i, (path, &optimal)) in paths.iter().zip(optimal_lengths.iter()).enumerate() {
+        if path.steps.is_empty() {
+            continue;
+        }
+
+        let cost = (path.steps.len() - 1) as f64;
+        // Scenario baselines are printed with limited precision
+        if cost + 1e-6 < optimal {
+            errors.push(ValidationError::new(
+                ValidationErrorType::BelowOptimal,
+                i,
+                None,
+                format!(
+                    "Agent {} path cost {} is below the optimal length {}",
+                    i, cost, optimal
+                ),
+            ));
+        }
+    }
+
+    errors
+}
+
+/// Summarize which agents are involved in errors.
+///
+/// An agent is invalid if it appears in any error's `agents`, so both parties
+/// of a collision lose credit.
+pub fn agent_statuses(paths: &[Path], errors: &[ValidationError]) -> Vec<AgentStatus> {
+    statuses_from_costs(paths.iter().map(|path| path.trimmed().cost()), errors)
+}
+
+fn statuses_from_costs(
+    costs: impl Iterator<Item = u32>,
+    errors: &[ValidationError],
+) -> Vec<AgentStatus> {
+    let mut statuses: Vec<AgentStatus> = costs
+        .enumerate()
+        .map(|(agent_index, cost)| AgentStatus {
+            agent_index,
+            valid: true,
+            cost,
+            first_error: None,
+        })
+        .collect();
+
+    for error in errors {
+        for &agent in &error.agents {
+            if let Some(status) = statuses.get_mut(agent) {
+                status.valid = false;
+                if status.first_error.is_none() {
+                    status.first_error = Some(error.clone());
+                }
+            }
+        }
+    }
+
+    statuses
+}
+
+/// Collect non-fatal diagnostics for every path.
+///
+/// Reports, at most once per agent and kind: explicit waits after the agent
+/// has finished at its final cell, returns to a previously left cell, and
+/// leaving the goal only to come back to it.
+pub fn collect_warnings(paths: &[Path], goals: &[Coordinate]) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+
+    for (i, path) in paths.iter().enumerate() {
+        if path.steps.is_empty() {
+            continue;
+        }
+        let parked = parked_from(path);
+        let goal = goals.get(i);
+
+        let trailing = path.steps.len() - 1 - parked;
+        if trailing > 0 {
+            warnings.push(ValidationWarning::new(
+                ValidationWarningType::TrailingWait,
+                i,
+                Some(parked + 1),
+                format!(
+                    "Agent {} waits {} step(s) after finishing at timestep {}; trailing waits are implicit",
+                    i, trailing, parked
+                ),
+            ));
+        }
+
+        // Cells the agent has left, with the timestep it first arrived
+        let mut left: HashMap<Coordinate, usize> = HashMap::new();
+        let mut detour_reported = false;
+        let mut revisit_reported = false;
+        for t in 1..=parked {
+            let (prev, pos) = (path.steps[t - 1], path.steps[t]);
+            if pos == prev {
+                continue;
+            }
+            left.entry(prev).or_insert(t - 1);
+
+            let Some(&first) = left.get(&pos) else { continue };
+            if Some(&pos) == goal {
+                if !revisit_reported {
+                    revisit_reported = true;
+                    warnings.push(ValidationWarning::new(
+                        ValidationWarningType::GoalRevisit,
+                        i,
+                        Some(t),
+                        format!(
+                            "Agent {} reached its goal ({},{}) at timestep {} but left and returned at timestep {}",
+                            i, pos.x, pos.y, first, t
+                        ),
+                    ));
+                }
+            } else if !detour_reported {
+                detour_reported = true;
+                warnings.push(ValidationWarning::new(
+                    ValidationWarningType::Detour,
+                    i,
+                    Some(t),
+                    format!(
+                        "Agent {} returns to ({},{}) at timestep {} after leaving it at timestep {}",
+                        i, pos.x, pos.y, t, first
+                    ),
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Cross-check the cost a solver declared against the independently computed one.
+///
+/// The WIT contract leaves the metric open, so the declared value is accepted
+/// if it equals the sum of costs or the makespan (counted in moves or in
+/// steps), before or after trimming trailing waits. On weighted maps the
+/// weighted sum of costs is accepted too.
+pub fn check_declared_cost(
+    solution: &Solution,
+    map: &GridMap,
+    declared: u64,
+) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+
+    let canonical = solution.canonicalize();
+    let mut candidates = Vec::new();
+    for paths in [&solution.paths, &canonical.paths] {
+        let moves = paths.iter().map(|p| p.cost() as u64);
+        let steps = paths.iter().map(|p| p.steps.len() as u64);
+        candidates.push(moves.clone().sum::<u64>());
+        candidates.push(steps.clone().sum::<u64>());
+        candidates.push(moves.max().unwrap_or(0));
+        candidates.push(steps.max().unwrap_or(0));
+        if map.is_weighted() {
+            candidates.push(paths.iter().map(|p| map.path_cost(p)).sum());
+        }
+    }
+
+    if !candidates.contains(&declared) {
+        let sum_of_costs = canonical.paths.iter().map(|p| map.path_cost(p)).sum::<u64>();
+        warnings.push(ValidationWarning::new(
+            ValidationWarningType::CostMismatch,
+            0,
+            None,
+            format!(
+                "Solver declared cost {} but the solution's sum of costs is {}",
+                declared, sum_of_costs
+            ),
+        ));
+    }
+
+    warnings
+}
+
+/// Sum of costs used for scoring, shared with the CLI and the browser.
+pub use mapf_scoring::scored_cost;
+
+/// Incremental validator fed one timestep of agent positions at a time.
+///
+/// Unlike [`validate_solution_with`], the full solution never has to be held
+/// in memory: only the previous timestep is kept. Every slice must contain one
+/// position per agent, so agents that have finished repeat their final cell.
+/// Any timestep after the horizon, waits included, is reported as
+/// `PathTooLong` and stops validation, which bounds the work spent on runaway
+/// outputs.
+///
+/// Warnings are not collected, as they need whole paths. Agents always stay
+/// at their final cell, since a stream cannot tell when a path has ended;
+/// `RuleSet::disappear_at_goal` is ignored.
+pub struct StreamingValidator<'a> {
+    map: &'a GridMap,
+    starts: &'a [Coordinate],
+    goals: &'a [Coordinate],
+    options: &'a ValidationOptions,
+    horizon: usize,
+    diagonals: bool,
+    timestep: usize,
+    previous: Vec<Coordinate>,
+    /// Last timestep at which each agent moved.
+    last_move: Vec<usize>,
+    /// Number of waypoints each agent has visited so far.
+    waypoints_visited: Vec<usize>,
+    errors: Vec<ValidationError>,
+    stopped: bool,
+}
+
+impl<'a> StreamingValidator<'a> {
+    pub fn new(
+        map: &'a GridMap,
+        starts: &'a [Coordinate],
+        goals: &'a [Coordinate],
+        options: &'a ValidationOptions,
+    ) -> Self {
+        let mut validator = Self {
+            map,
+            starts,
+            goals,
+            options,
+            horizon: options.horizon(map),
+            diagonals: options.diagonals_allowed(map),
+            timestep: 0,
+            previous: Vec::new(),
+            last_move: vec![0; starts.len()],
+            waypoints_visited: vec![0; starts.len()],
+            errors: Vec::new(),
+            stopped: false,
+        };
+
+        // Paths are only known to match the agent count once steps arrive
+        if starts.len() != goals.len() {
+            validator.errors.push(ValidationError::new(
+                ValidationErrorType::AgentCountMismatch,
+                starts.len().min(goals.len()),
+                None,
+                format!(
+                    "Instance has {} starts and {} goals",
+                    starts.len(),
+                    goals.len()
+                ),
+            ));
+        }
+        validator.errors.extend(validate_distinct_endpoints(starts, goals));
+        validator.check_stop();
+        validator
+    }
+
+    /// Validate the positions of all agents at the next timestep.
+    ///
+    /// Returns `false` once validation has stopped; later calls are ignored.
+    pub fn push(&mut self, positions: &[Coordinate]) -> bool {
+        if self.stopped {
+            return false;
+        }
+        let t = self.timestep;
+
+        if positions.len() != self.starts.len() {
+            self.errors.push(ValidationError::new(
+                ValidationErrorType::AgentCountMismatch,
+                positions.len().min(self.starts.len()),
+                Some(t),
+                format!(
+                    "Timestep {} has {} positions for {} agents",
+                    t,
+                    positions.len(),
+                    self.starts.len()
+                ),
+            ));
+            self.stopped = true;
+            return false;
+        }
+
+        // Waits count too, so an endless stream of them cannot run forever
+        if t > self.horizon {
+            self.errors.push(ValidationError::new(
+                ValidationErrorType::PathTooLong,
+                0,
+                Some(t),
+                format!(
+                    "Solution reaches timestep {}, exceeding the limit of {}",
+                    t, self.horizon
+                ),
+            ));
+            self.stopped = true;
+            return false;
+        }
+
+        if t == 0 {
+            self.check_starts(positions);
+        } else {
+            self.check_moves(positions, t);
+        }
+        self.check_cells(positions, t);
+        self.track_waypoints(positions);
+        self.check_vertex_collisions(positions, t);
+        if t > 0 {
+            self.check_edge_collisions(positions, t);
+        }
+
+        self.previous.clear();
+        self.previous.extend_from_slice(positions);
+        self.timestep += 1;
+        self.check_stop();
+        !self.stopped
+    }
+
+    /// Check goals and produce the final result.
+    pub fn finish(mut self) -> ValidationResult {
+        if self.timestep == 0 {
+            for i in 0..self.starts.len() {
+                self.errors.push(ValidationError::new(
+                    ValidationErrorType::EmptyPath,
+                    i,
+                    None,
+                    format!("Agent {} has empty path", i),
+                ));
+            }
+        } else if !self.stopped {
+            let end = self.timestep - 1;
+            for (i, (pos, goal)) in self.previous.iter().zip(self.goals).enumerate() {
+                if pos != goal {
+                    self.errors.push(
+                        ValidationError::new(
+                            ValidationErrorType::InvalidGoal,
+                            i,
+                            Some(end),
+                            format!(
+                                "Agent {} path ends at ({},{}) but should end at ({},{})",
+                                i, pos.x, pos.y, goal.x, goal.y
+                            ),
+                        )
+                        .with_positions(vec![*pos, *goal]),
+                    );
+                }
+            }
+            for (i, &visited) in self.waypoints_visited.iter().enumerate() {
+                let waypoints = self.options.waypoints.get(i).map_or(&[][..], Vec::as_slice);
+                if let Some(waypoint) = waypoints.get(visited) {
+                    self.errors.push(missed_waypoint(i, visited, waypoint));
+                }
+            }
+        }
+
+        if self.options.fail_fast && !self.errors.is_empty() {
+            return ValidationResult::first_error(self.errors);
+        }
+
+        ValidationResult {
+            valid: self.errors.is_empty(),
+            agents: statuses_from_costs(self.last_move.iter().map(|&t| t as u32), &self.errors),
+            errors: self.errors,
+            warnings: Vec::new(),
+        }
+    }
+
+    fn track_waypoints(&mut self, positions: &[Coordinate]) {
+        for (i, pos) in positions.iter().enumerate() {
+            if let Some(waypoints) = self.options.waypoints.get(i) {
+                self.waypoints_visited[i] =
+                    advance_waypoints(waypoints, self.waypoints_visited[i], pos);
+            }
+        }
+    }
+
+    fn check_stop(&mut self) {
+        if self.options.fail_fast && !self.errors.is_empty() {
+            self.stopped = true;
+        }
+    }
+
+    fn check_starts(&mut self, positions: &[Coordinate]) {
+        for (i, (pos, start)) in positions.iter().zip(self.starts).enumerate() {
+            if pos != start {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::InvalidStart,
+                        i,
+                        Some(0),
+                        format!(
+                            "Agent {} path starts at ({},{}) but should start at ({},{})",
+                            i, pos.x, pos.y, start.x, start.y
+                        ),
+                    )
+                    .with_positions(vec![*pos, *start]),
+                );
+            }
+        }
+    }
+
+    fn check_moves(&mut self, positions: &[Coordinate], t: usize) {
+        for (i, (from, to)) in self.previous.iter().zip(positions).enumerate() {
+            if from == to {
+                continue;
+            }
+            self.last_move[i] = t;
+
+            let dx = to.x.abs_diff(from.x);
+            let dy = to.y.abs_diff(from.y);
+            let diagonal = dx == 1 && dy == 1;
+            if dx > 1 || dy > 1 || (diagonal && !self.diagonals) {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::DiagonalMove,
+                        i,
+                        Some(t - 1),
+                        format!(
+                            "Agent {} made invalid move from ({},{}) to ({},{}) at timestep {}",
+                            i,
+                            from.x,
+                            from.y,
+                            to.x,
+                            to.y,
+                            t - 1
+                        ),
+                    )
+                    .with_positions(vec![*from, *to]),
+                );
+            } else if diagonal
+                && !(self.map.is_passable(to.x, from.y) && self.map.is_passable(from.x, to.y))
+            {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::CornerCutting,
+                        i,
+                        Some(t - 1),
+                        format!(
+                            "Agent {} cut a corner moving from ({},{}) to ({},{}) at timestep {}",
+                            i,
+                            from.x,
+                            from.y,
+                            to.x,
+                            to.y,
+                            t - 1
+                        ),
+                    )
+                    .with_positions(vec![*from, *to]),
+                );
+            }
+
+        }
+    }
+
+    fn check_cells(&mut self, positions: &[Coordinate], t: usize) {
+        for (i, pos) in positions.iter().enumerate() {
+            if pos.x >= self.map.width() || pos.y >= self.map.height() {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::OutOfBounds,
+                        i,
+                        Some(t),
+                        format!(
+                            "Agent {} at ({},{}) is out of bounds at timestep {}",
+                            i, pos.x, pos.y, t
+                        ),
+                    )
+                    .with_positions(vec![*pos]),
+                );
+            } else if !self.map.is_passable(pos.x, pos.y) {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::BlockedCell,
+                        i,
+                        Some(t),
+                        format!(
+                            "Agent {} at ({},{}) is on blocked cell at timestep {}",
+                            i, pos.x, pos.y, t
+                        ),
+                    )
+                    .with_positions(vec![*pos]),
+                );
+            } else if self
+                .options
+                .obstacles
+                .iter()
+                .any(|o| o.x == pos.x && o.y == pos.y && o.is_active_at(t))
+            {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::DynamicObstacle,
+                        i,
+                        Some(t),
+                        format!(
+                            "Agent {} at ({},{}) hits a dynamic obstacle at timestep {}",
+                            i, pos.x, pos.y, t
+                        ),
+                    )
+                    .with_positions(vec![*pos]),
+                );
+            }
+        }
+    }
+
+    fn check_vertex_collisions(&mut self, positions: &[Coordinate], t: usize) {
+        let mut occupant: HashMap<Coordinate, usize> = HashMap::with_capacity(positions.len());
+        for (agent, pos) in positions.iter().enumerate() {
+            if let Some(&other_agent) = occupant.get(pos) {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::VertexCollision,
+                        agent,
+                        Some(t),
+                        format!(
+                            "Agents {} and {} collide at ({},{}) at timestep {}",
+                            other_agent, agent, pos.x, pos.y, t
+                        ),
+                    )
+                    .with_agents(vec![other_agent, agent])
+                    .with_positions(vec![*pos]),
+                );
+            } else {
+                occupant.insert(*pos, agent);
+            }
+        }
+    }
+
+    fn check_edge_collisions(&mut self, positions: &[Coordinate], t: usize) {
+        let mut moves: HashMap<(Coordinate, Coordinate), usize> = HashMap::new();
+        for (agent, (from, to)) in self.previous.iter().zip(positions).enumerate() {
+            if from == to {
+                continue;
+            }
+            if let Some(&other_agent) = moves.get(&(*to, *from)) {
+                self.errors.push(
+                    ValidationError::new(
+                        ValidationErrorType::EdgeCollision,
+                        other_agent,
+                        Some(t - 1),
+                        format!(
+                            "Agents {} and {} swap positions between timesteps {} and {}",
+                            other_agent,
+                            agent,
+                            t - 1,
+                            t
+                        ),
+                    )
+                    .with_agents(vec![other_agent, agent])
+                    .with_positions(vec![*to, *from]),
+                );
+            }
+            moves.insert((*from, *to), agent);
+        }
+    }
+}
+
+/// Validate a solution given as a stream of per-timestep position slices.
+///
+/// Stops consuming the stream as soon as the validator has stopped.
+pub fn validate_stream<I, S>(
+    steps: I,
+    map: &GridMap,
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+    options: &ValidationOptions,
+) -> ValidationResult
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<[Coordinate]>,
+{
+    let mut validator = StreamingValidator::new(map, starts, goals, options);
+    for positions in steps {
+        if !validator.push(positions.as_ref()) {
+            break;
+        }
+    }
+    validator.finish()
+}
+
+/// Fully validate a MAPF solution with default options.
+///
+/// Checks:
+/// 0. One path per start/goal pair, with pairwise distinct starts and goals
+/// 1. All moves are cardinal (N/S/E/W) or wait, or octile without corner
+///    cutting when diagonals are allowed
+/// 2. All positions are within bounds, on passable cells, and clear of timed obstacles
+/// 3. No path exceeds the horizon
+/// 4. Paths start and end at correct positions, visiting waypoints in order
+/// 5. No vertex collisions (two agents at same cell)
+/// 6. No edge collisions (two agents swapping)
+///
+/// Shorter paths are implicitly extended with the agent waiting at its last
+/// position until the longest path ends. Those implicit positions are checked
+/// too: the final cell's passability covers every later timestep, and both
+/// the obstacle and collision checks extend paths instead of ignoring agents
+/// that have finished.
+pub fn validate_solution(
+    solution: &Solution,
+    map: &GridMap,
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+) -> ValidationResult {
+    validate_solution_with(solution, map, starts, goals, &ValidationOptions::default())
+}
+
+/// Fully validate a MAPF solution with the given options.
+pub fn validate_solution_with(
+    solution: &Solution,
+    map: &GridMap,
+    starts: &[Coordinate],
+    goals: &[Coordinate],
+    options: &ValidationOptions,
+) -> ValidationResult {
+    let mut errors = Vec::new();
+    let horizon = options.horizon(map);
+    let diagonals = options.diagonals_allowed(map);
+
+    // Validate the instance itself
+    errors.extend(validate_agent_counts(&solution.paths, starts, goals));
+    errors.extend(validate_distinct_endpoints(starts, goals));
+    if options.fail_fast && !errors.is_empty() {
+        return ValidationResult::first_error(errors);
+    }
+
+    // Validate each path individually
+    for (i, path) in solution.paths.iter().enumerate() {
+        if diagonals {
+            errors.extend(validate_path_octile(path, i, map));
+        } else {
+            errors.extend(validate_path_cardinal(path, i));
+        }
+        errors.extend(validate_path_on_map(path, i, map));
+        errors.extend(validate_path_length(path, i, horizon));
+        if !options.obstacles.is_empty() {
+            errors.extend(validate_path_dynamic_with(
+                path,
+                i,
+                &options.obstacles,
+                &options.rules,
+            ));
+        }
+        if options.fail_fast && !errors.is_empty() {
+            return ValidationResult::first_error(errors);
+        }
+    }
+
+    // Validate starts and goals
+    errors.extend(validate_starts_and_goals(&solution.paths, starts, goals));
+    for (i, path) in solution.paths.iter().enumerate() {
+        if let Some(waypoints) = options.waypoints.get(i) {
+            errors.extend(validate_waypoints(path, i, waypoints));
+        }
+    }
+    if options.fail_fast && !errors.is_empty() {
+        return ValidationResult::first_error(errors);
+    }
+
+    // Validate collisions between agents
+    if solution.paths.len() > 1 {
+        // Bitsets need every position on the map
+        let in_bounds = !errors
+            .iter()
+            .any(|e| matches!(e.error_type, ValidationErrorType::OutOfBounds));
+        if solution.paths.len() > BITSET_AGENT_THRESHOLD && in_bounds {
+            errors.extend(validate_no_vertex_collisions_bitset(
+                &solution.paths,
+                map.width(),
+                map.height(),
+                &options.rules,
+            ));
+        } else {
+            errors.extend(validate_no_vertex_collisions_with(
+                &solution.paths,
+                &options.rules,
+            ));
+        }
+        if options.fail_fast && !errors.is_empty() {
+            return ValidationResult::first_error(errors);
+        }
+        errors.extend(validate_no_edge_collisions_with(
+            &solution.paths,
+            &options.rules,
+        ));
+    }
+
+    if options.fail_fast && !errors.is_empty() {
+        return ValidationResult::first_error(errors);
+    }
+
+    ValidationResult {
+        valid: errors.is_empty(),
+        agents: agent_statuses(&solution.paths, &errors),
+        errors,
+        warnings: collect_warnings(&solution.paths, goals),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinal_moves() {
+        assert!(is_cardinal_move(&Coordinate { x: 0, y: 0 }, &Coordinate { x: 1, y: 0 }));
+        assert!(is_cardinal_move(&Coordinate { x: 0, y: 0 }, &Coordinate { x: 0, y: 1 }));
+        assert!(is_cardinal_move(&Coordinate { x: 0, y: 0 }, &Coordinate { x: 0, y: 0 }));
+        assert!(!is_cardinal_move(&Coordinate { x: 0, y: 0 }, &Coordinate { x: 1, y: 1 }));
+    }
+
+    #[test]
+    fn test_empty_path() {
+        let path = Path { steps: vec![] };
+        let errors = validate_path_cardinal(&path, 0);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].error_type, ValidationErrorType::EmptyPath));
+    }
+
+    #[test]
+    fn test_diagonal_move() {
+        let path = Path {
+            steps: vec![
+                Coordinate { x: 0, y: 0 },
+                Coordinate { x: 1, y: 1 }, // Diagonal!
+            ],
+        };
+        let errors = validate_path_cardinal(&path, 0);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].error_type, ValidationErrorType::DiagonalMove));
+    }
+
+    #[test]
+    fn test_below_optimal() {
+        let paths = vec![
+            Path {
+                steps: vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 1, y: 0 }],
+            },
+            Path {
+                steps: vec![Coordinate { x: 3, y: 0 }, Coordinate { x: 3, y: 1 }],
+            },
+        ];
+        let errors = validate_against_baselines(&paths, &[1.0, 3.0]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].agent_index, 1);
+        assert!(matches!(errors[0].error_type, ValidationErrorType::BelowOptimal));
+
+        // Extra paths or baselines aren't silently dropped
+        for optimal_lengths in [&[1.0][..], &[1.0, 1.0, 1.0]] {
+            let errors = validate_against_baselines(&paths, optimal_lengths);
+            assert_eq!(errors.len(), 1);
+            assert!(matches!(
+                errors[0].error_type,
+                ValidationErrorType::AgentCountMismatch
+            ));
+        }
+    }
+
+    #[test]
+    fn test_path_too_long() {
+        let map = GridMap::from_bytes(2, 1, &[1, 1]).unwrap();
+        let steps = [0, 1, 0, 1, 0, 1]
+            .iter()
+            .map(|&x| Coordinate { x, y: 0 })
+            .collect();
+        let solution = Solution {
+            paths: vec![Path { steps }],
+        };
+        let starts = [Coordinate { x: 0, y: 0 }];
+        let goals = [Coordinate { x: 1, y: 0 }];
+        let options = ValidationOptions {
+            max_path_length: Some(4),
+            ..Default::default()
+        };
+
+        let result = validate_solution_with(&solution, &map, &starts, &goals, &options);
+        assert!(!result.valid);
+        assert!(matches!(result.errors[0].error_type, ValidationErrorType::PathTooLong));
+
+        // Derived horizon of 16 * (2 + 1) easily admits five moves
+        assert!(validate_solution(&solution, &map, &starts, &goals).valid);
+    }
+
+    #[test]
+    fn test_fail_fast_reports_single_error() {
+        let map = GridMap::from_bytes(3, 1, &[1, 1, 1]).unwrap();
+        let solution = Solution {
+            paths: vec![
+                Path {
+                    steps: vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 1, y: 1 }],
+                },
+                Path {
+                    steps: vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 1, y: 1 }],
+                },
+            ],
+        };
+        let starts = [Coordinate { x: 0, y: 0 }, Coordinate { x: 2, y: 0 }];
+        let goals = [Coordinate { x: 1, y: 0 }, Coordinate { x: 2, y: 0 }];
+
+        let exhaustive = validate_solution(&solution, &map, &starts, &goals);
+        assert!(exhaustive.errors.len() > 1);
+
+        let options = ValidationOptions {
+            fail_fast: true,
+            ..Default::default()
+        };
+        let result = validate_solution_with(&solution, &map, &starts, &goals, &options);
+        assert!(!result.valid);
+        assert_eq!(result.errors.len(), 1);
+        assert!(matches!(result.errors[0].error_type, ValidationErrorType::DiagonalMove));
+    }
+
+    #[test]
+    fn test_vertex_and_edge_collisions() {
+        let paths = vec![
+            Path {
+                steps: vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 1, y: 0 }],
+            },
+            Path {
+                steps: vec![Coordinate { x: 1, y: 0 }, Coordinate { x: 0, y: 0 }],
+            },
+            Path {
+                steps: vec![Coordinate { x: 2, y: 0 }, Coordinate { x: 1, y: 0 }],
+            },
+        ];
+
+        let vertex = validate_no_vertex_collisions(&paths);
+        assert_eq!(vertex.len(), 1);
+        assert_eq!(vertex[0].agent_index, 2);
+        assert_eq!(vertex[0].timestep, Some(1));
+
+        let edge = validate_no_edge_collisions(&paths);
+        assert_eq!(edge.len(), 1);
+        assert_eq!(edge[0].agent_index, 0);
+        assert!(edge[0].details.contains("Agents 0 and 1"));
+    }
+
+    #[test]
+    fn test_octile_corner_cutting() {
+        // ..
+        // .#
+        let map = GridMap::from_bytes(2, 2, &[1, 1, 1, 0]).unwrap();
+        let path = Path {
+            steps: vec![
+                Coordinate { x: 0, y: 0 },
+                Coordinate { x: 0, y: 1 },
+                Coordinate { x: 1, y: 0 },
+            ],
+        };
+        let errors = validate_path_octile(&path, 0, &map);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].error_type, ValidationErrorType::CornerCutting));
+
+        let map = GridMap::from_bytes(2, 2, &[1, 1, 1, 1]).unwrap();
+        assert!(validate_path_octile(&path, 0, &map).is_empty());
+
+        let options = ValidationOptions {
+            rules: RuleSet {
+                allow_diagonals: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(options.diagonals_allowed(&map));
+        assert!(!options.diagonals_allowed(&map.with_map_type("tile")));
+    }
+
+    #[test]
+    fn test_error_codes_match_serialized_type() {
+        let error = ValidationError::new(ValidationErrorType::EdgeCollision, 0, Some(1), String::new())
+            .with_agents(vec![0, 1]);
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["type"], "edge_collision");
+        assert_eq!(json["code"], 301);
+        assert_eq!(json["agents"], serde_json::json!([0, 1]));
+        assert_eq!(ValidationErrorType::EdgeCollision.as_str(), "edge_collision");
+    }
+
+    #[test]
+    fn test_vertex_collisions_with_parked_agents() {
+        let paths = vec![
+            // Parks at (1,0) from t=1
+            Path {
+                steps: vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 1, y: 0 }],
+            },
+            // Drives through the parked agent at t=3
+            Path {
+                steps: vec![
+                    Coordinate { x: 1, y: 3 },
+                    Coordinate { x: 1, y: 2 },
+                    Coordinate { x: 1, y: 1 },
+                    Coordinate { x: 1, y: 0 },
+                    Coordinate { x: 2, y: 0 },
+                ],
+            },
+            // Parks on the same cell as agent 3 at t=4
+            Path {
+                steps: vec![
+                    Coordinate { x: 3, y: 3 },
+                    Coordinate { x: 3, y: 3 },
+                    Coordinate { x: 3, y: 3 },
+                    Coordinate { x: 3, y: 2 },
+                    Coordinate { x: 3, y: 1 },
+                ],
+            },
+            Path {
+                steps: vec![Coordinate { x: 3, y: 1 }],
+            },
+        ];
+
+        let errors = validate_no_vertex_collisions(&paths);
+        let summary: Vec<(usize, Option<usize>)> =
+            errors.iter().map(|e| (e.agent_index, e.timestep)).collect();
+        assert_eq!(summary, vec![(1, Some(3)), (3, Some(4))]);
+        assert_eq!(errors[0].agents, vec![0, 1]);
+        assert_eq!(errors[1].agents, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_agent_count_and_duplicate_endpoints() {
+        let map = GridMap::from_bytes(3, 1, &[1, 1, 1]).unwrap();
+        let solution = Solution {
+            paths: vec![Path {
+                steps: vec![Coordinate { x: 0, y: 0 }, Coordinate { x: 1, y: 0 }],
+            }],
+        };
+        let starts = [Coordinate { x: 0, y: 0 }, Coordinate { x: 0, y: 0 }];
+        let goals = [Coordinate { x: 1, y: 0 }, Coordinate { x: 2, y: 0 }];
+
+        // Must not panic on the missing path
+        let result = validate_solution(&solution, &map, &starts, &goals);
+        assert!(!result.valid);
+        let types: Vec<&str> = result.errors.iter().map(|e| e.error_type.as_str()).collect();
+        assert_eq!(types, vec!["agent_count_mismatch", "duplicate_start"]);
+        assert_eq!(result.errors[1].agents, vec![0, 1]);
+
+        // Extra paths beyond the starts must not panic either
+        let errors = validate_starts_and_goals(&solution.paths, &[], &[]);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn test_warnings_do_not_invalidate() {
+        let map = GridMap::from_bytes(3, 1, &[1, 1, 1]).unwrap();
+        let c = |x| Coordinate { x, y: 0 };
+        let solution = Solution {
+            paths: vec![Path {
+                // Reaches the goal, leaves, returns, then idles
+                steps: vec![c(0), c(1), c(2), c(1), c(2), c(1), c(1), c(1)],
+            }],
+        };
+        let result = validate_solution(&solution, &map, &[c(0)], &[c(1)]);
+        assert!(result.valid);
+
+        let kinds: Vec<&str> = result.warnings.iter().map(|w| w.warning_type.as_str()).collect();
+        assert_eq!(kinds, vec!["trailing_wait", "goal_revisit", "detour"]);
+        assert_eq!(result.warnings[0].timestep, Some(6));
+    }
+
+    #[test]
+    fn test_dynamic_obstacles() {
+        let c = |x| Coordinate { x, y: 0 };
+        let path = Path {
+            steps: vec![c(0), c(1), c(2)],
+        };
+        // Appears on (1,0) after the agent has passed
+        let late = TimedObstacle { x: 1, y: 0, from: 2, until: None };
+        assert!(validate_path_dynamic(&path, 0, &[late]).is_empty());
+
+        // Present while the agent passes through
+        let early = TimedObstacle { x: 1, y: 0, from: 0, until: Some(2) };
+        let errors = validate_path_dynamic(&path, 0, &[early]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].timestep, Some(1));
+
+        // Drops onto the goal after the agent has parked there
+        let on_goal = TimedObstacle { x: 2, y: 0, from: 10, until: Some(11) };
+        let errors = validate_path_dynamic(&path, 0, &[on_goal]);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].timestep, Some(10));
+        assert!(matches!(errors[0].error_type, ValidationErrorType::DynamicObstacle));
+    }
+
+    #[test]
+    fn test_waypoints_in_order() {
+        let map = GridMap::from_bytes(4, 1, &[1; 4]).unwrap();
+        let c = |x| Coordinate { x, y: 0 };
+        // Out to the far end and back
+        let path = Path {
+            steps: vec![c(1), c(2), c(3), c(2), c(1), c(0)],
+        };
+        assert!(validate_waypoints(&path, 0, &[c(3), c(1)]).is_empty());
+        assert!(validate_waypoints(&path, 0, &[]).is_empty());
+
+        let errors = validate_waypoints(&path, 0, &[c(0), c(3)]);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0].error_type, ValidationErrorType::MissedWaypoint));
+        assert_eq!(errors[0].positions, vec![c(3)]);
+
+        // The streaming validator agrees
+        let options = ValidationOptions {
+            waypoints: vec![vec![c(0), c(3)]],
+            ..Default::default()
+        };
+        let steps: Vec<Vec<Coordinate>> = path.steps.iter().map(|&p| vec![p]).collect();
+        let streamed = validate_stream(&steps, &map, &[c(1)], &[c(0)], &options);
+        let batch =
+            validate_solution_with(&Solution { paths: vec![path] }, &map, &[c(1)], &[c(0)], &options);
+        assert_eq!(streamed.errors.len(), 1);
+        assert_eq!(batch.errors.len(), 1);
+        assert_eq!(streamed.errors[0].code, batch.errors[0].code);
+    }
+
+    #[test]
+    fn test_declared_cost_cross_check() {
+        let c = |x| Coordinate { x, y: 0 };
+        let solution = Solution {
+            paths: vec![
+                Path {
+                    steps: vec![c(0), c(1), c(2), c(2)],
+                },
+                Path {
+                    steps: vec![c(5), c(4)],
+                },
+            ],
+        };
+        let map = GridMap::from_bytes(6, 1, &[1; 6]).unwrap();
+        // Sum of costs (trimmed), sum of costs (raw), makespan
+        for declared in [3, 4, 2] {
+            assert!(check_declared_cost(&solution, &map, declared).is_empty());
+        }
+        let warnings = check_declared_cost(&solution, &map, 42);
+        assert_eq!(warnings.len(), 1);
+        assert!(matches!(warnings[0].warning_type, ValidationWarningType::CostMismatch));
+
+        // Weighted sum of costs: 2 + 3 (trimmed) and 2 + 3 + 3 (raw), plus 9 for agent 1
+        let map = map.with_costs(vec![1, 2, 3, 1, 9, 1]).unwrap();
+        for declared in [14, 17] {
+            assert!(check_declared_cost(&solution, &map, declared).is_empty());
+        }
+        assert_eq!(scored_cost(&solution.canonicalize(), &map), 14);
+    }
+
+    #[test]
+    fn test_per_agent_status() {
+        let map = GridMap::from_bytes(4, 2, &[1; 8]).unwrap();
+        let c = |x, y| Coordinate { x, y };
+        let solution = Solution {
+            paths: vec![
+                Path {
+                    steps: vec![c(0, 0), c(1, 0)],
+                },
+                Path {
+                    steps: vec![c(2, 0), c(1, 0)],
+                },
+                Path {
+                    steps: vec![c(0, 1), c(1, 1), c(2, 1), c(2, 1)],
+                },
+            ],
+        };
+        let starts = [c(0, 0), c(2, 0), c(0, 1)];
+        let goals = [c(1, 0), c(3, 0), c(2, 1)];
+
+        let result = validate_solution(&solution, &map, &starts, &goals);
+        assert!(!result.valid);
+        assert_eq!(result.agents.len(), 3);
+
+        // Both parties of the collision lose credit; agent 1 also ends off-goal
+        assert!(!result.agents[0].valid);
+        assert!(!result.agents[1].valid);
+        assert!(matches!(
+            result.agents[1].first_error.as_ref().unwrap().error_type,
+            ValidationErrorType::InvalidGoal
+        ));
+
+        assert!(result.agents[2].valid);
+        assert_eq!(result.agents[2].cost, 2);
+        assert!(result.agents[2].first_error.is_none());
+    }
+
+    #[test]
+    fn test_streaming_matches_batch() {
+        let map = GridMap::from_bytes(4, 2, &[1; 8]).unwrap();
+        let c = |x, y| Coordinate { x, y };
+        let starts = [c(0, 0), c(3, 0)];
+        let goals = [c(3, 0), c(0, 0)];
+        // Agents swap along the top row
+        let solution = Solution {
+            paths: vec![
+                Path {
+                    steps: vec![c(0, 0), c(1, 0), c(2, 0), c(3, 0)],
+                },
+                Path {
+                    steps: vec![c(3, 0), c(2, 0), c(1, 0), c(0, 0)],
+                },
+            ],
+        };
+        let steps: Vec<Vec<Coordinate>> = (0..4)
+            .map(|t| solution.paths.iter().map(|p| p.steps[t]).collect())
+            .collect();
+
+        let options = ValidationOptions::default();
+        let batch = validate_solution_with(&solution, &map, &starts, &goals, &options);
+        let streamed = validate_stream(&steps, &map, &starts, &goals, &options);
+
+        assert!(!streamed.valid);
+        let kinds = |r: &ValidationResult| {
+            let mut kinds: Vec<_> = r
+                .errors
+                .iter()
+                .map(|e| (e.code, e.timestep, e.agents.clone()))
+                .collect();
+            kinds.sort();
+            kinds
+        };
+        assert_eq!(kinds(&streamed), kinds(&batch));
+        assert_eq!(streamed.agents[0].cost, 3);
+    }
+
+    #[test]
+    fn test_streaming_stops_past_horizon() {
+        let map = GridMap::from_bytes(2, 1, &[1; 2]).unwrap();
+        let c = |x, y| Coordinate { x, y };
+        let options = ValidationOptions {
+            max_path_length: Some(3),
+            ..Default::default()
+        };
+
+        // An agent oscillating forever
+        let mut consumed = 0;
+        let steps = (0..).map(|t| {
+            consumed += 1;
+            [c(t % 2, 0)]
+        });
+        let result = validate_stream(steps, &map, &[c(0, 0)], &[c(0, 0)], &options);
+
+        assert!(!result.valid);
+        assert_eq!(result.errors[0].code, ValidationErrorType::PathTooLong.code());
+        assert_eq!(consumed, 5);
+    }
+
+    #[test]
+    fn test_streaming_stops_waits_past_horizon() {
+        let map = GridMap::from_bytes(2, 1, &[1; 2]).unwrap();
+        let c = |x, y| Coordinate { x, y };
+        let options = ValidationOptions {
+            max_path_length: Some(3),
+            ..Default::default()
+        };
+
+        // An agent waiting at its goal forever
+        let mut consumed = 0;
+        let steps = std::iter::repeat_with(|| {
+            consumed += 1;
+            [c(1, 0)]
+        });
+        let result = validate_stream(steps, &map, &[c(1, 0)], &[c(1, 0)], &options);
+
+        assert!(!result.valid);
+        assert_eq!(result.errors[0].code, ValidationErrorType::PathTooLong.code());
+        assert_eq!(consumed, 5);
+    }
+
+    #[test]
+    fn test_disappear_at_goal() {
+        // Corridor: agent 0 starts on its goal, agent 1 passes through it
+        let c = |x, y| Coordinate { x, y };
+        let paths = vec![
+            Path {
+                steps: vec![c(1, 0)],
+            },
+            Path {
+                steps: vec![c(0, 0), c(1, 0), c(2, 0)],
+            },
+        ];
+
+        let stay = validate_no_vertex_collisions(&paths);
+        assert_eq!(stay.len(), 1);
+        assert_eq!(stay[0].timestep, Some(1));
+
+        let rules = RuleSet {
+            disappear_at_goal: true,
+            ..Default::default()
+        };
+        assert!(validate_no_vertex_collisions_with(&paths, &rules).is_empty());
+    }
+
+    #[test]
+    fn test_disappear_at_goal_edges() {
+        // Agent 0 vanishes at (1,0) after t=0; agent 1 then waits on that
+        // cell while agent 2 waits next to it
+        let c = |x, y| Coordinate { x, y };
+        let paths = vec![
+            Path {
+                steps: vec![c(1, 0)],
+            },
+            Path {
+                steps: vec![c(0, 1), c(1, 1), c(1, 0), c(1, 0), c(2, 0)],
+            },
+            Path {
+                steps: vec![c(3, 0), c(2, 0), c(2, 0), c(3, 0)],
+            },
+        ];
+        let rules = RuleSet {
+            disappear_at_goal: true,
+            ..Default::default()
+        };
+
+        assert!(validate_no_edge_collisions_with(&paths, &rules).is_empty());
+        assert!(validate_no_vertex_collisions_with(&paths, &rules).is_empty());
+
+        // Staying at the goal, agent 0 blocks agent 1 without any swap
+        assert!(validate_no_edge_collisions(&paths).is_empty());
+        assert!(!validate_no_vertex_collisions(&paths).is_empty());
+    }
+
+    #[test]
+    fn test_implicit_positions_checked() {
+        let map = GridMap::from_bytes(3, 2, &[1; 6]).unwrap();
+        let c = |x, y| Coordinate { x, y };
+        // Agent 0 finishes at t=1; at t=4 agent 1 walks onto its cell and an
+        // obstacle appears there
+        let solution = Solution {
+            paths: vec![
+                Path {
+                    steps: vec![c(0, 0), c(1, 0)],
+                },
+                Path {
+                    steps: vec![c(0, 1), c(1, 1), c(2, 1), c(2, 0), c(1, 0)],
+                },
+            ],
+        };
+        let options = ValidationOptions {
+            obstacles: vec![TimedObstacle {
+                x: 1,
+                y: 0,
+                from: 4,
+                until: None,
+            }],
+            ..Default::default()
+        };
+        let starts = [c(0, 0), c(0, 1)];
+        let goals = [c(1, 0), c(1, 0)];
+
+        let result = validate_solution_with(&solution, &map, &starts, &goals, &options);
+        let found = |kind: ValidationErrorType, agent: usize, t: usize| {
+            result.errors.iter().any(|e| {
+                e.code == kind.code() && e.agent_index == agent && e.timestep == Some(t)
+            })
+        };
+
+        assert!(found(ValidationErrorType::VertexCollision, 1, 4));
+        assert!(found(ValidationErrorType::DynamicObstacle, 0, 4));
+    }
+
+    #[test]
+    fn test_bitset_backend_matches() {
+        let (width, height) = (70, 3);
+        let c = |x, y| Coordinate { x, y };
+        // Pseudo-random walks crowded into a small map
+        let mut seed: u64 = 7;
+        let mut next = |n: u32| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            ((seed >> 33) % n as u64) as u32
+        };
+        let paths: Vec<Path> = (0..200)
+            .map(|_| Path {
+                steps: (0..next(8)).map(|_| c(next(width), next(height))).collect(),
+            })
+            .collect();
+
+        let key = |errors: Vec<ValidationError>| -> Vec<_> {
+            errors
+                .into_iter()
+                .map(|e| (e.timestep, e.agent_index, e.agents))
+                .collect()
+        };
+        for disappear_at_goal in [false, true] {
+            let rules = RuleSet {
+                disappear_at_goal,
+                ..Default::default()
+            };
+            let expected = key(validate_no_vertex_collisions_with(&paths, &rules));
+            assert!(!expected.is_empty());
+            assert_eq!(
+                key(validate_no_vertex_collisions_bitset(&paths, width, height, &rules)),
+                expected
+            );
+        }
+    }
+}
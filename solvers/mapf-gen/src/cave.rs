@@ -0,0 +1,85 @@
+//! Natural caves grown by cellular automaton smoothing of random noise,
+//! trimmed to their largest connected chamber.
+
+use mapf_core::GridMap;
+use rand::Rng;
+
+use crate::{check_size, into_map, keep_largest_region, rng, GenError};
+
+/// Shape of a cave.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaveParams {
+    pub width: u32,
+    pub height: u32,
+    /// Share of the initial noise that is rock (0-1); higher gives tighter caves
+    pub fill: f64,
+    /// Smoothing passes; more give rounder walls
+    pub smoothing: u32,
+}
+
+impl Default for CaveParams {
+    fn default() -> Self {
+        Self {
+            width: 64,
+            height: 64,
+            fill: 0.45,
+            smoothing: 5,
+        }
+    }
+}
+
+/// Generate a cave.
+///
+/// Each smoothing pass turns a cell to rock when more than four of its
+/// eight neighbors are rock (the map edge counts as rock) and opens it when
+/// fewer than four are. Chambers cut off from the largest are filled in.
+pub fn generate(params: &CaveParams, seed: u64) -> Result<GridMap, GenError> {
+    let p = params;
+    check_size(p.width, p.height)?;
+    if !(0.0..=1.0).contains(&p.fill) {
+        return Err(GenError::InvalidParams(format!(
+            "fill {} is not between 0 and 1",
+            p.fill
+        )));
+    }
+
+    let mut rng = rng(seed);
+    let mut tiles: Vec<u8> = (0..p.width * p.height)
+        .map(|_| !rng.gen_bool(p.fill) as u8)
+        .collect();
+    for _ in 0..p.smoothing {
+        tiles = smooth(p.width, p.height, &tiles);
+    }
+    keep_largest_region(p.width, p.height, &mut tiles);
+
+    if !tiles.contains(&1) {
+        return Err(GenError::InvalidParams(format!(
+            "fill {} leaves no open cave",
+            p.fill
+        )));
+    }
+    Ok(into_map(p.width, p.height, &tiles))
+}
+
+fn smooth(width: u32, height: u32, tiles: &[u8]) -> Vec<u8> {
+    let (w, h) = (width as i64, height as i64);
+    let rock =
+        |x: i64, y: i64| x < 0 || y < 0 || x >= w || y >= h || tiles[(y * w + x) as usize] == 0;
+
+    let mut next = Vec::with_capacity(tiles.len());
+    for y in 0..h {
+        for x in 0..w {
+            let walls = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .filter(|&(dx, dy)| (dx, dy) != (0, 0) && rock(x + dx, y + dy))
+                .count();
+            let open = match walls {
+                0..=3 => 1,
+                4 => tiles[(y * w + x) as usize],
+                _ => 0,
+            };
+            next.push(open);
+        }
+    }
+    next
+}
@@ -0,0 +1,89 @@
+//! City-like street grids: irregular blocks of buildings between straight
+//! streets, with the odd block left open as a park or plaza.
+
+use mapf_core::GridMap;
+use rand::Rng;
+
+use crate::{check_size, into_map, rng, GenError};
+
+/// Layout of a street grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CityParams {
+    pub width: u32,
+    pub height: u32,
+    /// Width of every street, in cells
+    pub street_width: u32,
+    /// Smallest side of a block, in cells
+    pub block_min: u32,
+    /// Largest side of a block, in cells
+    pub block_max: u32,
+    /// Chance that a block is open ground instead of a building (0-1)
+    pub park_probability: f64,
+}
+
+impl Default for CityParams {
+    fn default() -> Self {
+        Self {
+            width: 64,
+            height: 64,
+            street_width: 1,
+            block_min: 3,
+            block_max: 8,
+            park_probability: 0.1,
+        }
+    }
+}
+
+/// Generate a street grid. Streets run along the top and left edges, so
+/// every block borders a street.
+pub fn generate(params: &CityParams, seed: u64) -> Result<GridMap, GenError> {
+    let p = params;
+    check_size(p.width, p.height)?;
+    if p.street_width == 0 || p.block_min == 0 || p.block_min > p.block_max {
+        return Err(GenError::InvalidParams(
+            "streets need a width and blocks a size range of at least one cell".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&p.park_probability) {
+        return Err(GenError::InvalidParams(format!(
+            "park probability {} is not between 0 and 1",
+            p.park_probability
+        )));
+    }
+
+    let mut rng = rng(seed);
+    let columns = blocks(p.width, p, &mut rng);
+    let rows = blocks(p.height, p, &mut rng);
+    let block_count = |axis: &[Option<usize>]| axis.iter().flatten().max().map_or(0, |&b| b + 1);
+    let parks: Vec<bool> = (0..block_count(&columns) * block_count(&rows))
+        .map(|_| p.park_probability > 0.0 && rng.gen_bool(p.park_probability))
+        .collect();
+
+    let mut tiles = Vec::with_capacity((p.width * p.height) as usize);
+    for row in &rows {
+        for column in &columns {
+            let open = match (row, column) {
+                (Some(row), Some(column)) => parks[row * block_count(&columns) + column],
+                // A street
+                _ => true,
+            };
+            tiles.push(open as u8);
+        }
+    }
+    Ok(into_map(p.width, p.height, &tiles))
+}
+
+/// Split one axis into streets and blocks: for each coordinate, the index of
+/// the block it is in, or `None` on a street.
+fn blocks(length: u32, p: &CityParams, rng: &mut impl Rng) -> Vec<Option<usize>> {
+    let mut axis = Vec::with_capacity(length as usize);
+    let mut block = 0;
+    while axis.len() < length as usize {
+        axis.extend((0..p.street_width).map(|_| None));
+        let size = rng.gen_range(p.block_min..=p.block_max);
+        axis.extend((0..size).map(|_| Some(block)));
+        block += 1;
+    }
+    axis.truncate(length as usize);
+    axis
+}
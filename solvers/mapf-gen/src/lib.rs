@@ -0,0 +1,112 @@
+//! Procedural MAPF benchmark generator.
+//!
+//! Mints fresh, unseen contest instances: [`warehouse`] floors, city-like
+//! street grids ([`city`]) and [`cave`] maps, plus matching MovingAI
+//! scenarios for any map ([`scenario`]). Everything is seeded, so the same
+//! parameters and seed always give the same files.
+//!
+//! Generated maps are 4-connected: every free cell can reach every other.
+
+pub mod cave;
+pub mod city;
+pub mod scenario;
+pub mod warehouse;
+
+use std::collections::VecDeque;
+
+use mapf_core::GridMap;
+use rand::SeedableRng;
+use rand_pcg::Pcg64;
+use thiserror::Error;
+
+/// Errors from generating a map or scenario.
+#[derive(Debug, Error)]
+pub enum GenError {
+    #[error("invalid parameters: {0}")]
+    InvalidParams(String),
+
+    #[error("map has {free} free cells, too few for {agents} agents")]
+    TooManyAgents { agents: usize, free: usize },
+}
+
+/// Largest map generated, a little over the biggest MovingAI benchmarks.
+const MAX_CELLS: u64 = 1 << 24;
+
+fn rng(seed: u64) -> Pcg64 {
+    Pcg64::seed_from_u64(seed)
+}
+
+/// Fail unless a `width` x `height` map is non-empty and not too large.
+fn check_size(width: u32, height: u32) -> Result<(), GenError> {
+    let cells = width as u64 * height as u64;
+    if cells == 0 || cells > MAX_CELLS {
+        return Err(GenError::InvalidParams(format!(
+            "{}x{} map must have between 1 and {} cells",
+            width, height, MAX_CELLS
+        )));
+    }
+    Ok(())
+}
+
+/// Map from row-major occupancy, 1 = passable.
+fn into_map(width: u32, height: u32, tiles: &[u8]) -> GridMap {
+    GridMap::from_bytes(width, height, tiles).expect("tiles cover the map")
+}
+
+/// Label the 4-connected regions of passable cells.
+///
+/// Returns each cell's region (`usize::MAX` for blocked cells) and the size
+/// of every region.
+fn regions(width: u32, height: u32, tiles: &[u8]) -> (Vec<usize>, Vec<usize>) {
+    let mut labels = vec![usize::MAX; tiles.len()];
+    let mut sizes = Vec::new();
+    let mut queue = VecDeque::new();
+
+    for seed in 0..tiles.len() {
+        if tiles[seed] == 0 || labels[seed] != usize::MAX {
+            continue;
+        }
+        let region = sizes.len();
+        let mut size = 0;
+        labels[seed] = region;
+        queue.push_back(seed);
+        while let Some(cell) = queue.pop_front() {
+            size += 1;
+            for next in neighbors(width, height, cell) {
+                if tiles[next] != 0 && labels[next] == usize::MAX {
+                    labels[next] = region;
+                    queue.push_back(next);
+                }
+            }
+        }
+        sizes.push(size);
+    }
+    (labels, sizes)
+}
+
+/// Block every passable cell outside the largest region.
+fn keep_largest_region(width: u32, height: u32, tiles: &mut [u8]) {
+    let (labels, sizes) = regions(width, height, tiles);
+    let Some(largest) = (0..sizes.len()).max_by_key(|&r| (sizes[r], usize::MAX - r)) else {
+        return;
+    };
+    for (tile, label) in tiles.iter_mut().zip(labels) {
+        if label != largest {
+            *tile = 0;
+        }
+    }
+}
+
+/// In-bounds 4-neighbors of a row-major cell index.
+fn neighbors(width: u32, height: u32, cell: usize) -> impl Iterator<Item = usize> {
+    let (w, h) = (width as usize, height as usize);
+    let (x, y) = (cell % w, cell / w);
+    [
+        (x > 0).then(|| cell - 1),
+        (x + 1 < w).then(|| cell + 1),
+        (y > 0).then(|| cell - w),
+        (y + 1 < h).then(|| cell + w),
+    ]
+    .into_iter()
+    .flatten()
+}
@@ -0,0 +1,22 @@
+//! Random MovingAI scenarios for any map.
+
+use mapf_core::{GridMap, Scenario};
+
+use crate::GenError;
+
+/// Generate `agents` tasks on `map` with [`Scenario::generate`], writing
+/// `map_name` into every entry, e.g. `warehouse-44-36.map`.
+pub fn generate(
+    map: &GridMap,
+    map_name: &str,
+    agents: usize,
+    seed: u64,
+) -> Result<Scenario, GenError> {
+    match Scenario::generate(map, agents, seed) {
+        Some(scenario) => Ok(scenario.with_map_name(map_name)),
+        None => Err(GenError::TooManyAgents {
+            agents,
+            free: map.to_bytes().iter().filter(|&&b| b != 0).count(),
+        }),
+    }
+}
@@ -0,0 +1,104 @@
+//! Warehouse floors: a grid of shelf blocks separated by aisles, inside an
+//! open border where agents enter and leave, like the `warehouse-*`
+//! benchmarks.
+
+use mapf_core::GridMap;
+use rand::Rng;
+
+use crate::{check_size, into_map, rng, GenError};
+
+/// Layout of a warehouse floor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WarehouseParams {
+    /// Shelf blocks per row
+    pub columns: u32,
+    /// Rows of shelf blocks
+    pub rows: u32,
+    /// Length of a shelf block, in cells
+    pub shelf_length: u32,
+    /// Depth of a shelf block, in cells
+    pub shelf_depth: u32,
+    /// Width of the aisles between blocks
+    pub aisle_width: u32,
+    /// Width of the open area around the shelves
+    pub border: u32,
+    /// Chance that a shelf block is left out, opening up the floor (0-1)
+    pub gap_probability: f64,
+}
+
+impl Default for WarehouseParams {
+    fn default() -> Self {
+        Self {
+            columns: 4,
+            rows: 8,
+            shelf_length: 8,
+            shelf_depth: 2,
+            aisle_width: 2,
+            border: 3,
+            gap_probability: 0.0,
+        }
+    }
+}
+
+impl WarehouseParams {
+    /// Map size these parameters give, as (width, height).
+    pub fn size(&self) -> (u32, u32) {
+        let span = |blocks: u32, block: u32| {
+            2 * self.border + blocks * block + blocks.saturating_sub(1) * self.aisle_width
+        };
+        (
+            span(self.columns, self.shelf_length),
+            span(self.rows, self.shelf_depth),
+        )
+    }
+}
+
+/// Generate a warehouse; `seed` only matters with a `gap_probability`.
+pub fn generate(params: &WarehouseParams, seed: u64) -> Result<GridMap, GenError> {
+    let p = params;
+    if p.columns == 0 || p.rows == 0 || p.shelf_length == 0 || p.shelf_depth == 0 {
+        return Err(GenError::InvalidParams(
+            "warehouse needs at least one shelf block of at least one cell".to_string(),
+        ));
+    }
+    // The border ties the aisles together, so every free cell is reachable
+    if p.border == 0 {
+        return Err(GenError::InvalidParams(
+            "warehouse border must be at least one cell wide".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&p.gap_probability) {
+        return Err(GenError::InvalidParams(format!(
+            "gap probability {} is not between 0 and 1",
+            p.gap_probability
+        )));
+    }
+    let too_large = || GenError::InvalidParams("warehouse is too large".to_string());
+    let span = |blocks: u32, block: u32| {
+        blocks
+            .checked_mul(block)?
+            .checked_add((blocks - 1).checked_mul(p.aisle_width)?)?
+            .checked_add(p.border.checked_mul(2)?)
+    };
+    let width = span(p.columns, p.shelf_length).ok_or_else(too_large)?;
+    let height = span(p.rows, p.shelf_depth).ok_or_else(too_large)?;
+    check_size(width, height)?;
+
+    let mut rng = rng(seed);
+    let mut tiles = vec![1u8; (width * height) as usize];
+    for row in 0..p.rows {
+        for column in 0..p.columns {
+            if p.gap_probability > 0.0 && rng.gen_bool(p.gap_probability) {
+                continue;
+            }
+            let x0 = p.border + column * (p.shelf_length + p.aisle_width);
+            let y0 = p.border + row * (p.shelf_depth + p.aisle_width);
+            for y in y0..y0 + p.shelf_depth {
+                for x in x0..x0 + p.shelf_length {
+                    tiles[(y * width + x) as usize] = 0;
+                }
+            }
+        }
+    }
+    Ok(into_map(width, height, &tiles))
+}
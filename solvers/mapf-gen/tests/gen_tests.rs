@@ -0,0 +1,191 @@
+//! Tests for map and scenario generation.
+
+use mapf_core::GridMap;
+use mapf_gen::cave::{self, CaveParams};
+use mapf_gen::city::{self, CityParams};
+use mapf_gen::warehouse::{self, WarehouseParams};
+use mapf_gen::{scenario, GenError};
+use pretty_assertions::assert_eq;
+
+/// Whether every passable cell can reach every other one.
+fn connected(map: &GridMap) -> bool {
+    let (w, h) = (map.width(), map.height());
+    let free: Vec<(u32, u32)> = (0..h)
+        .flat_map(|y| (0..w).map(move |x| (x, y)))
+        .filter(|&(x, y)| map.is_passable(x, y))
+        .collect();
+    let Some(&first) = free.first() else {
+        return false;
+    };
+    let mut seen = vec![false; (w * h) as usize];
+    let mut stack = vec![first];
+    seen[(first.1 * w + first.0) as usize] = true;
+    let mut reached = 0;
+    while let Some((x, y)) = stack.pop() {
+        reached += 1;
+        let around = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (nx, ny) in around {
+            if map.is_passable(nx, ny) && !seen[(ny * w + nx) as usize] {
+                seen[(ny * w + nx) as usize] = true;
+                stack.push((nx, ny));
+            }
+        }
+    }
+    reached == free.len()
+}
+
+#[test]
+fn warehouse_lays_out_shelves_and_aisles() {
+    let params = WarehouseParams {
+        columns: 2,
+        rows: 2,
+        shelf_length: 3,
+        shelf_depth: 1,
+        aisle_width: 1,
+        border: 1,
+        gap_probability: 0.0,
+    };
+    assert_eq!(params.size(), (9, 5));
+    let map = warehouse::generate(&params, 0).unwrap();
+    assert_eq!(
+        map.to_movingai(),
+        "type octile\nheight 5\nwidth 9\nmap\n\
+         .........\n\
+         .@@@.@@@.\n\
+         .........\n\
+         .@@@.@@@.\n\
+         .........\n"
+    );
+}
+
+#[test]
+fn warehouse_gaps_depend_on_the_seed() {
+    let params = WarehouseParams {
+        gap_probability: 0.5,
+        ..WarehouseParams::default()
+    };
+    let a = warehouse::generate(&params, 1).unwrap();
+    let b = warehouse::generate(&params, 2).unwrap();
+    assert_eq!(
+        a.to_bytes(),
+        warehouse::generate(&params, 1).unwrap().to_bytes()
+    );
+    assert_ne!(a.to_bytes(), b.to_bytes());
+    assert!(connected(&a));
+}
+
+#[test]
+fn city_blocks_all_touch_a_street() {
+    let params = CityParams::default();
+    let map = city::generate(&params, 7).unwrap();
+    assert_eq!((map.width(), map.height()), (64, 64));
+    // Streets run along the top and left edges
+    assert!((0..64).all(|i| map.is_passable(i, 0) && map.is_passable(0, i)));
+    assert!(map.to_bytes().contains(&0));
+    assert!(connected(&map));
+}
+
+#[test]
+fn caves_are_one_connected_chamber() {
+    for seed in 0..5 {
+        let map = cave::generate(&CaveParams::default(), seed).unwrap();
+        assert!(connected(&map), "seed {}", seed);
+    }
+}
+
+#[test]
+fn same_seed_same_map() {
+    let params = CaveParams::default();
+    assert_eq!(
+        cave::generate(&params, 42).unwrap().to_bytes(),
+        cave::generate(&params, 42).unwrap().to_bytes()
+    );
+    assert_ne!(
+        cave::generate(&params, 42).unwrap().to_bytes(),
+        cave::generate(&params, 43).unwrap().to_bytes()
+    );
+}
+
+#[test]
+fn rejects_invalid_parameters() {
+    let bad_fill = CaveParams {
+        fill: 1.5,
+        ..CaveParams::default()
+    };
+    assert!(matches!(
+        cave::generate(&bad_fill, 0),
+        Err(GenError::InvalidParams(_))
+    ));
+    let no_border = WarehouseParams {
+        border: 0,
+        ..WarehouseParams::default()
+    };
+    assert!(matches!(
+        warehouse::generate(&no_border, 0),
+        Err(GenError::InvalidParams(_))
+    ));
+    let empty = CityParams {
+        width: 0,
+        ..CityParams::default()
+    };
+    assert!(matches!(
+        city::generate(&empty, 0),
+        Err(GenError::InvalidParams(_))
+    ));
+}
+
+#[test]
+fn scenarios_have_distinct_reachable_tasks() {
+    let map = cave::generate(&CaveParams::default(), 3).unwrap();
+    let scen = scenario::generate(&map, "cave-64-64.map", 50, 9).unwrap();
+    let entries = scen.entries();
+    assert_eq!(entries.len(), 50);
+
+    let (starts, goals) = scen.agents();
+    let mut unique_starts = starts.clone();
+    unique_starts.sort();
+    unique_starts.dedup();
+    let mut unique_goals = goals.clone();
+    unique_goals.sort();
+    unique_goals.dedup();
+    assert_eq!((unique_starts.len(), unique_goals.len()), (50, 50));
+
+    for e in entries {
+        assert_eq!(e.map_name, "cave-64-64.map");
+        assert!(map.is_passable(e.start_x, e.start_y));
+        assert!(map.is_passable(e.goal_x, e.goal_y));
+        // At least the Manhattan distance apart
+        let manhattan = e.start_x.abs_diff(e.goal_x) + e.start_y.abs_diff(e.goal_y);
+        assert!(e.optimal_length >= manhattan as f64);
+        assert_eq!(e.bucket, e.optimal_length as u32 / 4);
+    }
+    assert_eq!(
+        scen.to_movingai(),
+        scenario::generate(&map, "cave-64-64.map", 50, 9)
+            .unwrap()
+            .to_movingai()
+    );
+}
+
+#[test]
+fn scenario_lengths_are_shortest_paths() {
+    // A U-shaped corridor: (0,0) to (2,0) takes 6 moves around the wall
+    let map = GridMap::parse("type octile\nheight 3\nwidth 3\nmap\n.@.\n.@.\n...\n").unwrap();
+    let corridor = [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1), (2, 0)];
+    let along = |x: u32, y: u32| corridor.iter().position(|&c| c == (x, y)).unwrap();
+
+    let scen = scenario::generate(&map, "u.map", 7, 0).unwrap();
+    for e in scen.entries() {
+        let expected = along(e.start_x, e.start_y).abs_diff(along(e.goal_x, e.goal_y));
+        assert_eq!(e.optimal_length, expected as f64);
+    }
+    assert!(matches!(
+        scenario::generate(&map, "u.map", 8, 0),
+        Err(GenError::TooManyAgents { agents: 8, free: 7 })
+    ));
+}
@@ -0,0 +1,54 @@
+//! Leaderboard scoring of MAPF solutions.
+//!
+//! The server records these numbers on the leaderboard, and `mapf-cli` and
+//! the browser solver (through `mapf-astar`'s WASM exports) show the same
+//! ones, so a score seen locally is the score a submission gets.
+//!
+//! Solutions are scored in canonical form: trailing waits at the goal don't
+//! count. Costs count the cells a path occupies, so an agent that starts on
+//! its goal costs 1; on weighted maps each path costs its weighted
+//! [`GridMap::path_cost`] instead.
+
+use mapf_core::{GridMap, Solution};
+
+/// What the leaderboard records for a valid solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Score {
+    /// Sum of costs, as [`scored_cost`]
+    pub cost: u64,
+    /// Length of the longest path, as [`makespan`]
+    pub makespan: u64,
+}
+
+/// Score `solution` on `map`, ignoring trailing waits at the goal.
+///
+/// Validity isn't checked; only valid solutions are ranked.
+pub fn score(solution: &Solution, map: &GridMap) -> Score {
+    let canonical = solution.canonicalize();
+    Score {
+        cost: scored_cost(&canonical, map),
+        makespan: makespan(&canonical),
+    }
+}
+
+/// Sum of costs used for scoring: path lengths in steps, or the weighted
+/// path costs on maps with a cost channel.
+///
+/// Counts `solution` as given; use [`score`] to drop trailing waits first.
+pub fn scored_cost(solution: &Solution, map: &GridMap) -> u64 {
+    if map.is_weighted() {
+        solution.paths.iter().map(|p| map.path_cost(p)).sum()
+    } else {
+        solution.paths.iter().map(|p| p.steps.len() as u64).sum()
+    }
+}
+
+/// Length in steps of the longest path in `solution`, as given.
+pub fn makespan(solution: &Solution) -> u64 {
+    solution
+        .paths
+        .iter()
+        .map(|p| p.steps.len() as u64)
+        .max()
+        .unwrap_or(0)
+}
@@ -0,0 +1,64 @@
+//! Tests for leaderboard scoring.
+
+use mapf_core::{Coordinate, GridMap, Path, Solution};
+use mapf_scoring::{makespan, score, scored_cost, Score};
+use pretty_assertions::assert_eq;
+
+fn path(cells: &[(u32, u32)]) -> Path {
+    Path {
+        steps: cells.iter().map(|&(x, y)| Coordinate { x, y }).collect(),
+    }
+}
+
+/// 3x1 open corridor.
+fn corridor() -> GridMap {
+    GridMap::parse("type octile\nheight 1\nwidth 3\nmap\n...\n").unwrap()
+}
+
+#[test]
+fn counts_cells_occupied() {
+    let solution = Solution {
+        paths: vec![path(&[(0, 0), (1, 0), (2, 0)]), path(&[(1, 0)])],
+    };
+    assert_eq!(scored_cost(&solution, &corridor()), 4);
+    assert_eq!(makespan(&solution), 3);
+}
+
+#[test]
+fn trailing_waits_are_not_scored() {
+    let solution = Solution {
+        paths: vec![path(&[(0, 0), (1, 0), (2, 0), (2, 0), (2, 0)])],
+    };
+    assert_eq!(
+        score(&solution, &corridor()),
+        Score {
+            cost: 3,
+            makespan: 3
+        }
+    );
+    // The building blocks score the solution as given
+    assert_eq!(scored_cost(&solution, &corridor()), 5);
+}
+
+#[test]
+fn weighted_maps_use_path_costs() {
+    let map = corridor().with_costs(vec![1, 5, 2]).unwrap();
+    let solution = Solution {
+        paths: vec![path(&[(0, 0), (1, 0), (2, 0)])],
+    };
+    assert_eq!(
+        score(&solution, &map),
+        Score {
+            cost: 7,
+            makespan: 3
+        }
+    );
+}
+
+#[test]
+fn empty_solution_scores_zero() {
+    assert_eq!(
+        score(&Solution { paths: vec![] }, &corridor()),
+        Score::default()
+    );
+}
@@ -0,0 +1,470 @@
+//! Component-model glue behind [`export_solver!`](crate::export_solver).
+//!
+//! Converts between the WIT types and `mapf-core` types and keeps the
+//! per-call state (deadline, seed, node counts, stats) the guest exports need.
+
+use std::cell::{Cell, RefCell};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+use mapf_core::GridMap;
+
+use crate::bindings::exports::mapf::solver::solver::{
+    Guest, GuestPrepHandle, GuestSolverSession, PrepHandle, PrepHandleBorrow, SolverSession,
+};
+use crate::bindings::mapf::solver::types as wit;
+use crate::{Agent, Solver, TimedObstacle};
+
+/// Interface version the glue implements.
+const WIT_VERSION: u32 = 7;
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    static SEED: Cell<u64> = const { Cell::new(0) };
+    /// Nodes (expanded, generated) reported during the current call
+    static NODES: Cell<(u64, u64)> = const { Cell::new((0, 0)) };
+    /// Single-agent searches reported during the current call
+    static LOW_LEVEL_CALLS: Cell<u64> = const { Cell::new(0) };
+    static LAST_STATS: RefCell<Option<wit::Stats>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn time_left() -> Duration {
+    DEADLINE.get().map_or(Duration::MAX, |deadline| {
+        deadline.saturating_duration_since(Instant::now())
+    })
+}
+
+pub(crate) fn seed() -> u64 {
+    SEED.get()
+}
+
+pub(crate) fn report_nodes(expanded: u64, generated: u64) {
+    let (e, g) = NODES.get();
+    NODES.set((e + expanded, g + generated));
+}
+
+pub(crate) fn report_low_level_calls(calls: u64) {
+    LOW_LEVEL_CALLS.set(LOW_LEVEL_CALLS.get() + calls);
+}
+
+/// Exports `S` through the generated `Guest` traits.
+pub struct Component<S>(PhantomData<S>);
+
+impl<S: Solver> Guest for Component<S> {
+    type PrepHandle = Prep;
+    type SolverSession = Session<S>;
+
+    fn solve(
+        map_data: Vec<u8>,
+        width: u32,
+        height: u32,
+        costs: Option<Vec<u32>>,
+        starts: Vec<wit::Coordinate>,
+        goals: Vec<wit::Coordinate>,
+        waypoints: Vec<Vec<wit::Coordinate>>,
+        obstacles: Vec<wit::TimedObstacle>,
+        seed: u64,
+        deadline_ms: u64,
+    ) -> Result<wit::Solution, String> {
+        let map = decode_map(&map_data, width, height, costs)?;
+        let agents = decode_agents(&starts, &goals, &waypoints)?;
+        run::<S>(
+            &map,
+            &agents,
+            &decode_obstacles(&obstacles),
+            seed,
+            deadline_ms,
+        )
+    }
+
+    fn preprocess(
+        map_data: Vec<u8>,
+        width: u32,
+        height: u32,
+        costs: Option<Vec<u32>>,
+    ) -> Result<PrepHandle, String> {
+        Ok(PrepHandle::new(Prep {
+            map: decode_map(&map_data, width, height, costs)?,
+        }))
+    }
+
+    fn solve_with(
+        prep: PrepHandleBorrow<'_>,
+        starts: Vec<wit::Coordinate>,
+        goals: Vec<wit::Coordinate>,
+        waypoints: Vec<Vec<wit::Coordinate>>,
+        obstacles: Vec<wit::TimedObstacle>,
+        seed: u64,
+        deadline_ms: u64,
+    ) -> Result<wit::Solution, String> {
+        let agents = decode_agents(&starts, &goals, &waypoints)?;
+        run::<S>(
+            &prep.get::<Prep>().map,
+            &agents,
+            &decode_obstacles(&obstacles),
+            seed,
+            deadline_ms,
+        )
+    }
+
+    fn get_stats() -> Option<wit::Stats> {
+        LAST_STATS.with_borrow(Clone::clone)
+    }
+
+    fn info() -> String {
+        S::info()
+    }
+
+    fn wit_version() -> u32 {
+        WIT_VERSION
+    }
+}
+
+/// A decoded map kept for `solve-with`
+pub struct Prep {
+    map: GridMap,
+}
+
+impl GuestPrepHandle for Prep {}
+
+/// Session state; agents accumulate across `add-agents` calls
+pub struct Session<S> {
+    map: GridMap,
+    obstacles: Vec<TimedObstacle>,
+    agents: RefCell<Vec<Agent>>,
+    best: RefCell<Option<wit::Solution>>,
+    solver: PhantomData<S>,
+}
+
+impl<S: Solver> GuestSolverSession for Session<S> {
+    fn init(
+        map_data: Vec<u8>,
+        width: u32,
+        height: u32,
+        costs: Option<Vec<u32>>,
+        obstacles: Vec<wit::TimedObstacle>,
+    ) -> Result<SolverSession, String> {
+        Ok(SolverSession::new(Session::<S> {
+            map: decode_map(&map_data, width, height, costs)?,
+            obstacles: decode_obstacles(&obstacles),
+            agents: RefCell::new(Vec::new()),
+            best: RefCell::new(None),
+            solver: PhantomData,
+        }))
+    }
+
+    fn add_agents(
+        &self,
+        starts: Vec<wit::Coordinate>,
+        goals: Vec<wit::Coordinate>,
+        waypoints: Vec<Vec<wit::Coordinate>>,
+    ) -> Result<(), String> {
+        self.agents
+            .borrow_mut()
+            .extend(decode_agents(&starts, &goals, &waypoints)?);
+        // The previous best no longer covers every agent
+        self.best.replace(None);
+        Ok(())
+    }
+
+    fn solve_next(&self, seed: u64, deadline_ms: u64) -> Result<wit::Solution, String> {
+        let solution = run::<S>(
+            &self.map,
+            &self.agents.borrow(),
+            &self.obstacles,
+            seed,
+            deadline_ms,
+        )?;
+        self.best.replace(Some(solution.clone()));
+        Ok(solution)
+    }
+
+    fn best_solution(&self) -> Option<wit::Solution> {
+        self.best.borrow().clone()
+    }
+}
+
+/// Call the solver with the deadline set, then record its stats.
+fn run<S: Solver>(
+    map: &GridMap,
+    agents: &[Agent],
+    obstacles: &[TimedObstacle],
+    seed: u64,
+    deadline_ms: u64,
+) -> Result<wit::Solution, String> {
+    let started = Instant::now();
+    DEADLINE.set(started.checked_add(Duration::from_millis(deadline_ms)));
+    SEED.set(seed);
+    NODES.set((0, 0));
+    LOW_LEVEL_CALLS.set(0);
+
+    let result = S::solve_dynamic(map, agents, obstacles);
+    let elapsed = started.elapsed();
+    DEADLINE.set(None);
+
+    let (nodes_expanded, nodes_generated) = NODES.get();
+    let agent_costs = match &result {
+        Ok(solution) => solution.paths.iter().map(|p| map.path_cost(p)).collect(),
+        Err(_) => Vec::new(),
+    };
+    LAST_STATS.set(Some(wit::Stats {
+        nodes_expanded,
+        nodes_generated,
+        low_level_calls: LOW_LEVEL_CALLS.get(),
+        peak_memory_bytes: 0,
+        time_us: elapsed.as_micros() as u64,
+        agent_costs,
+    }));
+
+    result
+        .map(|solution| encode_solution(map, solution))
+        .map_err(|e| e.to_string())
+}
+
+fn decode_map(
+    map_data: &[u8],
+    width: u32,
+    height: u32,
+    costs: Option<Vec<u32>>,
+) -> Result<GridMap, String> {
+    let map = GridMap::from_bytes(width, height, map_data)
+        .ok_or_else(|| "Map data length doesn't match width*height".to_string())?;
+    match costs {
+        Some(costs) => map
+            .with_costs(costs)
+            .ok_or_else(|| "Map costs length doesn't match width*height".to_string()),
+        None => Ok(map),
+    }
+}
+
+fn decode_agents(
+    starts: &[wit::Coordinate],
+    goals: &[wit::Coordinate],
+    waypoints: &[Vec<wit::Coordinate>],
+) -> Result<Vec<Agent>, String> {
+    if starts.len() != goals.len() {
+        return Err("Starts and goals differ in length".to_string());
+    }
+    Ok(starts
+        .iter()
+        .zip(goals)
+        .enumerate()
+        .map(|(i, (start, goal))| Agent {
+            start: start.into(),
+            goal: goal.into(),
+            waypoints: waypoints
+                .get(i)
+                .map(|w| w.iter().map(Into::into).collect())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+impl From<&wit::Coordinate> for mapf_core::Coordinate {
+    fn from(c: &wit::Coordinate) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+impl From<mapf_core::Coordinate> for wit::Coordinate {
+    fn from(c: mapf_core::Coordinate) -> Self {
+        Self { x: c.x, y: c.y }
+    }
+}
+
+fn decode_obstacles(obstacles: &[wit::TimedObstacle]) -> Vec<TimedObstacle> {
+    obstacles
+        .iter()
+        .map(|o| TimedObstacle {
+            cell: (&o.cell).into(),
+            appears: o.appears,
+            disappears: o.disappears,
+        })
+        .collect()
+}
+
+/// Encode paths for the host, with the (weighted) sum of costs as the
+/// declared cost
+fn encode_solution(map: &GridMap, solution: mapf_core::Solution) -> wit::Solution {
+    let cost = solution.paths.iter().map(|p| map.path_cost(p)).sum();
+    wit::Solution {
+        paths: solution
+            .paths
+            .into_iter()
+            .map(|path| wit::Path {
+                steps: path.steps.into_iter().map(Into::into).collect(),
+            })
+            .collect(),
+        cost,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Solver};
+    use mapf_core::{Coordinate, Path, Solution};
+
+    thread_local! {
+        /// Seed and time left seen by the last `Probe::solve`
+        static SEEN: Cell<Option<(u64, Duration)>> = const { Cell::new(None) };
+    }
+
+    /// Moves every agent straight to its goal, reporting one expanded and
+    /// two generated nodes per agent.
+    struct Probe;
+
+    impl Solver for Probe {
+        fn solve(_map: &GridMap, agents: &[Agent]) -> Result<Solution, Error> {
+            SEEN.set(Some((crate::seed(), crate::time_left())));
+            crate::report_nodes(agents.len() as u64, agents.len() as u64);
+            crate::report_nodes(0, agents.len() as u64);
+            crate::report_low_level_calls(agents.len() as u64);
+            Ok(Solution {
+                paths: agents
+                    .iter()
+                    .map(|a| Path {
+                        steps: vec![a.start, a.goal],
+                    })
+                    .collect(),
+            })
+        }
+    }
+
+    struct Failing;
+
+    impl Solver for Failing {
+        fn solve(_map: &GridMap, _agents: &[Agent]) -> Result<Solution, Error> {
+            crate::report_nodes(3, 5);
+            Err(Error::NoSolution)
+        }
+    }
+
+    fn c(x: u32, y: u32) -> wit::Coordinate {
+        wit::Coordinate { x, y }
+    }
+
+    fn stats() -> wit::Stats {
+        LAST_STATS.with_borrow(Clone::clone).unwrap()
+    }
+
+    #[test]
+    fn test_decode_map() {
+        assert!(decode_map(&[1; 5], 3, 2, None).is_err());
+        assert!(decode_map(&[1; 6], 3, 2, Some(vec![1; 5])).is_err());
+
+        let map = decode_map(&[1, 0, 1, 1, 1, 1], 3, 2, Some(vec![1, 1, 4, 1, 1, 1])).unwrap();
+        assert!(!map.is_passable(1, 0));
+        assert_eq!(map.cell_cost(2, 0), 4);
+    }
+
+    #[test]
+    fn test_decode_agents() {
+        assert!(decode_agents(&[c(0, 0)], &[], &[]).is_err());
+
+        // Waypoints are optional per agent
+        let agents =
+            decode_agents(&[c(0, 0), c(1, 0)], &[c(2, 0), c(2, 1)], &[vec![c(1, 1)]]).unwrap();
+        assert_eq!(agents.len(), 2);
+        assert_eq!(agents[0].goal, Coordinate { x: 2, y: 0 });
+        assert_eq!(agents[0].waypoints, vec![Coordinate { x: 1, y: 1 }]);
+        assert!(agents[1].waypoints.is_empty());
+    }
+
+    #[test]
+    fn test_decode_obstacles() {
+        let obstacles = decode_obstacles(&[wit::TimedObstacle {
+            cell: c(1, 2),
+            appears: 3,
+            disappears: None,
+        }]);
+        assert_eq!(
+            obstacles,
+            vec![TimedObstacle {
+                cell: Coordinate { x: 1, y: 2 },
+                appears: 3,
+                disappears: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_encode_solution_uses_weighted_cost() {
+        let map = GridMap::from_bytes(3, 1, &[1; 3])
+            .unwrap()
+            .with_costs(vec![1, 2, 5])
+            .unwrap();
+        let solution = Solution {
+            paths: vec![Path {
+                steps: vec![
+                    Coordinate { x: 0, y: 0 },
+                    Coordinate { x: 1, y: 0 },
+                    Coordinate { x: 2, y: 0 },
+                ],
+            }],
+        };
+
+        let encoded = encode_solution(&map, solution);
+        assert_eq!(encoded.cost, 7);
+        assert_eq!(encoded.paths[0].steps.len(), 3);
+        assert_eq!(
+            (encoded.paths[0].steps[2].x, encoded.paths[0].steps[2].y),
+            (2, 0)
+        );
+    }
+
+    #[test]
+    fn test_run_sets_deadline_and_records_stats() {
+        let map = GridMap::from_bytes(3, 1, &[1; 3]).unwrap();
+        let agents = decode_agents(&[c(0, 0), c(2, 0)], &[c(1, 0), c(2, 0)], &[]).unwrap();
+
+        // The second agent waits on its goal, which still costs an action
+        let solution = run::<Probe>(&map, &agents, &[], 7, 1000).unwrap();
+        assert_eq!(solution.cost, 2);
+
+        let (seed, left) = SEEN.get().unwrap();
+        assert_eq!(seed, 7);
+        assert!(left <= Duration::from_millis(1000));
+        // The deadline only applies during the call
+        assert_eq!(time_left(), Duration::MAX);
+
+        let stats = stats();
+        assert_eq!((stats.nodes_expanded, stats.nodes_generated), (2, 4));
+        assert_eq!(stats.low_level_calls, 2);
+        assert_eq!(stats.agent_costs, vec![1, 1]);
+    }
+
+    #[test]
+    fn test_run_resets_nodes_between_calls() {
+        let map = GridMap::from_bytes(3, 1, &[1; 3]).unwrap();
+        let agents = decode_agents(&[c(0, 0)], &[c(1, 0)], &[]).unwrap();
+
+        for _ in 0..2 {
+            run::<Probe>(&map, &agents, &[], 0, 1000).unwrap();
+            let stats = stats();
+            assert_eq!((stats.nodes_expanded, stats.nodes_generated), (1, 2));
+            assert_eq!(stats.low_level_calls, 1);
+        }
+
+        // A failed call still reports its own effort, without costs
+        let error = run::<Failing>(&map, &agents, &[], 0, 1000).unwrap_err();
+        assert_eq!(error, Error::NoSolution.to_string());
+        let stats = stats();
+        assert_eq!((stats.nodes_expanded, stats.nodes_generated), (3, 5));
+        assert_eq!(stats.low_level_calls, 0);
+        assert!(stats.agent_costs.is_empty());
+    }
+
+    #[test]
+    fn test_default_solver_refuses_obstacles() {
+        let map = GridMap::from_bytes(3, 1, &[1; 3]).unwrap();
+        let agents = decode_agents(&[c(0, 0)], &[c(1, 0)], &[]).unwrap();
+        let obstacles = [TimedObstacle {
+            cell: Coordinate { x: 2, y: 0 },
+            appears: 0,
+            disappears: None,
+        }];
+
+        assert!(run::<Probe>(&map, &agents, &obstacles, 0, 1000).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! SDK for writing MAPF arena solvers.
+//!
+//! Implement [`Solver`] on a type and hand it to [`export_solver!`]; the
+//! macro generates the component-model glue for the `mapf-solver` world:
+//! map decoding, path encoding, preprocessing and sessions, and stats.
+//!
+//! ```ignore
+//! use mapf_solver_sdk::{export_solver, Agent, Error, GridMap, Solution, Solver};
+//!
+//! struct MySolver;
+//!
+//! impl Solver for MySolver {
+//!     fn solve(map: &GridMap, agents: &[Agent]) -> Result<Solution, Error> {
+//!         todo!()
+//!     }
+//! }
+//!
+//! export_solver!(MySolver);
+//! ```
+//!
+//! Build the crate as a `cdylib` for `wasm32-wasip2` to get a component
+//! the arena accepts.
+
+#[doc(hidden)]
+pub mod bindings {
+    wit_bindgen::generate!({
+        path: "../wit/mapf-solver.wit",
+        world: "mapf-solver",
+        pub_export_macro: true,
+        default_bindings_module: "mapf_solver_sdk::bindings",
+    });
+}
+
+#[doc(hidden)]
+pub mod glue;
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+pub use bindings::mapf::solver::logging::{log, Level};
+pub use mapf_core::{Coordinate, GridMap, Path, Solution, Tile};
+
+/// One agent of a MAPF instance.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Agent {
+    pub start: Coordinate,
+    pub goal: Coordinate,
+    /// Cells to visit in order before the goal; empty outside
+    /// pickup-and-delivery tracks.
+    pub waypoints: Vec<Coordinate>,
+}
+
+/// A cell blocked by a moving obstacle from timestep `appears` until
+/// (excluding) `disappears`; `None` means it never disappears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimedObstacle {
+    pub cell: Coordinate,
+    pub appears: u32,
+    pub disappears: Option<u32>,
+}
+
+impl TimedObstacle {
+    /// Whether the obstacle blocks its cell at timestep `t`.
+    pub fn is_active_at(&self, t: u32) -> bool {
+        t >= self.appears && self.disappears.is_none_or(|until| t < until)
+    }
+}
+
+/// Why a solver gave up on an instance.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("no solution found")]
+    NoSolution,
+
+    #[error("deadline passed before a solution was found")]
+    DeadlineExceeded,
+
+    #[error("solver does not support {0}")]
+    Unsupported(&'static str),
+
+    #[error("{0}")]
+    Other(String),
+}
+
+/// A MAPF solver the SDK can export as a component.
+pub trait Solver: 'static {
+    /// Find one path per agent, in the order of `agents`.
+    ///
+    /// The map is shared across calls when the host preprocesses it or
+    /// runs a session, so expensive analysis may be cached by the solver.
+    /// On weighted tracks the map carries terrain costs
+    /// ([`GridMap::cell_cost`]) that the solution is scored by.
+    fn solve(map: &GridMap, agents: &[Agent]) -> Result<Solution, Error>;
+
+    /// Solve among moving obstacles, for dynamic-environment variants.
+    ///
+    /// Without an override, instances with obstacles are refused and the
+    /// rest go to [`Solver::solve`].
+    fn solve_dynamic(
+        map: &GridMap,
+        agents: &[Agent],
+        obstacles: &[TimedObstacle],
+    ) -> Result<Solution, Error> {
+        if obstacles.is_empty() {
+            Self::solve(map, agents)
+        } else {
+            Err(Error::Unsupported("dynamic obstacles"))
+        }
+    }
+
+    /// Solver name and version for display.
+    fn info() -> String {
+        "MAPF solver".to_string()
+    }
+}
+
+/// Time left before the deadline of the current solve call.
+///
+/// Anytime solvers should return their best solution once this reaches
+/// zero; the host kills the guest a grace period later.
+pub fn time_left() -> Duration {
+    glue::time_left()
+}
+
+/// Seed of the current solve call.
+///
+/// Solvers must be deterministic given the seed, so derive all randomness
+/// from it.
+pub fn seed() -> u64 {
+    glue::seed()
+}
+
+/// Record search effort for the current solve call, reported in its stats.
+///
+/// Counts add up across calls within one solve.
+pub fn report_nodes(expanded: u64, generated: u64) {
+    glue::report_nodes(expanded, generated)
+}
+
+/// Record single-agent searches run for the current solve call, e.g. by
+/// prioritized planning or conflict-based search, reported in its stats.
+///
+/// Counts add up across calls within one solve.
+pub fn report_low_level_calls(calls: u64) {
+    glue::report_low_level_calls(calls)
+}
+
+/// Export a [`Solver`] implementation as the `mapf-solver` component.
+///
+/// Invoke once, at the root of a `cdylib` crate.
+#[macro_export]
+macro_rules! export_solver {
+    ($solver:ty) => {
+        #[doc(hidden)]
+        type __MapfSolverComponent = $crate::glue::Component<$solver>;
+
+        $crate::bindings::export!(__MapfSolverComponent with_types_in $crate::bindings);
+    };
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+
 use wit_bindgen::generate;
 
 generate!({
@@ -5,57 +7,225 @@ generate!({
     world: "mapf-solver",
 });
 
-use exports::mapf::solver::solver::Guest;
+use exports::mapf::solver::solver::{
+    Guest, GuestPrepHandle, GuestSolverSession, PrepHandle, PrepHandleBorrow, SolverSession,
+};
 // Types are generated in the root module for the package interfaces
-use mapf::solver::types::{Coordinate, Path, Solution, Stats};
+use mapf::solver::logging::{log, Level};
+use mapf::solver::types::{Coordinate, Path, Solution, Stats, TimedObstacle};
 
 struct Component;
 
+/// Misbehavior for exercising the host's failure paths.
+///
+/// Selected by the solve seed, which the host passes through unchanged:
+/// seeds 1 to 5 pick a misbehavior, any other seed gives normal behavior.
+#[derive(Clone, Copy)]
+enum Behavior {
+    Normal,
+    /// Teleport every agent from its start to its goal
+    InvalidPaths,
+    /// Spin until the host kills the instance
+    InfiniteLoop,
+    /// Allocate until memory runs out
+    HugeAllocation,
+    Panic,
+    /// Declare a cost no metric can match
+    WrongCost,
+}
+
+impl Behavior {
+    fn from_seed(seed: u64) -> Self {
+        match seed {
+            1 => Behavior::InvalidPaths,
+            2 => Behavior::InfiniteLoop,
+            3 => Behavior::HugeAllocation,
+            4 => Behavior::Panic,
+            5 => Behavior::WrongCost,
+            _ => Behavior::Normal,
+        }
+    }
+
+    /// Solve with this behavior applied
+    fn solve(self, starts: &[Coordinate], goals: &[Coordinate]) -> Result<Solution, String> {
+        match self {
+            Behavior::Normal => wait_at_start(starts, goals),
+            Behavior::InvalidPaths => Ok(Solution {
+                paths: starts
+                    .iter()
+                    .zip(goals)
+                    .map(|(start, goal)| Path {
+                        steps: vec![
+                            Coordinate { x: start.x, y: start.y },
+                            Coordinate { x: goal.x, y: goal.y },
+                        ],
+                    })
+                    .collect(),
+                cost: starts.len() as u64,
+            }),
+            Behavior::InfiniteLoop => {
+                let mut counter = 0u64;
+                loop {
+                    counter = std::hint::black_box(counter.wrapping_add(1));
+                }
+            }
+            Behavior::HugeAllocation => {
+                let mut hog = Vec::new();
+                loop {
+                    hog.push(vec![1u8; 64 << 20]);
+                    std::hint::black_box(&hog);
+                }
+            }
+            Behavior::Panic => panic!("Test component asked to panic"),
+            Behavior::WrongCost => {
+                let mut solution = wait_at_start(starts, goals)?;
+                solution.cost = u64::MAX;
+                Ok(solution)
+            }
+        }
+    }
+}
+
+/// Simple "Wait at start" solver for testing
+fn wait_at_start(starts: &[Coordinate], goals: &[Coordinate]) -> Result<Solution, String> {
+    // For each agent, just return a path containing the start position
+    let mut paths = Vec::new();
+
+    for (i, start) in starts.iter().enumerate() {
+        // Check if goal is reachable (trivial check)
+        if i >= goals.len() {
+            return Err("More starts than goals".to_string());
+        }
+
+        // Just stay at start for 1 step
+        paths.push(Path {
+            steps: vec![
+                Coordinate { x: start.x, y: start.y },
+                Coordinate { x: start.x, y: start.y } // Wait
+            ],
+        });
+    }
+
+    // One wait per agent
+    let cost = paths.len() as u64;
+    Ok(Solution { paths, cost })
+}
+
 impl Guest for Component {
+    type PrepHandle = Prep;
+    type SolverSession = Session;
+
     fn solve(
         _map_data: Vec<u8>,
         _width: u32,
         _height: u32,
+        _costs: Option<Vec<u32>>,
         starts: Vec<Coordinate>,
         goals: Vec<Coordinate>,
+        _waypoints: Vec<Vec<Coordinate>>,
+        _obstacles: Vec<TimedObstacle>,
+        seed: u64,
+        _deadline_ms: u64,
     ) -> Result<Solution, String> {
-        // Simple "Wait at start" solver for testing
-        // For each agent, just return a path containing the start position
-        
-        let mut paths = Vec::new();
-        
-        for (i, start) in starts.iter().enumerate() {
-            // Check if goal is reachable (trivial check)
-            if i >= goals.len() {
-                return Err("More starts than goals".to_string());
-            }
+        log(Level::Debug, &format!("Waiting at start for {} agents", starts.len()));
+        Behavior::from_seed(seed).solve(&starts, &goals)
+    }
 
-            // Just stay at start for 1 step
-            paths.push(Path {
-                steps: vec![
-                    Coordinate { x: start.x, y: start.y },
-                    Coordinate { x: start.x, y: start.y } // Wait
-                ],
-            });
-        }
+    fn preprocess(
+        _map_data: Vec<u8>,
+        _width: u32,
+        _height: u32,
+        _costs: Option<Vec<u32>>,
+    ) -> Result<PrepHandle, String> {
+        // Waiting at the start needs no map analysis
+        Ok(PrepHandle::new(Prep))
+    }
 
-        Ok(Solution {
-            paths,
-            cost: 0,
-        })
+    fn solve_with(
+        _prep: PrepHandleBorrow<'_>,
+        starts: Vec<Coordinate>,
+        goals: Vec<Coordinate>,
+        _waypoints: Vec<Vec<Coordinate>>,
+        _obstacles: Vec<TimedObstacle>,
+        seed: u64,
+        _deadline_ms: u64,
+    ) -> Result<Solution, String> {
+        Behavior::from_seed(seed).solve(&starts, &goals)
     }
 
     fn get_stats() -> Option<Stats> {
         Some(Stats {
             nodes_expanded: 0,
+            nodes_generated: 0,
+            low_level_calls: 0,
+            peak_memory_bytes: 0,
             time_us: 0,
+            agent_costs: Vec::new(),
         })
     }
 
     fn info() -> String {
         "Test Component Solver v0.1".to_string()
     }
+
+    fn wit_version() -> u32 {
+        7
+    }
 }
 
-export!(Component);
+/// Preprocessed map; empty for this solver
+struct Prep;
+
+impl GuestPrepHandle for Prep {}
+
+/// Session state; agents accumulate across `add-agents` calls
+struct Session {
+    starts: RefCell<Vec<Coordinate>>,
+    goals: RefCell<Vec<Coordinate>>,
+    best: RefCell<Option<Solution>>,
+}
 
+impl GuestSolverSession for Session {
+    fn init(
+        _map_data: Vec<u8>,
+        _width: u32,
+        _height: u32,
+        _costs: Option<Vec<u32>>,
+        _obstacles: Vec<TimedObstacle>,
+    ) -> Result<SolverSession, String> {
+        Ok(SolverSession::new(Session {
+            starts: RefCell::new(Vec::new()),
+            goals: RefCell::new(Vec::new()),
+            best: RefCell::new(None),
+        }))
+    }
+
+    fn add_agents(
+        &self,
+        starts: Vec<Coordinate>,
+        goals: Vec<Coordinate>,
+        _waypoints: Vec<Vec<Coordinate>>,
+    ) -> Result<(), String> {
+        if starts.len() != goals.len() {
+            return Err("Starts and goals differ in length".to_string());
+        }
+        self.starts.borrow_mut().extend(starts);
+        self.goals.borrow_mut().extend(goals);
+        // The previous best no longer covers every agent
+        self.best.replace(None);
+        Ok(())
+    }
+
+    fn solve_next(&self, seed: u64, _deadline_ms: u64) -> Result<Solution, String> {
+        let solution =
+            Behavior::from_seed(seed).solve(&self.starts.borrow(), &self.goals.borrow())?;
+        self.best.replace(Some(solution.clone()));
+        Ok(solution)
+    }
+
+    fn best_solution(&self) -> Option<Solution> {
+        self.best.borrow().clone()
+    }
+}
+
+export!(Component);
@@ -0,0 +1,199 @@
+//! Animated GIF replay of a solution, one frame per timestep.
+//!
+//! Writes GIF89a directly: a fixed 256-color palette, one full frame per
+//! timestep compressed with LZW, and a looping extension.
+
+use mapf_core::{Coordinate, GridMap, Solution};
+use weezl::{encode::Encoder, BitOrder};
+
+use crate::{agent_color, conflicts, horizon, position_at, RenderError, RenderOptions};
+
+const BACKGROUND: u8 = 0;
+const BLOCKED: u8 = 1;
+const CONFLICT: u8 = 2;
+/// First palette index used for agents; the rest of the palette cycles
+const FIRST_AGENT: u8 = 3;
+const AGENT_COLORS: usize = 256 - FIRST_AGENT as usize;
+
+/// Animate the agents moving through `solution`, with goals outlined in
+/// each agent's color and conflict cells filled red on the timesteps they
+/// happen.
+///
+/// Agents beyond the 253rd reuse palette colors. Fails if the image would
+/// exceed GIF's 65535-pixel sides.
+pub fn render_gif(
+    map: &GridMap,
+    solution: &Solution,
+    options: &RenderOptions,
+) -> Result<Vec<u8>, RenderError> {
+    let cell = options.cell_size.max(1);
+    let (width, height) = (
+        map.width() as u64 * cell as u64,
+        map.height() as u64 * cell as u64,
+    );
+    if width > u16::MAX as u64 || height > u16::MAX as u64 {
+        return Err(RenderError::TooLarge { width, height });
+    }
+    let mut canvas = Canvas::new(width as usize, height as usize, cell as usize);
+
+    for y in 0..map.height() {
+        for x in 0..map.width() {
+            if !map.is_passable(x, y) {
+                canvas.fill_cell(Coordinate { x, y }, BLOCKED);
+            }
+        }
+    }
+    let base = canvas.pixels.clone();
+
+    // Conflict cells, keyed by the timesteps they are shown on
+    let mut conflict_cells: Vec<(usize, Coordinate)> = Vec::new();
+    for conflict in conflicts(solution, options) {
+        let Some(t) = conflict.timestep else {
+            continue;
+        };
+        for &pos in &conflict.positions {
+            conflict_cells.push((t, pos));
+            // Edge collisions are drawn on both ends of the swap
+            if conflict.positions.len() > 1 {
+                conflict_cells.push((t + 1, pos));
+            }
+        }
+    }
+
+    let mut gif = header(width as u16, height as u16);
+    let delay = (options.frame_delay_ms.div_ceil(10)).min(u16::MAX as u32) as u16;
+
+    for t in 0..horizon(solution).max(1) {
+        canvas.pixels.copy_from_slice(&base);
+        for &(_, pos) in conflict_cells.iter().filter(|(step, _)| *step == t) {
+            canvas.fill_cell(pos, CONFLICT);
+        }
+        for (agent, path) in solution.paths.iter().enumerate() {
+            let color = agent_index(agent);
+            if let Some(&goal) = path.steps.last() {
+                canvas.outline_cell(goal, color);
+            }
+            if let Some(pos) = position_at(&path.steps, t) {
+                canvas.fill_circle(pos, color);
+            }
+        }
+        write_frame(&mut gif, &canvas, delay)?;
+    }
+
+    gif.push(0x3B);
+    Ok(gif)
+}
+
+fn agent_index(agent: usize) -> u8 {
+    FIRST_AGENT + (agent % AGENT_COLORS) as u8
+}
+
+/// Indexed-color pixels of one frame.
+struct Canvas {
+    width: usize,
+    height: usize,
+    cell: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, cell: usize) -> Self {
+        Self {
+            width,
+            height,
+            cell,
+            pixels: vec![BACKGROUND; width * height],
+        }
+    }
+
+    /// Top-left pixel of `pos`, or `None` if it is off the map.
+    fn cell_origin(&self, pos: Coordinate) -> Option<(usize, usize)> {
+        let (x, y) = (pos.x as usize * self.cell, pos.y as usize * self.cell);
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    fn fill_cell(&mut self, pos: Coordinate, color: u8) {
+        self.paint(pos, color, |_, _| true);
+    }
+
+    /// One-pixel (or thicker, for large cells) border just inside the cell.
+    fn outline_cell(&mut self, pos: Coordinate, color: u8) {
+        let last = self.cell - 1;
+        let thickness = (self.cell / 8).max(1);
+        self.paint(pos, color, |dx, dy| {
+            dx < thickness || dy < thickness || last - dx < thickness || last - dy < thickness
+        });
+    }
+
+    fn fill_circle(&mut self, pos: Coordinate, color: u8) {
+        let center = self.cell as f64 / 2.0;
+        let radius = self.cell as f64 * 0.4;
+        self.paint(pos, color, |dx, dy| {
+            let (px, py) = (dx as f64 + 0.5 - center, dy as f64 + 0.5 - center);
+            px * px + py * py <= radius * radius
+        });
+    }
+
+    /// Set the pixels of `pos` selected by `mask(dx, dy)`.
+    fn paint(&mut self, pos: Coordinate, color: u8, mask: impl Fn(usize, usize) -> bool) {
+        let Some((x0, y0)) = self.cell_origin(pos) else {
+            return;
+        };
+        for dy in 0..self.cell {
+            let row = (y0 + dy) * self.width;
+            for dx in 0..self.cell {
+                if mask(dx, dy) {
+                    self.pixels[row + x0 + dx] = color;
+                }
+            }
+        }
+    }
+}
+
+/// Signature, screen descriptor, global palette and looping extension.
+fn header(width: u16, height: u16) -> Vec<u8> {
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend_from_slice(&width.to_le_bytes());
+    gif.extend_from_slice(&height.to_le_bytes());
+    // Global color table of 2^(7+1) entries, 8 bits per channel
+    gif.extend_from_slice(&[0xF7, BACKGROUND, 0]);
+
+    let mut palette = [[0u8; 3]; 256];
+    palette[BACKGROUND as usize] = [0xFF, 0xFF, 0xFF];
+    palette[BLOCKED as usize] = [0x33, 0x33, 0x33];
+    palette[CONFLICT as usize] = [0xE0, 0x00, 0x00];
+    for agent in 0..AGENT_COLORS {
+        palette[agent_index(agent) as usize] = agent_color(agent);
+    }
+    gif.extend(palette.iter().flatten());
+
+    // NETSCAPE2.0 application extension: loop forever
+    gif.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+    gif.extend_from_slice(b"NETSCAPE2.0");
+    gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+    gif
+}
+
+/// Append one full-size frame shown for `delay` hundredths of a second.
+fn write_frame(gif: &mut Vec<u8>, canvas: &Canvas, delay: u16) -> Result<(), RenderError> {
+    // Graphic control extension: leave the frame in place, no transparency
+    gif.extend_from_slice(&[0x21, 0xF9, 0x04, 0x04]);
+    gif.extend_from_slice(&delay.to_le_bytes());
+    gif.extend_from_slice(&[0x00, 0x00]);
+
+    // Image descriptor covering the whole screen, no local palette
+    gif.push(0x2C);
+    gif.extend_from_slice(&[0, 0, 0, 0]);
+    gif.extend_from_slice(&(canvas.width as u16).to_le_bytes());
+    gif.extend_from_slice(&(canvas.height as u16).to_le_bytes());
+    gif.push(0x00);
+
+    let data = Encoder::new(BitOrder::Lsb, 8).encode(&canvas.pixels)?;
+    gif.push(8);
+    for block in data.chunks(255) {
+        gif.push(block.len() as u8);
+        gif.extend_from_slice(block);
+    }
+    gif.push(0x00);
+    Ok(())
+}
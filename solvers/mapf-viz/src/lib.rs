@@ -0,0 +1,96 @@
+//! Render MAPF solutions for reports, the CLI and replays.
+//!
+//! [`render_svg`] draws a static overview (every path, starts, goals and
+//! conflicts); [`render_gif`] animates the agents one timestep per frame.
+//! Agents get stable, well-separated colors, and vertex and edge collisions
+//! found by [`mapf_core::validate::check_collisions`] are highlighted in red.
+//! [`render_diff_svg`] overlays two solutions to show where they differ.
+
+mod gif;
+mod svg;
+
+use mapf_core::validate::{check_collisions, Violation};
+use mapf_core::{Coordinate, Solution};
+use thiserror::Error;
+
+pub use gif::render_gif;
+pub use svg::{render_diff_svg, render_svg};
+
+/// Errors from rendering a solution.
+#[derive(Debug, Error)]
+pub enum RenderError {
+    #[error("image of {width}x{height} pixels exceeds the GIF limit of 65535x65535")]
+    TooLarge { width: u64, height: u64 },
+
+    #[error("failed to compress frame: {0}")]
+    Encode(#[from] weezl::LzwError),
+}
+
+/// Layout and timing shared by both renderers.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Side of one grid cell, in pixels
+    pub cell_size: u32,
+    /// Time each GIF frame (one timestep) is shown, in milliseconds
+    pub frame_delay_ms: u32,
+    /// Highlight vertex and edge collisions
+    pub show_conflicts: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 16,
+            frame_delay_ms: 250,
+            show_conflicts: true,
+        }
+    }
+}
+
+/// RGB color of `agent`. Hues step by the golden angle, so neighbouring
+/// indices stay distinguishable however many agents there are.
+pub fn agent_color(agent: usize) -> [u8; 3] {
+    let hue = (agent as f64 * 137.507_764) % 360.0;
+    hsl_to_rgb(hue, 0.65, 0.5)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> [u8; 3] {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue / 60.0;
+    let x = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |c: f64| ((c + m) * 255.0).round() as u8;
+    [channel(r), channel(g), channel(b)]
+}
+
+/// Collisions to highlight, or none if `options` turns them off.
+fn conflicts(solution: &Solution, options: &RenderOptions) -> Vec<Violation> {
+    if options.show_conflicts {
+        check_collisions(&solution.paths)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Number of timesteps to draw: the longest path's length.
+fn horizon(solution: &Solution) -> usize {
+    solution
+        .paths
+        .iter()
+        .map(|p| p.steps.len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Position of an agent at timestep `t`; agents stay at their last cell.
+fn position_at(steps: &[Coordinate], t: usize) -> Option<Coordinate> {
+    steps.get(t).or_else(|| steps.last()).copied()
+}
@@ -0,0 +1,226 @@
+//! Static SVG overviews of a solution, or of two solutions compared.
+
+use std::fmt::Write;
+
+use mapf_core::diff::{diff, AgentDiff};
+use mapf_core::{Coordinate, GridMap, Solution};
+
+use crate::{agent_color, conflicts, RenderOptions};
+
+const BACKGROUND: &str = "#ffffff";
+const BLOCKED: &str = "#333333";
+const CONFLICT: &str = "#e00000";
+const UNCHANGED: &str = "#bbbbbb";
+
+/// Draw the map with every agent's path, start (dot) and goal (square),
+/// and a red outline on each conflict cell.
+///
+/// Elements carry `<title>`s, so browsers show the agent or conflict on
+/// hover.
+pub fn render_svg(map: &GridMap, solution: &Solution, options: &RenderOptions) -> String {
+    let cell = options.cell_size.max(1);
+    let mut svg = begin(map, cell);
+    let size = cell as f64;
+
+    for (agent, path) in solution.paths.iter().enumerate() {
+        let (Some(&start), Some(&goal)) = (path.steps.first(), path.steps.last()) else {
+            continue;
+        };
+        let color = hex(agent_color(agent));
+
+        let _ = writeln!(
+            svg,
+            r#"<g class="agent" data-agent="{agent}"><title>Agent {agent}</title>"#
+        );
+        if path.steps.len() > 1 {
+            let _ = writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="{}" stroke-linejoin="round" stroke-opacity="0.7"/>"#,
+                points(&path.steps, cell),
+                size / 4.0
+            );
+        }
+        let (gx, gy) = center(goal, cell);
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{color}" stroke-width="{}"/>"#,
+            gx - size * 0.35,
+            gy - size * 0.35,
+            size * 0.7,
+            size * 0.7,
+            size / 8.0
+        );
+        let (sx, sy) = center(start, cell);
+        let _ = writeln!(
+            svg,
+            r#"<circle cx="{sx}" cy="{sy}" r="{}" fill="{color}"/>"#,
+            size * 0.3
+        );
+        svg.push_str("</g>\n");
+    }
+
+    for conflict in conflicts(solution, options) {
+        for &pos in &conflict.positions {
+            let _ = writeln!(
+                svg,
+                r#"<rect class="conflict" x="{}" y="{}" width="{size}" height="{size}" fill="none" stroke="{CONFLICT}" stroke-width="{}"><title>{}: {}</title></rect>"#,
+                pos.x as f64 * size,
+                pos.y as f64 * size,
+                size / 6.0,
+                conflict.kind.as_str(),
+                escape(&conflict.details)
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Overlay two solutions to the same instance: paths that differ are drawn
+/// solid for `a` and dashed for `b` in the agent's color, with a ring where
+/// they first part; unchanged paths are drawn thin and grey.
+///
+/// Hovering a path shows the agent's cost in both solutions.
+pub fn render_diff_svg(
+    map: &GridMap,
+    a: &Solution,
+    b: &Solution,
+    options: &RenderOptions,
+) -> String {
+    let cell = options.cell_size.max(1);
+    let mut svg = begin(map, cell);
+    let comparison = diff(a, b);
+    let (a, b) = (a.canonicalize(), b.canonicalize());
+    let size = cell as f64;
+
+    // Unchanged agents first, so changed ones are drawn on top
+    let mut agents: Vec<&AgentDiff> = comparison.agents.iter().collect();
+    agents.sort_by_key(|agent| !agent.is_same());
+
+    for agent in agents {
+        let i = agent.agent;
+        let title = format!(
+            "Agent {}: cost {} -> {} ({:+})",
+            i,
+            agent.cost_a,
+            agent.cost_b,
+            agent.cost_delta()
+        );
+        let _ = writeln!(
+            svg,
+            r#"<g class="agent" data-agent="{i}"><title>{title}</title>"#
+        );
+
+        if agent.is_same() {
+            let _ = writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{UNCHANGED}" stroke-width="{}" stroke-linejoin="round"/>"#,
+                points(&a.paths[i].steps, cell),
+                size / 8.0
+            );
+        } else {
+            let color = hex(agent_color(i));
+            let _ = writeln!(
+                svg,
+                r#"<polyline class="a" points="{}" fill="none" stroke="{color}" stroke-width="{}" stroke-linejoin="round" stroke-opacity="0.7"/>"#,
+                points(&a.paths[i].steps, cell),
+                size / 4.0
+            );
+            let _ = writeln!(
+                svg,
+                r#"<polyline class="b" points="{}" fill="none" stroke="{color}" stroke-width="{}" stroke-linejoin="round" stroke-dasharray="{} {}"/>"#,
+                points(&b.paths[i].steps, cell),
+                size / 6.0,
+                size / 3.0,
+                size / 4.0
+            );
+            // Ring on the last shared cell, where the paths split
+            let split = agent.first_divergence().unwrap_or(0).saturating_sub(1);
+            if let Some(&pos) = a.paths[i].steps.get(split) {
+                let (x, y) = center(pos, cell);
+                let _ = writeln!(
+                    svg,
+                    r#"<circle class="split" cx="{x}" cy="{y}" r="{}" fill="none" stroke="{color}" stroke-width="{}"/>"#,
+                    size * 0.4,
+                    size / 8.0
+                );
+            }
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// SVG header, background and obstacles; obstacles are merged into
+/// horizontal runs to keep large maps small.
+fn begin(map: &GridMap, cell: u32) -> String {
+    let (width, height) = (map.width() * cell, map.height() * cell);
+    let mut svg = String::new();
+
+    // Writing to a String can't fail
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{width}" height="{height}" fill="{BACKGROUND}"/>"#
+    );
+
+    for y in 0..map.height() {
+        let mut x = 0;
+        while x < map.width() {
+            if map.is_passable(x, y) {
+                x += 1;
+                continue;
+            }
+            let run_start = x;
+            while x < map.width() && !map.is_passable(x, y) {
+                x += 1;
+            }
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{}" height="{cell}" fill="{BLOCKED}"/>"#,
+                run_start * cell,
+                y * cell,
+                (x - run_start) * cell
+            );
+        }
+    }
+    svg
+}
+
+/// Pixel center of a cell.
+fn center(c: Coordinate, cell: u32) -> (f64, f64) {
+    let cell = cell as f64;
+    (
+        c.x as f64 * cell + cell / 2.0,
+        c.y as f64 * cell + cell / 2.0,
+    )
+}
+
+/// `points` attribute of a polyline through the centers of `steps`.
+fn points(steps: &[Coordinate], cell: u32) -> String {
+    steps
+        .iter()
+        .map(|&c| {
+            let (x, y) = center(c, cell);
+            format!("{x},{y}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Escape text for use inside an XML element.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
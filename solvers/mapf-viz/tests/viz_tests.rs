@@ -0,0 +1,153 @@
+use mapf_core::{Coordinate, GridMap, Path, Solution};
+use mapf_viz::{agent_color, render_diff_svg, render_gif, render_svg, RenderError, RenderOptions};
+use pretty_assertions::assert_eq;
+use weezl::{decode::Decoder, BitOrder};
+
+fn path(steps: &[(u32, u32)]) -> Path {
+    Path {
+        steps: steps.iter().map(|&(x, y)| Coordinate { x, y }).collect(),
+    }
+}
+
+/// 3x2 map with one obstacle at (1,1).
+fn map() -> GridMap {
+    GridMap::parse("type octile\nheight 2\nwidth 3\nmap\n...\n.@.\n").unwrap()
+}
+
+/// Two agents swapping cells between timesteps 0 and 1.
+fn swapping() -> Solution {
+    Solution {
+        paths: vec![path(&[(0, 0), (1, 0), (2, 0)]), path(&[(1, 0), (0, 0)])],
+    }
+}
+
+/// Image descriptors in a GIF written by `render_gif`, as (width, height, pixels).
+fn frames(gif: &[u8]) -> Vec<(u16, u16, Vec<u8>)> {
+    // Header, screen descriptor, 256-color palette, looping extension
+    let mut at = 6 + 7 + 768 + 19;
+    let mut frames = Vec::new();
+    while gif[at] != 0x3B {
+        // Graphic control extension
+        assert_eq!(&gif[at..at + 3], &[0x21, 0xF9, 0x04]);
+        at += 8;
+        assert_eq!(gif[at], 0x2C);
+        let width = u16::from_le_bytes([gif[at + 5], gif[at + 6]]);
+        let height = u16::from_le_bytes([gif[at + 7], gif[at + 8]]);
+        at += 10;
+        assert_eq!(gif[at], 8);
+        at += 1;
+        let mut data = Vec::new();
+        while gif[at] != 0 {
+            let len = gif[at] as usize;
+            data.extend_from_slice(&gif[at + 1..at + 1 + len]);
+            at += 1 + len;
+        }
+        at += 1;
+        let pixels = Decoder::new(BitOrder::Lsb, 8).decode(&data).unwrap();
+        frames.push((width, height, pixels));
+    }
+    assert_eq!(at, gif.len() - 1);
+    frames
+}
+
+#[test]
+fn agent_colors_are_distinct() {
+    let colors: Vec<_> = (0..8).map(agent_color).collect();
+    for (i, a) in colors.iter().enumerate() {
+        for b in &colors[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+fn svg_draws_map_paths_and_conflicts() {
+    let options = RenderOptions {
+        cell_size: 10,
+        ..RenderOptions::default()
+    };
+    let svg = render_svg(&map(), &swapping(), &options);
+
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.contains(r#"width="30" height="20""#));
+    assert!(svg.contains(r##"<rect x="10" y="10" width="10" height="10" fill="#333333"/>"##));
+    assert_eq!(svg.matches(r#"<g class="agent""#).count(), 2);
+    assert!(svg.contains(r#"<polyline points="5,5 15,5 25,5""#));
+    // Both cells of the swapped edge are outlined
+    assert_eq!(svg.matches(r#"class="conflict""#).count(), 2);
+    assert!(svg.contains("edge_collision: Agents 0 and 1 swap positions"));
+}
+
+#[test]
+fn svg_conflicts_can_be_hidden() {
+    let options = RenderOptions {
+        show_conflicts: false,
+        ..RenderOptions::default()
+    };
+    let svg = render_svg(&map(), &swapping(), &options);
+    assert!(!svg.contains("conflict"));
+}
+
+#[test]
+fn gif_has_one_frame_per_timestep() {
+    let options = RenderOptions {
+        cell_size: 4,
+        ..RenderOptions::default()
+    };
+    let gif = render_gif(&map(), &swapping(), &options).unwrap();
+
+    assert_eq!(&gif[..6], b"GIF89a");
+    assert_eq!(&gif[6..10], &[12, 0, 8, 0]);
+    let frames = frames(&gif);
+    assert_eq!(frames.len(), 3);
+
+    let pixel = |frame: &[u8], x: usize, y: usize| frame[y * 12 + x];
+    for (width, height, pixels) in &frames {
+        assert_eq!((*width, *height), (12, 8));
+        assert_eq!(pixels.len(), 12 * 8);
+        // The obstacle is drawn in every frame
+        assert_eq!(pixel(pixels, 6, 6), 1);
+    }
+    // The swap is highlighted around the agents at t=0 but not at t=2
+    assert_eq!(pixel(&frames[0].2, 4, 0), 2);
+    assert_ne!(pixel(&frames[2].2, 4, 0), 2);
+    // Agent 0's disc reaches its goal cell by the last frame
+    assert_eq!(pixel(&frames[2].2, 10, 2), 3);
+}
+
+#[test]
+fn gif_rejects_oversized_images() {
+    let options = RenderOptions {
+        cell_size: 40_000,
+        ..RenderOptions::default()
+    };
+    let result = render_gif(&map(), &swapping(), &options);
+    assert!(matches!(
+        result,
+        Err(RenderError::TooLarge { width: 120_000, .. })
+    ));
+}
+
+#[test]
+fn diff_svg_marks_changed_agents() {
+    let a = swapping();
+    // Agent 0 waits a step in `b`; agent 1 is unchanged
+    let b = Solution {
+        paths: vec![path(&[(0, 0), (0, 0), (1, 0), (2, 0)]), a.paths[1].clone()],
+    };
+    let options = RenderOptions {
+        cell_size: 10,
+        ..RenderOptions::default()
+    };
+    let svg = render_diff_svg(&map(), &a, &b, &options);
+
+    assert_eq!(svg.matches(r#"<g class="agent""#).count(), 2);
+    assert!(svg.contains("Agent 0: cost 2 -> 3 (+1)"));
+    assert!(svg.contains("Agent 1: cost 1 -> 1 (+0)"));
+    assert!(svg.contains(r#"<polyline class="a" points="5,5 15,5 25,5""#));
+    assert!(svg.contains(r#"<polyline class="b" points="5,5 5,5 15,5 25,5""#));
+    // The paths split after the start cell
+    assert!(svg.contains(r#"<circle class="split" cx="5" cy="5""#));
+    // Unchanged agents are drawn first, underneath
+    assert!(svg.find("data-agent=\"1\"").unwrap() < svg.find("data-agent=\"0\"").unwrap());
+}